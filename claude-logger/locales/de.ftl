@@ -0,0 +1,25 @@
+report-title = Analysebericht der Claude-Code-Sitzungen
+report-generated = Erstellt: { $time }
+report-no-sessions = Keine Sitzungen zur Analyse gefunden.
+report-total-sessions = Analysierte Sitzungen: { $count }
+report-no-recommendations = Keine besonderen Empfehlungen - weitere Sitzungen protokollieren, um bessere Einblicke zu erhalten.
+
+heading-methodology-comparison = Methodenvergleich
+heading-quality = Qualität der Sitzungen
+heading-terminal = Terminalgröße
+heading-authors = Autoren
+heading-length = Gesprächslänge und Qualität
+heading-task-types = Methode nach Aufgabentyp
+heading-hotspots = Häufig bearbeitete Dateien
+heading-cost = Kosten
+heading-recommendations = Empfehlungen
+heading-billing = Abrechnung: { $month }
+
+billing-no-sessions = Keine Sitzungen in diesem Monat.
+billing-total = Gesamt: { $amount }
+cost-by-methodology = Durchschnitt pro Sitzung nach Methode:
+
+duration-minutes = { $minutes ->
+    [one] { $minutes } Minute
+   *[other] { $minutes } Minuten
+}
@@ -0,0 +1,25 @@
+report-title = Claude Code Session Analysis Report
+report-generated = Generated: { $time }
+report-no-sessions = No sessions found for analysis.
+report-total-sessions = Total Sessions Analyzed: { $count }
+report-no-recommendations = No specific recommendations - continue logging sessions for better insights.
+
+heading-methodology-comparison = Methodology Comparison
+heading-quality = Session Quality Analysis
+heading-terminal = Terminal Size Analysis
+heading-authors = Authors
+heading-length = Conversation Length vs Quality
+heading-task-types = Methodology by Task Type
+heading-hotspots = Edit Hotspots
+heading-cost = Cost
+heading-recommendations = Recommendations
+heading-billing = Billing: { $month }
+
+billing-no-sessions = No sessions in this month.
+billing-total = Total: { $amount }
+cost-by-methodology = Average per session by methodology:
+
+duration-minutes = { $minutes ->
+    [one] { $minutes } minute
+   *[other] { $minutes } minutes
+}
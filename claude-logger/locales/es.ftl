@@ -0,0 +1,25 @@
+report-title = Informe de análisis de sesiones de Claude Code
+report-generated = Generado: { $time }
+report-no-sessions = No se encontraron sesiones para analizar.
+report-total-sessions = Sesiones analizadas: { $count }
+report-no-recommendations = Sin recomendaciones concretas: siga registrando sesiones para obtener mejores conclusiones.
+
+heading-methodology-comparison = Comparación de metodologías
+heading-quality = Calidad de las sesiones
+heading-terminal = Tamaño del terminal
+heading-authors = Autores
+heading-length = Longitud de la conversación y calidad
+heading-task-types = Metodología por tipo de tarea
+heading-hotspots = Archivos más editados
+heading-cost = Coste
+heading-recommendations = Recomendaciones
+heading-billing = Facturación: { $month }
+
+billing-no-sessions = No hay sesiones en este mes.
+billing-total = Total: { $amount }
+cost-by-methodology = Media por sesión según la metodología:
+
+duration-minutes = { $minutes ->
+    [one] { $minutes } minuto
+   *[other] { $minutes } minutos
+}
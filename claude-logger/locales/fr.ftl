@@ -0,0 +1,25 @@
+report-title = Rapport d'analyse des sessions Claude Code
+report-generated = Généré : { $time }
+report-no-sessions = Aucune session à analyser.
+report-total-sessions = Sessions analysées : { $count }
+report-no-recommendations = Aucune recommandation particulière - continuez à enregistrer des sessions pour affiner l'analyse.
+
+heading-methodology-comparison = Comparaison des méthodes
+heading-quality = Qualité des sessions
+heading-terminal = Taille du terminal
+heading-authors = Auteurs
+heading-length = Longueur des conversations et qualité
+heading-task-types = Méthode par type de tâche
+heading-hotspots = Fichiers les plus modifiés
+heading-cost = Coût
+heading-recommendations = Recommandations
+heading-billing = Facturation : { $month }
+
+billing-no-sessions = Aucune session ce mois-ci.
+billing-total = Total : { $amount }
+cost-by-methodology = Moyenne par session selon la méthode :
+
+duration-minutes = { $minutes ->
+    [one] { $minutes } minute
+   *[other] { $minutes } minutes
+}
@@ -0,0 +1,105 @@
+use crate::session::{ActivityBreakdown, ActivitySource, SessionMetadata};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// A stretch without output longer than this counts as idle: stepped away rather than reading.
+pub const IDLE_GAP_SECS: i64 = 5 * 60;
+
+/// How often the log is checked for growth while a session is captured.
+const HEARTBEAT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Records when a capture's log grows, sampled every `HEARTBEAT_INTERVAL`.
+pub struct Heartbeats {
+    log_file: PathBuf,
+    last_size: u64,
+    last_check: Instant,
+    times: Vec<DateTime<Utc>>,
+}
+
+impl Heartbeats {
+    pub fn new(log_file: &Path) -> Self {
+        Self { log_file: log_file.to_path_buf(), last_size: 0, last_check: Instant::now(), times: Vec::new() }
+    }
+
+    /// Call from the capture loop; does nothing until the next sample is due.
+    pub fn poll(&mut self) {
+        if self.last_check.elapsed() < HEARTBEAT_INTERVAL {
+            return;
+        }
+        self.last_check = Instant::now();
+        let size = fs::metadata(&self.log_file).map(|metadata| metadata.len()).unwrap_or(0);
+        if size != self.last_size {
+            self.last_size = size;
+            self.times.push(Utc::now());
+        }
+    }
+
+    pub fn breakdown(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Option<ActivityBreakdown> {
+        breakdown(start, end, &self.times, ActivitySource::Heartbeats)
+    }
+}
+
+/// Active and idle time of a finished session from the timestamps of its transcript's messages.
+pub fn from_transcript(session: &SessionMetadata, content: &str) -> Option<ActivityBreakdown> {
+    let end = session.end_time.or_else(|| Some(session.timestamp + session.duration()?))?;
+    let times: Vec<DateTime<Utc>> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter_map(|entry| DateTime::parse_from_rfc3339(entry["timestamp"].as_str()?).ok())
+        .map(|time| time.with_timezone(&Utc))
+        .collect();
+    breakdown(session.timestamp, end, &times, ActivitySource::Transcript)
+}
+
+/// Split `start..end` at the moments something happened: gaps between them longer than
+/// `IDLE_GAP_SECS`, including before the first and after the last, are idle. `None` when
+/// nothing happened within the session.
+pub fn breakdown(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    times: &[DateTime<Utc>],
+    source: ActivitySource,
+) -> Option<ActivityBreakdown> {
+    let mut moments: Vec<DateTime<Utc>> = times.iter().copied().filter(|time| (start..=end).contains(time)).collect();
+    if moments.is_empty() {
+        return None;
+    }
+    moments.sort();
+    moments.insert(0, start);
+    moments.push(end);
+
+    let gaps: Vec<Duration> = moments.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    let idle: i64 = gaps.iter().map(Duration::num_seconds).filter(|gap| *gap > IDLE_GAP_SECS).sum();
+    let longest = gaps.iter().map(Duration::num_seconds).max().unwrap_or(0);
+    Some(ActivityBreakdown {
+        active_secs: (end - start).num_seconds() - idle,
+        idle_secs: idle,
+        longest_idle_secs: longest,
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn counts_long_gaps_as_idle() {
+        let start = Utc.with_ymd_and_hms(2025, 3, 1, 10, 0, 0).unwrap();
+        let at = |minutes: i64| start + Duration::minutes(minutes);
+        // Busy for 10 minutes, away for 20, busy again until the end at 40
+        let times: Vec<_> = [1, 4, 8, 10, 30, 33, 37, 39].map(at).to_vec();
+        let activity = breakdown(start, at(40), &times, ActivitySource::Heartbeats).unwrap();
+        assert_eq!((activity.active_secs, activity.idle_secs, activity.longest_idle_secs), (20 * 60, 20 * 60, 20 * 60));
+        assert_eq!(activity.idle_percent(), 50.0);
+
+        // Short pauses while reading are active
+        let activity = breakdown(start, at(12), &[at(2), at(6), at(10)], ActivitySource::Transcript).unwrap();
+        assert_eq!((activity.idle_secs, activity.longest_idle_secs), (0, 4 * 60));
+        assert!(breakdown(start, at(12), &[at(20)], ActivitySource::Transcript).is_none());
+    }
+}
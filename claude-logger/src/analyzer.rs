@@ -1,47 +1,136 @@
+use crate::compaction;
+use crate::config::{Config, ExclusionConfig};
+use crate::log_format::{self, LogFormat};
+use crate::cost::CostSummary;
+use crate::git::GitRepo;
+use crate::logger::ClaudeLogger;
+use crate::length::{LengthAnalysis, LengthSample};
+use crate::metadata_store::{open_metadata_store, SessionFilter};
 use crate::patterns::{analyze_session_quality, get_patterns, SessionQuality};
-use crate::session::{AnalysisMetrics, Methodology, MethodologyStats, SessionMetadata, SessionsMetadata};
+use crate::project;
+use crate::pty::CaptureMode;
+use crate::ranking::{self, QualityRank};
+use crate::session::{AnalysisMetrics, AnalysisSnapshot, Methodology, MethodologyStats, SessionMetadata, SessionsMetadata};
+use crate::storage::{open_store, LogStore};
+use crate::sync;
+use crate::task_type::{classify, TaskSample, TaskTypeAnalysis};
+use crate::edits::parse_edits;
+use crate::encryption;
+use crate::filter::Query;
+use crate::stats::{AggregateStats, Distribution};
+use crate::resume;
+use crate::report::{AuthorReport, EditHotspot, MethodologyReport, QualityAverages, Report, TerminalBucket};
+use crate::terminal::CRAMPED_COLUMNS;
+use crate::timezone::DisplayZone;
+use crate::transcript::Transcript;
 use anyhow::{Context, Result};
-use std::collections::HashMap;
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Number of edit hotspots included in the analysis report.
+const REPORT_HOTSPOTS: usize = 10;
+
 pub struct SessionAnalyzer {
     logs_dir: PathBuf,
     metadata: SessionsMetadata,
+    log_store: Option<Box<dyn LogStore>>,
+    git_repo: Option<GitRepo>,
+    /// Resumed sessions folded into their conversation's first session, oldest first
+    merged: HashMap<String, Vec<SessionMetadata>>,
 }
 
 impl SessionAnalyzer {
     pub fn new() -> Result<Self> {
-        let logs_dir = Self::get_logs_directory()?;
-        Self::new_with_dir(&logs_dir)
+        let logs_dir = ClaudeLogger::get_logs_directory()?;
+        let config = Config::load()?;
+        Self::new_with_config(&logs_dir, &config)
     }
 
     pub fn new_with_dir(logs_dir: &Path) -> Result<Self> {
-        let metadata_file = logs_dir.join("sessions_metadata.json");
-        let metadata = Self::load_metadata(&metadata_file)?;
+        Self::new_with_config(logs_dir, &Config::default())
+    }
+
+    pub fn new_with_config(logs_dir: &Path, config: &Config) -> Result<Self> {
+        let metadata = open_metadata_store(&config.metadata, logs_dir)?.load(config.load_mode())?;
+        let log_store = open_store(&config.storage, logs_dir)?;
 
         Ok(Self {
             logs_dir: logs_dir.to_path_buf(),
             metadata,
+            log_store,
+            git_repo: GitRepo::open(logs_dir),
+            merged: HashMap::new(),
         })
     }
 
-    fn get_logs_directory() -> Result<PathBuf> {
-        let home_dir = dirs::home_dir()
-            .context("Failed to get home directory")?;
-        Ok(home_dir.join(".claude-logs"))
+    /// Drop sessions matched by the configured exclusions, returning how many were removed.
+    pub fn apply_exclusions(&mut self, exclusions: &ExclusionConfig) -> usize {
+        let before = self.metadata.sessions.len();
+        self.metadata.sessions.retain(|_, session| !exclusions.excludes(session));
+        before - self.metadata.sessions.len()
     }
 
-    fn load_metadata(metadata_file: &Path) -> Result<SessionsMetadata> {
-        if metadata_file.exists() {
-            let content = fs::read_to_string(metadata_file)
-                .with_context(|| format!("Failed to read metadata file: {}", metadata_file.display()))?;
-            
-            serde_json::from_str(&content)
-                .with_context(|| format!("Failed to parse metadata file: {}", metadata_file.display()))
-        } else {
-            Ok(SessionsMetadata::new())
+    /// Keep only the sessions of `project`, returning how many remain.
+    pub fn retain_project(&mut self, project: &str) -> usize {
+        self.metadata.sessions.retain(|_, session| session.project == project);
+        self.metadata.sessions.len()
+    }
+
+    /// Keep only the sessions `query` matches, returning how many remain.
+    pub fn retain_matching(&mut self, query: &Query) -> usize {
+        self.metadata.sessions.retain(|_, session| query.matches(session));
+        self.metadata.sessions.len()
+    }
+
+    /// Keep only the sessions `author` recorded, returning how many remain.
+    pub fn retain_author(&mut self, author: &str) -> usize {
+        self.metadata.sessions.retain(|_, session| session.author.as_deref() == Some(author));
+        self.metadata.sessions.len()
+    }
+
+    /// Fold sessions that resumed a conversation into its first session, so a conversation
+    /// picked up several times counts once. Returns how many sessions were merged.
+    ///
+    /// Durations and token usage are summed and the logs read back-to-back; a session whose
+    /// parent isn't present (deleted or excluded) stays on its own.
+    pub fn merge_resumed(&mut self) -> usize {
+        let mut continuations: Vec<(String, String)> = self
+            .metadata
+            .sessions
+            .keys()
+            .map(|id| (resume::root_of(&self.metadata, id).to_string(), id.clone()))
+            .filter(|(root, id)| root != id)
+            .collect();
+        continuations.sort_by_key(|(_, id)| self.metadata.sessions[id].timestamp);
+
+        let count = continuations.len();
+        for (root_id, id) in continuations {
+            let Some(continuation) = self.metadata.sessions.remove(&id) else {
+                continue;
+            };
+            let Some(root) = self.metadata.get_session_mut(&root_id) else {
+                continue;
+            };
+
+            root.duration_seconds = match (root.duration_seconds, continuation.duration_seconds) {
+                (Some(a), Some(b)) => Some(a + b),
+                (a, b) => a.or(b),
+            };
+            root.end_time = root.end_time.max(continuation.end_time);
+            root.creative_energy = root.creative_energy.or(continuation.creative_energy);
+            if let Some(usage) = &continuation.usage {
+                root.usage.get_or_insert_with(Default::default).add(usage);
+            }
+            root.activity = match (root.activity, continuation.activity) {
+                (Some(a), Some(b)) => Some(a.combine(&b)),
+                (a, b) => a.or(b),
+            };
+            self.merged.entry(root_id).or_default().push(continuation);
         }
+        count
     }
 
     pub fn analyze_log_file(&self, log_path: &Path) -> Result<AnalysisMetrics> {
@@ -52,12 +141,133 @@ impl SessionAnalyzer {
         Ok(patterns.analyze_content(&content))
     }
 
+    /// The session's log with terminal escapes stripped, as analysis expects.
+    ///
+    /// Prefers the cleaned copy written at capture time, unless an older parser wrote it;
+    /// otherwise the raw log is read in whichever [`LogFormat`] it turns out to be.
+    pub fn read_session_log(&self, session: &SessionMetadata) -> Result<String> {
+        self.read_conversation(session, Self::read_single_session_log)
+    }
+
+    fn read_single_session_log(&self, session: &SessionMetadata) -> Result<String> {
+        if let Some(clean_log_file) = &session.clean_log_file
+            && session.log_parser.as_deref().is_none_or(LogFormat::is_current)
+            && let Ok(content) = project::read_text(clean_log_file)
+        {
+            return Ok(content);
+        }
+        Ok(log_format::to_text(&self.read_single_raw_session_log(session)?).1)
+    }
+
+    /// The log exactly as captured from the terminal, escape sequences and all.
+    pub fn read_raw_session_log(&self, session: &SessionMetadata) -> Result<String> {
+        self.read_conversation(session, Self::read_single_raw_session_log)
+    }
+
+    /// `session`'s log followed by those of any resumed sessions merged into it.
+    fn read_conversation(
+        &self,
+        session: &SessionMetadata,
+        read: fn(&Self, &SessionMetadata) -> Result<String>,
+    ) -> Result<String> {
+        let mut content = read(self, session)?;
+        for continuation in self.merged.get(&session.id).into_iter().flatten() {
+            content.push('\n');
+            content.push_str(&read(self, continuation)?);
+        }
+        Ok(content)
+    }
+
+    /// One session's raw log. When the local file has been pruned it is restored on demand from
+    /// the configured log store, then from the git history.
+    fn read_single_raw_session_log(&self, session: &SessionMetadata) -> Result<String> {
+        if session.log_file.exists() {
+            return Ok(String::from_utf8_lossy(&project::read_log(&session.log_file)?).into_owned());
+        }
+
+        let mut attempts = Vec::new();
+
+        if let Some(store) = &self.log_store {
+            let fetched = match &session.archived_remote {
+                Some(archive) => sync::fetch_archive(store.as_ref(), archive),
+                // Logs archived by a sync that couldn't save the metadata are found by manifest
+                None => store.get(&session.log_key()).or_else(|e| {
+                    sync::find_archive(store.as_ref(), &session.log_key())
+                        .and_then(|archive| sync::fetch_archive(store.as_ref(), &archive))
+                        .map_err(|_| e)
+                }),
+            };
+            match fetched {
+                Ok(content) => return Ok(String::from_utf8_lossy(&encryption::open(content)?).into_owned()),
+                Err(e) => attempts.push(format!("{} storage: {:#}", store.name(), e)),
+            }
+        }
+
+        if let Some(repo) = &self.git_repo {
+            match Self::read_from_history(repo, session) {
+                Ok(content) => return Ok(String::from_utf8_lossy(&content).into_owned()),
+                Err(e) => attempts.push(format!("git: {:#}", e)),
+            }
+        }
+
+        if attempts.is_empty() {
+            Err(anyhow::anyhow!("Log file not found: {}", session.log_file.display()))
+        } else {
+            Err(anyhow::anyhow!(
+                "Log file not found: {} ({})",
+                session.log_file.display(),
+                attempts.join("; ")
+            ))
+        }
+    }
+
+    /// `git show <commit>:<file>` using the recorded commit, or the last commit that
+    /// touched the file for sessions logged before commits were recorded or whose log was
+    /// compressed since.
+    fn read_from_history(repo: &GitRepo, session: &SessionMetadata) -> Result<Vec<u8>> {
+        let file = repo.relative_path(&session.log_file);
+        let commit = match &session.git_commit {
+            Some(commit) if !project::is_compressed(&session.log_file) => commit.clone(),
+            _ => repo
+                .last_commit_for(&file)?
+                .with_context(|| format!("{} was never committed", file))?,
+        };
+        project::decode_log(&session.log_file, repo.show_file(&commit, &file)?)
+    }
+
+    pub fn analyze_session_log(&self, session: &SessionMetadata) -> Result<AnalysisMetrics> {
+        let content = self.read_session_log(session)?;
+        Ok(Self::session_metrics(session, &content))
+    }
+
+    /// The session's metrics and quality scores from `content`, its log, as of now.
+    pub fn snapshot(session: &SessionMetadata, content: &str) -> AnalysisSnapshot {
+        AnalysisSnapshot {
+            analyzed_at: Utc::now(),
+            metrics: Self::session_metrics(session, content),
+            quality: analyze_session_quality(content),
+        }
+    }
+
+    /// Pattern metrics from the log, with exact counts from the linked transcript where there is one.
+    fn session_metrics(session: &SessionMetadata, content: &str) -> AnalysisMetrics {
+        let mut metrics = get_patterns().analyze_content(content);
+        if let Some(path) = &session.transcript_file {
+            match Transcript::read(path) {
+                Ok(transcript) => transcript.apply_to(&mut metrics),
+                Err(e) => eprintln!("Warning: Falling back to log estimates for {}: {:#}", session.id, e),
+            }
+        }
+        metrics
+    }
+
     pub fn analyze_session(&self, session_id: &str) -> Result<(AnalysisMetrics, SessionQuality)> {
         let session = self.metadata.get_session(session_id)
             .context("Session not found")?;
 
-        let metrics = self.analyze_log_file(&session.log_file)?;
-        let quality = analyze_session_quality(&fs::read_to_string(&session.log_file)?);
+        let content = self.read_session_log(session)?;
+        let metrics = Self::session_metrics(session, &content);
+        let quality = analyze_session_quality(&content);
 
         Ok((metrics, quality))
     }
@@ -69,15 +279,11 @@ impl SessionAnalyzer {
             let mut stats = MethodologyStats::new();
 
             for session in sessions {
-                if session.log_file.exists() {
-                    match self.analyze_log_file(&session.log_file) {
-                        Ok(metrics) => stats.add_session(session, metrics),
-                        Err(e) => {
-                            eprintln!("Warning: Failed to analyze session {}: {}", session.id, e);
-                        }
+                match self.analyze_session_log(session) {
+                    Ok(metrics) => stats.add_session(session, metrics),
+                    Err(e) => {
+                        eprintln!("Warning: Failed to analyze session {}: {:#}", session.id, e);
                     }
-                } else {
-                    eprintln!("Warning: Log file not found for session {}", session.id);
                 }
             }
 
@@ -87,106 +293,243 @@ impl SessionAnalyzer {
         Ok(methodology_stats)
     }
 
-    pub fn generate_report(&self) -> Result<()> {
-        println!("=== Claude Code Session Analysis Report ===\n");
+    pub fn generate_report(&self, zone: DisplayZone) -> Result<()> {
+        let report = self.build_report()?;
+        report.print(zone);
+        Ok(())
+    }
 
+    pub fn build_report(&self) -> Result<Report> {
         let methodology_stats = self.compare_methodologies()?;
 
-        if methodology_stats.is_empty() {
-            println!("No sessions found for analysis.");
-            return Ok(());
-        }
-
-        // Overall statistics
         let total_sessions: usize = methodology_stats.values().map(|stats| stats.sessions).sum();
-        println!("Total Sessions Analyzed: {}\n", total_sessions);
 
-        // Methodology comparison
-        println!("=== Methodology Comparison ===");
-        for (methodology, stats) in &methodology_stats {
-            if stats.sessions == 0 {
-                continue;
-            }
+        let mut methodologies: Vec<MethodologyReport> = methodology_stats
+            .iter()
+            .filter(|(_, stats)| stats.sessions > 0)
+            .map(|(methodology, stats)| MethodologyReport {
+                methodology: methodology.clone(),
+                stats: stats.clone(),
+                quality: self.quality_averages(methodology),
+            })
+            .collect();
+        methodologies.sort_by_key(|entry| entry.methodology.to_string());
+
+        let hotspots = self.edit_hotspots(None, REPORT_HOTSPOTS);
+        let length = self.length_analysis();
+        let task_types = self.task_type_analysis();
+        let mut recommendations = self.recommendations(&methodology_stats, &hotspots);
+        recommendations.extend(length.recommendations());
+        recommendations.extend(task_types.recommendations());
+
+        Ok(Report {
+            generated_at: Utc::now(),
+            total_sessions,
+            methodologies,
+            terminal: self.terminal_buckets(),
+            authors: self.author_comparison(),
+            hotspots,
+            length,
+            task_types,
+            cost: CostSummary::from_sessions(self.metadata.sessions.values()),
+            recommendations,
+        })
+    }
 
-            println!("\n{} Sessions:", methodology);
-            println!("  Sessions: {}", stats.sessions);
-            
-            if stats.avg_duration.num_minutes() > 0 {
-                println!("  Average Duration: {} minutes", stats.avg_duration.num_minutes());
-                println!("  Total Duration: {} minutes", stats.total_duration.num_minutes());
-            }
+    fn quality_averages(&self, methodology: &Methodology) -> Option<QualityAverages> {
+        // Sample a few sessions for detailed quality analysis
+        let sessions_by_methodology = self.metadata.sessions_by_methodology();
+        let sessions = sessions_by_methodology.get(methodology)?;
 
-            if let Some(avg_energy) = stats.avg_energy {
-                println!("  Average Creative Energy: {:.1}/3", avg_energy);
+        let mut quality_scores = Vec::new();
+        for session in sessions.iter().take(5) { // Sample first 5 sessions
+            if let Ok(content) = self.read_session_log(session) {
+                quality_scores.push(analyze_session_quality(&content));
             }
+        }
 
-            println!("  Conversation Metrics:");
-            println!("    Total Exchanges: {}", stats.metrics.exchanges);
-            println!("    Code Blocks: {}", stats.metrics.code_blocks);
-            println!("    Questions Asked: {}", stats.metrics.questions_asked);
-            println!("    Enthusiasm Markers: {}", stats.metrics.enthusiasm_markers);
-            println!("    Confusion Markers: {}", stats.metrics.confusion_markers);
-            println!("    Compaction Indicators: {}", stats.metrics.compaction_indicators);
-
-            // Calculate derived metrics
-            if stats.sessions > 0 {
-                let avg_exchanges = stats.metrics.exchanges as f64 / stats.sessions as f64;
-                let avg_code_blocks = stats.metrics.code_blocks as f64 / stats.sessions as f64;
-                println!("  Average per Session:");
-                println!("    Exchanges: {:.1}", avg_exchanges);
-                println!("    Code Blocks: {:.1}", avg_code_blocks);
-            }
+        if quality_scores.is_empty() {
+            return None;
         }
 
-        // Quality analysis
-        println!("\n=== Session Quality Analysis ===");
-        self.generate_quality_report(&methodology_stats)?;
+        let count = quality_scores.len() as f64;
+        Some(QualityAverages {
+            sampled_sessions: quality_scores.len(),
+            engagement: quality_scores.iter().map(|q| q.engagement_score).sum::<f64>() / count,
+            clarity: quality_scores.iter().map(|q| q.clarity_score).sum::<f64>() / count,
+            productivity: quality_scores.iter().map(|q| q.productivity_score).sum::<f64>() / count,
+            overall: quality_scores.iter().map(|q| q.overall_score).sum::<f64>() / count,
+        })
+    }
 
-        // Recommendations
-        println!("\n=== Recommendations ===");
-        self.generate_recommendations(&methodology_stats);
+    fn terminal_buckets(&self) -> Vec<TerminalBucket> {
+        // Compare confusion rates between cramped and roomy terminals
+        let mut cramped = (0usize, 0usize);
+        let mut roomy = (0usize, 0usize);
 
-        Ok(())
+        for session in self.metadata.sessions.values() {
+            let Some(is_cramped) = session.terminal.as_ref().and_then(|terminal| terminal.is_cramped()) else {
+                continue;
+            };
+
+            if let Ok(metrics) = self.analyze_session_log(session) {
+                let bucket = if is_cramped { &mut cramped } else { &mut roomy };
+                bucket.0 += 1;
+                bucket.1 += metrics.confusion_markers;
+            }
+        }
+
+        [
+            (format!("Cramped (<{} columns)", CRAMPED_COLUMNS), cramped),
+            (format!("Roomy (>={} columns)", CRAMPED_COLUMNS), roomy),
+        ]
+        .into_iter()
+        .filter(|(_, (sessions, _))| *sessions > 0)
+        .map(|(label, (sessions, confusion))| TerminalBucket {
+            label,
+            sessions,
+            confusion_per_session: confusion as f64 / sessions as f64,
+        })
+        .collect()
     }
 
-    fn generate_quality_report(&self, methodology_stats: &HashMap<Methodology, MethodologyStats>) -> Result<()> {
-        for (methodology, stats) in methodology_stats {
-            if stats.sessions == 0 {
-                continue;
+    /// Sessions, hours and quality per author, for logs shared by a team. Empty unless at least
+    /// two authors have recorded sessions.
+    pub fn author_comparison(&self) -> Vec<AuthorReport> {
+        let mut by_author: BTreeMap<&str, Vec<&SessionMetadata>> = BTreeMap::new();
+        for session in self.metadata.sessions.values() {
+            if let Some(author) = &session.author {
+                by_author.entry(author).or_default().push(session);
             }
+        }
+        if by_author.len() < 2 {
+            return Vec::new();
+        }
 
-            println!("\n{} Quality Metrics:", methodology);
-            
-            // Sample a few sessions for detailed quality analysis
-            let sessions_by_methodology = self.metadata.sessions_by_methodology();
-            if let Some(sessions) = sessions_by_methodology.get(methodology) {
-                let mut quality_scores = Vec::new();
-
-                for session in sessions.iter().take(5) { // Sample first 5 sessions
-                    if let Ok(content) = fs::read_to_string(&session.log_file) {
-                        let quality = analyze_session_quality(&content);
-                        quality_scores.push(quality);
+        by_author
+            .into_iter()
+            .map(|(author, sessions)| {
+                let mut exchanges = 0;
+                let mut scores = Vec::new();
+                for session in &sessions {
+                    if let Ok(content) = self.read_session_log(session) {
+                        exchanges += get_patterns().analyze_content(&content).exchanges;
+                        scores.push(analyze_session_quality(&content).overall_score);
                     }
                 }
+                let seconds: i64 = sessions.iter().filter_map(|session| session.duration()).map(|duration| duration.num_seconds()).sum();
+                AuthorReport {
+                    author: author.to_string(),
+                    sessions: sessions.len(),
+                    hours: seconds as f64 / 3600.0,
+                    exchanges_per_session: exchanges as f64 / scores.len().max(1) as f64,
+                    overall_quality: (!scores.is_empty()).then(|| scores.iter().sum::<f64>() / scores.len() as f64),
+                }
+            })
+            .collect()
+    }
 
-                if !quality_scores.is_empty() {
-                    let avg_engagement = quality_scores.iter().map(|q| q.engagement_score).sum::<f64>() / quality_scores.len() as f64;
-                    let avg_clarity = quality_scores.iter().map(|q| q.clarity_score).sum::<f64>() / quality_scores.len() as f64;
-                    let avg_productivity = quality_scores.iter().map(|q| q.productivity_score).sum::<f64>() / quality_scores.len() as f64;
-                    let avg_overall = quality_scores.iter().map(|q| q.overall_score).sum::<f64>() / quality_scores.len() as f64;
+    /// Exchange count and duration of every readable session against its overall quality.
+    pub fn length_analysis(&self) -> LengthAnalysis {
+        let samples: Vec<LengthSample> = self
+            .metadata
+            .sessions
+            .values()
+            .filter_map(|session| {
+                let content = self.read_session_log(session).ok()?;
+                Some(LengthSample {
+                    exchanges: get_patterns().analyze_content(&content).exchanges,
+                    minutes: session.duration().map(|duration| duration.num_minutes()),
+                    quality: analyze_session_quality(&content).overall_score,
+                })
+            })
+            .collect();
+
+        LengthAnalysis::from_samples(&samples)
+    }
 
-                    println!("  Average Engagement Score: {:.1}/100", avg_engagement);
-                    println!("  Average Clarity Score: {:.1}/100", avg_clarity);
-                    println!("  Average Productivity Score: {:.1}/100", avg_productivity);
-                    println!("  Average Overall Score: {:.1}/100", avg_overall);
-                }
+    /// Overall quality of every readable session by methodology, within each kind of task.
+    pub fn task_type_analysis(&self) -> TaskTypeAnalysis {
+        let samples: Vec<TaskSample> = self
+            .metadata
+            .sessions
+            .values()
+            .filter_map(|session| {
+                let content = self.read_session_log(session).ok()?;
+                Some(TaskSample {
+                    task_type: classify(&content),
+                    methodology: session.methodology.clone(),
+                    quality: analyze_session_quality(&content).overall_score,
+                })
+            })
+            .collect();
+
+        TaskTypeAnalysis::from_samples(&samples)
+    }
+
+    /// Files edited most often across sessions, optionally limited to one project.
+    pub fn edit_hotspots(&self, project: Option<&str>, limit: usize) -> Vec<EditHotspot> {
+        let mut by_file: HashMap<(String, String), (EditHotspot, HashSet<String>)> = HashMap::new();
+
+        for session in self.metadata.sessions.values() {
+            if project.is_some_and(|project| project != session.project) {
+                continue;
+            }
+            let Ok(content) = self.read_session_log(session) else {
+                continue;
+            };
+
+            for edit in parse_edits(&content) {
+                // Absolute paths inside the project are shown relative to it
+                let file = Path::new(&edit.file)
+                    .strip_prefix(&session.working_directory)
+                    .map(|relative| relative.display().to_string())
+                    .unwrap_or(edit.file);
+
+                let (hotspot, sessions) = by_file
+                    .entry((session.project.clone(), file.clone()))
+                    .or_insert_with(|| {
+                        (
+                            EditHotspot {
+                                project: session.project.clone(),
+                                file,
+                                edits: 0,
+                                sessions: 0,
+                                lines_added: 0,
+                                lines_removed: 0,
+                            },
+                            HashSet::new(),
+                        )
+                    });
+                hotspot.edits += 1;
+                hotspot.lines_added += edit.added;
+                hotspot.lines_removed += edit.removed;
+                sessions.insert(session.id.clone());
             }
         }
 
-        Ok(())
+        let mut hotspots: Vec<EditHotspot> = by_file
+            .into_values()
+            .map(|(mut hotspot, sessions)| {
+                hotspot.sessions = sessions.len();
+                hotspot
+            })
+            .collect();
+        hotspots.sort_by(|a, b| {
+            b.edits
+                .cmp(&a.edits)
+                .then(b.sessions.cmp(&a.sessions))
+                .then_with(|| (&a.project, &a.file).cmp(&(&b.project, &b.file)))
+        });
+        hotspots.truncate(limit);
+        hotspots
     }
 
-    fn generate_recommendations(&self, methodology_stats: &HashMap<Methodology, MethodologyStats>) {
+    fn recommendations(
+        &self,
+        methodology_stats: &HashMap<Methodology, MethodologyStats>,
+        hotspots: &[EditHotspot],
+    ) -> Vec<String> {
         let mut recommendations = Vec::new();
 
         // Find the methodology with highest engagement
@@ -194,20 +537,19 @@ impl SessionAnalyzer {
             .iter()
             .filter(|(_, stats)| stats.sessions > 0)
             .max_by(|(_, a), (_, b)| {
-                let a_score = if let Some(energy) = a.avg_energy { energy } else { 0.0 };
-                let b_score = if let Some(energy) = b.avg_energy { energy } else { 0.0 };
+                let a_score = a.avg_energy.unwrap_or(0.0);
+                let b_score = b.avg_energy.unwrap_or(0.0);
                 a_score.partial_cmp(&b_score).unwrap_or(std::cmp::Ordering::Equal)
             });
 
-        if let Some((methodology, stats)) = best_methodology {
-            if let Some(avg_energy) = stats.avg_energy {
-                if avg_energy > 2.0 {
-                    recommendations.push(format!(
-                        "Continue using {} methodology - it shows high creative energy ({:.1}/3)",
-                        methodology, avg_energy
-                    ));
-                }
-            }
+        if let Some((methodology, stats)) = best_methodology
+            && let Some(avg_energy) = stats.avg_energy
+            && avg_energy > 2.0
+        {
+            recommendations.push(format!(
+                "Continue using {} methodology - it shows high creative energy ({:.1}/3)",
+                methodology, avg_energy
+            ));
         }
 
         // Check for confusion patterns
@@ -236,13 +578,83 @@ impl SessionAnalyzer {
             }
         }
 
-        if recommendations.is_empty() {
-            println!("No specific recommendations - continue logging sessions for better insights.");
-        } else {
-            for (i, recommendation) in recommendations.iter().enumerate() {
-                println!("{}. {}", i + 1, recommendation);
+        // Compaction means the conversation outgrew the context window
+        let compacted: Vec<&SessionMetadata> =
+            self.metadata.sessions.values().filter(|session| !session.compactions.is_empty()).collect();
+        if !compacted.is_empty() {
+            let mut first_at: Vec<usize> = compacted.iter().map(|session| session.compactions[0].after_exchanges).collect();
+            first_at.sort_unstable();
+            let repeated = match compacted.iter().filter(|session| session.compactions.len() > 1).count() {
+                0 => String::new(),
+                repeated => format!(", {} more than once", repeated),
+            };
+            recommendations.push(format!(
+                "{} of {} sessions had their context compacted{}, the first time after a median of {} exchanges - consider starting a fresh session before then",
+                compacted.len(),
+                self.metadata.sessions.len(),
+                repeated,
+                first_at[first_at.len() / 2]
+            ));
+        }
+
+        // Files that keep being reworked are candidates for refactoring or documentation
+        for hotspot in hotspots.iter().filter(|hotspot| hotspot.is_churning()).take(3) {
+            recommendations.push(format!(
+                "{} in {} was edited {} times across {} sessions - consider refactoring it or documenting it for Claude",
+                hotspot.file, hotspot.project, hotspot.edits, hotspot.sessions
+            ));
+        }
+
+        recommendations
+    }
+
+    /// Sessions matching `filter`, newest first.
+    pub fn filtered_sessions(&self, filter: &SessionFilter) -> Vec<&SessionMetadata> {
+        let mut sessions: Vec<&SessionMetadata> =
+            self.metadata.sessions.values().filter(|session| filter.matches(session)).collect();
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.timestamp));
+        sessions.truncate(filter.limit.unwrap_or(usize::MAX));
+        sessions
+    }
+
+    /// Counts, sums and distributions over the sessions matching `filter`.
+    ///
+    /// With a `limit`, only the most recent matching sessions are aggregated.
+    pub fn aggregate(&self, filter: &SessionFilter) -> AggregateStats {
+        let sessions = self.filtered_sessions(filter);
+
+        let mut stats = AggregateStats {
+            sessions: sessions.len(),
+            first_session: sessions.last().map(|session| session.timestamp),
+            last_session: sessions.first().map(|session| session.timestamp),
+            ..AggregateStats::default()
+        };
+        let (mut minutes, mut exchanges, mut file_edits, mut quality) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+
+        for session in sessions {
+            *stats.by_methodology.entry(session.methodology.to_string()).or_default() += 1;
+            *stats.by_project.entry(session.project.clone()).or_default() += 1;
+            if let Some(energy) = session.creative_energy {
+                *stats.by_energy.entry(energy).or_default() += 1;
             }
+            minutes.extend(session.duration().map(|duration| duration.num_minutes() as f64));
+
+            let Ok(content) = self.read_session_log(session) else {
+                continue;
+            };
+            let metrics = Self::session_metrics(session, &content);
+            stats.analyzed += 1;
+            stats.totals.add(&metrics);
+            exchanges.push(metrics.exchanges as f64);
+            file_edits.push(metrics.file_edits as f64);
+            quality.push(analyze_session_quality(&content).overall_score);
         }
+
+        stats.minutes = Distribution::from_values(&minutes);
+        stats.exchanges = Distribution::from_values(&exchanges);
+        stats.file_edits = Distribution::from_values(&file_edits);
+        stats.overall_quality = Distribution::from_values(&quality);
+        stats
     }
 
     pub fn get_session_summary(&self, session_id: &str) -> Result<SessionSummary> {
@@ -250,53 +662,226 @@ impl SessionAnalyzer {
             .context("Session not found")?;
 
         let (metrics, quality) = self.analyze_session(session_id)?;
+        let history = self
+            .metadata
+            .sessions
+            .values()
+            .filter(|other| other.id != session.id)
+            .filter_map(|other| other.analysis.as_ref().map(|snapshot| &snapshot.quality));
+        let ranks = ranking::rank(&quality, history);
 
         Ok(SessionSummary {
             session: session.clone(),
             metrics,
             quality,
+            ranks,
         })
     }
 
+    /// What `show --json` prints: the summary, the ids of the conversation it belongs to and, with
+    /// `with_edits`, the file edits in its log.
+    pub fn summary_json(&self, summary: &SessionSummary, with_edits: bool) -> Result<serde_json::Value> {
+        let mut value = serde_json::to_value(summary).context("Failed to serialize session to JSON")?;
+        let conversation: Vec<&str> =
+            resume::chain(&self.metadata, &summary.session.id).iter().map(|session| session.id.as_str()).collect();
+        value["conversation"] = serde_json::json!(conversation);
+        if with_edits {
+            let edits = parse_edits(&self.read_session_log(&summary.session)?);
+            value["edits"] = serde_json::to_value(edits).context("Failed to serialize edits to JSON")?;
+        }
+        Ok(value)
+    }
+
     pub fn metadata(&self) -> &SessionsMetadata {
         &self.metadata
     }
+
+    pub fn logs_dir(&self) -> &Path {
+        &self.logs_dir
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct SessionSummary {
     pub session: SessionMetadata,
     pub metrics: AnalysisMetrics,
     pub quality: SessionQuality,
+    /// How the quality scores compare with the other sessions' last analyses
+    pub ranks: Vec<QualityRank>,
 }
 
 impl SessionSummary {
-    pub fn print_summary(&self) {
-        println!("=== Session Summary: {} ===", self.session.id);
-        println!("Project: {}", self.session.project);
-        println!("Methodology: {}", self.session.methodology);
-        println!("Timestamp: {}", self.session.timestamp.format("%Y-%m-%d %H:%M:%S UTC"));
+    pub fn print_summary(&self, zone: DisplayZone) {
+        println!("{}", self.summary_text(zone));
+    }
+
+    /// The summary `show` prints, as text.
+    pub fn summary_text(&self, zone: DisplayZone) -> String {
+        let mut lines = Vec::new();
+        lines.push(format!("=== Session Summary: {} ===", self.session.id));
+        lines.push(format!("Project: {}", self.session.project));
+        lines.push(format!("Methodology: {}", self.session.methodology));
+        lines.push(format!("Timestamp: {}", zone.format_full(self.session.timestamp)));
+        lines.push(format!("Review: {}", self.session.review_state));
+        if let Some(hold) = &self.session.hold {
+            lines.push(format!("Hold: {} (since {})", hold.reason, zone.format(hold.placed_at, "%Y-%m-%d")));
+        }
+        if let Some(parent) = &self.session.parent_session_id {
+            lines.push(format!("Resumed from: {}", parent));
+        }
         
-        if let Some(duration) = self.session.duration {
-            println!("Duration: {} minutes", duration.num_minutes());
+        if let Some(duration) = self.session.duration() {
+            let estimated = if self.session.duration_estimated { " (estimated)" } else { "" };
+            lines.push(format!("Duration: {} minutes{}", duration.num_minutes(), estimated));
+        }
+        if self.session.capture_mode == CaptureMode::Pipe {
+            lines.push("Captured: through pipes, without a terminal".to_string());
+        }
+        if let Some(activity) = &self.session.activity {
+            lines.push(format!(
+                "Active: {} minutes ({:.0}% idle, longest idle gap {} minutes)",
+                activity.active_secs / 60,
+                activity.idle_percent(),
+                activity.longest_idle_secs / 60
+            ));
+        }
+
+        if !self.session.features_worked_on.is_empty() {
+            lines.push(format!("Features: {}", self.session.features_worked_on.join(", ")));
+        }
+
+        if let Some(changes) = &self.session.project_changes {
+            lines.push(format!("Project changes: {}", changes.describe()));
+        }
+
+        if !self.session.compactions.is_empty() {
+            let events: Vec<String> = self
+                .session
+                .compactions
+                .iter()
+                .map(|event| compaction::describe(event, |at| zone.format(at, "%H:%M")))
+                .collect();
+            lines.push(format!("Context compactions: {} ({})", events.len(), events.join("; ")));
         }
 
         if let Some(energy) = self.session.creative_energy {
-            println!("Creative Energy: {}/3", energy);
+            lines.push(format!("Creative Energy: {}/3", energy));
         }
 
-        println!("\nConversation Metrics:");
-        println!("  Exchanges: {}", self.metrics.exchanges);
-        println!("  Code Blocks: {}", self.metrics.code_blocks);
-        println!("  Questions Asked: {}", self.metrics.questions_asked);
-        println!("  Enthusiasm Markers: {}", self.metrics.enthusiasm_markers);
-        println!("  Confusion Markers: {}", self.metrics.confusion_markers);
-        println!("  Compaction Indicators: {}", self.metrics.compaction_indicators);
+        if let Some(terminal) = &self.session.terminal {
+            lines.push(format!("Terminal: {}", terminal.describe()));
+        }
+
+        if let Some(reason) = &self.session.termination_reason {
+            lines.push(format!("Stopped by guard: {}", reason));
+        }
 
-        println!("\nQuality Scores:");
-        println!("  Engagement: {:.1}/100", self.quality.engagement_score);
-        println!("  Clarity: {:.1}/100", self.quality.clarity_score);
-        println!("  Productivity: {:.1}/100", self.quality.productivity_score);
-        println!("  Overall: {:.1}/100", self.quality.overall_score);
+        let mut extra: Vec<_> = self.session.extra.iter().collect();
+        extra.sort_by_key(|(key, _)| *key);
+        for (key, value) in extra {
+            lines.push(format!("{}: {}", key, value));
+        }
+
+        if !self.session.annotations.is_empty() {
+            lines.push("\nAnnotations:".to_string());
+            for annotation in &self.session.annotations {
+                lines.push(format!("  [{}] lines {}-{}: {}",
+                    annotation.category, annotation.start_line, annotation.end_line, annotation.note));
+            }
+        }
+
+        if !self.session.notes.is_empty() {
+            lines.push("\nNotes:".to_string());
+            for note in &self.session.notes {
+                lines.push(format!("  {}  {}", zone.format(note.created_at, "%Y-%m-%d %H:%M"), note.text));
+            }
+        }
+
+        lines.push("\nConversation Metrics:".to_string());
+        lines.push(format!("  Exchanges: {}", self.metrics.exchanges));
+        lines.push(format!("  Code Blocks: {}", self.metrics.code_blocks));
+        lines.push(format!("  File Edits: {}", self.metrics.file_edits));
+        lines.push(format!("  Questions Asked: {}", self.metrics.questions_asked));
+        lines.push(format!("  Enthusiasm Markers: {}", self.metrics.enthusiasm_markers));
+        lines.push(format!("  Confusion Markers: {}", self.metrics.confusion_markers));
+        lines.push(format!("  Compaction Indicators: {}", self.metrics.compaction_indicators));
+        lines.push(format!("  Errors: {}, Retries: {}", self.metrics.error_events, self.metrics.retry_events));
+        for (category, count) in &self.metrics.custom_markers {
+            lines.push(format!("  {}: {}", category, count));
+        }
+        if !self.metrics.tool_usage.is_empty() {
+            let tools: Vec<String> = self
+                .metrics
+                .tool_distribution()
+                .into_iter()
+                .map(|(tool, calls, _)| format!("{} {}", tool, calls))
+                .collect();
+            lines.push(format!("  Tool Usage: {}", tools.join(", ")));
+        }
+        if self.session.transcript_file.is_some() {
+            lines.push(format!("  Tool Calls: {}", self.metrics.tool_calls));
+            lines.push(format!("  Tokens: {} in, {} out", self.metrics.input_tokens, self.metrics.output_tokens));
+        }
+
+        lines.push("\nQuality Scores:".to_string());
+        let scores = [
+            ("Engagement", "engagement", self.quality.engagement_score),
+            ("Clarity", "clarity", self.quality.clarity_score),
+            ("Productivity", "productivity", self.quality.productivity_score),
+            ("Overall", "overall", self.quality.overall_score),
+        ];
+        for (label, key, value) in scores {
+            let mut line = format!("  {}: {:.1}/100", label, value);
+            if let Some(rank) = self.ranks.iter().find(|rank| rank.score == key) {
+                line.push_str(&format!(" — {}", rank.describe()));
+            }
+            lines.push(line);
+        }
+
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::LoadMode;
+    use crate::synth::{generate, sessions};
+
+    #[test]
+    fn prints_sessions_as_json_for_list_and_show() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut logger = ClaudeLogger::new_with_dir(dir.path()).unwrap();
+        let mut ids = Vec::new();
+        for generated in generate(&sessions(2, 8, "api"), dir.path(), Utc::now()) {
+            fs::create_dir_all(generated.session.log_file.parent().unwrap()).unwrap();
+            fs::write(&generated.session.log_file, &generated.log).unwrap();
+            ids.push(generated.session.id.clone());
+            logger.add_session(generated.session);
+        }
+        ids.sort();
+        logger.metadata_mut().get_session_mut(&ids[1]).unwrap().parent_session_id = Some(ids[0].clone());
+        logger.save_metadata().unwrap();
+
+        // `list --json`: the matching sessions' metadata, newest first
+        let store = open_metadata_store(&Config::default().metadata, dir.path()).unwrap();
+        let listed = serde_json::to_value(store.query(&SessionFilter::default(), LoadMode::Strict).unwrap()).unwrap();
+        let listed = listed.as_array().unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0]["id"], ids[1].as_str());
+        assert_eq!(listed[0]["project"], "api");
+        assert!(listed[0]["duration_seconds"].is_u64());
+
+        let analyzer = SessionAnalyzer::new_with_dir(dir.path()).unwrap();
+        let summary = analyzer.get_session_summary(&ids[1]).unwrap();
+        let shown = analyzer.summary_json(&summary, false).unwrap();
+        for key in ["session", "metrics", "quality", "ranks", "conversation"] {
+            assert!(shown.get(key).is_some(), "missing {}", key);
+        }
+        assert_eq!(shown["session"]["id"], ids[1].as_str());
+        assert!(shown["quality"]["overall_score"].is_number());
+        assert_eq!(shown["conversation"], serde_json::json!([ids[0], ids[1]]));
+        assert!(shown.get("edits").is_none());
+        assert!(analyzer.summary_json(&summary, true).unwrap()["edits"].is_array());
     }
-}
\ No newline at end of file
+}
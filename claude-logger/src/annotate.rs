@@ -0,0 +1,180 @@
+use crate::logger::ClaudeLogger;
+use crate::session::{Annotation, SessionMetadata, SessionNote, SessionsMetadata};
+use anyhow::{Context, Result};
+use chrono::Utc;
+
+/// Parse a 1-based line range such as `120-140` or a single line `120`.
+pub fn parse_line_range(range: &str) -> Result<(usize, usize)> {
+    let (start, end) = match range.split_once('-') {
+        Some((start, end)) => (start.trim(), end.trim()),
+        None => (range.trim(), range.trim()),
+    };
+
+    let start: usize = start
+        .parse()
+        .with_context(|| format!("Invalid line range '{}': expected e.g. 120-140", range))?;
+    let end: usize = end
+        .parse()
+        .with_context(|| format!("Invalid line range '{}': expected e.g. 120-140", range))?;
+
+    if start == 0 || end < start {
+        return Err(anyhow::anyhow!(
+            "Invalid line range '{}': lines start at 1 and the end must not precede the start",
+            range
+        ));
+    }
+
+    Ok((start, end))
+}
+
+/// Build an annotation over `start..=end` of the log `content`, capturing the excerpt.
+pub fn new_annotation(content: &str, (start, end): (usize, usize), category: &str, note: &str) -> Result<Annotation> {
+    let lines: Vec<&str> = content.lines().collect();
+    if end > lines.len() {
+        return Err(anyhow::anyhow!(
+            "Line range {}-{} is past the end of the log ({} lines)",
+            start,
+            end,
+            lines.len()
+        ));
+    }
+
+    Ok(Annotation {
+        category: category.trim().to_lowercase(),
+        note: note.trim().to_string(),
+        start_line: start,
+        end_line: end,
+        excerpt: lines[start - 1..end].join("\n"),
+        created_at: Utc::now(),
+    })
+}
+
+/// Split an annotation typed as `category: note`, e.g. `mistake: edited the wrong file`; without
+/// a one-word category before the colon, all of it is the note and the category is `note`.
+pub fn split_category(input: &str) -> (String, String) {
+    match input.split_once(':') {
+        Some((category, note))
+            if !category.trim().is_empty()
+                && category.trim().chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') =>
+        {
+            (category.trim().to_lowercase(), note.trim().to_string())
+        }
+        _ => ("note".to_string(), input.trim().to_string()),
+    }
+}
+
+/// Add `annotation` to a session and save the metadata.
+pub fn add_annotation(logger: &mut ClaudeLogger, session_id: &str, annotation: Annotation) -> Result<()> {
+    if annotation.note.is_empty() {
+        anyhow::bail!("The annotation is empty");
+    }
+    logger
+        .metadata_mut()
+        .get_session_mut(session_id)
+        .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?
+        .annotations
+        .push(annotation);
+    logger.save_metadata()
+}
+
+/// Append a timestamped note to a session and commit the metadata, returning the commit.
+pub fn add_note(logger: &mut ClaudeLogger, session_id: &str, text: &str) -> Result<Option<String>> {
+    let text = text.trim();
+    if text.is_empty() {
+        anyhow::bail!("The note is empty");
+    }
+    let session = logger
+        .metadata_mut()
+        .get_session_mut(session_id)
+        .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+    session.notes.push(SessionNote {
+        created_at: Utc::now(),
+        text: text.to_string(),
+    });
+    let message = format!("Add note to session {} | {}", session.id, session.project);
+
+    logger.save_metadata()?;
+    logger.commit_metadata(&message)
+}
+
+/// Annotations whose category and project match and whose note, category or excerpt
+/// contains `query` (case-insensitive), newest session first.
+pub fn search<'a>(
+    metadata: &'a SessionsMetadata,
+    query: Option<&str>,
+    category: Option<&str>,
+    project: Option<&str>,
+) -> Vec<(&'a SessionMetadata, &'a Annotation)> {
+    let query = query.map(str::to_lowercase);
+
+    metadata
+        .recent_sessions(None, usize::MAX)
+        .into_iter()
+        .filter(|session| project.is_none_or(|project| session.project == project))
+        .flat_map(|session| session.annotations.iter().map(move |annotation| (session, annotation)))
+        .filter(|(_, annotation)| category.is_none_or(|c| annotation.category.eq_ignore_ascii_case(c)))
+        .filter(|(_, annotation)| {
+            query.as_ref().is_none_or(|q| {
+                annotation.note.to_lowercase().contains(q)
+                    || annotation.category.to_lowercase().contains(q)
+                    || annotation.excerpt.to_lowercase().contains(q)
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::metadata_store::JSON_FILE;
+    use crate::synth::{generate, sessions};
+    use std::fs;
+    use std::process::Command;
+
+    #[test]
+    fn parses_line_ranges_and_captures_excerpts() {
+        assert_eq!(parse_line_range("120-140").unwrap(), (120, 140));
+        assert_eq!(parse_line_range(" 7 ").unwrap(), (7, 7));
+        for invalid in ["0-5", "5-3", "0", "a-b", "3-", ""] {
+            assert!(parse_line_range(invalid).is_err(), "{}", invalid);
+        }
+
+        let content = "Human: one\nAssistant: two\nHuman: three\n";
+        let annotation = new_annotation(content, (2, 3), " Insight ", " retry loop ").unwrap();
+        assert_eq!((annotation.category.as_str(), annotation.note.as_str()), ("insight", "retry loop"));
+        assert_eq!(annotation.excerpt, "Assistant: two\nHuman: three");
+        assert!(new_annotation(content, (3, 3), "note", "last line").is_ok());
+        let error = new_annotation(content, (3, 4), "note", "past the end").unwrap_err();
+        assert!(error.to_string().contains("past the end of the log (3 lines)"), "{}", error);
+
+        assert_eq!(split_category("Mistake: edited the wrong file"), ("mistake".to_string(), "edited the wrong file".to_string()));
+        assert_eq!(split_category("see https://example.com"), ("note".to_string(), "see https://example.com".to_string()));
+        assert_eq!(split_category("two words: no category"), ("note".to_string(), "two words: no category".to_string()));
+    }
+
+    #[test]
+    fn notes_are_saved_and_committed() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut logger = ClaudeLogger::new_with_config(dir.path(), Config::default()).unwrap();
+        for (key, value) in [("user.email", "test@example.com"), ("user.name", "Test")] {
+            Command::new("git").args(["config", key, value]).current_dir(dir.path()).status().unwrap();
+        }
+        let options = sessions(1, 8, "api");
+        let generated = generate(&options, dir.path(), Utc::now()).remove(0);
+        fs::create_dir_all(generated.session.log_file.parent().unwrap()).unwrap();
+        fs::write(&generated.session.log_file, &generated.log).unwrap();
+        logger.complete_session(&generated.session).unwrap();
+        let id = generated.session.id;
+
+        assert!(add_note(&mut logger, &id, "   ").is_err());
+        assert!(add_note(&mut logger, "missing", "hi").is_err());
+        assert!(add_note(&mut logger, &id, " Really about the retry bug ").unwrap().is_some());
+
+        let notes = &logger.get_session(&id).unwrap().notes;
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].text, "Really about the retry bug");
+        let tracked = Command::new("git").args(["ls-files", JSON_FILE]).current_dir(dir.path()).output().unwrap();
+        assert_eq!(String::from_utf8_lossy(&tracked.stdout).trim(), JSON_FILE);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::config::NotificationsConfig;
+use crate::notify::{Event, Notifier};
+use crate::project;
+use crate::session::{SessionMetadata, SessionsMetadata};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::fs;
+
+/// Output is measured over at least this long, so one burst of scrollback isn't a runaway.
+const RATE_WINDOW_SECS: i64 = 60;
+
+/// Finished sessions needed before output rates are compared with a baseline.
+const MIN_BASELINE_SESSIONS: usize = 3;
+
+/// What the watcher knows about one live capture.
+#[derive(Debug, Clone)]
+struct Activity {
+    /// Log size and time at the start of the current rate window
+    window_size: u64,
+    window_start: DateTime<Utc>,
+    size: u64,
+    last_growth: DateTime<Utc>,
+    /// Already reported, so each episode is announced once
+    runaway: bool,
+    stalled: bool,
+}
+
+/// Watches live captures for runaway output and long silences.
+///
+/// Log sizes are sampled on every watcher pass; rates are compared with the median
+/// bytes-per-minute of finished sessions.
+pub struct ActivityMonitor {
+    runaway_factor: f64,
+    stall_minutes: i64,
+    /// Median output rate of finished sessions, in bytes per minute
+    baseline: Option<f64>,
+    live: HashMap<String, Activity>,
+}
+
+impl ActivityMonitor {
+    pub fn new(config: &NotificationsConfig) -> Self {
+        Self {
+            runaway_factor: config.runaway_factor,
+            stall_minutes: config.stall_minutes,
+            baseline: None,
+            live: HashMap::new(),
+        }
+    }
+
+    /// Recompute the normal output rate from finished sessions with an uncompressed local log.
+    pub fn refresh_baseline(&mut self, metadata: &SessionsMetadata) {
+        let mut rates: Vec<f64> = metadata
+            .sessions
+            .values()
+            .filter_map(|session| {
+                if project::is_compressed(&session.log_file) {
+                    return None;
+                }
+                let minutes = session.duration()?.num_seconds() as f64 / 60.0;
+                let size = fs::metadata(&session.log_file).ok()?.len();
+                (minutes >= 1.0).then(|| size as f64 / minutes)
+            })
+            .collect();
+        if rates.len() < MIN_BASELINE_SESSIONS {
+            self.baseline = None;
+            return;
+        }
+        rates.sort_by(f64::total_cmp);
+        self.baseline = Some(rates[rates.len() / 2]);
+    }
+
+    /// Record a live log's size, returning any anomaly that just began.
+    pub fn observe(&mut self, session_id: &str, size: u64, now: DateTime<Utc>) -> Vec<Event> {
+        let activity = self.live.entry(session_id.to_string()).or_insert(Activity {
+            window_size: size,
+            window_start: now,
+            size,
+            last_growth: now,
+            runaway: false,
+            stalled: false,
+        });
+
+        let mut events = Vec::new();
+        if size != activity.size {
+            activity.size = size;
+            activity.last_growth = now;
+            activity.stalled = false;
+        }
+
+        let idle = now - activity.last_growth;
+        if idle >= Duration::minutes(self.stall_minutes) && !activity.stalled {
+            activity.stalled = true;
+            events.push(Event::SessionStalled {
+                session_id: session_id.to_string(),
+                minutes: idle.num_minutes(),
+            });
+        }
+
+        let window = now - activity.window_start;
+        if window >= Duration::seconds(RATE_WINDOW_SECS) {
+            let rate = size.saturating_sub(activity.window_size) as f64 / (window.num_seconds() as f64 / 60.0);
+            activity.window_size = size;
+            activity.window_start = now;
+
+            if let Some(baseline) = self.baseline.filter(|baseline| *baseline > 0.0) {
+                let runaway = rate >= baseline * self.runaway_factor;
+                if runaway && !activity.runaway {
+                    events.push(Event::RunawayOutput {
+                        session_id: session_id.to_string(),
+                        rate,
+                        baseline,
+                    });
+                }
+                activity.runaway = runaway;
+            }
+        }
+
+        events
+    }
+
+    /// Sample the live captures and report new anomalies; sessions no longer live are forgotten.
+    pub fn check(&mut self, live: &[SessionMetadata], notifier: &Notifier) {
+        let now = Utc::now();
+        self.live.retain(|id, _| live.iter().any(|session| session.id == *id));
+
+        for session in live {
+            let Ok(log) = fs::metadata(&session.log_file) else {
+                continue;
+            };
+            for event in self.observe(&session.id, log.len(), now) {
+                notifier.emit(&event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(baseline: Option<f64>) -> ActivityMonitor {
+        ActivityMonitor {
+            runaway_factor: 10.0,
+            stall_minutes: 30,
+            baseline,
+            live: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn reports_runaway_output_once_per_episode() {
+        let mut monitor = monitor(Some(1_000.0));
+        let start = Utc::now();
+
+        assert!(monitor.observe("s", 0, start).is_empty());
+        let events = monitor.observe("s", 20_000, start + Duration::minutes(1));
+        assert!(matches!(events.as_slice(), [Event::RunawayOutput { rate, .. }] if *rate == 20_000.0));
+        assert!(monitor.observe("s", 40_000, start + Duration::minutes(2)).is_empty());
+
+        // Back to normal, then running away again is a new episode
+        assert!(monitor.observe("s", 41_000, start + Duration::minutes(3)).is_empty());
+        assert_eq!(monitor.observe("s", 80_000, start + Duration::minutes(4)).len(), 1);
+    }
+
+    #[test]
+    fn reports_stall_after_silence() {
+        let mut monitor = monitor(None);
+        let start = Utc::now();
+
+        monitor.observe("s", 500, start);
+        assert!(monitor.observe("s", 500, start + Duration::minutes(29)).is_empty());
+        let events = monitor.observe("s", 500, start + Duration::minutes(30));
+        assert!(matches!(events.as_slice(), [Event::SessionStalled { minutes: 30, .. }]));
+        assert!(monitor.observe("s", 500, start + Duration::minutes(45)).is_empty());
+
+        monitor.observe("s", 600, start + Duration::minutes(46));
+        assert_eq!(monitor.observe("s", 600, start + Duration::minutes(76)).len(), 1);
+    }
+}
@@ -0,0 +1,275 @@
+use crate::session::{SessionMetadata, SessionsMetadata};
+use crate::timezone::DisplayZone;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+/// Append-only record of changes to the metadata store, and of the commands run, one JSON entry per line.
+pub const AUDIT_FILE: &str = "audit.jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Added,
+    Edited,
+    Deleted,
+    /// Copied into another metadata store by `migrate-metadata`
+    Imported,
+    /// Put under a legal hold
+    Held,
+    /// Released from its legal hold
+    Released,
+    /// A claude-logger command ran; only its subcommands and flag names are kept, for `self-stats`
+    Ran,
+}
+
+impl Action {
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Added => "added",
+            Action::Edited => "edited",
+            Action::Deleted => "deleted",
+            Action::Imported => "imported",
+            Action::Held => "held",
+            Action::Released => "released",
+            Action::Ran => "ran",
+        }
+    }
+}
+
+/// One change to the store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub action: Action,
+    /// The session changed; none for store-wide data such as phases
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Metadata fields that changed, for edits; custom fields as `meta.KEY`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<String>,
+    /// The command line that made the change
+    pub command: String,
+}
+
+impl AuditEntry {
+    fn new(action: Action, session_id: Option<&str>, fields: Vec<String>) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            action,
+            session_id: session_id.map(str::to_string),
+            fields,
+            command: command_line(),
+        }
+    }
+
+    pub fn imported(session_id: &str) -> Self {
+        Self::new(Action::Imported, Some(session_id), Vec::new())
+    }
+
+    /// A run of `invocation`, as built by `self_stats::invocation`.
+    pub fn ran(invocation: String) -> Self {
+        Self { command: invocation, ..Self::new(Action::Ran, None, Vec::new()) }
+    }
+}
+
+/// The entries recording the difference between two states of the store.
+pub fn changes(before: &SessionsMetadata, after: &SessionsMetadata) -> Vec<AuditEntry> {
+    let ids: BTreeSet<&String> = before.sessions.keys().chain(after.sessions.keys()).collect();
+    let mut entries: Vec<AuditEntry> = ids
+        .into_iter()
+        .filter_map(|id| session_change(before.sessions.get(id), after.sessions.get(id)))
+        .collect();
+
+    if to_json(&before.phases) != to_json(&after.phases) {
+        entries.push(AuditEntry::new(Action::Edited, None, vec!["phases".to_string()]));
+    }
+    entries
+}
+
+/// The entry recording a change to one session, if it changed at all.
+pub fn session_change(before: Option<&SessionMetadata>, after: Option<&SessionMetadata>) -> Option<AuditEntry> {
+    match (before, after) {
+        (None, Some(after)) => Some(AuditEntry::new(Action::Added, Some(&after.id), Vec::new())),
+        (Some(before), None) => Some(AuditEntry::new(Action::Deleted, Some(&before.id), Vec::new())),
+        (Some(before), Some(after)) => {
+            let fields = changed_fields(&to_json(before), &to_json(after));
+            let action = match (&before.hold, &after.hold) {
+                (None, Some(_)) => Action::Held,
+                (Some(_), None) => Action::Released,
+                _ => Action::Edited,
+            };
+            (!fields.is_empty()).then(|| AuditEntry::new(action, Some(&after.id), fields))
+        }
+        (None, None) => None,
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or_default()
+}
+
+fn changed_fields(before: &serde_json::Value, after: &serde_json::Value) -> Vec<String> {
+    let (Some(before), Some(after)) = (before.as_object(), after.as_object()) else {
+        return Vec::new();
+    };
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter(|key| before.get(*key) != after.get(*key))
+        .flat_map(|key| {
+            let custom = if key == "extra" { changed_fields(&before[key], &after[key]) } else { Vec::new() };
+            if custom.is_empty() {
+                vec![key.clone()]
+            } else {
+                custom.into_iter().map(|field| format!("meta.{}", field)).collect()
+            }
+        })
+        .collect()
+}
+
+/// This process's command line, as it would be typed.
+fn command_line() -> String {
+    let mut args = std::env::args();
+    let program = args
+        .next()
+        .and_then(|program| Path::new(&program).file_name().map(|name| name.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "claude-logger".to_string());
+    std::iter::once(program)
+        .chain(args.map(|arg| {
+            if arg.is_empty() || arg.contains(char::is_whitespace) || arg.contains('\'') {
+                format!("'{}'", arg.replace('\'', r"'\''"))
+            } else {
+                arg
+            }
+        }))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Append `entries` to the audit log in `logs_dir`.
+pub fn append(logs_dir: &Path, entries: &[AuditEntry]) -> Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let path = logs_dir.join(AUDIT_FILE);
+    let mut lines = String::new();
+    for entry in entries {
+        lines.push_str(&serde_json::to_string(entry).context("Failed to serialize audit entry")?);
+        lines.push('\n');
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(lines.as_bytes()))
+        .with_context(|| format!("Failed to write audit log: {}", path.display()))
+}
+
+/// Every entry in the audit log, oldest first; unreadable lines are reported and skipped.
+pub fn read(logs_dir: &Path) -> Result<Vec<AuditEntry>> {
+    let path = logs_dir.join(AUDIT_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read audit log: {}", path.display()))?;
+    Ok(content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(index, line)| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                eprintln!("Warning: Skipping line {} of {}: {}", index + 1, path.display(), e);
+                None
+            }
+        })
+        .collect())
+}
+
+pub fn print(entries: &[&AuditEntry], zone: DisplayZone) {
+    if entries.is_empty() {
+        println!("No matching changes recorded.");
+        return;
+    }
+    for entry in entries {
+        let mut change = format!("{:<8} {}", entry.action.name(), entry.session_id.as_deref().unwrap_or("-"));
+        if !entry.fields.is_empty() {
+            change.push_str(&format!(" ({})", entry.fields.join(", ")));
+        }
+        println!("{} | {} | {}", zone.format(entry.timestamp, "%Y-%m-%d %H:%M:%S"), change, entry.command);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::{generate, sessions, SynthOptions};
+
+    #[test]
+    fn records_changes_between_states() {
+        let options = SynthOptions { days: 2, ..sessions(3, 9, "api") };
+        let mut before = SessionsMetadata::new();
+        for generated in generate(&options, Path::new("/logs"), Utc::now()) {
+            before.add_session(generated.session);
+        }
+        let mut ids: Vec<String> = before.sessions.keys().cloned().collect();
+        ids.sort();
+
+        let mut after = before.clone();
+        after.remove_session(&ids[0]);
+        let edited = after.get_session_mut(&ids[1]).unwrap();
+        edited.tags.push("keep".to_string());
+        edited.creative_energy = Some(3);
+        edited.extra.insert("ticket".to_string(), serde_json::json!("ABC-12"));
+        let mut added = after.get_session(&ids[2]).unwrap().clone();
+        added.id = "2099-01-01_00-00-00".to_string();
+        after.add_session(added);
+
+        let entries = changes(&before, &after);
+        let summary: Vec<(Action, &str, Vec<&str>)> = entries
+            .iter()
+            .map(|entry| {
+                let fields = entry.fields.iter().map(String::as_str).collect();
+                (entry.action, entry.session_id.as_deref().unwrap(), fields)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (Action::Deleted, ids[0].as_str(), vec![]),
+                (Action::Edited, ids[1].as_str(), vec!["creative_energy", "meta.ticket", "tags"]),
+                (Action::Added, "2099-01-01_00-00-00", vec![]),
+            ]
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        append(dir.path(), &entries).unwrap();
+        append(dir.path(), &changes(&after, &after)).unwrap();
+        let read_back = read(dir.path()).unwrap();
+        assert_eq!(read_back.len(), 3);
+        assert_eq!(read_back[1].fields, ["creative_energy", "meta.ticket", "tags"]);
+    }
+
+    #[test]
+    fn records_holds_and_releases() {
+        let mut before = SessionsMetadata::new();
+        for generated in generate(&sessions(1, 9, "api"), Path::new("/logs"), Utc::now()) {
+            before.add_session(generated.session);
+        }
+        let id = before.sessions.keys().next().unwrap().clone();
+        let mut after = before.clone();
+        after.get_session_mut(&id).unwrap().hold =
+            Some(crate::session::Hold { reason: "litigation".to_string(), placed_at: Utc::now() });
+
+        let summary = |entries: Vec<AuditEntry>| -> Vec<(Action, Option<String>, Vec<String>)> {
+            entries.into_iter().map(|entry| (entry.action, entry.session_id, entry.fields)).collect()
+        };
+        assert_eq!(summary(changes(&before, &after)), [(Action::Held, Some(id.clone()), vec!["hold".to_string()])]);
+        assert_eq!(summary(changes(&after, &before)), [(Action::Released, Some(id), vec!["hold".to_string()])]);
+    }
+}
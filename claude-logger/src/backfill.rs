@@ -0,0 +1,175 @@
+use crate::analyzer::SessionAnalyzer;
+use crate::logger::ClaudeLogger;
+use crate::session::SessionMetadata;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local, NaiveDateTime, TimeZone, Utc};
+use regex::Regex;
+use std::fs;
+use std::sync::OnceLock;
+
+/// Estimates spanning longer than this are more likely stray dates than one sitting.
+const MAX_ESTIMATE_HOURS: i64 = 24;
+
+/// Where an estimated duration came from, most reliable first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EstimateSource {
+    /// First and last timestamps in the linked Claude Code transcript
+    Transcript,
+    /// The `Script started on` / `Script done on` lines of a `script` capture
+    ScriptMarkers,
+    /// First and last ISO 8601 timestamps in the log
+    ContentTimestamps,
+    /// The session's start to the log file's last modification
+    FileTimes,
+}
+
+impl EstimateSource {
+    pub fn name(self) -> &'static str {
+        match self {
+            EstimateSource::Transcript => "transcript",
+            EstimateSource::ScriptMarkers => "script markers",
+            EstimateSource::ContentTimestamps => "content timestamps",
+            EstimateSource::FileTimes => "file times",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Estimate {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub source: EstimateSource,
+}
+
+impl Estimate {
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+
+    fn new(start: DateTime<Utc>, end: DateTime<Utc>, source: EstimateSource) -> Option<Self> {
+        let span = end - start;
+        (span > Duration::zero() && span <= Duration::hours(MAX_ESTIMATE_HOURS)).then_some(Self { start, end, source })
+    }
+}
+
+/// Sessions with no duration that aren't still being captured.
+pub fn missing_duration(session: &SessionMetadata) -> bool {
+    session.duration_seconds.is_none() && !(session.tmux_pane.is_some() && session.end_time.is_none())
+}
+
+/// Estimate when `session` started and ended from `raw`, its log as captured, or from its files.
+pub fn estimate(session: &SessionMetadata, raw: &str) -> Option<Estimate> {
+    let transcript = session
+        .transcript_file
+        .as_ref()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| span(&content, EstimateSource::Transcript));
+
+    transcript
+        .or_else(|| script_markers(raw))
+        .or_else(|| span(raw, EstimateSource::ContentTimestamps))
+        .or_else(|| {
+            let modified: DateTime<Utc> = fs::metadata(&session.log_file).ok()?.modified().ok()?.into();
+            Estimate::new(session.timestamp, modified, EstimateSource::FileTimes)
+        })
+}
+
+fn script_markers(raw: &str) -> Option<Estimate> {
+    let time = |prefix: &str| {
+        raw.lines()
+            .find_map(|line| line.trim().strip_prefix(prefix))
+            .and_then(|rest| parse_script_time(rest.split(" [").next().unwrap_or(rest).trim()))
+    };
+    Estimate::new(time("Script started on ")?, time("Script done on ")?, EstimateSource::ScriptMarkers)
+}
+
+/// util-linux writes `2025-01-10 10:00:00+00:00`; older versions omit the offset, meaning local time.
+fn parse_script_time(text: &str) -> Option<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%:z") {
+        return Some(time.with_timezone(&Utc));
+    }
+    let naive = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S").ok()?;
+    Local.from_local_datetime(&naive).earliest().map(|time| time.with_timezone(&Utc))
+}
+
+fn span(content: &str, source: EstimateSource) -> Option<Estimate> {
+    static TIMESTAMP: OnceLock<Regex> = OnceLock::new();
+    let timestamp = TIMESTAMP.get_or_init(|| {
+        Regex::new(r"\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:\d{2})").unwrap()
+    });
+    let times: Vec<DateTime<Utc>> = timestamp
+        .find_iter(content)
+        .filter_map(|found| DateTime::parse_from_rfc3339(found.as_str()).ok())
+        .map(|time| time.with_timezone(&Utc))
+        .collect();
+    Estimate::new(*times.iter().min()?, *times.iter().max()?, source)
+}
+
+/// Estimates for every session missing a duration, oldest first; sessions with nothing to go on are left out.
+pub fn estimate_all(analyzer: &SessionAnalyzer) -> Vec<(String, Estimate)> {
+    let mut sessions: Vec<&SessionMetadata> =
+        analyzer.metadata().sessions.values().filter(|session| missing_duration(session)).collect();
+    sessions.sort_by_key(|session| session.timestamp);
+
+    sessions
+        .into_iter()
+        .filter_map(|session| {
+            let raw = analyzer.read_raw_session_log(session).unwrap_or_default();
+            estimate(session, &raw).map(|estimate| (session.id.clone(), estimate))
+        })
+        .collect()
+}
+
+/// Store the estimates as the sessions' durations and end times, flagged as estimated.
+pub fn apply(logger: &mut ClaudeLogger, estimates: &[(String, Estimate)]) -> Result<usize> {
+    let mut applied = 0;
+    for (session_id, estimate) in estimates {
+        if let Some(session) = logger.metadata_mut().get_session_mut(session_id)
+            && session.duration_seconds.is_none()
+        {
+            session.set_duration(estimate.duration());
+            session.end_time.get_or_insert(estimate.end);
+            session.duration_estimated = true;
+            applied += 1;
+        }
+    }
+    if applied > 0 {
+        logger.save_metadata()?;
+    }
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::{generate, sessions};
+    use std::path::Path;
+
+    #[test]
+    fn estimates_from_the_best_available_source() {
+        let options = sessions(1, 3, "api");
+        let mut session = generate(&options, Path::new("/nonexistent"), Utc::now()).remove(0).session;
+        session.duration_seconds = None;
+        assert!(missing_duration(&session));
+
+        let capture = "Script started on 2025-01-10 10:00:00+00:00 [COMMAND=\"claude\"]\nHuman: hi\n\
+                       Script done on 2025-01-10 10:42:30+00:00 [COMMAND_EXIT_CODE=\"0\"]\n";
+        let estimate = estimate(&session, capture).unwrap();
+        assert_eq!(estimate.source, EstimateSource::ScriptMarkers);
+        assert_eq!(estimate.duration(), Duration::seconds(42 * 60 + 30));
+
+        let jsonl = "{\"timestamp\":\"2025-01-10T10:00:00Z\"}\n{\"timestamp\":\"2025-01-10T10:05:00.5Z\"}\n\
+                     {\"timestamp\":\"2025-01-10T10:20:00+01:00\"}\n";
+        let estimate = super::estimate(&session, jsonl).unwrap();
+        assert_eq!(estimate.source, EstimateSource::ContentTimestamps);
+        // 10:20+01:00 is the earliest of the three
+        assert_eq!(estimate.duration(), Duration::minutes(45) + Duration::milliseconds(500));
+
+        // Dates a year apart are not one session, and the missing log file offers nothing
+        assert_eq!(super::estimate(&session, "released 2023-01-01T00:00:00Z, then 2024-01-01T00:00:00Z"), None);
+
+        session.tmux_pane = Some("%1".to_string());
+        session.end_time = None;
+        assert!(!missing_duration(&session));
+    }
+}
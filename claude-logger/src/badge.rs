@@ -0,0 +1,176 @@
+use crate::analyzer::SessionAnalyzer;
+use crate::session::SessionMetadata;
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+
+/// What a badge shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum BadgeMetric {
+    /// Overall quality score, averaged over a project's sessions
+    #[default]
+    Quality,
+    /// Number of logged sessions
+    Sessions,
+}
+
+/// A shields.io-style badge: a grey label on the left, a coloured value on the right.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Badge {
+    pub label: String,
+    pub value: String,
+    /// Hex colour of the value side
+    pub color: &'static str,
+}
+
+const SESSIONS_COLOR: &str = "#007ec6";
+const NO_DATA_COLOR: &str = "#9f9f9f";
+
+/// Colour for a 0-100 quality score, red through bright green.
+pub fn quality_color(score: f64) -> &'static str {
+    match score {
+        score if score >= 80.0 => "#4c1",
+        score if score >= 60.0 => "#97ca00",
+        score if score >= 40.0 => "#dfb317",
+        score if score >= 20.0 => "#fe7d37",
+        _ => "#e05d44",
+    }
+}
+
+/// A badge for one session. A session counts as one session, so `Sessions` is only useful for
+/// projects.
+pub fn for_session(analyzer: &SessionAnalyzer, session_id: &str, metric: BadgeMetric, label: Option<&str>) -> Result<Badge> {
+    let session = analyzer
+        .metadata()
+        .get_session(session_id)
+        .with_context(|| format!("Session not found: {}", session_id))?;
+    match metric {
+        BadgeMetric::Quality => {
+            let score = quality(analyzer, session)?;
+            Ok(Badge::quality(label.unwrap_or("AI pairing quality"), Some(score)))
+        }
+        BadgeMetric::Sessions => Ok(Badge::sessions(label.unwrap_or("AI pairing sessions"), 1)),
+    }
+}
+
+/// A badge for every session in `project`; sessions whose log can't be read are left out of the average.
+pub fn for_project(analyzer: &SessionAnalyzer, project: &str, metric: BadgeMetric, label: Option<&str>) -> Result<Badge> {
+    let sessions: Vec<&SessionMetadata> =
+        analyzer.metadata().sessions.values().filter(|session| session.project == project).collect();
+    if sessions.is_empty() {
+        anyhow::bail!("No sessions found for project: {}", project);
+    }
+
+    match metric {
+        BadgeMetric::Quality => {
+            let scores: Vec<f64> = sessions
+                .iter()
+                .filter_map(|session| match quality(analyzer, session) {
+                    Ok(score) => Some(score),
+                    Err(e) => {
+                        eprintln!("Warning: Leaving {} out of the badge: {:#}", session.id, e);
+                        None
+                    }
+                })
+                .collect();
+            let average = (!scores.is_empty()).then(|| scores.iter().sum::<f64>() / scores.len() as f64);
+            Ok(Badge::quality(label.unwrap_or("AI pairing quality"), average))
+        }
+        BadgeMetric::Sessions => Ok(Badge::sessions(label.unwrap_or("AI pairing sessions"), sessions.len())),
+    }
+}
+
+/// The stored score, or one computed from the log for sessions analysed before scores were kept.
+fn quality(analyzer: &SessionAnalyzer, session: &SessionMetadata) -> Result<f64> {
+    match &session.analysis {
+        Some(snapshot) => Ok(snapshot.quality.overall_score),
+        None => {
+            let content = analyzer.read_session_log(session)?;
+            Ok(SessionAnalyzer::snapshot(session, &content).quality.overall_score)
+        }
+    }
+}
+
+impl Badge {
+    pub fn quality(label: &str, score: Option<f64>) -> Self {
+        match score {
+            Some(score) => Self { label: label.to_string(), value: format!("{:.0}/100", score), color: quality_color(score) },
+            None => Self { label: label.to_string(), value: "no data".to_string(), color: NO_DATA_COLOR },
+        }
+    }
+
+    pub fn sessions(label: &str, count: usize) -> Self {
+        Self { label: label.to_string(), value: count.to_string(), color: SESSIONS_COLOR }
+    }
+
+    /// The badge as a standalone SVG in shields.io's flat style.
+    pub fn to_svg(&self) -> String {
+        let (label_width, value_width) = (text_width(&self.label) + 10, text_width(&self.value) + 10);
+        let width = label_width + value_width;
+        let (label, value) = (escape(&self.label), escape(&self.value));
+        // Text is drawn at 10x scale, as shields.io does, for crisper kerning
+        let label_x = label_width * 5;
+        let value_x = (label_width + value_width / 2) * 10;
+        format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {value}">
+  <title>{label}: {value}</title>
+  <linearGradient id="s" x2="0" y2="100%">
+    <stop offset="0" stop-color="#bbb" stop-opacity=".1"/>
+    <stop offset="1" stop-opacity=".1"/>
+  </linearGradient>
+  <clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath>
+  <g clip-path="url(#r)">
+    <rect width="{label_width}" height="20" fill="#555"/>
+    <rect x="{label_width}" width="{value_width}" height="20" fill="{color}"/>
+    <rect width="{width}" height="20" fill="url(#s)"/>
+  </g>
+  <g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="110">
+    <text x="{label_x}" y="150" fill="#010101" fill-opacity=".3" transform="scale(.1)">{label}</text>
+    <text x="{label_x}" y="140" transform="scale(.1)">{label}</text>
+    <text x="{value_x}" y="150" fill="#010101" fill-opacity=".3" transform="scale(.1)">{value}</text>
+    <text x="{value_x}" y="140" transform="scale(.1)">{value}</text>
+  </g>
+</svg>
+"##,
+            color = self.color,
+        )
+    }
+}
+
+/// Approximate width in pixels of `text` in 11px Verdana.
+fn text_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c {
+            'i' | 'l' | 'j' | '.' | ',' | ':' | ';' | '|' | '!' | '\'' => 3,
+            'f' | 't' | 'r' | 'I' | ' ' | '(' | ')' | '[' | ']' | '/' | '-' => 5,
+            'm' | 'w' | 'M' | 'W' | '%' => 10,
+            c if c.is_ascii_uppercase() || c.is_ascii_digit() => 8,
+            _ => 7,
+        })
+        .sum()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_coloured_escaped_badges() {
+        let badge = Badge::quality("R&D <pairing>", Some(83.4));
+        assert_eq!((badge.value.as_str(), badge.color), ("83/100", "#4c1"));
+        let svg = badge.to_svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.contains(">R&amp;D &lt;pairing&gt;</text>"));
+        assert!(svg.contains("fill=\"#4c1\""));
+        let width = format!("width=\"{}\"", text_width("R&D <pairing>") + text_width("83/100") + 20);
+        assert!(svg.contains(&width));
+
+        assert_eq!(quality_color(45.0), "#dfb317");
+        assert_eq!(quality_color(5.0), "#e05d44");
+        assert_eq!(Badge::quality("q", None).value, "no data");
+        assert_eq!(Badge::sessions("s", 12).to_svg().matches(">12</text>").count(), 2);
+    }
+}
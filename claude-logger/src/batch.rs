@@ -0,0 +1,245 @@
+use crate::config::FieldSpec;
+use crate::fields;
+use crate::filter::Query;
+use crate::logger::ClaudeLogger;
+use crate::session::{parse_extra_field, ReviewState, SessionMetadata};
+use crate::sync;
+use anyhow::Result;
+use clap::ValueEnum;
+use std::str::FromStr;
+
+/// A change `batch --apply` makes to each selected session.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    /// `tag=NAME`
+    Tag(String),
+    /// `untag=NAME`
+    Untag(String),
+    /// `retag=OLD:NEW`, renaming a tag where present
+    Retag(String, String),
+    /// `review=STATE`
+    Review(ReviewState),
+    /// `meta=KEY=VALUE`
+    Meta(String, serde_json::Value),
+    /// `unset=KEY`
+    Unset(String),
+    /// `archive`: upload the log to the configured remote storage, as `sync` does
+    Archive,
+    /// `reanalyze`: rebuild the cleaned log and re-derive usage and the transcript link
+    Reanalyze,
+}
+
+impl FromStr for Operation {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (name, value) = text.split_once('=').unwrap_or((text, ""));
+        let needs_value = |what: &str| {
+            if value.is_empty() { Err(format!("'{}' needs a value: {}={}", name, name, what)) } else { Ok(value.to_string()) }
+        };
+        match name {
+            "tag" => Ok(Operation::Tag(needs_value("TAG")?)),
+            "untag" => Ok(Operation::Untag(needs_value("TAG")?)),
+            "retag" => {
+                let value = needs_value("OLD:NEW")?;
+                match value.split_once(':') {
+                    Some((old, new)) if !old.is_empty() && !new.is_empty() => {
+                        Ok(Operation::Retag(old.to_string(), new.to_string()))
+                    }
+                    _ => Err(format!("expected retag=OLD:NEW, got retag={}", value)),
+                }
+            }
+            "review" => ReviewState::from_str(&needs_value("STATE")?, true)
+                .map(Operation::Review)
+                .map_err(|_| format!("unknown review state '{}'", value)),
+            "meta" => parse_extra_field(&needs_value("KEY=VALUE")?).map(|(key, value)| Operation::Meta(key, value)),
+            "unset" => Ok(Operation::Unset(needs_value("KEY")?)),
+            "archive" if value.is_empty() => Ok(Operation::Archive),
+            "reanalyze" if value.is_empty() => Ok(Operation::Reanalyze),
+            _ => Err(format!(
+                "unknown operation '{}': use tag=, untag=, retag=, review=, meta=, unset=, archive or reanalyze",
+                text
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operation::Tag(tag) => write!(f, "tag {}", tag),
+            Operation::Untag(tag) => write!(f, "untag {}", tag),
+            Operation::Retag(old, new) => write!(f, "retag {} as {}", old, new),
+            Operation::Review(state) => write!(f, "mark {}", state),
+            Operation::Meta(key, value) => write!(f, "set {} = {}", key, value),
+            Operation::Unset(key) => write!(f, "unset {}", key),
+            Operation::Archive => write!(f, "archive"),
+            Operation::Reanalyze => write!(f, "reanalyze"),
+        }
+    }
+}
+
+impl Operation {
+    /// Apply a metadata-only operation, returning whether the session changed.
+    ///
+    /// `Archive` and `Reanalyze` work on the logs and are carried out by `run`.
+    fn apply(&self, session: &mut SessionMetadata) -> bool {
+        match self {
+            Operation::Tag(tag) => {
+                let missing = !session.tags.contains(tag);
+                if missing {
+                    session.tags.push(tag.clone());
+                }
+                missing
+            }
+            Operation::Untag(tag) => {
+                let before = session.tags.len();
+                session.tags.retain(|existing| existing != tag);
+                session.tags.len() != before
+            }
+            Operation::Retag(old, new) => {
+                let Some(position) = session.tags.iter().position(|tag| tag == old) else {
+                    return false;
+                };
+                if session.tags.contains(new) {
+                    session.tags.remove(position);
+                } else {
+                    session.tags[position] = new.clone();
+                }
+                true
+            }
+            Operation::Review(state) => std::mem::replace(&mut session.review_state, *state) != *state,
+            Operation::Meta(key, value) => session.extra.insert(key.clone(), value.clone()).as_ref() != Some(value),
+            Operation::Unset(key) => session.extra.remove(key).is_some(),
+            Operation::Archive => sync::needs_archive(session),
+            Operation::Reanalyze => session.log_file.exists(),
+        }
+    }
+}
+
+/// What a batch run changed, or would change on a dry run.
+#[derive(Debug, Default)]
+pub struct BatchOutcome {
+    pub matched: usize,
+    /// Session id and the operations that changed it
+    pub changed: Vec<(String, Vec<String>)>,
+    pub failed: Vec<(String, String)>,
+}
+
+/// Apply `operations` in order to every session matching `query`.
+///
+/// Metadata changes are saved in one pass at the end; with `dry_run` nothing is written.
+pub fn run(logger: &mut ClaudeLogger, query: &Query, operations: &[Operation], dry_run: bool) -> Result<BatchOutcome> {
+    let operations = check_fields(&logger.config().fields, operations)?;
+    if operations.contains(&Operation::Archive) && logger.log_store().is_none() {
+        anyhow::bail!("archive requires a remote storage backend; set [storage] in the config file");
+    }
+    let mut sessions: Vec<SessionMetadata> =
+        logger.metadata().sessions.values().filter(|session| query.matches(session)).cloned().collect();
+    sessions.sort_by_key(|session| session.timestamp);
+
+    let mut outcome = BatchOutcome { matched: sessions.len(), ..BatchOutcome::default() };
+    let mut to_archive = Vec::new();
+    for mut session in sessions {
+        let mut applied = Vec::new();
+        for operation in &operations {
+            if !operation.apply(&mut session) {
+                continue;
+            }
+            applied.push(operation.to_string());
+            match operation {
+                Operation::Archive => to_archive.push(session.id.clone()),
+                Operation::Reanalyze if !dry_run => logger.derive_from_log(&mut session, true),
+                _ => {}
+            }
+        }
+        if applied.is_empty() {
+            continue;
+        }
+        if !dry_run {
+            logger.add_session(session.clone());
+        }
+        outcome.changed.push((session.id, applied));
+    }
+
+    if dry_run || outcome.changed.is_empty() {
+        return Ok(outcome);
+    }
+    logger.save_metadata()?;
+
+    if !to_archive.is_empty() {
+        let archived = sync::archive_sessions(logger, &to_archive, false)?;
+        outcome.failed.extend(archived.failed);
+    }
+    Ok(outcome)
+}
+
+/// Hold `meta=` and `unset=` to the declared `[[fields]]` rules, as `edit` does, normalizing values.
+fn check_fields(specs: &[FieldSpec], operations: &[Operation]) -> Result<Vec<Operation>> {
+    operations
+        .iter()
+        .map(|operation| match operation {
+            Operation::Meta(key, value) => match specs.iter().find(|spec| spec.name == *key) {
+                Some(spec) => fields::check_value(spec, value)
+                    .map(|value| Operation::Meta(key.clone(), value))
+                    .map_err(|e| anyhow::anyhow!("Invalid custom field {}: {}, got {}", key, e, value)),
+                None => Ok(operation.clone()),
+            },
+            Operation::Unset(key) if specs.iter().any(|spec| spec.required && spec.name == *key) => {
+                Err(anyhow::anyhow!("Required fields cannot be removed: {}", key))
+            }
+            _ => Ok(operation.clone()),
+        })
+        .collect()
+}
+
+pub fn print_outcome(outcome: &BatchOutcome, dry_run: bool) {
+    let verb = if dry_run { "Would change" } else { "Changed" };
+    for (session_id, applied) in &outcome.changed {
+        println!("{} {}: {}", verb, session_id, applied.join(", "));
+    }
+    for (session_id, error) in &outcome.failed {
+        eprintln!("Warning: Failed to archive {}: {}", session_id, error);
+    }
+    println!(
+        "{} matching sessions, {} {}",
+        outcome.matched,
+        outcome.changed.len(),
+        if dry_run { "would change (dry run, nothing saved)" } else { "changed" }
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::{generate, sessions, SynthOptions};
+    use crate::timezone::DisplayZone;
+    use chrono::Utc;
+    use std::path::Path;
+
+    #[test]
+    fn filters_sessions_and_applies_operations() {
+        let options = SynthOptions { projects: vec!["api".to_string(), "web".to_string()], days: 10, ..sessions(6, 7, "api") };
+        let mut sessions: Vec<SessionMetadata> =
+            generate(&options, Path::new("/logs"), Utc::now()).into_iter().map(|generated| generated.session).collect();
+        sessions[0].tags.push("experiment".to_string());
+
+        let query = Query::parse("project:api -tag:experiment", DisplayZone::Utc).unwrap();
+        let selected: Vec<&SessionMetadata> = sessions.iter().filter(|session| query.matches(session)).collect();
+        assert!(!selected.is_empty());
+        assert!(selected.iter().all(|session| session.project == "api" && !session.tags.contains(&"experiment".to_string())));
+        assert!(Query::parse("colour:blue", DisplayZone::Utc).is_err());
+        assert!(Query::parse("", DisplayZone::Utc).unwrap().matches(&sessions[0]));
+
+        let session = &mut sessions[0];
+        assert!("retag=experiment:pilot".parse::<Operation>().unwrap().apply(session));
+        assert!(session.tags.contains(&"pilot".to_string()) && !session.tags.contains(&"experiment".to_string()));
+        assert!(!"tag=pilot".parse::<Operation>().unwrap().apply(session));
+        assert!("review=flagged".parse::<Operation>().unwrap().apply(session));
+        assert_eq!(session.review_state, ReviewState::Flagged);
+        assert!("meta=ticket=ABC-1".parse::<Operation>().unwrap().apply(session));
+        assert!(Query::parse("field:ticket=ABC-1 review:flagged", DisplayZone::Utc).unwrap().matches(session));
+        assert!("retag=pilot".parse::<Operation>().is_err());
+        assert!("archive=now".parse::<Operation>().is_err());
+    }
+}
@@ -0,0 +1,211 @@
+use crate::config::BillingConfig;
+use crate::export::csv_field;
+use crate::locale;
+use crate::session::SessionMetadata;
+use chrono::{Datelike, Months, NaiveDate};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Logged hours and AI spend for one project over the billing period.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProjectBill {
+    pub project: String,
+    pub sessions: usize,
+    /// Sessions without an end time, which contribute no hours
+    pub unfinished_sessions: usize,
+    /// Sessions without recorded usage, which contribute no cost
+    pub unpriced_sessions: usize,
+    /// Logged time, summed unrounded so short sessions don't each lose their last minute
+    pub seconds: i64,
+    pub hourly_rate: Option<f64>,
+    pub ai_cost: f64,
+}
+
+impl ProjectBill {
+    pub fn hours(&self) -> f64 {
+        self.seconds as f64 / 3600.0
+    }
+
+    /// Logged hours at the project's rate; zero when no rate is configured.
+    pub fn labor(&self) -> f64 {
+        self.hours() * self.hourly_rate.unwrap_or(0.0)
+    }
+
+    pub fn total(&self, config: &BillingConfig) -> f64 {
+        self.labor() + if config.pass_through_ai_cost { self.ai_cost } else { 0.0 }
+    }
+}
+
+/// A month's bills with derived amounts, as handed to billing templates.
+#[derive(Debug, Clone, Serialize)]
+pub struct BillingSummary {
+    /// `YYYY-MM`
+    pub month: String,
+    pub currency: String,
+    pub projects: Vec<BillingLine>,
+    pub total: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BillingLine {
+    #[serde(flatten)]
+    pub bill: ProjectBill,
+    pub hours: f64,
+    pub labor: f64,
+    pub total: f64,
+}
+
+impl BillingSummary {
+    pub fn new(bills: &[ProjectBill], month: NaiveDate, config: &BillingConfig) -> Self {
+        let projects: Vec<BillingLine> = bills
+            .iter()
+            .map(|bill| BillingLine {
+                bill: bill.clone(),
+                hours: bill.hours(),
+                labor: bill.labor(),
+                total: bill.total(config),
+            })
+            .collect();
+        Self {
+            month: month.format("%Y-%m").to_string(),
+            currency: config.currency.clone(),
+            total: projects.iter().map(|line| line.total).sum(),
+            projects,
+        }
+    }
+}
+
+/// Parse a `YYYY-MM` month into its first day.
+pub fn parse_month(month: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")
+        .map_err(|_| format!("expected a month as YYYY-MM, got '{}'", month))
+}
+
+/// The first day of the month after `month`.
+pub fn next_month(month: NaiveDate) -> NaiveDate {
+    month.with_day(1).unwrap_or(month) + Months::new(1)
+}
+
+/// One bill per project, by project name.
+pub fn bills(sessions: &[&SessionMetadata], config: &BillingConfig) -> Vec<ProjectBill> {
+    let mut by_project: BTreeMap<&str, ProjectBill> = BTreeMap::new();
+    for session in sessions {
+        let bill = by_project.entry(&session.project).or_insert_with(|| ProjectBill {
+            project: session.project.clone(),
+            hourly_rate: config.rates.get(&session.project).copied().or(config.hourly_rate),
+            ..ProjectBill::default()
+        });
+
+        bill.sessions += 1;
+        match session.duration() {
+            Some(duration) => bill.seconds += duration.num_seconds(),
+            None => bill.unfinished_sessions += 1,
+        }
+        match &session.usage {
+            Some(usage) => bill.ai_cost += usage.cost,
+            None => bill.unpriced_sessions += 1,
+        }
+    }
+    by_project.into_values().collect()
+}
+
+pub fn to_csv(bills: &[ProjectBill], config: &BillingConfig) -> String {
+    let mut csv = String::from("project,sessions,hours,hourly_rate,labor,ai_cost,total,currency\n");
+    for bill in bills {
+        let fields = [
+            csv_field(&bill.project),
+            bill.sessions.to_string(),
+            format!("{:.2}", bill.hours()),
+            bill.hourly_rate.map(|rate| format!("{:.2}", rate)).unwrap_or_default(),
+            format!("{:.2}", bill.labor()),
+            format!("{:.2}", bill.ai_cost),
+            format!("{:.2}", bill.total(config)),
+            csv_field(&config.currency),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+pub fn print(bills: &[ProjectBill], month: NaiveDate, config: &BillingConfig) {
+    let locale = locale::current();
+    println!("=== {} ===", locale.text_with("heading-billing", &[("month", locale.month(month).into())]));
+    if bills.is_empty() {
+        println!("{}", locale.text("billing-no-sessions"));
+        return;
+    }
+
+    let currency = &config.currency;
+    let amount = |value: f64| format!("{} {}", locale.number(value, 2), currency);
+    for bill in bills {
+        let rate = bill.hourly_rate.map(|rate| format!("{}/h", amount(rate))).unwrap_or_else(|| "no rate".to_string());
+        println!("\n{}", bill.project);
+        println!("  Sessions: {}", bill.sessions);
+        println!("  Hours: {} at {}", locale.number(bill.hours(), 2), rate);
+        println!("  Labor: {}", amount(bill.labor()));
+        println!("  AI cost: {}", amount(bill.ai_cost));
+        println!("  Total: {}", amount(bill.total(config)));
+        if bill.unfinished_sessions > 0 {
+            println!("  Note: {} session(s) have no end time and add no hours", bill.unfinished_sessions);
+        }
+        if bill.unpriced_sessions > 0 {
+            println!("  Note: {} session(s) have no recorded usage; try: claude-logger cost --refresh", bill.unpriced_sessions);
+        }
+    }
+
+    let total: f64 = bills.iter().map(|bill| bill.total(config)).sum();
+    println!("\n{}", locale.text_with("billing-total", &[("amount", amount(total).into())]));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::TokenUsage;
+    use crate::synth::{generate, sessions};
+    use chrono::Utc;
+    use std::path::Path;
+
+    #[test]
+    fn bills_per_project_rates_and_escapes_the_csv() {
+        let template = generate(&sessions(1, 2, "api"), Path::new("/logs"), Utc::now()).remove(0).session;
+        let session = |project: &str, seconds: Option<i64>, cost: Option<f64>| SessionMetadata {
+            project: project.to_string(),
+            duration_seconds: seconds,
+            usage: cost.map(|cost| TokenUsage { cost, ..TokenUsage::default() }),
+            ..template.clone()
+        };
+        let mut logged = vec![session("Acme, \"Inc\"", Some(179), Some(0.5)); 20];
+        logged.push(session("api", Some(3600), Some(1.0)));
+        logged.push(session("api", None, None));
+        let logged: Vec<&SessionMetadata> = logged.iter().collect();
+
+        let mut config = BillingConfig { hourly_rate: Some(50.0), currency: "EUR".to_string(), ..BillingConfig::default() };
+        config.rates.insert("Acme, \"Inc\"".to_string(), 100.0);
+        let bills = bills(&logged, &config);
+        let summary: Vec<(&str, usize, i64, Option<f64>)> =
+            bills.iter().map(|bill| (bill.project.as_str(), bill.sessions, bill.seconds, bill.hourly_rate)).collect();
+        assert_eq!(summary, [("Acme, \"Inc\"", 20, 3580, Some(100.0)), ("api", 2, 3600, Some(50.0))]);
+        assert_eq!((bills[1].unfinished_sessions, bills[1].unpriced_sessions), (1, 1));
+        assert!((bills[0].total(&config) - (3580.0 / 36.0 + 10.0)).abs() < 1e-9);
+
+        assert_eq!(
+            to_csv(&bills, &config),
+            "project,sessions,hours,hourly_rate,labor,ai_cost,total,currency\n\
+             \"Acme, \"\"Inc\"\"\",20,0.99,100.00,99.44,10.00,109.44,EUR\n\
+             api,2,1.00,50.00,50.00,1.00,51.00,EUR\n"
+        );
+
+        let labor_only = BillingConfig { pass_through_ai_cost: false, ..config };
+        assert_eq!(bills[1].total(&labor_only), 50.0);
+        let unrated = BillingConfig::default();
+        assert_eq!(super::bills(&logged[20..], &unrated)[0].total(&unrated), 1.0);
+    }
+
+    #[test]
+    fn parses_billing_months() {
+        assert_eq!(parse_month("2025-06"), Ok(NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()));
+        assert!(parse_month("2025-13").is_err() && parse_month("June").is_err());
+        assert_eq!(next_month(NaiveDate::from_ymd_opt(2025, 12, 15).unwrap()), NaiveDate::from_ymd_opt(2026, 1, 1).unwrap());
+    }
+}
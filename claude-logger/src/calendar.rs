@@ -0,0 +1,121 @@
+use crate::config::{CalendarConfig, WeekStart};
+use crate::session::SessionMetadata;
+use crate::timezone::DisplayZone;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use clap::ValueEnum;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Period {
+    Week,
+    Month,
+}
+
+/// Date range `[start, end)` that a session falls into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Bucket {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl Bucket {
+    /// Human label, e.g. `2025-01-05 to 2025-01-11` (the last day is inclusive).
+    pub fn label(&self) -> String {
+        format!("{} to {}", self.start, self.end - Duration::days(1))
+    }
+}
+
+impl Period {
+    /// Bucket containing `date` under the configured week start and month convention.
+    pub fn bucket(self, date: NaiveDate, calendar: &CalendarConfig) -> Bucket {
+        match self {
+            Period::Week => {
+                let first = match calendar.week_start {
+                    WeekStart::Monday => Weekday::Mon,
+                    WeekStart::Sunday => Weekday::Sun,
+                };
+                let start = date - Duration::days(date.weekday().days_since(first) as i64);
+                Bucket {
+                    start,
+                    end: start + Duration::days(7),
+                }
+            }
+            Period::Month => {
+                let day = calendar.month_start_day.clamp(1, 28);
+                let (year, month) = if date.day() >= day {
+                    (date.year(), date.month())
+                } else {
+                    previous_month(date.year(), date.month())
+                };
+                let (next_year, next_month) = next_month(year, month);
+                Bucket {
+                    start: ymd(year, month, day),
+                    end: ymd(next_year, next_month, day),
+                }
+            }
+        }
+    }
+}
+
+/// Group sessions by period bucket, using each session's date in `zone`.
+pub fn bucket_sessions<'a>(
+    sessions: impl IntoIterator<Item = &'a SessionMetadata>,
+    period: Period,
+    calendar: &CalendarConfig,
+    zone: DisplayZone,
+) -> BTreeMap<Bucket, Vec<&'a SessionMetadata>> {
+    let mut buckets: BTreeMap<Bucket, Vec<&SessionMetadata>> = BTreeMap::new();
+    for session in sessions {
+        let bucket = period.bucket(zone.date(session.timestamp), calendar);
+        buckets.entry(bucket).or_default().push(session);
+    }
+    buckets
+}
+
+fn previous_month(year: i32, month: u32) -> (i32, u32) {
+    if month == 1 { (year - 1, 12) } else { (year, month - 1) }
+}
+
+fn next_month(year: i32, month: u32) -> (i32, u32) {
+    if month == 12 { (year + 1, 1) } else { (year, month + 1) }
+}
+
+fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+    // Days are clamped to 28, which exists in every month
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid calendar date")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn weeks_follow_configured_start_day() {
+        let mut calendar = CalendarConfig::default();
+        // 2025-01-08 is a Wednesday
+        assert_eq!(Period::Week.bucket(date("2025-01-08"), &calendar).start, date("2025-01-06"));
+
+        calendar.week_start = WeekStart::Sunday;
+        let bucket = Period::Week.bucket(date("2025-01-08"), &calendar);
+        assert_eq!(bucket.start, date("2025-01-05"));
+        assert_eq!(bucket.label(), "2025-01-05 to 2025-01-11");
+        assert_eq!(Period::Week.bucket(date("2025-01-05"), &calendar).start, date("2025-01-05"));
+    }
+
+    #[test]
+    fn fiscal_months_wrap_across_years() {
+        let calendar = CalendarConfig {
+            month_start_day: 26,
+            ..CalendarConfig::default()
+        };
+
+        let bucket = Period::Month.bucket(date("2025-01-10"), &calendar);
+        assert_eq!(bucket.start, date("2024-12-26"));
+        assert_eq!(bucket.end, date("2025-01-26"));
+        assert_eq!(Period::Month.bucket(date("2025-01-26"), &calendar).start, date("2025-01-26"));
+    }
+}
@@ -0,0 +1,121 @@
+use crate::session::ProjectChanges;
+use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A project's working tree at one moment, untracked files included, written to git as a tree.
+/// Ignored files are left out, and the project's own index is left alone.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    repo: PathBuf,
+    tree: String,
+}
+
+/// Snapshot the git repository `project_dir` is in; `None` when it isn't in one.
+pub fn snapshot(project_dir: &Path) -> Result<Option<Snapshot>> {
+    let Ok(toplevel) = git(project_dir, &["rev-parse", "--show-toplevel"], None) else {
+        return Ok(None);
+    };
+    let repo = PathBuf::from(toplevel.trim());
+    let tree = write_tree(&repo)?;
+    Ok(Some(Snapshot { repo, tree }))
+}
+
+/// What changed in the working tree since `before` was taken.
+pub fn since(before: &Snapshot) -> Result<ProjectChanges> {
+    let after = write_tree(&before.repo)?;
+    let numstat = git(&before.repo, &["diff", "--numstat", "-z", "--no-renames", &before.tree, &after], None)?;
+    Ok(parse_numstat(&numstat))
+}
+
+/// Stage the whole working tree into a scratch copy of the index and write it out as a tree.
+fn write_tree(repo: &Path) -> Result<String> {
+    let index = repo.join(git(repo, &["rev-parse", "--git-path", "index"], None)?.trim());
+    let scratch = index.with_file_name(format!("claude-logger-index-{}", std::process::id()));
+    if index.exists() {
+        // Starting from the real index saves hashing files that haven't changed
+        fs::copy(&index, &scratch).with_context(|| format!("Failed to copy {}", index.display()))?;
+    }
+    let tree = git(repo, &["add", "--all"], Some(&scratch)).and_then(|_| git(repo, &["write-tree"], Some(&scratch)));
+    let _ = fs::remove_file(&scratch);
+    Ok(tree?.trim().to_string())
+}
+
+/// Totals from `git diff --numstat -z --no-renames`; binary files count as touched with no lines.
+fn parse_numstat(numstat: &str) -> ProjectChanges {
+    let mut changes = ProjectChanges::default();
+    let mut files = BTreeSet::new();
+    for record in numstat.split('\0').filter(|record| !record.is_empty()) {
+        let mut fields = record.splitn(3, '\t');
+        let (Some(added), Some(removed), Some(path)) = (fields.next(), fields.next(), fields.next()) else {
+            continue;
+        };
+        changes.lines_added += added.parse::<u64>().unwrap_or(0);
+        changes.lines_removed += removed.parse::<u64>().unwrap_or(0);
+        files.insert(path.to_string());
+    }
+    changes.files_touched = files.len();
+    changes.files = files.into_iter().collect();
+    changes
+}
+
+fn git(dir: &Path, args: &[&str], index: Option<&Path>) -> Result<String> {
+    let mut command = Command::new("git");
+    command.args(args).current_dir(dir);
+    if let Some(index) = index {
+        command.env("GIT_INDEX_FILE", index);
+    }
+    let output = command.output().with_context(|| format!("Failed to run git {}", args.join(" ")))?;
+    if !output.status.success() {
+        anyhow::bail!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_changes_made_after_the_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let run = |args: &[&str]| {
+            let status = Command::new("git")
+                .args(args)
+                .current_dir(dir.path())
+                .env("GIT_AUTHOR_NAME", "T")
+                .env("GIT_AUTHOR_EMAIL", "t@example.com")
+                .env("GIT_COMMITTER_NAME", "T")
+                .env("GIT_COMMITTER_EMAIL", "t@example.com")
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        run(&["init", "-q"]);
+        fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        fs::write(dir.path().join("lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        run(&["add", "."]);
+        run(&["commit", "-q", "-m", "init"]);
+        // Uncommitted before the session, so not the session's work
+        fs::write(dir.path().join("draft.md"), "notes\n").unwrap();
+
+        assert!(snapshot(&dir.path().join("missing")).unwrap().is_none());
+        let before = snapshot(dir.path()).unwrap().unwrap();
+
+        fs::write(dir.path().join("lib.rs"), "fn a() {}\nfn c() {}\nfn d() {}\n").unwrap();
+        fs::write(dir.path().join("new file.rs"), "x\n").unwrap();
+        fs::create_dir(dir.path().join("target")).unwrap();
+        fs::write(dir.path().join("target/out"), "built\n").unwrap();
+        run(&["add", "lib.rs"]);
+        run(&["commit", "-q", "-m", "edit"]);
+
+        let changes = since(&before).unwrap();
+        assert_eq!(changes.files, ["lib.rs", "new file.rs"]);
+        assert_eq!((changes.files_touched, changes.lines_added, changes.lines_removed), (2, 3, 1));
+        // The project's own index is untouched
+        let staged = git(dir.path(), &["diff", "--cached", "--name-only"], None).unwrap();
+        assert!(staged.is_empty());
+    }
+}
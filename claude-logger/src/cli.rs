@@ -1,4 +1,12 @@
-use clap::{Parser, Subcommand};
+use crate::audit::Action;
+use crate::badge::BadgeMetric;
+use crate::calendar::Period;
+use crate::session::ReviewState;
+use crate::shell::Shell;
+use crate::synth::Preset;
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "claude-logger")]
@@ -15,20 +23,101 @@ pub struct Cli {
     /// Track creative energy after session
     #[arg(short = 'e', long)]
     pub track_energy: bool,
+
+    /// Show timestamps in UTC instead of local time
+    #[arg(long, global = true, conflicts_with = "local")]
+    pub utc: bool,
+
+    /// Show timestamps in local time (the default)
+    #[arg(long, global = true)]
+    pub local: bool,
+
+    /// Keep logs and metadata here instead of logs.directory from the config or ~/.claude-logs
+    /// [env: CLAUDE_LOGGER_DIR]
+    #[arg(long, global = true, value_name = "DIR")]
+    pub logs_dir: Option<PathBuf>,
+
+    /// Count sessions matched by analysis.exclude in the config
+    #[arg(long, global = true)]
+    pub include_excluded: bool,
+
+    /// Print the results of list, show, analyze and git-log as JSON
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Attach a custom field to the session (key=value; JSON values keep their type)
+    #[arg(long = "meta", value_name = "KEY=VALUE", value_parser = crate::session::parse_extra_field)]
+    pub meta: Vec<(String, serde_json::Value)>,
+
+    /// Record the session under this methodology instead of detecting it
+    #[arg(long, value_name = "NAME", value_parser = parse_methodology)]
+    pub methodology: Option<crate::session::Methodology>,
+}
+
+fn parse_methodology(name: &str) -> Result<crate::session::Methodology, String> {
+    crate::session::Methodology::from_name(name).ok_or_else(|| "methodology name is empty".to_string())
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Analyze logged sessions
-    #[command(name = "analyze")]
+    #[command(name = "analyze", visible_alias = "report")]
     Analyze {
         /// Analyze sessions using specific methodology
         #[arg(long)]
         methodology: Option<String>,
+
+        /// Only sessions in this project
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Only sessions recorded by this author
+        #[arg(long)]
+        author: Option<String>,
+
+        /// Only sessions matching this query (same terms as `batch --filter`)
+        #[arg(long, default_value = "", allow_hyphen_values = true)]
+        filter: String,
         
         /// Generate comparative analysis between methodologies
         #[arg(long)]
         comparative: bool,
+
+        /// Relate exchange count and duration to session quality
+        #[arg(long)]
+        length: bool,
+
+        /// Compare methodologies within debugging, feature and refactoring sessions
+        #[arg(long)]
+        task_types: bool,
+
+        /// Compare sessions before and after a methodology switch
+        #[arg(long)]
+        transition: bool,
+
+        /// Switch date (YYYY-MM-DD); defaults to analysis.transition_date in the config
+        #[arg(long, requires = "transition")]
+        switch_date: Option<NaiveDate>,
+
+        /// Sessions to compare on each side; defaults to analysis.transition_window
+        #[arg(long, requires = "transition")]
+        window: Option<usize>,
+
+        /// Logged hours and AI cost per project, priced at the configured hourly rates
+        #[arg(long)]
+        billing: bool,
+
+        /// Billing month (YYYY-MM); defaults to the current month
+        #[arg(long, requires = "billing", value_parser = crate::billing::parse_month)]
+        month: Option<NaiveDate>,
+
+        /// Also write the billing summary as CSV to this file
+        #[arg(short, long, requires = "billing", conflicts_with = "template")]
+        output: Option<PathBuf>,
+
+        /// Render the report (or, with --billing, the billing summary) through this Handlebars template
+        #[arg(long, conflicts_with_all = ["comparative", "length", "transition", "methodology"])]
+        template: Option<PathBuf>,
     },
     
     /// List all logged sessions
@@ -37,12 +126,48 @@ pub enum Commands {
         /// Filter by methodology
         #[arg(short, long)]
         methodology: Option<String>,
-        
+
+        /// Only sessions in this project
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Only sessions recorded by this author
+        #[arg(long)]
+        author: Option<String>,
+
+        /// Only sessions matching this query (same terms as `batch --filter`)
+        #[arg(long, default_value = "", allow_hyphen_values = true)]
+        filter: String,
+
+        /// Only sessions on or after this day (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<NaiveDate>,
+
+        /// Only sessions on or before this day (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<NaiveDate>,
+
         /// Limit number of sessions shown
         #[arg(short, long, default_value = "10")]
         limit: usize,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: ListFormat,
     },
     
+    /// Session counts and logged hours per project
+    #[command(name = "projects")]
+    Projects {
+        /// Only sessions on or after this day (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<NaiveDate>,
+
+        /// Only sessions on or before this day (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<NaiveDate>,
+    },
+
     /// Show git log of sessions
     #[command(name = "git-log")]
     GitLog {
@@ -57,12 +182,843 @@ pub enum Commands {
         /// Session ID to display
         session_id: String,
         
-        /// Show full log content
-        #[arg(short, long)]
+        /// Print the conversation itself instead of the summary
+        #[arg(long, value_enum)]
+        format: Option<ShowFormat>,
+
+        /// Same as --format raw
+        #[arg(short, long, hide = true, conflicts_with = "format")]
         full: bool,
+
+        /// Print code blocks without syntax highlighting
+        #[arg(long)]
+        no_highlight: bool,
+
+        /// List every file edit proposed in the session
+        #[arg(long)]
+        edits: bool,
+    },
+
+    /// Narrate how a project evolved, session by session, as a markdown document
+    #[command(name = "story")]
+    Story {
+        /// Project name, as shown by `list`
+        project: String,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Convert a captured terminal log into a markdown transcript
+    #[command(name = "convert")]
+    Convert {
+        /// Log file to convert; need not be a logged session
+        log_file: PathBuf,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Write a redacted, self-contained HTML transcript of a session for sharing
+    #[command(name = "share")]
+    Share {
+        /// Session ID to share
+        session_id: String,
+
+        /// Output file (defaults to <session-id>.html in the current directory)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Leave code blocks unhighlighted
+        #[arg(long)]
+        no_highlight: bool,
+
+        /// Show what would be redacted, in context, instead of writing the transcript
+        #[arg(long)]
+        preview: bool,
+    },
+
+    /// Write redacted copies of sessions into their projects, as `[mirror]` does for new sessions
+    #[command(name = "mirror")]
+    Mirror {
+        /// Sessions to mirror
+        #[arg(required = true)]
+        session_ids: Vec<String>,
+
+        /// Format to write, in place of `format` under [mirror]
+        #[arg(short, long, value_enum)]
+        format: Option<crate::config::MirrorFormat>,
+
+        /// Show what would be redacted, in context, instead of writing anything
+        #[arg(long)]
+        preview: bool,
+    },
+
+    /// Export session data to a file or stdout
+    #[command(name = "export")]
+    Export {
+        /// Output format
+        #[arg(short, long, value_enum)]
+        format: ExportFormat,
+
+        /// Maximum number of sessions to include
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+
+        /// Only sessions with this methodology
+        #[arg(short, long)]
+        methodology: Option<String>,
+
+        /// Only sessions in this project
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Only sessions on or after this day (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<NaiveDate>,
+
+        /// Only sessions on or before this day (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<NaiveDate>,
+
+        /// Only sessions matching this query (same terms as `batch --filter`)
+        #[arg(long, default_value = "", allow_hyphen_values = true)]
+        filter: String,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Token usage and estimated spend, overall and per methodology
+    #[command(name = "cost")]
+    Cost {
+        /// Show one session instead of a summary
+        session_id: Option<String>,
+
+        /// Only sessions with this methodology
+        #[arg(short, long)]
+        methodology: Option<String>,
+
+        /// Only sessions in this project
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Only sessions on or after this day (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<NaiveDate>,
+
+        /// Only sessions on or before this day (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<NaiveDate>,
+
+        /// First record usage for sessions that have none, from transcripts and logs
+        #[arg(long)]
+        refresh: bool,
+    },
+
+    /// Serve a read-only JSON API over logged sessions
+    #[command(name = "serve")]
+    Serve {
+        /// Address to listen on
+        #[arg(short, long, default_value = "127.0.0.1:8787")]
+        bind: String,
+
+        /// Bearer token required on every request (overrides config)
+        #[arg(long, env = "CLAUDE_LOGGER_TOKEN", hide_env_values = true)]
+        token: Option<String>,
+
+        /// PEM certificate for HTTPS (requires the `tls` feature)
+        #[arg(long, requires = "tls_key")]
+        tls_cert: Option<PathBuf>,
+
+        /// PEM private key for HTTPS (requires the `tls` feature)
+        #[arg(long, requires = "tls_cert")]
+        tls_key: Option<PathBuf>,
+
+        /// Allow serving on a non-loopback address without a token
+        #[arg(long)]
+        allow_unauthenticated: bool,
+    },
+
+    /// Attach a note to a range of lines in a session log
+    #[command(name = "annotate")]
+    Annotate {
+        /// Session ID to annotate
+        session_id: String,
+
+        /// Line range in the log, e.g. 120-140
+        #[arg(short, long)]
+        lines: String,
+
+        /// Category such as insight, mistake or todo
+        #[arg(short, long, default_value = "note")]
+        category: String,
+
+        /// The annotation text
+        note: String,
+    },
+
+    /// Add a timestamped note about a whole session, committed with the metadata
+    Note {
+        /// Session ID to note
+        session_id: String,
+
+        /// The note text
+        text: String,
+    },
+
+    /// Search annotations across sessions
+    #[command(name = "annotations")]
+    Annotations {
+        /// Text to look for in notes, categories and excerpts
+        query: Option<String>,
+
+        /// Only show this category
+        #[arg(short, long)]
+        category: Option<String>,
+
+        /// Only sessions in this project
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Include the annotated excerpt
+        #[arg(long)]
+        excerpts: bool,
+    },
+
+    /// Work through sessions that haven't been reviewed yet
+    #[command(name = "review")]
+    Review {
+        #[command(subcommand)]
+        action: ReviewAction,
+    },
+
+    /// List follow-up items found at the end of conversations
+    #[command(name = "todos")]
+    Todos {
+        #[command(subcommand)]
+        action: Option<TodoAction>,
+
+        /// Include done and dismissed items
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Schedule a follow-up on a session
+    #[command(name = "remind")]
+    Remind {
+        /// Session ID to follow up on
+        session_id: String,
+
+        /// Delay until the reminder is due, e.g. 30m, 12h, 3d or 2w
+        #[arg(long = "in", default_value = "1d")]
+        delay: String,
+
+        /// What to check
+        note: String,
+    },
+
+    /// List pending reminders
+    #[command(name = "reminders")]
+    Reminders {
+        #[command(subcommand)]
+        action: Option<ReminderAction>,
+    },
+
+    /// Add or remove tags on a session
+    #[command(name = "tag")]
+    Tag {
+        /// Session ID to tag
+        session_id: String,
+
+        /// Tags to add (or remove with --remove)
+        #[arg(required = true)]
+        tags: Vec<String>,
+
+        /// Remove the tags instead of adding them
+        #[arg(short, long)]
+        remove: bool,
+    },
+
+    /// Set or remove custom fields on a session, or link it to the session it resumed
+    #[command(name = "edit")]
+    Edit {
+        /// Session ID to edit
+        session_id: String,
+
+        /// Field to set (key=value; JSON values keep their type)
+        #[arg(long = "meta", value_name = "KEY=VALUE", value_parser = crate::session::parse_extra_field)]
+        meta: Vec<(String, serde_json::Value)>,
+
+        /// Field to remove
+        #[arg(long = "unset", value_name = "KEY")]
+        unset: Vec<String>,
+
+        /// Mark the session as resuming this one; pass "" to unlink it
+        #[arg(long, value_name = "SESSION_ID")]
+        parent: Option<String>,
+    },
+
+    /// Import sessions_metadata.json into the SQLite metadata database
+    #[command(name = "migrate-metadata")]
+    MigrateMetadata,
+
+    /// Upgrade stored metadata and logs written by an older claude-logger to the current layout,
+    /// applying every pending migration in order after backing up the metadata
+    Migrate {
+        /// Show the stored layout version and pending migrations without changing anything
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+
+    /// Link sessions to Claude Code's JSONL transcripts for exact metrics
+    #[command(name = "transcripts")]
+    Transcripts {
+        #[command(subcommand)]
+        action: TranscriptAction,
+    },
+
+    /// Import sessions from Claude Code's own transcripts (~/.claude/projects), such as those from
+    /// before claude-logger was installed or started without it
+    Import {
+        /// List the transcripts that would be imported without importing them
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+
+    /// Mark experiment phases (e.g. a new CLAUDE.md) on the timeline
+    #[command(name = "phase")]
+    Phase {
+        #[command(subcommand)]
+        action: Option<PhaseAction>,
+    },
+
+    /// Put sessions under a legal hold, exempting them from archiving, local pruning, merging and
+    /// deletion until released; lists held sessions without an action
+    Hold {
+        #[command(subcommand)]
+        action: Option<HoldAction>,
+    },
+
+    /// Overview of sessions, review queue, action items and reminders
+    #[command(name = "status")]
+    Status,
+
+    /// Files Claude edits most often across sessions (churn hotspots)
+    #[command(name = "hotspots")]
+    Hotspots {
+        /// Only count edits in this project
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Number of files to show
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
+
+    /// Summarize sessions per week or month
+    #[command(name = "digest")]
+    Digest {
+        /// Bucket size; boundaries follow [calendar] in the config file
+        #[arg(short, long, value_enum, default_value = "week")]
+        period: Period,
+
+        /// Number of most recent periods to show
+        #[arg(short, long, default_value = "4")]
+        count: usize,
+
+        /// Also save the latest period's summary as Markdown under digests/ in the logs
+        /// repository and commit it
+        #[arg(short, long)]
+        write: bool,
+    },
+
+    /// Chart enthusiasm, confusion, quality and duration per week or month
+    Trends {
+        /// Bucket size; boundaries follow [calendar] in the config file
+        #[arg(short, long, value_enum, default_value = "week")]
+        period: Period,
+
+        /// Number of most recent periods to chart
+        #[arg(short, long, default_value = "12")]
+        count: usize,
+
+        /// Print the series as CSV instead of sparklines
+        #[arg(long)]
+        csv: bool,
+
+        /// Write the CSV to this file instead of stdout
+        #[arg(short, long, requires = "csv")]
+        output: Option<PathBuf>,
+    },
+
+    /// Log every claude session automatically: installs a `claude` shell function that runs it
+    /// through claude-logger (set CLAUDE_LOGGER_DISABLE=1 to skip logging)
+    #[command(name = "install-shell-alias")]
+    InstallShellAlias {
+        /// Shell to install for; defaults to the one in SHELL
+        #[arg(long, value_enum)]
+        shell: Option<Shell>,
+
+        /// Startup file to write instead of ~/.bashrc or ~/.zshrc
+        #[arg(long, value_name = "FILE")]
+        rc_file: Option<PathBuf>,
+
+        /// Print the function instead of installing it
+        #[arg(long)]
+        print: bool,
+
+        /// Remove the function again
+        #[arg(long, conflicts_with = "print")]
+        uninstall: bool,
+    },
+
+    /// Compare two sessions' metrics and quality scores side by side
+    Diff {
+        /// The baseline session
+        session_a: String,
+
+        /// The session compared against it
+        session_b: String,
+
+        /// Also show a unified diff of the two sessions' prompts
+        #[arg(long)]
+        prompts: bool,
+    },
+
+    /// SVG badge in shields.io style with a session's or project's quality or session count
+    Badge {
+        /// Session to badge
+        #[arg(required_unless_present = "project", conflicts_with = "project")]
+        session_id: Option<String>,
+
+        /// Badge every session in this project instead
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// What the badge shows
+        #[arg(short, long, value_enum, default_value = "quality")]
+        metric: BadgeMetric,
+
+        /// Text on the left of the badge
+        #[arg(long)]
+        label: Option<String>,
+
+        /// Write the SVG to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Work rhythm: typical session lengths, breaks, longest streaks and pacing suggestions
+    Rhythm {
+        /// Only sessions in this project
+        #[arg(short, long)]
+        project: Option<String>,
+    },
+
+    /// How you use claude-logger itself: commands run, sessions per week, commands never tried
+    /// and configured features never exercised, from the local audit log
+    SelfStats,
+
+    /// Archive completed session logs to the configured remote storage
+    #[command(name = "sync")]
+    Sync {
+        /// Run a single pass instead of looping
+        #[arg(long)]
+        once: bool,
+
+        /// Seconds between sync passes
+        #[arg(short, long, default_value = "300")]
+        interval: u64,
+
+        /// Delete local log files once their archive is verified
+        #[arg(long)]
+        prune_local: bool,
+    },
+
+    /// Check stored metadata against its JSON Schema and invariants
+    #[command(name = "validate")]
+    Validate {
+        /// Print the sessions_metadata.json schema instead of validating
+        #[arg(long)]
+        print_schema: bool,
+
+        /// Also reject fields unknown to this version (for CI)
+        #[arg(long)]
+        strict: bool,
+    },
+
+    /// Follow a running session with a live status line of exchanges, code blocks and elapsed time
+    #[command(name = "watch")]
+    Watch {
+        /// Session to follow; defaults to the most recently active one
+        session_id: Option<String>,
+
+        /// Seconds between refreshes
+        #[arg(short, long, default_value = "2")]
+        interval: u64,
+    },
+
+    /// Generate synthetic sessions with chosen metrics, for demos, benchmarks and tests
+    #[command(name = "synth")]
+    Synth {
+        /// Number of sessions
+        #[arg(short = 'n', long, default_value = "20")]
+        count: usize,
+
+        /// Metrics every session starts from
+        #[arg(long, value_enum, default_value = "balanced")]
+        profile: Preset,
+
+        /// Override one metric: exchanges, code_blocks, file_edits, questions, enthusiasm,
+        /// confusion, compaction, minutes or energy
+        #[arg(long = "set", value_name = "METRIC=VALUE")]
+        set: Vec<String>,
+
+        /// Relative spread of each metric between sessions (0 reproduces the profile exactly)
+        #[arg(long, default_value = "0.25")]
+        jitter: f64,
+
+        /// The same seed always generates the same sessions
+        #[arg(long, default_value = "0")]
+        seed: u64,
+
+        /// Methodology of every session; alternates between the two when unset
+        #[arg(short, long)]
+        methodology: Option<String>,
+
+        /// Projects to spread the sessions over
+        #[arg(short, long, default_values = ["demo-api", "demo-web"])]
+        project: Vec<String>,
+
+        /// Spread sessions over this many days up to now
+        #[arg(long, default_value = "30")]
+        days: u32,
+
+        /// Logs directory to fill; defaults to the configured logs directory
+        #[arg(long)]
+        dir: Option<PathBuf>,
+
+        /// Add sessions even when the directory already holds real ones
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Browse sessions in an interactive terminal dashboard (requires the `tui` feature)
+    #[command(name = "tui")]
+    Tui,
+
+    /// Apply operations to every session matching a filter, e.g. retagging a year of history
+    #[command(name = "batch")]
+    Batch {
+        /// Space-separated terms, all of which must match: project:NAME, methodology:NAME, tag:NAME,
+        /// author:NAME, review:STATE, field:KEY[=VALUE], since:DATE, until:DATE or words in the id,
+        /// project and tags; prefix a term with - to negate it
+        #[arg(short, long, default_value = "", allow_hyphen_values = true)]
+        filter: String,
+
+        /// Operation to apply, in order: tag=NAME, untag=NAME, retag=OLD:NEW, review=STATE,
+        /// meta=KEY=VALUE, unset=KEY, archive or reanalyze
+        #[arg(short, long = "apply", value_name = "OPERATION", required = true)]
+        apply: Vec<crate::batch::Operation>,
+
+        /// Show what would change without saving anything
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+
+    /// Move a session's logs and metadata to the trash, recording the removal in the logs repository
+    #[command(name = "delete")]
+    Delete {
+        /// Session ID to delete
+        session_id: String,
+
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Join two captures of one session, e.g. split by a crash: the logs are concatenated in
+    /// time order, the metadata combined and the originals moved to the trash
+    #[command(name = "merge-sessions")]
+    MergeSessions {
+        /// One of the sessions to merge
+        first: String,
+
+        /// The other session
+        second: String,
+    },
+
+    /// List or restore deleted sessions
+    #[command(name = "trash")]
+    Trash {
+        #[command(subcommand)]
+        action: TrashAction,
+    },
+
+    /// Compress the logs of sessions that finished long ago, recording the change in the logs repository
+    #[command(name = "archive")]
+    Archive {
+        /// Age past which sessions are archived, e.g. 90d or 12w
+        #[arg(long, value_name = "DURATION")]
+        older_than: String,
+
+        /// List the sessions that would be archived without changing anything
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+
+    /// Encrypt the logs of sessions recorded before `[encryption]` was enabled
+    #[command(name = "encrypt-existing")]
+    EncryptExisting {
+        /// List the sessions that would be encrypted without changing anything
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+
+    /// Scale the `[analysis.sentiment]` backend's enthusiasm and confusion to match the built-in
+    /// patterns' over past sessions, so scores stay comparable across the switch
+    #[command(name = "calibrate-sentiment")]
+    CalibrateSentiment {
+        /// Most recent sessions to calibrate against
+        #[arg(short, long, default_value = "200")]
+        limit: usize,
+    },
+
+    /// Manage the cache of conversation embeddings
+    #[command(name = "cache")]
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+
+    /// Show recorded changes to session metadata: what changed, when and by which command
+    #[command(name = "audit")]
+    Audit {
+        /// Only changes to this session
+        session_id: Option<String>,
+
+        /// Only this kind of change
+        #[arg(short, long, value_enum)]
+        action: Option<Action>,
+
+        /// Only changes made on or after this day (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<NaiveDate>,
+
+        /// Number of most recent changes to show
+        #[arg(short, long, default_value = "50")]
+        limit: usize,
+    },
+
+    /// Recompute metrics and quality scores with the current patterns and show how they moved
+    #[command(name = "reanalyze")]
+    Reanalyze {
+        /// Only sessions matching this query (same terms as `batch --filter`)
+        #[arg(short, long, default_value = "", allow_hyphen_values = true)]
+        filter: String,
+
+        /// Report the changes without saving the new scores
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+
+    /// Estimate durations for sessions recorded without one, from timestamps in their logs or files
+    #[command(name = "backfill-durations")]
+    BackfillDurations {
+        /// List the estimates without saving them
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+
+    /// Capture claude sessions running inside tmux panes
+    #[command(name = "tmux")]
+    Tmux {
+        #[command(subcommand)]
+        action: TmuxAction,
+    },
+
+    /// Back up the logs repository to a remote, e.g. a private GitHub repository
+    #[command(name = "git")]
+    Git {
+        #[command(subcommand)]
+        action: GitAction,
     },
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ListFormat {
+    /// One readable line per session
+    Table,
+    /// Session metadata as a JSON array
+    Json,
+    /// Tab-separated, one session per line, no header; columns are id, start, end,
+    /// duration_seconds, methodology, project, creative_energy, review_state, tags, log_file
+    Tsv,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ShowFormat {
+    /// The log exactly as captured, terminal escapes included
+    Raw,
+    /// The log with terminal escapes and redraws stripped
+    Clean,
+    /// The conversation as readable markdown turns
+    Markdown,
+    /// The structured conversation model
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ExportFormat {
+    /// Atom feed of recent sessions
+    Atom,
+    /// Metadata, metrics and quality scores per session
+    Json,
+    /// One row per session, for spreadsheets
+    Csv,
+    /// A markdown table, one row per session
+    Markdown,
+}
+
+#[derive(Subcommand)]
+pub enum ReviewAction {
+    /// List sessions waiting for review, oldest first
+    Queue {
+        /// Include flagged sessions
+        #[arg(long)]
+        flagged: bool,
+    },
+
+    /// Step through unreviewed sessions and record a verdict for each
+    Walk,
+
+    /// Set a session's review state
+    Mark {
+        /// Session ID to update
+        session_id: String,
+
+        #[arg(value_enum)]
+        state: ReviewState,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TodoAction {
+    /// Mark an action item as done
+    Done {
+        session_id: String,
+        /// Item number as shown by `todos`
+        number: usize,
+    },
+
+    /// Dismiss an action item that doesn't need doing
+    Dismiss {
+        session_id: String,
+        /// Item number as shown by `todos`
+        number: usize,
+    },
+
+    /// Extract action items from completed sessions that have none yet
+    Scan,
+}
+
+#[derive(Subcommand)]
+pub enum ReminderAction {
+    /// Mark a reminder as handled
+    Done {
+        session_id: String,
+        /// Reminder number as shown by `reminders`
+        number: usize,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CacheAction {
+    /// Show how many sessions and chunks are embedded and how much space the cache takes
+    Status,
+
+    /// Delete the cache
+    Clear,
+
+    /// Embed every session again from scratch
+    Rebuild,
+}
+
+#[derive(Subcommand)]
+pub enum HoldAction {
+    /// Place a hold on sessions
+    Place {
+        /// Session IDs
+        #[arg(required = true)]
+        session_ids: Vec<String>,
+
+        /// Why the sessions must be preserved, e.g. a matter or ticket number
+        #[arg(short, long)]
+        reason: String,
+    },
+
+    /// Release sessions' holds
+    Release {
+        /// Session IDs
+        #[arg(required = true)]
+        session_ids: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PhaseAction {
+    /// Start a phase now, ending the running one
+    Start {
+        /// What changed, e.g. "new CLAUDE.md v2"
+        name: String,
+    },
+
+    /// End the running phase
+    End,
+
+    /// Compare metrics phase over phase; with three phases or more, as confidence interval plots
+    Compare {
+        /// Also write the interval plots to this SVG file
+        #[arg(long, value_name = "FILE")]
+        svg: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TranscriptAction {
+    /// Find transcripts for sessions that are not linked yet
+    Link,
+
+    /// Exchanges, tool calls and token usage from a session's transcript
+    Show {
+        /// Session ID
+        session_id: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TmuxAction {
+    /// Start capturing all panes currently running claude
+    Attach,
+
+    /// Stop all tmux captures and commit their logs
+    Detach,
+
+    /// Keep attaching to new claude panes and finalize ended ones
+    Watch {
+        /// Seconds between pane scans
+        #[arg(short, long, default_value = "5")]
+        interval: u64,
+    },
+
+    /// Show active tmux captures
+    Status,
+}
+
 impl Cli {
     pub fn is_command_mode(&self) -> bool {
         self.command.is_some()
@@ -71,4 +1027,78 @@ impl Cli {
     pub fn should_run_claude(&self) -> bool {
         !self.is_command_mode()
     }
-}
\ No newline at end of file
+}
+
+#[derive(Subcommand)]
+pub enum TrashAction {
+    /// Deleted sessions, most recent first, with when each is purged
+    List,
+
+    /// Put a deleted session back
+    Restore {
+        /// Session ID to restore
+        session_id: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum GitAction {
+    /// Configure the remote the logs repository is pushed to
+    Remote {
+        #[command(subcommand)]
+        action: RemoteAction,
+    },
+
+    /// Push the logs repository to its remote now
+    Push {
+        /// Remote to push to; defaults to `remote` under [git] in the config
+        #[arg(long)]
+        remote: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RemoteAction {
+    /// Add the remote, refusing to replace an existing one
+    Add {
+        url: String,
+        /// Remote name; defaults to `remote` under [git] in the config
+        #[arg(long)]
+        name: Option<String>,
+    },
+
+    /// Point the remote at a new URL, adding it if missing
+    Set {
+        url: String,
+        /// Remote name; defaults to `remote` under [git] in the config
+        #[arg(long)]
+        name: Option<String>,
+    },
+
+    /// Show where the remote points and whether auto-push is on
+    Show {
+        /// Remote name; defaults to `remote` under [git] in the config
+        #[arg(long)]
+        name: Option<String>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_json_anywhere_on_the_command_line() {
+        for args in [
+            ["claude-logger", "--json", "list"],
+            ["claude-logger", "list", "--json"],
+            ["claude-logger", "analyze", "--json"],
+        ] {
+            let cli = Cli::try_parse_from(args).unwrap();
+            assert!(cli.json, "{:?}", args);
+        }
+        let cli = Cli::try_parse_from(["claude-logger", "analyze", "--billing", "-o", "bill.csv", "--json"]).unwrap();
+        assert!(cli.json);
+        assert!(matches!(cli.command, Some(Commands::Analyze { output: Some(_), .. })));
+    }
+}
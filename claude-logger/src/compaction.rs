@@ -0,0 +1,167 @@
+use crate::conversation;
+use crate::session::{CompactionEvent, CompactionTrigger};
+use crate::transcript;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde_json::Value;
+use std::sync::OnceLock;
+
+/// When a session's context was compacted: from its transcript when one was found, which has
+/// the time, trigger and context size of each compaction, otherwise from the banners Claude Code
+/// prints in the terminal.
+pub fn detect(transcript: Option<&str>, log: &str) -> Vec<CompactionEvent> {
+    match transcript {
+        Some(transcript) => from_transcript(transcript),
+        None => from_log(log),
+    }
+}
+
+/// `compact_boundary` entries in Claude Code's JSONL transcript. Versions that predate them
+/// only left the summary that replaces the compacted conversation, which is counted instead.
+pub fn from_transcript(content: &str) -> Vec<CompactionEvent> {
+    let mut events = Vec::new();
+    let mut exchanges = 0;
+    // A boundary is followed by its summary, which mustn't count a second time
+    let mut awaiting_summary = false;
+
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        let Ok(entry) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        let at = entry["timestamp"]
+            .as_str()
+            .and_then(|timestamp| DateTime::parse_from_rfc3339(timestamp).ok())
+            .map(|timestamp| timestamp.with_timezone(&Utc));
+
+        if entry["type"] == "system" && entry["subtype"] == "compact_boundary" {
+            let metadata = &entry["compactMetadata"];
+            let trigger = match metadata["trigger"].as_str() {
+                Some("auto") => CompactionTrigger::Auto,
+                Some("manual") => CompactionTrigger::Manual,
+                _ => CompactionTrigger::Unknown,
+            };
+            events.push(CompactionEvent { at, after_exchanges: exchanges, trigger, tokens_before: metadata["preTokens"].as_u64() });
+            awaiting_summary = true;
+        } else if entry["isCompactSummary"] == true {
+            if !awaiting_summary {
+                events.push(CompactionEvent { at, after_exchanges: exchanges, trigger: CompactionTrigger::Unknown, tokens_before: None });
+            }
+            awaiting_summary = false;
+        } else if entry["type"] == "user"
+            && entry["isMeta"] != true
+            && transcript::is_prompt(&entry["message"]["content"])
+        {
+            exchanges += 1;
+            awaiting_summary = false;
+        }
+    }
+    events
+}
+
+/// Compaction banners in a cleaned terminal log, such as `✻ Conversation compacted` or
+/// `⎿ Compacted (ctrl+r to see full summary)`. Redraws can print a banner more than once, so
+/// only one compaction is counted between two prompts.
+pub fn from_log(content: &str) -> Vec<CompactionEvent> {
+    static BANNER: OnceLock<Regex> = OnceLock::new();
+    let banner = BANNER.get_or_init(|| {
+        Regex::new(
+            r"(?i)(conversation compacted|compacted[ .(]+ctrl\+[a-z] to see full summary|being continued from a previous conversation that ran out of context)",
+        )
+        .expect("valid regex")
+    });
+
+    let mut events: Vec<CompactionEvent> = Vec::new();
+    let mut exchanges = 0;
+    let mut last_prompt = "";
+    let mut auto_warning = false;
+    let mut compacted_since_prompt = false;
+
+    let content = conversation::normalize(content);
+    for line in content.lines() {
+        if banner.is_match(line) {
+            if !compacted_since_prompt {
+                let trigger = if last_prompt.trim_start().starts_with("/compact") {
+                    CompactionTrigger::Manual
+                } else if auto_warning {
+                    CompactionTrigger::Auto
+                } else {
+                    CompactionTrigger::Unknown
+                };
+                events.push(CompactionEvent { at: None, after_exchanges: exchanges, trigger, tokens_before: None });
+                compacted_since_prompt = true;
+            }
+        } else if let Some(prompt) = line.strip_prefix("Human:") {
+            exchanges += 1;
+            last_prompt = prompt;
+            auto_warning = false;
+            compacted_since_prompt = false;
+        } else if line.to_lowercase().contains("auto-compact") {
+            // "Context left until auto-compact: 3%"
+            auto_warning = true;
+        }
+    }
+    events
+}
+
+/// e.g. `auto after 31 exchanges at 14:05`.
+pub fn describe(event: &CompactionEvent, format_time: impl Fn(DateTime<Utc>) -> String) -> String {
+    let mut description = format!("{} after {} exchanges", event.trigger, event.after_exchanges);
+    if let Some(at) = event.at {
+        description.push_str(&format!(" at {}", format_time(at)));
+    }
+    if let Some(tokens) = event.tokens_before {
+        description.push_str(&format!(", {}k tokens", tokens / 1000));
+    }
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_compactions_in_transcripts_and_terminal_logs() {
+        let transcript = r#"{"type":"user","timestamp":"2025-03-01T10:00:00Z","message":{"role":"user","content":"build the importer"}}
+{"type":"assistant","timestamp":"2025-03-01T10:00:05Z","message":{"id":"m1","content":[{"type":"text","text":"On it"}]}}
+{"type":"user","timestamp":"2025-03-01T10:20:00Z","message":{"role":"user","content":"now the exporter"}}
+{"type":"system","subtype":"compact_boundary","timestamp":"2025-03-01T10:41:00Z","compactMetadata":{"trigger":"auto","preTokens":154000}}
+{"type":"user","isCompactSummary":true,"timestamp":"2025-03-01T10:41:01Z","message":{"role":"user","content":"This session is being continued from a previous conversation that ran out of context."}}
+{"type":"user","timestamp":"2025-03-01T10:45:00Z","message":{"role":"user","content":"/compact"}}
+{"type":"system","subtype":"compact_boundary","timestamp":"2025-03-01T10:45:30Z","compactMetadata":{"trigger":"manual","preTokens":40000}}
+{"type":"user","isCompactSummary":true,"timestamp":"2025-03-01T10:46:00Z","message":{"role":"user","content":"This session is being continued from a previous conversation."}}
+"#;
+        let events = from_transcript(transcript);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].trigger, CompactionTrigger::Auto);
+        assert_eq!((events[0].after_exchanges, events[0].tokens_before), (2, Some(154000)));
+        assert_eq!(events[0].at.unwrap().to_rfc3339(), "2025-03-01T10:41:00+00:00");
+        assert_eq!((events[1].trigger, events[1].after_exchanges), (CompactionTrigger::Manual, 3));
+        assert_eq!(describe(&events[0], |at| at.format("%H:%M").to_string()), "auto after 2 exchanges at 10:41, 154k tokens");
+        // The summaries aren't prompts
+        assert_eq!(transcript::Transcript::parse(transcript).exchanges, 3);
+
+        let log = "\
+> add retries to the uploader
+
+⏺ Update(src/upload.rs)
+  ⎿  Updated src/upload.rs with 3 additions
+                                                Context left until auto-compact: 2%
+✻ Conversation compacted · ctrl+o for history
+✻ Conversation compacted · ctrl+o for history
+
+> now write the tests
+
+⏺ Done.
+
+> /compact
+  ⎿  Compacted (ctrl+r to see full summary)
+
+> the word compact alone doesn't count
+";
+        let events = from_log(log);
+        assert_eq!(events.len(), 2);
+        assert_eq!((events[0].trigger, events[0].after_exchanges), (CompactionTrigger::Auto, 1));
+        assert_eq!((events[1].trigger, events[1].after_exchanges), (CompactionTrigger::Manual, 3));
+        assert!(events.iter().all(|event| event.at.is_none()));
+    }
+}
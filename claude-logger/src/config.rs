@@ -0,0 +1,659 @@
+use crate::fields;
+use crate::metadata::LoadMode;
+use crate::methodology;
+use crate::session::SessionMetadata;
+use crate::timezone::DisplayZone;
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Files that may sit next to session logs but should never be committed.
+pub const DEFAULT_IGNORE_PATTERNS: &[&str] = &[
+    "*.timing",
+    "*.tmp",
+    "*.decrypted",
+    "*.lock",
+    "sync_state.json",
+    "embeddings_cache.json",
+    "corrupt/",
+    ".trash/",
+    "backups/",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub git: GitConfig,
+    pub serve: ServeConfig,
+    pub storage: StorageConfig,
+    pub metadata: MetadataConfig,
+    pub calendar: CalendarConfig,
+    pub notifications: NotificationsConfig,
+    pub redaction: RedactionConfig,
+    pub analysis: AnalysisConfig,
+    pub guard: GuardConfig,
+    pub pricing: PricingConfig,
+    pub billing: BillingConfig,
+    pub display: DisplayConfig,
+    pub logs: LogsConfig,
+    pub hooks: HooksConfig,
+    pub trash: TrashConfig,
+    pub mirror: MirrorConfig,
+    pub methodology: MethodologyConfig,
+    pub features: FeaturesConfig,
+    pub team: TeamConfig,
+    pub encryption: EncryptionConfig,
+    pub embeddings: EmbeddingsConfig,
+    /// Structured fields collected after each session, declared as `[[fields]]`
+    pub fields: Vec<FieldSpec>,
+}
+
+/// A typed custom field, stored with the session's other custom fields.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldSpec {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: FieldKind,
+    /// Question asked after the session; defaults to the name
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Keep asking until answered rather than allowing Enter to skip
+    #[serde(default)]
+    pub required: bool,
+    /// Bounds for integer and number fields
+    #[serde(default)]
+    pub min: Option<f64>,
+    #[serde(default)]
+    pub max: Option<f64>,
+    /// Allowed values for enum fields
+    #[serde(default)]
+    pub values: Vec<String>,
+    /// Longest accepted text field, in characters
+    #[serde(default)]
+    pub max_length: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldKind {
+    Integer,
+    Number,
+    Enum,
+    Text,
+}
+
+/// How a new session's methodology is told from its project, ahead of the built-in `.claude/CLAUDE.md` check.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MethodologyConfig {
+    /// Environment variable that names the methodology outright, ahead of any rule
+    pub env_var: String,
+    /// Tried in order; the first that matches names the methodology
+    pub rules: Vec<MethodologyRule>,
+}
+
+impl Default for MethodologyConfig {
+    fn default() -> Self {
+        Self { env_var: "CLAUDE_LOGGER_METHODOLOGY".to_string(), rules: Vec::new() }
+    }
+}
+
+/// A user-defined methodology and how to recognise a project using it. With both `paths` and
+/// `content`, both must match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MethodologyRule {
+    pub name: String,
+    /// Globs relative to the project directory; matching any existing path is enough
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Regular expression looked for in `files`
+    #[serde(default)]
+    pub content: Option<String>,
+    /// Files (globs allowed) searched for `content`, relative to the project directory
+    #[serde(default = "default_rule_files")]
+    pub files: Vec<String>,
+}
+
+fn default_rule_files() -> Vec<String> {
+    vec![".claude/CLAUDE.md".to_string(), "CLAUDE.md".to_string()]
+}
+
+/// Where new session logs are written.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogsConfig {
+    /// Logs directory in place of `~/.claude-logs`; `~` is expanded
+    pub directory: Option<PathBuf>,
+    /// File logs under a subdirectory per project rather than flat in the logs directory
+    pub per_project: bool,
+}
+
+impl Default for LogsConfig {
+    fn default() -> Self {
+        Self { directory: None, per_project: true }
+    }
+}
+
+/// A redacted copy of each finished session written into the repository it ran in, so the
+/// project carries its own AI collaboration history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MirrorConfig {
+    pub enabled: bool,
+    /// Directory relative to the project's repository root
+    pub directory: PathBuf,
+    pub format: MirrorFormat,
+}
+
+impl Default for MirrorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: PathBuf::from("docs/ai-sessions"),
+            format: MirrorFormat::Summary,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum MirrorFormat {
+    /// Title, metrics, files edited and follow-ups
+    #[default]
+    Summary,
+    /// The whole conversation as markdown turns
+    Transcript,
+}
+
+/// How a session's `features_worked_on` are filled in.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeaturesConfig {
+    /// Ask after each session to keep the detected features or enter others
+    pub confirm: bool,
+}
+
+/// Who is recording, for a logs repository shared by a team.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TeamConfig {
+    /// Name recorded as each session's author and used for its commits; defaults to
+    /// `git config user.name` in the project
+    pub author: Option<String>,
+    /// Email for those commits; defaults to `git config user.email`
+    pub email: Option<String>,
+}
+
+/// Encryption of session logs at rest; requires the `encryption` feature.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EncryptionConfig {
+    /// Encrypt each session's logs when it finishes, with a key kept in the OS keyring
+    pub enabled: bool,
+}
+
+/// The cache of conversation chunk embeddings, managed with `claude-logger cache`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmbeddingsConfig {
+    /// Embed each session's new chunks as it finishes
+    pub enabled: bool,
+    /// Largest the cache may grow, in megabytes; the least recently updated sessions go first
+    pub max_mb: u64,
+}
+
+impl Default for EmbeddingsConfig {
+    fn default() -> Self {
+        Self { enabled: false, max_mb: 64 }
+    }
+}
+
+/// How long deleted sessions can be restored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrashConfig {
+    /// Days a deleted session stays in the trash before it is purged
+    pub retention_days: u32,
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        Self { retention_days: 30 }
+    }
+}
+
+/// Shell commands run as sessions start and finish, e.g. to notify or update a time tracker.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// Before claude starts
+    pub pre_session: Vec<String>,
+    /// Once a finished session is saved
+    pub post_session: Vec<String>,
+    /// Once a finished session's log is committed
+    pub post_commit: Vec<String>,
+    /// Seconds a hook may run before it is stopped
+    pub timeout_secs: u64,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            pre_session: Vec::new(),
+            post_session: Vec::new(),
+            post_commit: Vec::new(),
+            timeout_secs: 30,
+        }
+    }
+}
+
+/// `path` with a leading `~` replaced by the home directory.
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplayConfig {
+    /// Language for report headings and number formats, e.g. "de-DE"; defaults to `LANG`
+    pub locale: Option<String>,
+}
+
+/// Hourly rates for `analyze --billing`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BillingConfig {
+    /// Rate for projects without their own entry in `rates`
+    pub hourly_rate: Option<f64>,
+    /// Per-project rates, keyed by project name
+    pub rates: BTreeMap<String, f64>,
+    /// Label for amounts; AI cost is estimated in US dollars whatever this says
+    pub currency: String,
+    /// Add each project's AI cost to its total
+    pub pass_through_ai_cost: bool,
+}
+
+impl Default for BillingConfig {
+    fn default() -> Self {
+        Self {
+            hourly_rate: None,
+            rates: BTreeMap::new(),
+            currency: "USD".to_string(),
+            pass_through_ai_cost: true,
+        }
+    }
+}
+
+/// Limits on a wrapped claude session; off unless `enabled` and a limit is set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuardConfig {
+    pub enabled: bool,
+    /// What to do once a limit is passed
+    pub action: GuardAction,
+    pub max_minutes: Option<u64>,
+    /// Size of the raw capture, in kilobytes
+    pub max_output_kb: Option<u64>,
+    /// Estimated spend in US dollars, from the session's transcript and [pricing]
+    pub max_cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GuardAction {
+    /// Print a warning and let the session continue
+    #[default]
+    Warn,
+    /// Stop the claude process
+    Terminate,
+}
+
+/// Model prices used for cost estimates, in US dollars per million tokens.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PricingConfig {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+    pub cache_read_per_mtok: f64,
+    pub cache_write_per_mtok: f64,
+}
+
+impl Default for PricingConfig {
+    fn default() -> Self {
+        Self {
+            input_per_mtok: 3.0,
+            output_per_mtok: 15.0,
+            cache_read_per_mtok: 0.3,
+            cache_write_per_mtok: 3.75,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalysisConfig {
+    /// Date the methodology switch happened, for `analyze --transition`
+    pub transition_date: Option<NaiveDate>,
+    /// Sessions compared on each side of the switch
+    pub transition_window: usize,
+    /// Sessions left out of reports unless `--include-excluded` is passed
+    pub exclude: ExclusionConfig,
+    /// Count sessions resumed with `claude --resume`/`--continue` as part of the conversation they resumed
+    pub merge_resumed: bool,
+    /// What detects enthusiasm and confusion
+    pub sentiment: SentimentConfig,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        Self {
+            transition_date: None,
+            transition_window: 10,
+            exclude: ExclusionConfig::default(),
+            merge_resumed: true,
+            sentiment: SentimentConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SentimentConfig {
+    pub backend: SentimentBackendKind,
+    /// For the `command` backend: run with the conversation on stdin, printing
+    /// `{"enthusiasm": <number>, "confusion": <number>}`
+    pub command: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SentimentBackendKind {
+    /// The built-in enthusiasm and confusion patterns, as adjusted by `patterns.toml`
+    #[default]
+    Patterns,
+    /// The on-device lexicon model (requires the `sentiment-model` feature)
+    Model,
+    /// An external command
+    Command,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExclusionConfig {
+    pub projects: Vec<String>,
+    pub tags: Vec<String>,
+    /// Date ranges (inclusive, local dates)
+    pub dates: Vec<DateRange>,
+    /// Leave out non-interactive `claude -p` runs
+    pub headless: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DateRange {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl ExclusionConfig {
+    pub fn excludes(&self, session: &SessionMetadata) -> bool {
+        let date = DisplayZone::Local.date(session.timestamp);
+
+        self.projects.contains(&session.project)
+            || session.tags.iter().any(|tag| self.tags.contains(tag))
+            || (self.headless && session.is_headless())
+            || self.dates.iter().any(|range| {
+                range.from.is_none_or(|from| date >= from) && range.to.is_none_or(|to| date <= to)
+            })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionConfig {
+    /// Extra regular expressions to scrub, on top of the built-in secret patterns
+    pub patterns: Vec<String>,
+    /// Leave email addresses in place
+    pub keep_emails: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    /// Show native desktop notifications (requires the `notifications` feature)
+    pub desktop: bool,
+    /// URL each notification is POSTed to as `{"text": ...}`, e.g. a Slack incoming webhook
+    /// (requires the `notifications` feature)
+    pub webhook: Option<String>,
+    /// Most notifications each channel delivers a minute
+    pub rate_limits: RateLimits,
+    pub session_finalized: bool,
+    pub quality_regression: bool,
+    pub digest_ready: bool,
+    pub reminder_due: bool,
+    /// Points below the recent average overall score that count as a regression
+    pub regression_threshold: f64,
+    /// Runaway output and stalled captures while `tmux watch` runs
+    pub anomalies: bool,
+    /// Multiple of the usual output rate that counts as runaway
+    pub runaway_factor: f64,
+    /// Minutes without output before a live capture counts as stalled
+    pub stall_minutes: i64,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            desktop: false,
+            webhook: None,
+            rate_limits: RateLimits::default(),
+            session_finalized: true,
+            quality_regression: true,
+            digest_ready: true,
+            reminder_due: true,
+            regression_threshold: 15.0,
+            anomalies: true,
+            runaway_factor: 10.0,
+            stall_minutes: 30,
+        }
+    }
+}
+
+/// Notifications a minute per channel; 0 is unlimited. Events over the limit wait, and those of
+/// a kind that waited together are delivered as one, e.g. "34 sessions finalized".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimits {
+    pub console: u32,
+    pub desktop: u32,
+    pub webhook: u32,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self { console: 0, desktop: 6, webhook: 10 }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetadataConfig {
+    /// Refuse to load metadata with malformed entries or unknown fields
+    pub strict: bool,
+    /// Where session metadata is kept
+    pub backend: MetadataBackend,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetadataBackend {
+    /// `sessions_metadata.json`
+    #[default]
+    Json,
+    /// `sessions.db`, requires the `sqlite` feature
+    Sqlite,
+}
+
+impl std::fmt::Display for MetadataBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetadataBackend::Json => write!(f, "json"),
+            MetadataBackend::Sqlite => write!(f, "sqlite"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarConfig {
+    /// First day of weekly buckets
+    pub week_start: WeekStart,
+    /// Day of the month (1-28) on which monthly buckets begin, for fiscal months
+    pub month_start_day: u32,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            week_start: WeekStart::Monday,
+            month_start_day: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GitConfig {
+    /// Extra .gitignore patterns maintained in the logs repository
+    pub ignore: Vec<String>,
+    /// Push the logs repository after each session is committed
+    pub auto_push: bool,
+    /// Remote pushed to, as set with `git remote add`
+    pub remote: String,
+    /// Tries per push before giving up, waiting twice as long after each failure
+    pub push_attempts: u32,
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        Self {
+            ignore: Vec::new(),
+            auto_push: false,
+            remote: "origin".to_string(),
+            push_attempts: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServeConfig {
+    /// Bearer token required by the HTTP API
+    pub token: Option<String>,
+    /// PEM certificate chain for HTTPS (requires the `tls` feature)
+    pub tls_cert: Option<PathBuf>,
+    /// PEM private key for HTTPS (requires the `tls` feature)
+    pub tls_key: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorageConfig {
+    pub backend: StorageBackend,
+    /// Root directory for the local backend (defaults to the logs directory)
+    pub path: Option<PathBuf>,
+    /// WebDAV collection URL, or a custom S3-compatible endpoint
+    pub url: Option<String>,
+    /// Key prefix inside the bucket (S3 only)
+    pub prefix: Option<String>,
+    pub bucket: Option<String>,
+    pub region: Option<String>,
+    pub username: Option<String>,
+    /// WebDAV password; prefer the CLAUDE_LOGGER_WEBDAV_PASSWORD environment variable
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    #[default]
+    Local,
+    S3,
+    Webdav,
+}
+
+impl std::fmt::Display for StorageBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageBackend::Local => write!(f, "local"),
+            StorageBackend::S3 => write!(f, "s3"),
+            StorageBackend::Webdav => write!(f, "webdav"),
+        }
+    }
+}
+
+impl Config {
+    pub fn load() -> Result<Self> {
+        match Self::config_path() {
+            Some(path) => Self::load_from(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+
+        let config: Self = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+        fields::check_specs(&config.fields)
+            .map_err(|e| anyhow::anyhow!("Invalid [[fields]] in config file {}: {}", path.display(), e))?;
+        methodology::Rules::compile(&config.methodology.rules)
+            .map_err(|e| anyhow::anyhow!("Invalid [methodology] rules in config file {}: {:#}", path.display(), e))?;
+        Ok(config)
+    }
+
+    pub fn config_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("claude-logger").join("config.toml"))
+    }
+
+    pub fn load_mode(&self) -> LoadMode {
+        if self.metadata.strict {
+            LoadMode::Strict
+        } else {
+            LoadMode::Lenient
+        }
+    }
+
+    /// Built-in ignore patterns followed by user-configured ones, without duplicates.
+    pub fn ignore_patterns(&self) -> Vec<String> {
+        let mut patterns: Vec<String> = DEFAULT_IGNORE_PATTERNS.iter().map(|p| p.to_string()).collect();
+        for pattern in &self.git.ignore {
+            if !patterns.contains(pattern) {
+                patterns.push(pattern.clone());
+            }
+        }
+        patterns
+    }
+}
@@ -0,0 +1,424 @@
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+/// Lines sampled when guessing a log's exchange style.
+const DETECT_LINES: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Speaker {
+    Human,
+    Assistant,
+    Other,
+}
+
+impl Speaker {
+    pub fn label(self) -> &'static str {
+        match self {
+            Speaker::Human => "You",
+            Speaker::Assistant => "Claude",
+            Speaker::Other => "Terminal",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Block {
+    Text { text: String },
+    Code { lang: String, code: String },
+    ToolOutput { lines: Vec<String> },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Turn {
+    pub speaker: Speaker,
+    pub blocks: Vec<Block>,
+}
+
+/// A session's turns, as emitted by `show --format json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Conversation {
+    pub session_id: String,
+    pub turns: Vec<Turn>,
+}
+
+/// Strip terminal control sequences, and the banners of logs captured with `script`, from a raw capture.
+pub fn clean_log(raw: &str) -> String {
+    static ANSI: OnceLock<Regex> = OnceLock::new();
+    let ansi = ANSI.get_or_init(|| {
+        Regex::new(r"\x1b\[[0-?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)|\x1b[@-_]").unwrap()
+    });
+    let stripped = ansi.replace_all(raw, "");
+
+    let mut lines: Vec<String> = Vec::new();
+    for line in stripped.split('\n') {
+        // A bare carriage return redraws the line; keep only what was drawn last
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let line = line.rsplit('\r').next().unwrap_or(line);
+
+        let mut cleaned = String::with_capacity(line.len());
+        for c in line.chars() {
+            match c {
+                '\u{8}' => {
+                    cleaned.pop();
+                }
+                '\t' => cleaned.push(c),
+                c if c.is_control() => {}
+                c => cleaned.push(c),
+            }
+        }
+
+        if cleaned.starts_with("Script started on") || cleaned.starts_with("Script done on") {
+            continue;
+        }
+        let cleaned = cleaned.trim_end().to_string();
+        if cleaned.is_empty() && lines.last().is_some_and(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(cleaned);
+    }
+
+    lines.join("\n").trim().to_string()
+}
+
+/// How a log marks who is speaking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExchangeStyle {
+    /// `Human:` / `Assistant:` at the start of each turn
+    Labeled,
+    /// Claude Code's own rendering: prompts echoed as `> ...`, replies and tool calls as `⏺ ...`
+    Prompt,
+    /// One JSON message per line with a `user` or `assistant` role, as in imported transcripts
+    Jsonl,
+}
+
+impl ExchangeStyle {
+    /// The style `content` is written in; logs with no recognisable markers count as labeled.
+    pub fn detect(content: &str) -> Self {
+        if content.lines().any(|line| line.starts_with("Human:") || line.starts_with("Assistant:")) {
+            return ExchangeStyle::Labeled;
+        }
+
+        let sample: Vec<&str> = content.lines().filter(|line| !line.trim().is_empty()).take(DETECT_LINES).collect();
+        if !sample.is_empty()
+            && sample.iter().all(|line| line.trim_start().starts_with('{'))
+            && sample.iter().any(|line| serde_json::from_str::<Value>(line).ok().and_then(|entry| role(&entry)).is_some())
+        {
+            return ExchangeStyle::Jsonl;
+        }
+
+        if content.lines().any(|line| prompt_line(line).is_some())
+            && content.lines().any(|line| line.starts_with(['⏺', '●']))
+        {
+            return ExchangeStyle::Prompt;
+        }
+        ExchangeStyle::Labeled
+    }
+}
+
+/// `content` rewritten with `Human:` / `Assistant:` markers, whatever style it was captured in,
+/// so every analysis can read it the same way.
+pub fn normalize(content: &str) -> Cow<'_, str> {
+    match ExchangeStyle::detect(content) {
+        ExchangeStyle::Labeled => Cow::Borrowed(content),
+        ExchangeStyle::Prompt => Cow::Owned(normalize_prompt(content)),
+        ExchangeStyle::Jsonl => Cow::Owned(normalize_jsonl(content)),
+    }
+}
+
+fn prompt_line(line: &str) -> Option<&str> {
+    if line == ">" {
+        return Some("");
+    }
+    line.strip_prefix("> ")
+}
+
+/// A `⏺ Tool(...)` call line, which the edit and tool parsers read as is.
+fn is_tool_call(line: &str) -> bool {
+    static TOOL: OnceLock<Regex> = OnceLock::new();
+    TOOL.get_or_init(|| Regex::new(r"^[⏺●]\s*[A-Z][A-Za-z]+\(").unwrap()).is_match(line)
+}
+
+fn normalize_prompt(content: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut speaker = Speaker::Other;
+
+    for line in content.lines() {
+        if let Some(prompt) = prompt_line(line) {
+            lines.push(format!("Human: {}", prompt));
+            speaker = Speaker::Human;
+        } else if let Some(reply) = line.strip_prefix(['⏺', '●']) {
+            if is_tool_call(line) {
+                if speaker != Speaker::Assistant {
+                    lines.push("Assistant:".to_string());
+                }
+                lines.push(line.to_string());
+            } else if speaker != Speaker::Assistant {
+                lines.push(format!("Assistant: {}", reply.trim_start()));
+            } else {
+                lines.push(reply.trim_start().to_string());
+            }
+            speaker = Speaker::Assistant;
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+    lines.join("\n")
+}
+
+/// The speaker of a transcript entry, from `message.role`, `role` or `type`.
+fn role(entry: &Value) -> Option<Speaker> {
+    let role = [&entry["message"]["role"], &entry["role"], &entry["type"]]
+        .into_iter()
+        .find_map(Value::as_str)?;
+    match role {
+        "user" | "human" => Some(Speaker::Human),
+        "assistant" => Some(Speaker::Assistant),
+        _ => None,
+    }
+}
+
+fn normalize_jsonl(content: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut speaker = Speaker::Other;
+
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        let Ok(entry) = serde_json::from_str::<Value>(line) else {
+            lines.push(line.to_string());
+            continue;
+        };
+        let Some(role) = role(&entry) else {
+            continue;
+        };
+        let body = if entry["message"].is_object() { &entry["message"]["content"] } else { &entry["content"] };
+
+        let mut text: Vec<String> = Vec::new();
+        let mut calls: Vec<String> = Vec::new();
+        match body {
+            Value::String(body) => text.push(body.clone()),
+            Value::Array(blocks) => {
+                for block in blocks {
+                    match block["type"].as_str() {
+                        Some("text") => text.extend(block["text"].as_str().map(str::to_string)),
+                        Some("tool_use") => calls.push(format!(
+                            "⏺ {}({})",
+                            block["name"].as_str().unwrap_or("Tool"),
+                            ["command", "file_path", "url", "pattern"]
+                                .iter()
+                                .find_map(|key| block["input"][key].as_str())
+                                .unwrap_or_default()
+                        )),
+                        Some("tool_result") => {
+                            let output = match &block["content"] {
+                                Value::String(output) => output.clone(),
+                                Value::Array(parts) => parts.iter().filter_map(|part| part["text"].as_str()).collect::<Vec<_>>().join("\n"),
+                                _ => String::new(),
+                            };
+                            let mut output = output.lines();
+                            calls.push(format!("  ⎿  {}", output.next().unwrap_or_default()));
+                            calls.extend(output.map(|line| format!("     {}", line)));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        // Tool results come back as user entries but belong to the assistant's turn
+        let text = text.join("\n");
+        let has_text = !text.trim().is_empty();
+        if (has_text || role == Speaker::Assistant && !calls.is_empty()) && role != speaker {
+            let label = if role == Speaker::Human { "Human" } else { "Assistant" };
+            lines.push(String::new());
+            lines.push(format!("{}: {}", label, text).trim_end().to_string());
+            speaker = role;
+        } else if has_text {
+            lines.push(text);
+        }
+        lines.extend(calls);
+    }
+    lines.join("\n").trim().to_string()
+}
+
+/// Split a cleaned log into speaker turns, in any [`ExchangeStyle`].
+pub fn parse_turns(content: &str) -> Vec<Turn> {
+    let content = normalize(content);
+    let mut turns: Vec<Turn> = Vec::new();
+    let mut speaker = Speaker::Other;
+    let mut lines: Vec<&str> = Vec::new();
+
+    for line in content.lines() {
+        let (next, rest) = if let Some(rest) = line.strip_prefix("Human:") {
+            (Speaker::Human, rest)
+        } else if let Some(rest) = line.strip_prefix("Assistant:") {
+            (Speaker::Assistant, rest)
+        } else {
+            lines.push(line);
+            continue;
+        };
+
+        push_turn(&mut turns, speaker, &lines);
+        speaker = next;
+        lines = vec![rest.trim_start()];
+    }
+    push_turn(&mut turns, speaker, &lines);
+
+    turns
+}
+
+fn push_turn(turns: &mut Vec<Turn>, speaker: Speaker, lines: &[&str]) {
+    let blocks = parse_blocks(lines);
+    if !blocks.is_empty() {
+        turns.push(Turn { speaker, blocks });
+    }
+}
+
+/// Split a turn into prose, fenced code and `⎿` tool output blocks.
+fn parse_blocks(lines: &[&str]) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut text: Vec<&str> = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = lines[index];
+        let trimmed = line.trim_start();
+
+        if let Some(fence) = trimmed.strip_prefix("```") {
+            flush_text(&mut blocks, &mut text);
+            let mut code: Vec<&str> = Vec::new();
+            index += 1;
+            while index < lines.len() && !lines[index].trim_start().starts_with("```") {
+                code.push(lines[index]);
+                index += 1;
+            }
+            blocks.push(Block::Code {
+                lang: fence.trim().to_string(),
+                code: code.join("\n"),
+            });
+        } else if let Some(first) = trimmed.strip_prefix('⎿') {
+            flush_text(&mut blocks, &mut text);
+            let mut output = vec![first.trim().to_string()];
+            while index + 1 < lines.len()
+                && lines[index + 1].starts_with(char::is_whitespace)
+                && !lines[index + 1].trim().is_empty()
+            {
+                index += 1;
+                output.push(lines[index].trim().to_string());
+            }
+            blocks.push(Block::ToolOutput { lines: output });
+        } else {
+            text.push(line);
+        }
+        index += 1;
+    }
+    flush_text(&mut blocks, &mut text);
+
+    blocks
+}
+
+fn flush_text(blocks: &mut Vec<Block>, text: &mut Vec<&str>) {
+    let joined = text.join("\n").trim().to_string();
+    if !joined.is_empty() {
+        blocks.push(Block::Text { text: joined });
+    }
+    text.clear();
+}
+
+/// Readable markdown transcript: one bold heading per turn, code kept fenced and
+/// tool output folded into `<details>`.
+pub fn to_markdown(turns: &[Turn]) -> String {
+    let mut markdown = String::new();
+
+    for turn in turns {
+        markdown.push_str(&format!("**{}**\n\n", turn.speaker.label()));
+        for block in &turn.blocks {
+            match block {
+                Block::Text { text } => markdown.push_str(&format!("{}\n\n", text)),
+                Block::Code { lang, code } => markdown.push_str(&format!("```{}\n{}\n```\n\n", lang, code)),
+                Block::ToolOutput { lines } => markdown.push_str(&format!(
+                    "<details><summary>Tool output ({} lines)</summary>\n\n```\n{}\n```\n</details>\n\n",
+                    lines.len(),
+                    lines.join("\n")
+                )),
+            }
+        }
+    }
+
+    markdown.trim_end().to_string() + "\n"
+}
+
+/// Convert any raw terminal capture straight to a markdown transcript.
+pub fn log_to_markdown(raw: &str) -> String {
+    to_markdown(&parse_turns(&crate::log_format::to_text(raw).1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cleans_terminal_noise() {
+        let raw = "Script started on 2025-01-10\n\x1b[1;32mHuman:\x1b[0m hi\r\nprogress 10%\rprogress 100%\nabc\u{8}d\n\n\n\nScript done on 2025-01-10\n";
+        assert_eq!(clean_log(raw), "Human: hi\nprogress 100%\nabd");
+    }
+
+    #[test]
+    fn splits_code_and_tool_output() {
+        let lines = vec![
+            "Running tests",
+            "⏺ Bash(cargo test)",
+            "  ⎿  running 3 tests",
+            "     test a ... ok",
+            "```rust",
+            "fn main() {}",
+            "```",
+        ];
+        assert_eq!(
+            parse_blocks(&lines),
+            vec![
+                Block::Text {
+                    text: "Running tests\n⏺ Bash(cargo test)".to_string()
+                },
+                Block::ToolOutput {
+                    lines: vec!["running 3 tests".to_string(), "test a ... ok".to_string()]
+                },
+                Block::Code {
+                    lang: "rust".to_string(),
+                    code: "fn main() {}".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn detects_and_normalizes_exchange_styles() {
+        assert_eq!(ExchangeStyle::detect("Human: hi\n\nAssistant: hello"), ExchangeStyle::Labeled);
+        assert_eq!(ExchangeStyle::detect("just some output"), ExchangeStyle::Labeled);
+
+        let prompt = "> fix the test\n\n⏺ Bash(cargo test)\n  ⎿  1 failed\n\n⏺ The assertion was off by one.\n\n> thanks\n\n⏺ Done.";
+        assert_eq!(ExchangeStyle::detect(prompt), ExchangeStyle::Prompt);
+        let turns = parse_turns(prompt);
+        let speakers: Vec<Speaker> = turns.iter().map(|turn| turn.speaker).collect();
+        assert_eq!(speakers, [Speaker::Human, Speaker::Assistant, Speaker::Human, Speaker::Assistant]);
+        assert_eq!(crate::patterns::get_patterns().analyze_content(prompt).tool_usage["Bash"], 1);
+
+        let jsonl = concat!(
+            r#"{"type":"user","message":{"role":"user","content":"why does it crash?"}}"#, "\n",
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","name":"Read","input":{"file_path":"src/main.rs"}}]}}"#, "\n",
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","content":"fn main() {}"}]}}"#, "\n",
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"It unwraps a None."}]}}"#, "\n",
+        );
+        assert_eq!(ExchangeStyle::detect(jsonl), ExchangeStyle::Jsonl);
+        assert_eq!(
+            normalize(jsonl),
+            "Human: why does it crash?\n\nAssistant:\n⏺ Read(src/main.rs)\n  ⎿  fn main() {}\nIt unwraps a None."
+        );
+        assert_eq!(crate::patterns::get_patterns().analyze_content(jsonl).exchanges, 2);
+    }
+}
@@ -0,0 +1,199 @@
+use crate::config::PricingConfig;
+use crate::log_format;
+use crate::locale;
+use crate::logger::ClaudeLogger;
+use crate::project;
+use crate::session::{SessionMetadata, TokenUsage};
+use crate::transcript::Transcript;
+use anyhow::Result;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+struct CostPatterns {
+    total: Regex,
+    usage: Regex,
+}
+
+fn patterns() -> &'static CostPatterns {
+    static PATTERNS: OnceLock<CostPatterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| CostPatterns {
+        total: Regex::new(r"Total cost:\s+\$([0-9]+(?:\.[0-9]+)?)").unwrap(),
+        usage: Regex::new(
+            r"([0-9.]+[kmb]?) input, ([0-9.]+[kmb]?) output(?:, ([0-9.]+[kmb]?) cache read, ([0-9.]+[kmb]?) cache write)?",
+        )
+        .unwrap(),
+    })
+}
+
+/// Usage from the last `/cost` summary Claude Code printed in a cleaned log.
+///
+/// Token counts come from its "Usage by model" lines, summed over models; they are rounded
+/// (`1.2k`) so the dollar figure is taken as printed rather than re-estimated.
+pub fn parse_cost_output(content: &str) -> Option<TokenUsage> {
+    let patterns = patterns();
+    let total = patterns.total.captures_iter(content).last()?;
+    let mut usage = TokenUsage {
+        cost: total[1].parse().ok()?,
+        ..TokenUsage::default()
+    };
+
+    let after = &content[total.get(0)?.end()..];
+    // Model lines follow the totals; stop at the first line after them that isn't one
+    let mut seen_model = false;
+    for line in after.lines().skip(1) {
+        match patterns.usage.captures(line) {
+            Some(caps) => {
+                seen_model = true;
+                let count = |i: usize| caps.get(i).map_or(0, |m| parse_count(m.as_str()));
+                usage.input_tokens += count(1);
+                usage.output_tokens += count(2);
+                usage.cache_read_tokens += count(3);
+                usage.cache_creation_tokens += count(4);
+            }
+            None if seen_model => break,
+            None => {}
+        }
+    }
+    Some(usage)
+}
+
+/// `1.2k` style counts as printed by Claude Code.
+fn parse_count(text: &str) -> u64 {
+    let (number, scale) = match text.chars().last() {
+        Some('k') => (&text[..text.len() - 1], 1e3),
+        Some('m') => (&text[..text.len() - 1], 1e6),
+        Some('b') => (&text[..text.len() - 1], 1e9),
+        _ => (text, 1.0),
+    };
+    number.parse::<f64>().map_or(0, |n| (n * scale).round() as u64)
+}
+
+/// A session's usage: exact counts from its transcript when linked, else the `/cost` output in its log.
+pub fn session_usage(session: &SessionMetadata, log: Option<&str>, pricing: &PricingConfig) -> Option<TokenUsage> {
+    let from_transcript = session
+        .transcript_file
+        .as_ref()
+        .and_then(|path| Transcript::read(path).ok())
+        .filter(|transcript| transcript.assistant_messages > 0)
+        .map(|transcript| transcript.usage(pricing));
+    from_transcript.or_else(|| log.and_then(parse_cost_output))
+}
+
+/// Record usage for every session that has none yet, returning how many gained it.
+pub fn record_all(logger: &mut ClaudeLogger, pricing: &PricingConfig) -> Result<usize> {
+    let mut recorded = 0;
+    for session in logger.metadata_mut().sessions.values_mut() {
+        if session.usage.is_some() {
+            continue;
+        }
+        let log = match &session.clean_log_file {
+            Some(path) => project::read_text(path).ok(),
+            None => project::read_log(&session.log_file).ok().map(|raw| log_format::to_text(&String::from_utf8_lossy(&raw)).1),
+        };
+        if let Some(usage) = session_usage(session, log.as_deref(), pricing) {
+            session.usage = Some(usage);
+            recorded += 1;
+        }
+    }
+
+    if recorded > 0 {
+        logger.save_metadata()?;
+    }
+    Ok(recorded)
+}
+
+/// Spend over a set of sessions, overall and per methodology.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CostSummary {
+    pub sessions: usize,
+    /// Of those, sessions with recorded usage
+    pub priced_sessions: usize,
+    pub total: TokenUsage,
+    pub by_methodology: BTreeMap<String, MethodologyCost>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MethodologyCost {
+    pub priced_sessions: usize,
+    pub total: TokenUsage,
+}
+
+impl MethodologyCost {
+    pub fn average_cost(&self) -> f64 {
+        self.total.cost / self.priced_sessions.max(1) as f64
+    }
+
+    pub fn average_tokens(&self) -> f64 {
+        self.total.total_tokens() as f64 / self.priced_sessions.max(1) as f64
+    }
+}
+
+impl CostSummary {
+    pub fn from_sessions<'a>(sessions: impl IntoIterator<Item = &'a SessionMetadata>) -> Self {
+        let mut summary = CostSummary::default();
+        for session in sessions {
+            summary.sessions += 1;
+            let Some(usage) = &session.usage else {
+                continue;
+            };
+            summary.priced_sessions += 1;
+            summary.total.add(usage);
+
+            let methodology = summary.by_methodology.entry(session.methodology.to_string()).or_default();
+            methodology.priced_sessions += 1;
+            methodology.total.add(usage);
+        }
+        summary
+    }
+
+    pub fn print(&self) {
+        let locale = locale::current();
+        println!("Sessions with usage: {} of {}", self.priced_sessions, self.sessions);
+        print_usage("Total", &self.total);
+
+        if !self.by_methodology.is_empty() {
+            println!("\n{}", locale.text("cost-by-methodology"));
+            for (methodology, cost) in &self.by_methodology {
+                println!("  {:<14} ${:>8}  {:>10.0} tokens  ({} sessions)",
+                    methodology, locale.number(cost.average_cost(), 2), cost.average_tokens(), cost.priced_sessions);
+            }
+        }
+    }
+}
+
+pub fn print_usage(label: &str, usage: &TokenUsage) {
+    println!("{}: ${}", label, locale::current().number(usage.cost, 2));
+    println!("  Tokens: {} in, {} out ({} cache read, {} cache write)",
+        usage.input_tokens, usage.output_tokens, usage.cache_read_tokens, usage.cache_creation_tokens);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_last_cost_summary() {
+        let content = "\
+> /cost
+  Total cost:            $0.10
+> more work
+> /cost
+  Total cost:            $0.55
+  Total duration (API):  6m 19.7s
+  Usage by model:
+      claude-3-5-haiku:  1.2k input, 110 output, 0 cache read, 0 cache write
+         claude-sonnet:  310 input, 28.9k output, 1.1m cache read, 67.4k cache write
+> thanks
+";
+        let usage = parse_cost_output(content).unwrap();
+
+        assert_eq!(usage.cost, 0.55);
+        assert_eq!(usage.input_tokens, 1_510);
+        assert_eq!(usage.output_tokens, 29_010);
+        assert_eq!(usage.cache_read_tokens, 1_100_000);
+        assert_eq!(usage.cache_creation_tokens, 67_400);
+        assert!(parse_cost_output("no usage here").is_none());
+    }
+}
@@ -0,0 +1,246 @@
+use crate::analyzer::SessionAnalyzer;
+use crate::conversation::{parse_turns, Block, Speaker};
+use crate::reanalyze::SCORES;
+use crate::session::{AnalysisMetrics, SessionMetadata};
+use crate::timezone::DisplayZone;
+use anyhow::Result;
+
+/// Unchanged lines kept around each change in the prompt diff.
+const CONTEXT_LINES: usize = 3;
+
+type Metric = fn(&AnalysisMetrics) -> f64;
+
+const METRICS: [(&str, Metric); 12] = [
+    ("Exchanges", |metrics| metrics.exchanges as f64),
+    ("Questions", |metrics| metrics.questions_asked as f64),
+    ("Code blocks", |metrics| metrics.code_blocks as f64),
+    ("File edits", |metrics| metrics.file_edits as f64),
+    ("Tool calls", |metrics| metrics.tool_calls as f64),
+    ("Enthusiasm", |metrics| metrics.enthusiasm_markers as f64),
+    ("Confusion", |metrics| metrics.confusion_markers as f64),
+    ("Compaction", |metrics| metrics.compaction_indicators as f64),
+    ("Errors", |metrics| metrics.error_events as f64),
+    ("Retries", |metrics| metrics.retry_events as f64),
+    ("Input tokens", |metrics| metrics.input_tokens as f64),
+    ("Output tokens", |metrics| metrics.output_tokens as f64),
+];
+
+/// One measure for both sessions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub name: &'static str,
+    pub a: f64,
+    pub b: f64,
+}
+
+impl Row {
+    pub fn delta(&self) -> f64 {
+        self.b - self.a
+    }
+
+    /// Change relative to the first session; `None` when it had nothing to compare against.
+    pub fn percent(&self) -> Option<f64> {
+        (self.a != 0.0).then(|| self.delta() / self.a * 100.0)
+    }
+}
+
+/// Two sessions side by side, analysed afresh under the current patterns.
+#[derive(Debug, Clone)]
+pub struct SessionDiff {
+    pub a: SessionMetadata,
+    pub b: SessionMetadata,
+    pub metrics: Vec<Row>,
+    pub quality: Vec<Row>,
+    /// Unified diff of the human prompts, when asked for
+    pub prompts: Option<String>,
+}
+
+pub fn compare(analyzer: &SessionAnalyzer, a: &SessionMetadata, b: &SessionMetadata, with_prompts: bool) -> Result<SessionDiff> {
+    let (content_a, content_b) = (analyzer.read_session_log(a)?, analyzer.read_session_log(b)?);
+    let (snapshot_a, snapshot_b) = (SessionAnalyzer::snapshot(a, &content_a), SessionAnalyzer::snapshot(b, &content_b));
+
+    let mut metrics: Vec<Row> = METRICS
+        .iter()
+        .map(|(name, metric)| Row { name, a: metric(&snapshot_a.metrics), b: metric(&snapshot_b.metrics) })
+        .collect();
+    if let (Some(duration_a), Some(duration_b)) = (a.duration(), b.duration()) {
+        metrics.insert(0, Row {
+            name: "Duration (m)",
+            a: duration_a.num_seconds() as f64 / 60.0,
+            b: duration_b.num_seconds() as f64 / 60.0,
+        });
+    }
+    let quality = SCORES
+        .iter()
+        .map(|(name, score)| Row { name, a: score(&snapshot_a.quality), b: score(&snapshot_b.quality) })
+        .collect();
+
+    let prompts = with_prompts.then(|| unified_diff(&a.id, &b.id, &prompt_lines(&content_a), &prompt_lines(&content_b)));
+    Ok(SessionDiff { a: a.clone(), b: b.clone(), metrics, quality, prompts })
+}
+
+/// The human side of the conversation, one prompt after another with a blank line between.
+pub fn prompt_lines(content: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    for turn in parse_turns(content).into_iter().filter(|turn| turn.speaker == Speaker::Human) {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        for block in turn.blocks {
+            match block {
+                Block::Text { text } => lines.extend(text.lines().map(str::to_string)),
+                Block::Code { lang, code } => {
+                    lines.push(format!("```{}", lang));
+                    lines.extend(code.lines().map(str::to_string));
+                    lines.push("```".to_string());
+                }
+                Block::ToolOutput { .. } => {}
+            }
+        }
+    }
+    lines
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Same,
+    Removed,
+    Added,
+}
+
+/// A unified diff of `a` against `b`, empty when they are the same.
+pub fn unified_diff(a_name: &str, b_name: &str, a: &[String], b: &[String]) -> String {
+    // Longest common subsequence lengths of every pair of suffixes
+    let mut common = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            common[i][j] = if a[i] == b[j] { common[i + 1][j + 1] + 1 } else { common[i + 1][j].max(common[i][j + 1]) };
+        }
+    }
+
+    // Each step with the lines of `a` and `b` before it
+    let mut steps: Vec<(Op, usize, usize)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            steps.push((Op::Same, i, j));
+            (i, j) = (i + 1, j + 1);
+        } else if j == b.len() || (i < a.len() && common[i + 1][j] >= common[i][j + 1]) {
+            steps.push((Op::Removed, i, j));
+            i += 1;
+        } else {
+            steps.push((Op::Added, i, j));
+            j += 1;
+        }
+    }
+
+    let changes: Vec<usize> = (0..steps.len()).filter(|&index| steps[index].0 != Op::Same).collect();
+    let Some(&first) = changes.first() else {
+        return String::new();
+    };
+
+    // Changes close enough that their context would touch share a hunk
+    let mut hunks: Vec<(usize, usize)> = vec![(first, first)];
+    for &index in &changes[1..] {
+        let last = hunks.last_mut().expect("a hunk");
+        if index - last.1 <= 2 * CONTEXT_LINES + 1 {
+            last.1 = index;
+        } else {
+            hunks.push((index, index));
+        }
+    }
+
+    let mut diff = format!("--- {}\n+++ {}\n", a_name, b_name);
+    for (first, last) in hunks {
+        let range = first.saturating_sub(CONTEXT_LINES)..(last + CONTEXT_LINES + 1).min(steps.len());
+        let hunk = &steps[range];
+        let a_count = hunk.iter().filter(|(op, _, _)| *op != Op::Added).count();
+        let b_count = hunk.iter().filter(|(op, _, _)| *op != Op::Removed).count();
+        let (_, a_start, b_start) = hunk[0];
+        // An empty side is numbered by the line before it, as diff does
+        let start = |line: usize, count: usize| if count == 0 { line } else { line + 1 };
+        diff.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            start(a_start, a_count),
+            a_count,
+            start(b_start, b_count),
+            b_count
+        ));
+        for &(op, i, j) in hunk {
+            match op {
+                Op::Same => diff.push_str(&format!(" {}\n", a[i])),
+                Op::Removed => diff.push_str(&format!("-{}\n", a[i])),
+                Op::Added => diff.push_str(&format!("+{}\n", b[j])),
+            }
+        }
+    }
+    diff
+}
+
+pub fn print(diff: &SessionDiff, zone: DisplayZone) {
+    println!("=== {} vs {} ===", diff.a.id, diff.b.id);
+    for (side, session) in [("A", &diff.a), ("B", &diff.b)] {
+        println!("{}: {} | {} | {} | {}", side, session.id, session.project, session.methodology, zone.format(session.timestamp, "%Y-%m-%d %H:%M"));
+    }
+
+    for (heading, rows, decimals) in [("Metric", &diff.metrics, 0), ("Quality", &diff.quality, 1)] {
+        println!("\n{:<16} {:>10} {:>10} {:>10} {:>9}", heading, "A", "B", "Change", "%");
+        for row in rows {
+            let percent = row.percent().map(|percent| format!("{:+.0}%", percent)).unwrap_or_else(|| "-".to_string());
+            println!(
+                "{:<16} {:>10.decimals$} {:>10.decimals$} {:>+10.decimals$} {:>9}",
+                row.name,
+                row.a,
+                row.b,
+                row.delta(),
+                percent,
+                decimals = decimals
+            );
+        }
+    }
+
+    if let Some(prompts) = &diff.prompts {
+        println!("\nPrompts:");
+        if prompts.is_empty() {
+            println!("(identical)");
+        } else {
+            print!("{}", prompts);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn diffs_prompts_into_hunks() {
+        let a = prompt_lines("Human: Fix the upload test\n\nAssistant: Done.\n\nHuman: Now run clippy\n");
+        let b = prompt_lines("Human: Fix the upload test, it panics\n\nAssistant: Done.\n\nHuman: Now run clippy\n");
+        assert_eq!(a, ["Fix the upload test", "", "Now run clippy"]);
+        assert_eq!(
+            unified_diff("a", "b", &a, &b),
+            "--- a\n+++ b\n@@ -1,3 +1,3 @@\n-Fix the upload test\n+Fix the upload test, it panics\n \n Now run clippy\n"
+        );
+        assert_eq!(unified_diff("a", "b", &a, &a), "");
+
+        // Distant changes get their own hunks, and additions into nothing are numbered from 0
+        let long = lines("1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12");
+        let edited = lines("1\nTWO\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13");
+        let diff = unified_diff("a", "b", &long, &edited);
+        let headers: Vec<&str> = diff.lines().filter(|line| line.starts_with("@@")).collect();
+        assert_eq!(headers, ["@@ -1,5 +1,5 @@", "@@ -10,3 +10,4 @@"]);
+        assert!(unified_diff("a", "b", &[], &lines("x")).contains("@@ -0,0 +1,1 @@"));
+    }
+
+    #[test]
+    fn percent_change_needs_a_baseline() {
+        let row = Row { name: "Exchanges", a: 8.0, b: 12.0 };
+        assert_eq!((row.delta(), row.percent()), (4.0, Some(50.0)));
+        assert_eq!(Row { name: "Errors", a: 0.0, b: 3.0 }.percent(), None);
+    }
+}
@@ -0,0 +1,217 @@
+use crate::calendar::{Bucket, Period};
+use crate::config::CalendarConfig;
+use crate::session::{Methodology, SessionMetadata};
+use crate::timezone::DisplayZone;
+use chrono::Duration;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Sessions shown in each of the notable high- and low-energy lists.
+const NOTABLE_SESSIONS: usize = 3;
+
+/// Changes smaller than this share of the earlier value read as flat.
+const FLAT_CHANGE: f64 = 0.05;
+
+/// Totals for one period, as the narrative digest describes them.
+#[derive(Debug, Clone, Default)]
+pub struct PeriodSummary {
+    pub sessions: usize,
+    pub minutes: i64,
+    /// (project, sessions, minutes), most time first
+    pub projects: Vec<(String, usize, i64)>,
+    /// Most sessions first
+    pub methodologies: Vec<(Methodology, usize)>,
+    /// Mean overall score of the sessions analyzed so far
+    pub quality: Option<f64>,
+    pub energy: Option<f64>,
+    /// (session id, project) of the first sessions rated 3 and 1
+    pub high_energy: Vec<(String, String)>,
+    pub low_energy: Vec<(String, String)>,
+}
+
+impl PeriodSummary {
+    pub fn new(sessions: &[&SessionMetadata]) -> Self {
+        let minutes_of = |session: &SessionMetadata| session.duration().map_or(0, |duration| duration.num_minutes());
+
+        let mut projects: BTreeMap<&str, (usize, i64)> = BTreeMap::new();
+        let mut methodologies: BTreeMap<String, (Methodology, usize)> = BTreeMap::new();
+        for session in sessions {
+            let project = projects.entry(&session.project).or_default();
+            project.0 += 1;
+            project.1 += minutes_of(session);
+            methodologies.entry(session.methodology.to_string()).or_insert((session.methodology.clone(), 0)).1 += 1;
+        }
+        let mut projects: Vec<(String, usize, i64)> =
+            projects.into_iter().map(|(project, (count, minutes))| (project.to_string(), count, minutes)).collect();
+        projects.sort_by(|a, b| b.2.cmp(&a.2).then(b.1.cmp(&a.1)));
+        let mut methodologies: Vec<(Methodology, usize)> = methodologies.into_values().collect();
+        methodologies.sort_by_key(|(_, sessions)| std::cmp::Reverse(*sessions));
+
+        let scores: Vec<f64> = sessions.iter().filter_map(|session| Some(session.analysis.as_ref()?.quality.overall_score)).collect();
+        let energies: Vec<f64> = sessions.iter().filter_map(|session| session.creative_energy).map(f64::from).collect();
+
+        let mut by_time: Vec<&&SessionMetadata> = sessions.iter().collect();
+        by_time.sort_by_key(|session| session.timestamp);
+        let notable = |energy: u8| -> Vec<(String, String)> {
+            by_time
+                .iter()
+                .filter(|session| session.creative_energy == Some(energy))
+                .take(NOTABLE_SESSIONS)
+                .map(|session| (session.id.clone(), session.project.clone()))
+                .collect()
+        };
+
+        Self {
+            sessions: sessions.len(),
+            minutes: sessions.iter().map(|session| minutes_of(session)).sum(),
+            projects,
+            methodologies,
+            quality: mean(&scores),
+            energy: mean(&energies),
+            high_energy: notable(3),
+            low_energy: notable(1),
+        }
+    }
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// The period before `bucket`.
+pub fn previous(bucket: Bucket, period: Period, calendar: &CalendarConfig) -> Bucket {
+    period.bucket(bucket.start - Duration::days(1), calendar)
+}
+
+/// `↑`, `↓` or `→` for the change from `before` to `after`.
+fn arrow(before: f64, after: f64) -> &'static str {
+    let change = after - before;
+    if change.abs() <= before.abs() * FLAT_CHANGE {
+        "→"
+    } else if change > 0.0 {
+        "↑"
+    } else {
+        "↓"
+    }
+}
+
+fn hours(minutes: i64) -> String {
+    format!("{:.1}h", minutes as f64 / 60.0)
+}
+
+/// A Markdown narrative of `current` against `previous`, e.g. for the week `bucket`.
+pub fn render(period: Period, bucket: Bucket, current: &PeriodSummary, previous: &PeriodSummary, zone: DisplayZone) -> String {
+    let name = match period {
+        Period::Week => "week",
+        Period::Month => "month",
+    };
+    let mut text = String::new();
+    let _ = writeln!(text, "## Digest: {}", bucket.label());
+    let _ = writeln!(text);
+
+    let _ = write!(text, "You logged {} over {} sessions", hours(current.minutes), current.sessions);
+    if previous.sessions == 0 {
+        let _ = writeln!(text, ", with none the {} before.", name);
+    } else {
+        let _ = writeln!(
+            text,
+            " ({} {} and {} {} sessions the {} before).",
+            arrow(previous.minutes as f64, current.minutes as f64),
+            hours(previous.minutes),
+            arrow(previous.sessions as f64, current.sessions as f64),
+            previous.sessions,
+            name
+        );
+    }
+
+    let projects: Vec<String> = current
+        .projects
+        .iter()
+        .map(|(project, sessions, minutes)| format!("{} ({} sessions, {})", project, sessions, hours(*minutes)))
+        .collect();
+    if let Some((first, rest)) = projects.split_first() {
+        let _ = write!(text, "Most of it went to {}", first);
+        if !rest.is_empty() {
+            let _ = write!(text, ", then {}", rest.join(", "));
+        }
+        let _ = writeln!(text, ".");
+    }
+    let methodologies: Vec<String> =
+        current.methodologies.iter().map(|(methodology, sessions)| format!("{} {}", methodology, sessions)).collect();
+    let _ = writeln!(text, "Top methodologies: {}.", methodologies.join(", "));
+
+    let mut scores = Vec::new();
+    if let Some(quality) = current.quality {
+        scores.push(match previous.quality {
+            Some(before) => format!("average quality {:.1}/100 ({} {:+.1})", quality, arrow(before, quality), quality - before),
+            None => format!("average quality {:.1}/100", quality),
+        });
+    }
+    if let Some(energy) = current.energy {
+        scores.push(match previous.energy {
+            Some(before) => format!("average energy {:.1}/3 ({} {:+.1})", energy, arrow(before, energy), energy - before),
+            None => format!("average energy {:.1}/3", energy),
+        });
+    }
+    if !scores.is_empty() {
+        let mut line = scores.join(", ");
+        line[..1].make_ascii_uppercase();
+        let _ = writeln!(text, "{}.", line);
+    }
+
+    for (heading, sessions) in [("High-energy sessions", &current.high_energy), ("Low-energy sessions", &current.low_energy)] {
+        if sessions.is_empty() {
+            continue;
+        }
+        let _ = writeln!(text, "\n{}:", heading);
+        for (id, project) in sessions {
+            let _ = writeln!(text, "- {} ({})", id, project);
+        }
+    }
+    let _ = writeln!(text, "\n_Generated {}_", zone.format(chrono::Utc::now(), "%Y-%m-%d %H:%M"));
+    text
+}
+
+/// Where `--write` keeps a period's digest, relative to the logs directory.
+pub fn file_name(period: Period, bucket: Bucket) -> String {
+    let name = match period {
+        Period::Week => "week",
+        Period::Month => "month",
+    };
+    format!("digests/{}-{}.md", name, bucket.start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::{generate, sessions, SynthOptions};
+    use chrono::Utc;
+    use std::path::Path;
+
+    #[test]
+    fn narrates_a_period_against_the_one_before() {
+        let options = SynthOptions { projects: vec!["api".to_string(), "web".to_string()], ..sessions(6, 11, "api") };
+        let mut sessions: Vec<SessionMetadata> =
+            generate(&options, Path::new("/logs"), Utc::now()).into_iter().map(|generated| generated.session).collect();
+        for (session, energy) in sessions.iter_mut().zip([3, 3, 1, 2, 2, 2]) {
+            session.creative_energy = Some(energy);
+            session.duration_seconds = Some(1800);
+        }
+        let current: Vec<&SessionMetadata> = sessions.iter().collect();
+        let summary = PeriodSummary::new(&current);
+        assert_eq!((summary.sessions, summary.minutes), (6, 180));
+        assert_eq!(summary.projects.iter().map(|project| project.1).sum::<usize>(), 6);
+        assert_eq!((summary.high_energy.len(), summary.low_energy.len()), (2, 1));
+        assert!((summary.energy.unwrap() - 13.0 / 6.0).abs() < 1e-9);
+
+        let previous = PeriodSummary::new(&current[..2]);
+        let bucket = Period::Week.bucket(chrono::NaiveDate::from_ymd_opt(2025, 1, 8).unwrap(), &CalendarConfig::default());
+        let text = render(Period::Week, bucket, &summary, &previous, DisplayZone::Utc);
+        assert!(text.contains("You logged 3.0h over 6 sessions (↑ 1.0h and ↑ 2 sessions the week before)."), "{}", text);
+        assert!(text.contains("Average energy 2.2/3 (↓ -0.8)."), "{}", text);
+        assert!(text.contains("High-energy sessions:"));
+
+        assert_eq!(arrow(100.0, 103.0), "→");
+        assert_eq!(file_name(Period::Week, bucket), format!("digests/week-{}.md", bucket.start));
+    }
+}
@@ -0,0 +1,212 @@
+use regex::Regex;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+/// A file change Claude proposed, parsed from the `⏺ Update(path)` blocks the CLI prints.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileEdit {
+    /// Tool that made the change: Update, Edit, MultiEdit, Write or Create
+    pub tool: String,
+    pub file: String,
+    pub hunks: Vec<Hunk>,
+    pub added: usize,
+    pub removed: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Hunk {
+    /// Line number of the first line shown, when the CLI printed one
+    pub start_line: Option<usize>,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffLine {
+    pub kind: LineKind,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineKind {
+    Added,
+    Removed,
+    Context,
+}
+
+struct EditPatterns {
+    header: Regex,
+    updated: Regex,
+    wrote: Regex,
+    diff_line: Regex,
+    gap: Regex,
+}
+
+fn patterns() -> &'static EditPatterns {
+    static PATTERNS: OnceLock<EditPatterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| EditPatterns {
+        header: Regex::new(r"^\s*[⏺●]\s*(Update|Edit|MultiEdit|Write|Create)\((.+)\)\s*$").unwrap(),
+        updated: Regex::new(
+            r"Updated .+? with (?:(\d+) additions?)?(?: and )?(?:(\d+) removals?)?",
+        )
+        .unwrap(),
+        wrote: Regex::new(r"Wrote (\d+) lines? to").unwrap(),
+        diff_line: Regex::new(r"^\s*(\d+) (.*)$").unwrap(),
+        gap: Regex::new(r"^\s*(?:\.\.\.|…)").unwrap(),
+    })
+}
+
+/// Every file edit in a log, in the order they appear.
+pub fn parse_edits(content: &str) -> Vec<FileEdit> {
+    let patterns = patterns();
+    let lines: Vec<&str> = content.lines().collect();
+    let mut edits = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let Some(captures) = patterns.header.captures(lines[index]) else {
+            index += 1;
+            continue;
+        };
+
+        let mut edit = FileEdit {
+            tool: captures[1].to_string(),
+            file: captures[2].trim().to_string(),
+            hunks: Vec::new(),
+            added: 0,
+            removed: 0,
+        };
+        let writes_whole_file = matches!(edit.tool.as_str(), "Write" | "Create");
+        let mut reported: Option<(usize, usize)> = None;
+        let mut hunk = Hunk::default();
+        index += 1;
+
+        // The body is the indented block under the header, starting with a `⎿` summary
+        while index < lines.len() {
+            let line = lines[index];
+            if line.trim().is_empty() || !line.starts_with(char::is_whitespace) {
+                break;
+            }
+            let body = line.trim_start().trim_start_matches('⎿');
+
+            if let Some(captures) = patterns.updated.captures(body) {
+                let count = |group: usize| captures.get(group).and_then(|m| m.as_str().parse().ok()).unwrap_or(0);
+                reported = Some((count(1), count(2)));
+            } else if let Some(captures) = patterns.wrote.captures(body) {
+                reported = Some((captures[1].parse().unwrap_or(0), 0));
+            } else if let Some(captures) = patterns.diff_line.captures(body) {
+                let rest = captures.get(2).map_or("", |m| m.as_str());
+                // Written files are listed as plain numbered lines; diffs carry a +/- marker
+                let (kind, text) = if writes_whole_file {
+                    (LineKind::Added, rest)
+                } else if let Some(text) = rest.strip_prefix('+') {
+                    (LineKind::Added, text)
+                } else if let Some(text) = rest.strip_prefix('-') {
+                    (LineKind::Removed, text)
+                } else {
+                    (LineKind::Context, rest.strip_prefix(' ').unwrap_or(rest))
+                };
+                let text = text.strip_prefix("  ").or_else(|| text.strip_prefix(' ')).unwrap_or(text);
+
+                if hunk.lines.is_empty() {
+                    hunk.start_line = captures[1].parse().ok();
+                }
+                hunk.lines.push(DiffLine {
+                    kind,
+                    text: text.to_string(),
+                });
+            } else if patterns.gap.is_match(body) && !hunk.lines.is_empty() {
+                edit.hunks.push(std::mem::take(&mut hunk));
+            }
+            index += 1;
+        }
+        if !hunk.lines.is_empty() {
+            edit.hunks.push(hunk);
+        }
+
+        let count_kind = |kind: LineKind| {
+            edit.hunks
+                .iter()
+                .flat_map(|hunk| &hunk.lines)
+                .filter(|line| line.kind == kind)
+                .count()
+        };
+        // Long diffs are truncated on screen, so trust the CLI's own summary when it has one
+        let (added, removed) = reported.unwrap_or_else(|| (count_kind(LineKind::Added), count_kind(LineKind::Removed)));
+        edit.added = added;
+        edit.removed = removed;
+
+        edits.push(edit);
+    }
+
+    edits
+}
+
+pub fn print_edits(edits: &[FileEdit]) {
+    if edits.is_empty() {
+        println!("No file edits found.");
+        return;
+    }
+
+    for edit in edits {
+        println!("\n{} ({}, +{} -{})", edit.file, edit.tool, edit.added, edit.removed);
+        for hunk in &edit.hunks {
+            match hunk.start_line {
+                Some(line) => println!("  @@ line {}", line),
+                None => println!("  @@"),
+            }
+            for line in &hunk.lines {
+                let marker = match line.kind {
+                    LineKind::Added => '+',
+                    LineKind::Removed => '-',
+                    LineKind::Context => ' ',
+                };
+                println!("  {} {}", marker, line.text);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_updates_and_writes() {
+        let content = "\
+Assistant: Fixing the greeting.
+⏺ Update(src/main.rs)
+  ⎿  Updated src/main.rs with 2 additions and 1 removal
+       10    fn main() {
+       11 -      println!(\"a\");
+       11 +      println!(\"b\");
+       12 +      greet();
+       ...
+       40    }
+
+⏺ Write(tests/greet.rs)
+  ⎿  Wrote 2 lines to tests/greet.rs
+       1 #[test]
+       2 fn greets() {}
+
+```rust
+fn not_an_edit() {}
+```
+";
+
+        let edits = parse_edits(content);
+        assert_eq!(edits.len(), 2);
+
+        assert_eq!(edits[0].tool, "Update");
+        assert_eq!(edits[0].file, "src/main.rs");
+        assert_eq!((edits[0].added, edits[0].removed), (2, 1));
+        assert_eq!(edits[0].hunks.len(), 2);
+        assert_eq!(edits[0].hunks[0].start_line, Some(10));
+        assert_eq!(edits[0].hunks[0].lines[1].kind, LineKind::Removed);
+        assert_eq!(edits[0].hunks[0].lines[1].text, "    println!(\"a\");");
+
+        assert_eq!(edits[1].file, "tests/greet.rs");
+        assert_eq!((edits[1].added, edits[1].removed), (2, 0));
+        assert!(edits[1].hunks[0].lines.iter().all(|line| line.kind == LineKind::Added));
+    }
+}
@@ -0,0 +1,226 @@
+use crate::conversation;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// Conversation chunk embeddings, kept in the logs directory and never committed.
+pub const CACHE_FILE: &str = "embeddings_cache.json";
+
+/// Names how vectors were computed; a cache built by another embedder is discarded.
+pub const MODEL: &str = "hashed-bow-256-v1";
+
+const DIMENSIONS: usize = 256;
+
+/// Longest chunk embedded as one vector, in characters; longer exchanges are split at lines.
+const CHUNK_CHARS: usize = 4000;
+
+/// Every cached session's chunk vectors, keyed by session and by each chunk's content hash so
+/// a session whose log grew only embeds its new chunks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingCache {
+    pub model: String,
+    pub sessions: BTreeMap<String, CachedSession>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedSession {
+    pub updated_at: DateTime<Utc>,
+    pub chunks: Vec<ChunkEmbedding>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkEmbedding {
+    /// First 16 hex digits of the chunk's SHA-256
+    pub hash: String,
+    /// Unit vector scaled to -127..=127
+    pub vector: Vec<i8>,
+}
+
+impl Default for EmbeddingCache {
+    fn default() -> Self {
+        Self { model: MODEL.to_string(), sessions: BTreeMap::new() }
+    }
+}
+
+impl EmbeddingCache {
+    pub fn load(logs_dir: &Path) -> Result<Self> {
+        let path = logs_dir.join(CACHE_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let cache: Self = serde_json::from_str(&content).with_context(|| format!("Invalid embeddings cache in {}", path.display()))?;
+        Ok(if cache.model == MODEL { cache } else { Self::default() })
+    }
+
+    /// Write the cache, returning its size in bytes.
+    pub fn save(&self, logs_dir: &Path) -> Result<u64> {
+        let path = logs_dir.join(CACHE_FILE);
+        let content = serde_json::to_vec(self)?;
+        fs::write(&path, &content).with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(content.len() as u64)
+    }
+
+    pub fn remove(logs_dir: &Path) -> Result<bool> {
+        let path = logs_dir.join(CACHE_FILE);
+        if !path.exists() {
+            return Ok(false);
+        }
+        fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))?;
+        Ok(true)
+    }
+
+    pub fn chunks(&self) -> usize {
+        self.sessions.values().map(|session| session.chunks.len()).sum()
+    }
+
+    /// Embed `content`'s chunks as `session_id`'s, reusing the vectors of chunks already cached
+    /// for it. Returns how many chunks were reused and how many embedded.
+    pub fn update(&mut self, session_id: &str, content: &str, now: DateTime<Utc>) -> (usize, usize) {
+        let cached: HashMap<String, Vec<i8>> = self
+            .sessions
+            .remove(session_id)
+            .map(|session| session.chunks.into_iter().map(|chunk| (chunk.hash, chunk.vector)).collect())
+            .unwrap_or_default();
+
+        let (mut reused, mut embedded) = (0, 0);
+        let chunks = chunk(content)
+            .iter()
+            .map(|text| {
+                let hash = hash(text);
+                let vector = match cached.get(&hash) {
+                    Some(vector) => {
+                        reused += 1;
+                        vector.clone()
+                    }
+                    None => {
+                        embedded += 1;
+                        embed(text)
+                    }
+                };
+                ChunkEmbedding { hash, vector }
+            })
+            .collect();
+        self.sessions.insert(session_id.to_string(), CachedSession { updated_at: now, chunks });
+        (reused, embedded)
+    }
+
+    /// Drop the least recently updated sessions until the cache fits in `max_bytes`. Returns how
+    /// many were dropped.
+    pub fn enforce_limit(&mut self, max_bytes: u64) -> usize {
+        let mut sizes: Vec<(DateTime<Utc>, String, u64)> = self
+            .sessions
+            .iter()
+            .map(|(id, session)| (session.updated_at, id.clone(), serde_json::to_vec(session).map_or(0, |json| json.len() as u64)))
+            .collect();
+        let mut total: u64 = sizes.iter().map(|(_, _, size)| size).sum();
+        sizes.sort();
+
+        let mut evicted = 0;
+        for (_, id, size) in sizes {
+            if total <= max_bytes {
+                break;
+            }
+            self.sessions.remove(&id);
+            total -= size;
+            evicted += 1;
+        }
+        evicted
+    }
+}
+
+/// A conversation split into exchanges, each starting at a prompt, with long ones cut at lines.
+pub fn chunk(content: &str) -> Vec<String> {
+    let content = conversation::normalize(content);
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for line in content.lines() {
+        let starts_exchange = line.starts_with("Human:");
+        if !current.is_empty() && (starts_exchange || current.len() + line.len() > CHUNK_CHARS) {
+            chunks.push(std::mem::take(&mut current));
+        }
+        current.push_str(line);
+        current.push('\n');
+    }
+    if !current.trim().is_empty() {
+        chunks.push(current);
+    }
+    chunks.retain(|chunk| !chunk.trim().is_empty());
+    chunks
+}
+
+fn hash(text: &str) -> String {
+    let digest = Sha256::digest(text.as_bytes());
+    digest[..8].iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// An on-device embedding: words and word pairs hashed into `DIMENSIONS` signed buckets, so
+/// chunks sharing vocabulary point the same way.
+pub fn embed(text: &str) -> Vec<i8> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.len() > 1)
+        .map(str::to_lowercase)
+        .collect();
+    let mut vector = vec![0.0f64; DIMENSIONS];
+    let pairs = words.windows(2).map(|pair| format!("{} {}", pair[0], pair[1]));
+    for feature in words.iter().cloned().chain(pairs) {
+        let hash = fnv1a(feature.as_bytes());
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[(hash % DIMENSIONS as u64) as usize] += sign;
+    }
+
+    let norm = vector.iter().map(|value| value * value).sum::<f64>().sqrt();
+    vector
+        .into_iter()
+        .map(|value| if norm > 0.0 { (value / norm * 127.0).round() as i8 } else { 0 })
+        .collect()
+}
+
+/// Cosine similarity of two cached vectors, -1 to 1.
+pub fn similarity(a: &[i8], b: &[i8]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(&x, &y)| x as f64 * y as f64).sum();
+    let norm = |vector: &[i8]| vector.iter().map(|&value| (value as f64).powi(2)).sum::<f64>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms > 0.0 { dot / norms } else { 0.0 }
+}
+
+/// FNV-1a: stable across builds, unlike the standard library's hasher.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embeds_only_new_chunks_and_evicts_the_oldest_sessions() {
+        let first = "Human: add retries to the upload client\nAssistant: Added exponential backoff to the upload client.\n";
+        let grown = format!("{}Human: now write tests for the retries\nAssistant: Tests added.\n", first);
+        let now = Utc::now();
+
+        let mut cache = EmbeddingCache::default();
+        assert_eq!(cache.update("s1", first, now), (0, 1));
+        assert_eq!(cache.update("s1", &grown, now), (1, 1));
+        assert_eq!(cache.chunks(), 2);
+
+        let vectors = &cache.sessions["s1"].chunks;
+        assert!(similarity(&vectors[0].vector, &embed("retries for the upload client")) > similarity(&vectors[0].vector, &embed("render the weekly chart")));
+        assert!((similarity(&vectors[0].vector, &vectors[0].vector) - 1.0).abs() < 1e-9);
+
+        cache.update("s2", "Human: something else entirely\n", now + chrono::Duration::minutes(1));
+        let size = serde_json::to_vec(&cache.sessions["s2"]).unwrap().len() as u64;
+        assert_eq!(cache.enforce_limit(size), 1);
+        assert!(cache.sessions.contains_key("s2") && !cache.sessions.contains_key("s1"));
+
+        let dir = tempfile::tempdir().unwrap();
+        cache.save(dir.path()).unwrap();
+        assert_eq!(EmbeddingCache::load(dir.path()).unwrap().chunks(), 1);
+        assert!(EmbeddingCache::remove(dir.path()).unwrap());
+    }
+}
@@ -0,0 +1,317 @@
+use crate::logger::ClaudeLogger;
+use crate::project;
+use crate::session::{SessionMetadata, SessionsMetadata};
+use anyhow::{Context, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Starts every encrypted log, ahead of the nonce and the ChaCha20-Poly1305 ciphertext.
+const MAGIC: &[u8] = b"claude-logger-encrypted-v1\n";
+/// Hex-encoded key used instead of the keyring's, e.g. on a headless server.
+pub const KEY_ENV: &str = "CLAUDE_LOGGER_ENCRYPTION_KEY";
+
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// The plaintext of a log read from disk, storage or git history; logs that were never
+/// encrypted are returned as they are.
+pub fn open(bytes: Vec<u8>) -> Result<Vec<u8>> {
+    if !is_encrypted(&bytes) {
+        return Ok(bytes);
+    }
+    imp::decrypt(&imp::key(false)?, &bytes)
+}
+
+/// Encrypt `plaintext` with the key from the keyring, creating the key on first use.
+pub fn seal(plaintext: &[u8]) -> Result<Vec<u8>> {
+    imp::encrypt(&imp::key(true)?, plaintext)
+}
+
+/// Make sure logs can be encrypted before a session starts rather than after it, creating
+/// the key if there is none yet.
+pub fn ensure_key() -> Result<()> {
+    imp::key(true).map(|_| ())
+}
+
+/// Encrypt the file at `path` in place, keeping a log `archive` compressed.
+/// Returns `false` when it already was encrypted.
+pub fn seal_file(path: &Path) -> Result<bool> {
+    seal_file_with(&imp::key(true)?, path)
+}
+
+/// Written beside the log and renamed over it, so a crash or a full disk midway never leaves the
+/// only copy of a log half-written.
+fn seal_file_with(key: &imp::Key, path: &Path) -> Result<bool> {
+    let bytes = project::read_stored_log(path)?;
+    if is_encrypted(&bytes) {
+        return Ok(false);
+    }
+    let mut sealed = imp::encrypt(key, &bytes)?;
+    if project::is_compressed(path) {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&sealed)?;
+        sealed = encoder.finish().with_context(|| format!("Failed to compress {}", path.display()))?;
+    }
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let temporary = PathBuf::from(temporary);
+    fs::write(&temporary, sealed).with_context(|| format!("Failed to write {}", temporary.display()))?;
+    fs::rename(&temporary, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(true)
+}
+
+/// Whether any of a finished session's logs on disk is still plaintext. Sessions still being
+/// captured are left alone; their logs are encrypted when they finish.
+pub fn has_plaintext_logs(session: &SessionMetadata) -> bool {
+    let finished = session.end_time.is_some() || session.duration_seconds.is_some();
+    finished
+        && std::iter::once(&session.log_file)
+            .chain(&session.clean_log_file)
+            .any(|path| project::read_stored_log(path).is_ok_and(|bytes| !is_encrypted(&bytes)))
+}
+
+/// The sessions `encrypt_existing` would encrypt, oldest first.
+pub fn unencrypted(metadata: &SessionsMetadata) -> Vec<&SessionMetadata> {
+    let mut sessions: Vec<&SessionMetadata> = metadata.sessions.values().filter(|session| has_plaintext_logs(session)).collect();
+    sessions.sort_by_key(|session| session.timestamp);
+    sessions
+}
+
+#[derive(Debug, Default)]
+pub struct EncryptOutcome {
+    /// Sessions whose logs were encrypted
+    pub encrypted: Vec<String>,
+    pub failed: Vec<(String, String)>,
+    /// Log files encrypted, raw and cleaned
+    pub files: usize,
+    /// The logs repository commit recording the change, if any of the logs were tracked
+    pub commit: Option<String>,
+}
+
+/// Encrypt the given sessions' logs in place and commit the change.
+pub fn encrypt_existing(logger: &mut ClaudeLogger, session_ids: &[String]) -> Result<EncryptOutcome> {
+    ensure_key()?;
+    let mut outcome = EncryptOutcome::default();
+    let mut changed = Vec::new();
+
+    for session_id in session_ids {
+        let Some(session) = logger.get_session(session_id) else {
+            continue;
+        };
+        match ClaudeLogger::seal_logs(session) {
+            Ok(files) => {
+                outcome.files += files;
+                changed.push(session.log_file.clone());
+                changed.extend(session.clean_log_file.clone());
+                outcome.encrypted.push(session_id.clone());
+            }
+            Err(e) => outcome.failed.push((session_id.clone(), format!("{:#}", e))),
+        }
+    }
+    if outcome.encrypted.is_empty() {
+        return Ok(outcome);
+    }
+
+    let changed: Vec<&Path> = changed.iter().map(PathBuf::as_path).filter(|path| path.exists()).collect();
+    let message = format!("Encrypt the logs of {} sessions", outcome.encrypted.len());
+    outcome.commit = logger.git_repo().commit_changes(&[], &changed, &message)?;
+    if outcome.commit.is_some() {
+        logger.push_if_enabled();
+    }
+    Ok(outcome)
+}
+
+pub fn print_outcome(outcome: &EncryptOutcome) {
+    for (session_id, error) in &outcome.failed {
+        eprintln!("Warning: Failed to encrypt {}: {}", session_id, error);
+    }
+    if outcome.encrypted.is_empty() {
+        return;
+    }
+    println!("Encrypted {} log files of {} sessions", outcome.files, outcome.encrypted.len());
+    if let Some(commit) = &outcome.commit {
+        println!("Recorded in commit {}", &commit[..commit.len().min(7)]);
+        println!("Earlier commits still hold the plaintext; rewrite the logs repository's history to remove it.");
+    }
+}
+
+#[cfg(feature = "encryption")]
+mod imp {
+    use super::{KEY_ENV, MAGIC};
+    use anyhow::{Context, Result};
+    use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+    use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+    pub use chacha20poly1305::Key;
+    use std::sync::OnceLock;
+
+    const KEYRING_SERVICE: &str = "claude-logger";
+    const KEYRING_USER: &str = "log-encryption-key";
+    const NONCE_LEN: usize = 12;
+
+    /// The key is looked up once per process; every log read would otherwise ask the keyring.
+    static KEY: OnceLock<Key> = OnceLock::new();
+
+    pub fn key(create: bool) -> Result<Key> {
+        if let Some(key) = KEY.get() {
+            return Ok(*key);
+        }
+        let hex = match std::env::var(KEY_ENV) {
+            Ok(hex) => hex,
+            Err(_) => keyring_key(create)?,
+        };
+        let key = parse_key(&hex)?;
+        Ok(*KEY.get_or_init(|| key))
+    }
+
+    fn keyring_key(create: bool) -> Result<String> {
+        let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER).context("Failed to open the OS keyring")?;
+        match entry.get_password() {
+            Ok(hex) => Ok(hex),
+            Err(keyring::Error::NoEntry) if create => {
+                let hex = to_hex(&ChaCha20Poly1305::generate_key(&mut OsRng));
+                entry.set_password(&hex).context("Failed to store the encryption key in the OS keyring")?;
+                eprintln!(
+                    "Created a log encryption key in the OS keyring ({} / {}); back it up, logs can't be read without it",
+                    KEYRING_SERVICE, KEYRING_USER
+                );
+                Ok(hex)
+            }
+            Err(keyring::Error::NoEntry) => Err(anyhow::anyhow!(
+                "No log encryption key in the OS keyring ({} / {}) and {} is not set",
+                KEYRING_SERVICE,
+                KEYRING_USER,
+                KEY_ENV
+            )),
+            Err(e) => Err(e).context("Failed to read the encryption key from the OS keyring"),
+        }
+    }
+
+    pub fn parse_key(hex: &str) -> Result<Key> {
+        let hex = hex.trim();
+        let bytes: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| hex.get(i..i + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()))
+            .collect::<Option<_>>()
+            .filter(|bytes: &Vec<u8>| bytes.len() == 32)
+            .context("The encryption key must be 64 hex digits")?;
+        Ok(*Key::from_slice(&bytes))
+    }
+
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    pub fn encrypt(key: &Key, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = ChaCha20Poly1305::new(key)
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| anyhow::anyhow!("Failed to encrypt log"))?;
+        let mut sealed = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(MAGIC);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    pub fn decrypt(key: &Key, sealed: &[u8]) -> Result<Vec<u8>> {
+        let body = &sealed[MAGIC.len()..];
+        if body.len() < NONCE_LEN {
+            anyhow::bail!("Encrypted log is truncated");
+        }
+        let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+        ChaCha20Poly1305::new(key)
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow::anyhow!("Failed to decrypt log: wrong key or corrupted file"))
+    }
+}
+
+#[cfg(not(feature = "encryption"))]
+mod imp {
+    use anyhow::Result;
+
+    pub struct Key;
+
+    fn unavailable() -> anyhow::Error {
+        anyhow::anyhow!("Log encryption is not compiled in; rebuild with `--features encryption`")
+    }
+
+    pub fn key(_create: bool) -> Result<Key> {
+        Err(unavailable())
+    }
+
+    pub fn encrypt(_key: &Key, _plaintext: &[u8]) -> Result<Vec<u8>> {
+        Err(unavailable())
+    }
+
+    pub fn decrypt(_key: &Key, _sealed: &[u8]) -> Result<Vec<u8>> {
+        Err(unavailable())
+    }
+}
+
+#[cfg(all(test, feature = "encryption"))]
+mod tests {
+    use super::*;
+    use crate::synth::{generate, sessions};
+    use chrono::Utc;
+
+    #[test]
+    fn round_trips_and_rejects_the_wrong_key() {
+        let key = imp::parse_key(&"ab".repeat(32)).unwrap();
+        let sealed = imp::encrypt(&key, b"Human: here is my API key").unwrap();
+        assert!(is_encrypted(&sealed));
+        assert!(!sealed.windows(7).any(|window| window == b"API key"));
+        assert_eq!(imp::decrypt(&key, &sealed).unwrap(), b"Human: here is my API key");
+
+        let other = imp::parse_key(&"cd".repeat(32)).unwrap();
+        assert!(imp::decrypt(&other, &sealed).is_err());
+        assert!(imp::parse_key("abc").is_err());
+        assert_eq!(open(b"plain".to_vec()).unwrap(), b"plain");
+    }
+
+    #[test]
+    fn seals_a_compressed_archive_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.log.gz");
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"Human: archived").unwrap();
+        fs::write(&path, encoder.finish().unwrap()).unwrap();
+
+        let key = imp::parse_key(&"ab".repeat(32)).unwrap();
+        assert!(seal_file_with(&key, &path).unwrap());
+        let stored = project::read_stored_log(&path).unwrap();
+        assert!(is_encrypted(&stored));
+        assert_eq!(imp::decrypt(&key, &stored).unwrap(), b"Human: archived");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        assert!(!seal_file_with(&key, &path).unwrap());
+    }
+
+    #[test]
+    fn lists_finished_sessions_with_plaintext_logs() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut metadata = SessionsMetadata::new();
+        for synthetic in generate(&sessions(3, 5, "api"), dir.path(), Utc::now()) {
+            fs::create_dir_all(synthetic.session.log_file.parent().unwrap()).unwrap();
+            fs::write(&synthetic.session.log_file, &synthetic.log).unwrap();
+            metadata.sessions.insert(synthetic.session.id.clone(), synthetic.session);
+        }
+        let mut ids: Vec<String> = metadata.sessions.keys().cloned().collect();
+        ids.sort();
+
+        let key = imp::parse_key(&"ab".repeat(32)).unwrap();
+        seal_file_with(&key, &metadata.sessions[&ids[0]].log_file).unwrap();
+        let running = metadata.sessions.get_mut(&ids[2]).unwrap();
+        running.end_time = None;
+        running.duration_seconds = None;
+
+        assert!(!has_plaintext_logs(&metadata.sessions[&ids[0]]));
+        assert!(has_plaintext_logs(&metadata.sessions[&ids[1]]));
+        assert!(!has_plaintext_logs(&metadata.sessions[&ids[2]]));
+        let pending: Vec<&str> = unencrypted(&metadata).iter().map(|session| session.id.as_str()).collect();
+        assert_eq!(pending, vec![ids[1].as_str()]);
+    }
+}
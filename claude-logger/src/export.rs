@@ -0,0 +1,214 @@
+use crate::analyzer::{SessionAnalyzer, SessionSummary};
+use crate::session::SessionMetadata;
+use crate::timezone::DisplayZone;
+use anyhow::{Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// Column headers shared by the CSV and markdown exports.
+const COLUMNS: &[&str] = &[
+    "id",
+    "timestamp",
+    "project",
+    "methodology",
+    "duration_minutes",
+    "creative_energy",
+    "files_touched",
+    "lines_added",
+    "lines_removed",
+    "review_state",
+    "tags",
+    "exchanges",
+    "code_blocks",
+    "file_edits",
+    "questions_asked",
+    "enthusiasm_markers",
+    "confusion_markers",
+    "compaction_indicators",
+    "context_compactions",
+    "error_events",
+    "retry_events",
+    "tool_calls",
+    "input_tokens",
+    "output_tokens",
+    "engagement",
+    "clarity",
+    "productivity",
+    "overall",
+];
+
+/// Columns of `list --format tsv`, in this order. Columns are only ever appended, so scripts that
+/// pick fields by position keep working.
+pub const TSV_COLUMNS: &[&str] = &[
+    "id",
+    "start",
+    "end",
+    "duration_seconds",
+    "methodology",
+    "project",
+    "creative_energy",
+    "review_state",
+    "tags",
+    "log_file",
+];
+
+/// Metadata, metrics and quality for each session; sessions whose log can't be read are skipped.
+pub fn summaries(analyzer: &SessionAnalyzer, sessions: &[&SessionMetadata]) -> Vec<SessionSummary> {
+    sessions
+        .iter()
+        .filter_map(|session| match analyzer.get_session_summary(&session.id) {
+            Ok(summary) => Some(summary),
+            Err(e) => {
+                eprintln!("Warning: Failed to analyze session {}: {:#}", session.id, e);
+                None
+            }
+        })
+        .collect()
+}
+
+pub fn to_json(summaries: &[SessionSummary]) -> Result<String> {
+    serde_json::to_string_pretty(summaries).context("Failed to serialize sessions to JSON")
+}
+
+pub fn to_csv(summaries: &[SessionSummary], zone: DisplayZone) -> String {
+    let extra_keys = extra_keys(summaries);
+    let mut csv = header(&extra_keys).join(",") + "\n";
+    for summary in summaries {
+        let fields: Vec<String> = row(summary, &extra_keys, zone).iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+pub fn to_markdown(summaries: &[SessionSummary], zone: DisplayZone) -> String {
+    let extra_keys = extra_keys(summaries);
+    let header = header(&extra_keys);
+    let mut markdown = format!("| {} |\n", header.join(" | "));
+    markdown.push_str(&format!("|{}\n", "---|".repeat(header.len())));
+    for summary in summaries {
+        let fields: Vec<String> = row(summary, &extra_keys, zone).iter().map(|field| field.replace('|', "\\|")).collect();
+        markdown.push_str(&format!("| {} |\n", fields.join(" | ")));
+    }
+    markdown
+}
+
+/// Every custom field used by any of the sessions, sorted; each becomes a `meta.<key>` column.
+fn extra_keys(summaries: &[SessionSummary]) -> Vec<&str> {
+    let keys: BTreeSet<&str> =
+        summaries.iter().flat_map(|summary| summary.session.extra.keys().map(String::as_str)).collect();
+    keys.into_iter().collect()
+}
+
+fn header(extra_keys: &[&str]) -> Vec<String> {
+    COLUMNS
+        .iter()
+        .map(|column| column.to_string())
+        .chain(extra_keys.iter().map(|key| format!("meta.{}", key)))
+        .collect()
+}
+
+fn row(summary: &SessionSummary, extra_keys: &[&str], zone: DisplayZone) -> Vec<String> {
+    let (session, metrics, quality) = (&summary.session, &summary.metrics, &summary.quality);
+    let mut row = vec![
+        session.id.clone(),
+        zone.rfc3339(session.timestamp),
+        session.project.clone(),
+        session.methodology.to_string(),
+        session.duration().map(|duration| duration.num_minutes().to_string()).unwrap_or_default(),
+        session.creative_energy.map(|energy| energy.to_string()).unwrap_or_default(),
+        session.project_changes.as_ref().map(|changes| changes.files_touched.to_string()).unwrap_or_default(),
+        session.project_changes.as_ref().map(|changes| changes.lines_added.to_string()).unwrap_or_default(),
+        session.project_changes.as_ref().map(|changes| changes.lines_removed.to_string()).unwrap_or_default(),
+        session.review_state.to_string(),
+        session.tags.join(" "),
+        metrics.exchanges.to_string(),
+        metrics.code_blocks.to_string(),
+        metrics.file_edits.to_string(),
+        metrics.questions_asked.to_string(),
+        metrics.enthusiasm_markers.to_string(),
+        metrics.confusion_markers.to_string(),
+        metrics.compaction_indicators.to_string(),
+        session.compactions.len().to_string(),
+        metrics.error_events.to_string(),
+        metrics.retry_events.to_string(),
+        metrics.tool_calls.to_string(),
+        metrics.input_tokens.to_string(),
+        metrics.output_tokens.to_string(),
+        format!("{:.1}", quality.engagement_score),
+        format!("{:.1}", quality.clarity_score),
+        format!("{:.1}", quality.productivity_score),
+        format!("{:.1}", quality.overall_score),
+    ];
+    // Strings are written bare; other values as JSON
+    row.extend(extra_keys.iter().map(|key| match session.extra.get(*key) {
+        Some(Value::String(text)) => text.clone(),
+        Some(value) => value.to_string(),
+        None => String::new(),
+    }));
+    row
+}
+
+/// One line per session with the [`TSV_COLUMNS`] and no header. Times are UTC RFC 3339 whatever
+/// the display zone, missing values are empty and tags are comma-separated.
+pub fn to_tsv(sessions: &[SessionMetadata]) -> String {
+    let time = |timestamp: DateTime<Utc>| timestamp.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let mut tsv = String::new();
+    for session in sessions {
+        let fields = [
+            session.id.clone(),
+            time(session.timestamp),
+            session.end_time.map(time).unwrap_or_default(),
+            session.duration().map(|duration| duration.num_seconds().to_string()).unwrap_or_default(),
+            session.methodology.to_string(),
+            session.project.clone(),
+            session.creative_energy.map(|energy| energy.to_string()).unwrap_or_default(),
+            session.review_state.to_string(),
+            session.tags.join(","),
+            session.log_file.display().to_string(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|field| tsv_field(field)).collect();
+        tsv.push_str(&fields.join("\t"));
+        tsv.push('\n');
+    }
+    tsv
+}
+
+/// Backslash-escape the separators so every session stays one line of tab-separated fields.
+fn tsv_field(field: &str) -> String {
+    field.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n").replace('\r', "\\r")
+}
+
+/// Quote a field when it holds a separator, quote or line break (RFC 4180).
+pub(crate) fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::{generate, sessions};
+
+    #[test]
+    fn tsv_rows_keep_their_columns() {
+        let options = sessions(1, 3, "api");
+        let dir = tempfile::tempdir().unwrap();
+        let mut session = generate(&options, dir.path(), Utc::now()).remove(0).session;
+        session.project = "odd\tname".to_string();
+        session.tags = vec!["a\\b".to_string(), "two\nlines".to_string()];
+
+        let tsv = to_tsv(&[session.clone()]);
+        assert_eq!(tsv.lines().count(), 1);
+        let fields: Vec<&str> = tsv.trim_end_matches('\n').split('\t').collect();
+        assert_eq!(fields.len(), TSV_COLUMNS.len());
+        assert_eq!(fields[0], session.id);
+        assert!(fields[1].ends_with('Z'));
+        assert_eq!(fields[5], "odd\\tname");
+        assert_eq!(fields[8], "a\\\\b,two\\nlines");
+    }
+}
@@ -0,0 +1,192 @@
+use crate::conversation::{parse_turns, Block, Speaker};
+use crate::edits::parse_edits;
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::HashMap;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// At most this many features are recorded for a session.
+const MAX_FEATURES: usize = 5;
+/// Edited areas of the code named as features, most edited first.
+const MAX_EDITED: usize = 3;
+
+/// Branches that name a line of development rather than a feature.
+const TRUNK_BRANCHES: &[&str] = &["main", "master", "develop", "development", "dev", "trunk", "HEAD"];
+/// Directories too general to name a feature; the edited file's own name is used instead.
+const GENERIC_DIRS: &[&str] = &["src", "lib", "app", "tests", "test", "spec", "pkg", "internal", "cmd", "bin", "components", "."];
+/// File names that say nothing about what is in them.
+const GENERIC_FILES: &[&str] = &["mod", "index", "main", "lib", "__init__", "init", "readme", "cargo", "package"];
+/// Words that end a phrase like "add rate limiting to the upload handler".
+const STOP_WORDS: &[&str] = &[
+    "to", "in", "for", "so", "that", "with", "and", "on", "of", "from", "into", "which", "when", "by", "using", "it",
+    "this", "please", "then", "but", "or", "as", "at", "if",
+];
+/// Phrases too vague to be a feature on their own.
+const VAGUE: &[&str] = &["test", "tests", "code", "function", "method", "file", "feature", "support", "some", "more", "new", "logging"];
+
+/// Features a session worked on, from the branch it ran on, what was asked for ("implement X",
+/// "add support for Y") and the parts of the code Claude edited. Names are lowercase and
+/// hyphenated; the branch comes first.
+pub fn detect(content: &str, branch: Option<&str>) -> Vec<String> {
+    let mut features: Vec<String> = Vec::new();
+    let candidates = branch.and_then(from_branch).into_iter().chain(from_prompts(content)).chain(from_edits(content));
+    for feature in candidates {
+        if !features.contains(&feature) {
+            features.push(feature);
+        }
+    }
+    features.truncate(MAX_FEATURES);
+    features
+}
+
+/// The branch checked out in `project_dir`, when it is in a git repository and not detached.
+pub fn current_branch(project_dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .current_dir(project_dir)
+        .output()
+        .ok()?;
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !branch.is_empty()).then_some(branch)
+}
+
+/// `feature/123-upload-retries` names `upload-retries`; trunk branches name nothing.
+fn from_branch(branch: &str) -> Option<String> {
+    if TRUNK_BRANCHES.contains(&branch) {
+        return None;
+    }
+    static TICKET: OnceLock<Regex> = OnceLock::new();
+    let ticket = TICKET.get_or_init(|| Regex::new(r"^(?:[A-Za-z]+-)?\d+[-_]").expect("valid regex"));
+    let name = branch.rsplit('/').next().unwrap_or(branch);
+    let name = normalize(&ticket.replace(name, "").replace('_', "-"));
+    (!name.is_empty()).then_some(name)
+}
+
+fn from_prompts(content: &str) -> Vec<String> {
+    static ASK: OnceLock<Regex> = OnceLock::new();
+    let ask = ASK.get_or_init(|| {
+        Regex::new(
+            r"(?i)\b(?:implement|implementing|add|adding|build|building|create|creating)\s+(?:(?:an?|the|some)\s+)?(?:support\s+for\s+(?:(?:an?|the)\s+)?)?([a-z][a-z0-9-]*(?:\s+[a-z][a-z0-9-]*){0,3})",
+        )
+        .expect("valid regex")
+    });
+
+    let mut features = Vec::new();
+    for turn in parse_turns(content).into_iter().filter(|turn| turn.speaker == Speaker::Human) {
+        for block in turn.blocks {
+            let Block::Text { text } = block else {
+                continue;
+            };
+            for captures in ask.captures_iter(&text) {
+                let words: Vec<&str> = captures[1]
+                    .split_whitespace()
+                    .take_while(|word| !STOP_WORDS.contains(&word.to_lowercase().as_str()))
+                    .collect();
+                let feature = normalize(&words.join(" "));
+                if !feature.is_empty() && !VAGUE.contains(&feature.as_str()) {
+                    features.push(feature);
+                }
+            }
+        }
+    }
+    features
+}
+
+/// The directory of each edited file, or the file's own name when the directory is a generic
+/// one like `src`, counted and most edited first.
+fn from_edits(content: &str) -> Vec<String> {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for edit in parse_edits(content) {
+        let path = Path::new(&edit.file);
+        let directory = path.parent().and_then(Path::file_name).and_then(|name| name.to_str()).unwrap_or(".");
+        let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+        let name = if !GENERIC_DIRS.contains(&directory) {
+            directory
+        } else if !GENERIC_FILES.contains(&stem.to_lowercase().as_str()) {
+            stem
+        } else {
+            continue;
+        };
+        let name = normalize(name);
+        if name.is_empty() {
+            continue;
+        }
+        match index.get(&name) {
+            Some(&position) => counts[position].1 += 1,
+            None => {
+                index.insert(name.clone(), counts.len());
+                counts.push((name, 1));
+            }
+        }
+    }
+    // Stable, so ties keep the order the files were first edited in
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    counts.into_iter().take(MAX_EDITED).map(|(name, _)| name).collect()
+}
+
+/// Lowercase words joined by hyphens.
+fn normalize(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Show the detected features and let the developer keep them (Enter) or type their own,
+/// comma-separated. Off a terminal the detected features are kept.
+pub fn confirm(detected: Vec<String>) -> Result<Vec<String>> {
+    if !io::stdin().is_terminal() {
+        return Ok(detected);
+    }
+    let shown = if detected.is_empty() { "none detected".to_string() } else { detected.join(", ") };
+    print!("Features worked on [{}] (Enter to keep, or a comma-separated list): ", shown);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).context("Failed to read the features worked on")?;
+    if input.trim().is_empty() {
+        return Ok(detected);
+    }
+    Ok(input.split(',').map(str::trim).filter(|feature| !feature.is_empty()).map(str::to_string).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_features_in_branch_prompts_and_edits() {
+        let content = "\
+Human: Please implement rate limiting for the upload endpoint, and add support for resumable uploads.
+
+Assistant: I'll start with the limiter.
+
+⏺ Update(src/upload/limiter.rs)
+  ⎿  Updated src/upload/limiter.rs with 2 additions
+       1 +  pub struct Limiter;
+       2 +  impl Limiter {}
+
+⏺ Write(src/quota.rs)
+  ⎿  Wrote 1 lines to src/quota.rs
+       1 pub fn quota() {}
+
+⏺ Update(src/upload/mod.rs)
+  ⎿  Updated src/upload/mod.rs with 1 addition
+       1 +  pub mod limiter;
+
+Human: Now add tests to cover it.
+";
+        assert_eq!(
+            detect(content, Some("feature/PROJ-142-upload-throttling")),
+            ["upload-throttling", "rate-limiting", "resumable-uploads", "upload", "quota"]
+        );
+        // Trunk branches and vague requests ("add tests") name nothing
+        assert_eq!(detect("Human: add tests\n", Some("main")), Vec::<String>::new());
+        assert_eq!(from_branch("fix/flaky_ci"), Some("flaky-ci".to_string()));
+    }
+}
@@ -0,0 +1,104 @@
+use crate::analyzer::SessionAnalyzer;
+use crate::session::SessionMetadata;
+use crate::timezone::DisplayZone;
+use chrono::Utc;
+
+const FEED_ID: &str = "urn:claude-logger:sessions";
+
+/// Render recent sessions as an Atom feed.
+///
+/// `base_url` is used for entry links when the feed is served over HTTP; timestamps
+/// carry the offset of `zone`.
+pub fn sessions_feed(analyzer: &SessionAnalyzer, limit: usize, base_url: Option<&str>, zone: DisplayZone) -> String {
+    render_feed(analyzer, &analyzer.metadata().recent_sessions(None, limit), base_url, zone)
+}
+
+/// Render the given sessions, newest first, as an Atom feed.
+pub fn render_feed(
+    analyzer: &SessionAnalyzer,
+    sessions: &[&SessionMetadata],
+    base_url: Option<&str>,
+    zone: DisplayZone,
+) -> String {
+    let updated = sessions
+        .first()
+        .map(|session| session.end_time.unwrap_or(session.timestamp))
+        .unwrap_or_else(Utc::now);
+
+    let mut feed = String::new();
+    feed.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    feed.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    feed.push_str("  <title>Claude Code Sessions</title>\n");
+    feed.push_str(&format!("  <id>{}</id>\n", FEED_ID));
+    feed.push_str(&format!("  <updated>{}</updated>\n", zone.rfc3339(updated)));
+    feed.push_str("  <author><name>claude-logger</name></author>\n");
+    feed.push_str("  <generator>claude-logger</generator>\n");
+    if let Some(base_url) = base_url {
+        feed.push_str(&format!(
+            "  <link rel=\"self\" href=\"{}/feed.atom\"/>\n",
+            escape_xml(base_url)
+        ));
+    }
+
+    for session in sessions {
+        feed.push_str(&entry(analyzer, session, base_url, zone));
+    }
+
+    feed.push_str("</feed>\n");
+    feed
+}
+
+fn entry(analyzer: &SessionAnalyzer, session: &SessionMetadata, base_url: Option<&str>, zone: DisplayZone) -> String {
+    let title = format!("{} session on {}", session.methodology, session.project);
+    let updated = session.end_time.unwrap_or(session.timestamp);
+
+    let mut entry = String::new();
+    entry.push_str("  <entry>\n");
+    entry.push_str(&format!("    <title>{}</title>\n", escape_xml(&title)));
+    entry.push_str(&format!("    <id>{}:{}</id>\n", FEED_ID, escape_xml(&session.id)));
+    entry.push_str(&format!("    <published>{}</published>\n", zone.rfc3339(session.timestamp)));
+    entry.push_str(&format!("    <updated>{}</updated>\n", zone.rfc3339(updated)));
+    if let Some(base_url) = base_url {
+        entry.push_str(&format!(
+            "    <link href=\"{}/sessions/{}\"/>\n",
+            escape_xml(base_url),
+            escape_xml(&session.id)
+        ));
+    }
+    entry.push_str(&format!(
+        "    <summary>{}</summary>\n",
+        escape_xml(&summary(analyzer, session))
+    ));
+    entry.push_str("  </entry>\n");
+    entry
+}
+
+fn summary(analyzer: &SessionAnalyzer, session: &SessionMetadata) -> String {
+    let mut parts = vec![format!("Project: {}", session.project)];
+
+    if let Some(duration) = session.duration() {
+        parts.push(format!("Duration: {}m", duration.num_minutes()));
+    }
+    if let Some(energy) = session.creative_energy {
+        parts.push(format!("Energy: {}/3", energy));
+    }
+    if let Ok(summary) = analyzer.get_session_summary(&session.id) {
+        parts.push(format!("Exchanges: {}", summary.metrics.exchanges));
+        parts.push(format!("Code Blocks: {}", summary.metrics.code_blocks));
+        parts.push(format!("File Edits: {}", summary.metrics.file_edits));
+        parts.push(format!("Quality: {:.1}/100", summary.quality.overall_score));
+    }
+    if !session.features_worked_on.is_empty() {
+        parts.push(format!("Features: {}", session.features_worked_on.join(", ")));
+    }
+
+    parts.join(" | ")
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
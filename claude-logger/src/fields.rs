@@ -0,0 +1,215 @@
+use crate::config::{FieldKind, FieldSpec};
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, IsTerminal, Write};
+
+/// Reject field declarations that could never accept a value.
+pub fn check_specs(specs: &[FieldSpec]) -> Result<()> {
+    let mut seen = HashSet::new();
+    for spec in specs {
+        if spec.name.trim().is_empty() {
+            anyhow::bail!("a field has an empty name");
+        }
+        if !seen.insert(spec.name.as_str()) {
+            anyhow::bail!("field '{}' is declared twice", spec.name);
+        }
+        if spec.kind == FieldKind::Enum && spec.values.is_empty() {
+            anyhow::bail!("enum field '{}' lists no values", spec.name);
+        }
+        if let (Some(min), Some(max)) = (spec.min, spec.max)
+            && min > max
+        {
+            anyhow::bail!("field '{}' has min {} above max {}", spec.name, min, max);
+        }
+    }
+    Ok(())
+}
+
+/// The accepted values, as shown after the prompt: `1-5`, `low/medium/high` or `text`.
+pub fn describe(spec: &FieldSpec) -> String {
+    match spec.kind {
+        FieldKind::Enum => spec.values.join("/"),
+        FieldKind::Text => match spec.max_length {
+            Some(max_length) => format!("text, up to {} characters", max_length),
+            None => "text".to_string(),
+        },
+        FieldKind::Integer | FieldKind::Number => {
+            let kind = if spec.kind == FieldKind::Integer { "whole number" } else { "number" };
+            match (spec.min, spec.max) {
+                (Some(min), Some(max)) => format!("{}-{}", min, max),
+                (Some(min), None) => format!("{} from {}", kind, min),
+                (None, Some(max)) => format!("{} up to {}", kind, max),
+                (None, None) => kind.to_string(),
+            }
+        }
+    }
+}
+
+/// `value` in the field's type, or why it doesn't fit.
+///
+/// Strings are parsed for numeric fields and numbers kept as text for text fields, so answers
+/// typed at the prompt and `--meta` values are held to the same rules.
+pub fn check_value(spec: &FieldSpec, value: &Value) -> Result<Value, String> {
+    let expected = || format!("expected {}", describe(spec));
+    match spec.kind {
+        FieldKind::Integer | FieldKind::Number => {
+            let number = match value {
+                Value::Number(number) => number.as_f64(),
+                Value::String(text) => text.trim().parse::<f64>().ok(),
+                _ => None,
+            }
+            .filter(|number| number.is_finite())
+            .ok_or_else(expected)?;
+
+            let in_range = spec.min.is_none_or(|min| number >= min) && spec.max.is_none_or(|max| number <= max);
+            if !in_range || (spec.kind == FieldKind::Integer && number.fract() != 0.0) {
+                return Err(expected());
+            }
+            Ok(if spec.kind == FieldKind::Integer { Value::from(number as i64) } else { Value::from(number) })
+        }
+        FieldKind::Enum => {
+            let text = match value {
+                Value::String(text) => text.trim().to_string(),
+                other => other.to_string(),
+            };
+            spec.values
+                .iter()
+                .find(|allowed| allowed.eq_ignore_ascii_case(&text))
+                .map(|allowed| Value::String(allowed.clone()))
+                .ok_or_else(expected)
+        }
+        FieldKind::Text => {
+            let text = match value {
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            };
+            if spec.max_length.is_some_and(|max_length| text.chars().count() > max_length) {
+                return Err(expected());
+            }
+            Ok(Value::String(text))
+        }
+    }
+}
+
+/// Check and normalize the declared fields among `extra`; undeclared keys are left alone.
+pub fn validate(specs: &[FieldSpec], extra: &mut HashMap<String, Value>) -> Result<()> {
+    let mut problems = Vec::new();
+    for spec in specs {
+        let Some(value) = extra.get_mut(&spec.name) else {
+            continue;
+        };
+        match check_value(spec, value) {
+            Ok(normalized) => *value = normalized,
+            Err(e) => problems.push(format!("{}: {}, got {}", spec.name, e, value)),
+        }
+    }
+
+    if !problems.is_empty() {
+        anyhow::bail!("Invalid custom fields: {}", problems.join("; "));
+    }
+    Ok(())
+}
+
+/// Required fields with no value yet.
+pub fn missing_required<'a>(specs: &'a [FieldSpec], extra: &HashMap<String, Value>) -> Vec<&'a str> {
+    specs
+        .iter()
+        .filter(|spec| spec.required && !extra.contains_key(&spec.name))
+        .map(|spec| spec.name.as_str())
+        .collect()
+}
+
+/// Ask for each declared field not already set, re-asking until the answer fits.
+///
+/// Nothing is asked when stdin isn't a terminal, so scripted runs never block.
+pub fn prompt(specs: &[FieldSpec], extra: &mut HashMap<String, Value>) -> Result<()> {
+    if !io::stdin().is_terminal() {
+        let missing = missing_required(specs, extra);
+        if !missing.is_empty() {
+            eprintln!("Warning: Required fields left unset: {}; add them with `claude-logger edit`", missing.join(", "));
+        }
+        return Ok(());
+    }
+
+    for spec in specs {
+        if extra.contains_key(&spec.name) {
+            continue;
+        }
+        let question = spec.prompt.as_deref().unwrap_or(&spec.name);
+        let skip = if spec.required { "" } else { ", or press Enter to skip" };
+        loop {
+            print!("{} ({}{}): ", question, describe(spec), skip);
+            io::stdout().flush()?;
+
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)
+                .with_context(|| format!("Failed to read a value for {}", spec.name))?;
+            let input = input.trim();
+
+            if input.is_empty() {
+                if spec.required {
+                    println!("{} is required.", spec.name);
+                    continue;
+                }
+                break;
+            }
+            match check_value(spec, &Value::String(input.to_string())) {
+                Ok(value) => {
+                    extra.insert(spec.name.clone(), value);
+                    break;
+                }
+                Err(e) => println!("Invalid input: {}.", e),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(name: &str, kind: FieldKind) -> FieldSpec {
+        FieldSpec {
+            name: name.to_string(),
+            kind,
+            prompt: None,
+            required: false,
+            min: None,
+            max: None,
+            values: Vec::new(),
+            max_length: None,
+        }
+    }
+
+    #[test]
+    fn validates_and_normalizes_typed_fields() {
+        let complexity = FieldSpec { min: Some(1.0), max: Some(5.0), ..spec("complexity", FieldKind::Integer) };
+        let outcome = FieldSpec { values: vec!["Shipped".to_string(), "Blocked".to_string()], ..spec("outcome", FieldKind::Enum) };
+        let specs = vec![complexity.clone(), outcome.clone(), spec("ticket", FieldKind::Text)];
+
+        assert_eq!(check_value(&complexity, &Value::String(" 3 ".to_string())), Ok(Value::from(3)));
+        assert!(check_value(&complexity, &Value::from(6)).is_err());
+        assert!(check_value(&complexity, &Value::from(2.5)).is_err());
+        assert_eq!(check_value(&outcome, &Value::from("shipped")), Ok(Value::from("Shipped")));
+
+        let mut extra = HashMap::from([
+            ("ticket".to_string(), Value::from(123)),
+            ("outcome".to_string(), Value::from("blocked")),
+            ("other".to_string(), Value::Bool(true)),
+        ]);
+        validate(&specs, &mut extra).unwrap();
+        assert_eq!(extra["ticket"], Value::from("123"));
+        assert_eq!(extra["outcome"], Value::from("Blocked"));
+        assert_eq!(extra["other"], Value::Bool(true));
+
+        extra.insert("complexity".to_string(), Value::from("hard"));
+        let error = validate(&specs, &mut extra).unwrap_err().to_string();
+        assert!(error.contains("complexity: expected 1-5"), "{}", error);
+
+        let duplicate = vec![spec("a", FieldKind::Text), spec("a", FieldKind::Text)];
+        assert!(check_specs(&duplicate).is_err());
+        assert!(check_specs(&[spec("level", FieldKind::Enum)]).is_err());
+    }
+}
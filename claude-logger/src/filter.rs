@@ -0,0 +1,133 @@
+use crate::session::{Methodology, ReviewState, SessionMetadata};
+use crate::timezone::DisplayZone;
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::ValueEnum;
+
+/// One condition of a `--filter` query.
+#[derive(Debug, Clone, PartialEq)]
+enum Term {
+    Project(String),
+    Methodology(Methodology),
+    Tag(String),
+    Author(String),
+    Review(ReviewState),
+    /// A custom field, optionally with the value it must have
+    Field(String, Option<String>),
+    Since(DateTime<Utc>),
+    Until(DateTime<Utc>),
+    /// Case-insensitive text in the id, project or tags
+    Text(String),
+    Not(Box<Term>),
+}
+
+impl Term {
+    fn matches(&self, session: &SessionMetadata) -> bool {
+        match self {
+            Term::Project(project) => session.project.eq_ignore_ascii_case(project),
+            Term::Methodology(methodology) => session.methodology == *methodology,
+            Term::Tag(tag) => session.tags.contains(tag),
+            Term::Author(author) => session.author.as_ref().is_some_and(|name| name.eq_ignore_ascii_case(author)),
+            Term::Review(state) => session.review_state == *state,
+            Term::Field(key, expected) => match (session.extra.get(key), expected) {
+                (Some(value), Some(expected)) => {
+                    value.as_str() == Some(expected.as_str())
+                        || serde_json::from_str::<serde_json::Value>(expected).is_ok_and(|expected| expected == *value)
+                }
+                (value, None) => value.is_some(),
+                (None, Some(_)) => false,
+            },
+            Term::Since(from) => session.timestamp >= *from,
+            Term::Until(to) => session.timestamp < *to,
+            Term::Text(text) => {
+                let text = text.to_lowercase();
+                [&session.id, &session.project]
+                    .into_iter()
+                    .chain(&session.tags)
+                    .any(|field| field.to_lowercase().contains(&text))
+            }
+            Term::Not(term) => !term.matches(session),
+        }
+    }
+}
+
+/// Sessions selected by `--filter`, the same in every command that takes one: every term must match.
+///
+/// Terms are separated by spaces: `project:NAME`, `methodology:NAME`, `tag:NAME`, `author:NAME`,
+/// `review:STATE`, `field:KEY` or `field:KEY=VALUE`, `since:YYYY-MM-DD`, `until:YYYY-MM-DD`
+/// (inclusive, in the display zone) and bare words matched against the id, project and tags.
+/// A leading `-` negates a term. An empty query matches every session.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+impl Query {
+    pub fn parse(query: &str, zone: DisplayZone) -> Result<Self> {
+        let terms = query.split_whitespace().map(|word| parse_term(word, zone)).collect::<Result<_>>()?;
+        Ok(Self { terms })
+    }
+
+    pub fn matches(&self, session: &SessionMetadata) -> bool {
+        self.terms.iter().all(|term| term.matches(session))
+    }
+
+    /// Whether the query has no terms, and so selects every session.
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+}
+
+fn parse_term(word: &str, zone: DisplayZone) -> Result<Term> {
+    if let Some(rest) = word.strip_prefix('-')
+        && !rest.is_empty()
+    {
+        return Ok(Term::Not(Box::new(parse_term(rest, zone)?)));
+    }
+    let Some((key, value)) = word.split_once(':') else {
+        return Ok(Term::Text(word.to_string()));
+    };
+    let date = || {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .with_context(|| format!("Invalid date in '{}': expected YYYY-MM-DD", word))
+    };
+    Ok(match key {
+        "project" => Term::Project(value.to_string()),
+        "methodology" => Term::Methodology(
+            Methodology::from_name(value).with_context(|| format!("Unknown methodology: {}", value))?,
+        ),
+        "tag" => Term::Tag(value.to_string()),
+        "author" => Term::Author(value.to_string()),
+        "review" => Term::Review(
+            ReviewState::from_str(value, true).map_err(|_| anyhow::anyhow!("Unknown review state: {}", value))?,
+        ),
+        "field" => match value.split_once('=') {
+            Some((key, expected)) => Term::Field(key.to_string(), Some(expected.to_string())),
+            None => Term::Field(value.to_string(), None),
+        },
+        "since" => Term::Since(zone.start_of_day(date()?)),
+        "until" => Term::Until(zone.start_of_day(date()?.succ_opt().context("Date out of range")?)),
+        _ => anyhow::bail!(
+            "Unknown filter '{}': use project:, methodology:, tag:, author:, review:, field:, since: or until:",
+            key
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::{generate, sessions};
+    use std::path::Path;
+
+    #[test]
+    fn matches_authors_case_insensitively() {
+        let options = sessions(1, 3, "api");
+        let mut session = generate(&options, Path::new("/logs"), Utc::now()).remove(0).session;
+        let query = Query::parse("author:ada project:API", DisplayZone::Utc).unwrap();
+        assert!(!query.matches(&session));
+        session.author = Some("Ada".to_string());
+        assert!(query.matches(&session));
+        assert!(!Query::parse("-author:Ada", DisplayZone::Utc).unwrap().matches(&session));
+    }
+}
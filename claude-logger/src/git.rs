@@ -1,10 +1,37 @@
 use crate::session::SessionMetadata;
 use anyhow::{Context, Result};
+use serde::Serialize;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+const MANAGED_BLOCK_START: &str = "# BEGIN claude-logger managed patterns";
+const MANAGED_BLOCK_END: &str = "# END claude-logger managed patterns";
+
+/// Wait before the second push attempt, doubling for each one after.
+const PUSH_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// One commit in the logs repository, as `git-log --json` prints it.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    pub hash: String,
+    /// Author date, RFC 3339
+    pub date: String,
+    pub author: String,
+    pub subject: String,
+}
+
+/// Who commits are made as, ahead of git's own `user.name` and `user.email`.
+#[derive(Debug, Clone, Default)]
+pub struct Identity {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
 
 pub struct GitRepo {
     repo_path: PathBuf,
+    identity: Identity,
 }
 
 impl GitRepo {
@@ -44,33 +71,129 @@ impl GitRepo {
 
         Ok(Self {
             repo_path: path.to_path_buf(),
+            identity: Identity::default(),
         })
     }
 
-    pub fn commit_session(&self, session: &SessionMetadata, log_file: &Path) -> Result<String> {
-        // Add the log file to git
-        let log_filename = log_file
-            .file_name()
-            .and_then(|name| name.to_str())
-            .context("Invalid log file name")?;
+    /// Open an existing repository without initializing one.
+    pub fn open(path: &Path) -> Option<Self> {
+        path.join(".git").exists().then(|| Self {
+            repo_path: path.to_path_buf(),
+            identity: Identity::default(),
+        })
+    }
 
-        let add_output = Command::new("git")
-            .args(["add", log_filename])
-            .current_dir(&self.repo_path)
-            .output()
-            .context("Failed to run git add")?;
+    /// Make commits as `identity` rather than whoever git is configured for.
+    pub fn with_identity(mut self, identity: Identity) -> Self {
+        self.identity = identity;
+        self
+    }
+
+    /// `git commit`, run in the repository as the configured identity.
+    fn commit_command(&self) -> Command {
+        let mut command = Command::new("git");
+        command.arg("commit").current_dir(&self.repo_path);
+        if let Some(name) = &self.identity.name {
+            command.env("GIT_AUTHOR_NAME", name).env("GIT_COMMITTER_NAME", name);
+        }
+        if let Some(email) = &self.identity.email {
+            command.env("GIT_AUTHOR_EMAIL", email).env("GIT_COMMITTER_EMAIL", email);
+        }
+        command
+    }
+
+    /// Commit a session's log files (the raw capture and its cleaned copy) in one commit.
+    pub fn commit_session(&self, session: &SessionMetadata, log_files: &[&Path]) -> Result<String> {
+        for log_file in log_files {
+            // Add the log file to git
+            let log_filename = log_file
+                .file_name()
+                .and_then(|name| name.to_str())
+                .context("Invalid log file name")?;
 
-        if !add_output.status.success() {
-            let stderr = String::from_utf8_lossy(&add_output.stderr);
-            return Err(anyhow::anyhow!("Git add failed: {}", stderr));
+            if self.is_ignored(log_file) {
+                return Err(anyhow::anyhow!(
+                    "Log file {} matches an ignore pattern and cannot be committed",
+                    log_filename
+                ));
+            }
+
+            let add_output = Command::new("git")
+                .args(["add", &self.relative_path(log_file)])
+                .current_dir(&self.repo_path)
+                .output()
+                .context("Failed to run git add")?;
+
+            if !add_output.status.success() {
+                let stderr = String::from_utf8_lossy(&add_output.stderr);
+                return Err(anyhow::anyhow!("Git add failed: {}", stderr));
+            }
         }
 
         // Create commit message
         let commit_message = self.generate_commit_message(session);
 
-        let commit_output = Command::new("git")
-            .args(["commit", "-m", &commit_message])
+        let commit_output = self
+            .commit_command()
+            .args(["-m", &commit_message])
+            .output()
+            .context("Failed to run git commit")?;
+
+        if !commit_output.status.success() {
+            let stderr = String::from_utf8_lossy(&commit_output.stderr);
+            return Err(anyhow::anyhow!("Git commit failed: {}", stderr));
+        }
+
+        self.head()
+    }
+
+    /// Commit the removal of `removed` and the addition of `added` in one commit.
+    ///
+    /// Paths git never tracked are skipped; returns `None` when that leaves nothing to commit.
+    pub fn commit_changes(&self, removed: &[&Path], added: &[&Path], message: &str) -> Result<Option<String>> {
+        for path in removed {
+            let output = Command::new("git")
+                .args(["rm", "--cached", "--ignore-unmatch", "-q", "--"])
+                .arg(self.relative_path(path))
+                .current_dir(&self.repo_path)
+                .output()
+                .context("Failed to run git rm")?;
+
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(anyhow::anyhow!("Git rm failed: {}", stderr));
+            }
+        }
+
+        for path in added {
+            if self.is_ignored(path) {
+                continue;
+            }
+            let output = Command::new("git")
+                .args(["add", "--"])
+                .arg(self.relative_path(path))
+                .current_dir(&self.repo_path)
+                .output()
+                .context("Failed to run git add")?;
+
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(anyhow::anyhow!("Git add failed: {}", stderr));
+            }
+        }
+
+        let staged = Command::new("git")
+            .args(["diff", "--cached", "--quiet"])
             .current_dir(&self.repo_path)
+            .status()
+            .context("Failed to run git diff")?;
+        if staged.success() {
+            return Ok(None);
+        }
+
+        let commit_output = self
+            .commit_command()
+            .args(["-m", message])
             .output()
             .context("Failed to run git commit")?;
 
@@ -79,15 +202,102 @@ impl GitRepo {
             return Err(anyhow::anyhow!("Git commit failed: {}", stderr));
         }
 
-        // Get the commit hash
+        self.head().map(Some)
+    }
+
+    fn head(&self) -> Result<String> {
         let hash_output = Command::new("git")
             .args(["rev-parse", "HEAD"])
             .current_dir(&self.repo_path)
             .output()
             .context("Failed to get commit hash")?;
 
-        let commit_hash = String::from_utf8_lossy(&hash_output.stdout).trim().to_string();
-        Ok(commit_hash)
+        Ok(String::from_utf8_lossy(&hash_output.stdout).trim().to_string())
+    }
+
+    /// Rewrite the tool-managed block of `.gitignore`, committing it when it changes.
+    ///
+    /// Lines outside the managed block are left untouched so manual entries survive. A block
+    /// missing its end marker is an error, since rewriting it would drop every line after it.
+    pub fn sync_gitignore(&self, patterns: &[String]) -> Result<bool> {
+        let gitignore_path = self.repo_path.join(".gitignore");
+        let existing = std::fs::read_to_string(&gitignore_path).unwrap_or_default();
+
+        let mut unmanaged = Vec::new();
+        let mut in_block = false;
+        for line in existing.lines() {
+            match line {
+                MANAGED_BLOCK_START => in_block = true,
+                MANAGED_BLOCK_END => in_block = false,
+                _ if !in_block => unmanaged.push(line),
+                _ => {}
+            }
+        }
+        if in_block {
+            anyhow::bail!(
+                "{} has '{}' without a matching '{}' line; restore or remove it by hand",
+                gitignore_path.display(),
+                MANAGED_BLOCK_START,
+                MANAGED_BLOCK_END
+            );
+        }
+        while unmanaged.last().is_some_and(|line| line.trim().is_empty()) {
+            unmanaged.pop();
+        }
+
+        let mut updated = String::new();
+        for line in &unmanaged {
+            updated.push_str(line);
+            updated.push('\n');
+        }
+        if !unmanaged.is_empty() {
+            updated.push('\n');
+        }
+        updated.push_str(MANAGED_BLOCK_START);
+        updated.push('\n');
+        for pattern in patterns {
+            updated.push_str(pattern);
+            updated.push('\n');
+        }
+        updated.push_str(MANAGED_BLOCK_END);
+        updated.push('\n');
+
+        if updated == existing {
+            return Ok(false);
+        }
+
+        std::fs::write(&gitignore_path, &updated)
+            .with_context(|| format!("Failed to write {}", gitignore_path.display()))?;
+
+        Command::new("git")
+            .args(["add", ".gitignore"])
+            .current_dir(&self.repo_path)
+            .output()
+            .context("Failed to add .gitignore")?;
+
+        let commit_output = self
+            .commit_command()
+            .args(["-m", "Update managed .gitignore patterns", "--", ".gitignore"])
+            .output()
+            .context("Failed to commit .gitignore")?;
+
+        if !commit_output.status.success() {
+            let stderr = String::from_utf8_lossy(&commit_output.stderr);
+            return Err(anyhow::anyhow!("Git commit of .gitignore failed: {}", stderr));
+        }
+
+        Ok(true)
+    }
+
+    /// Whether the given path is excluded by the repository's ignore rules.
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        Command::new("git")
+            .args(["check-ignore", "-q"])
+            .arg(path)
+            .current_dir(&self.repo_path)
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
     }
 
     fn generate_commit_message(&self, session: &SessionMetadata) -> String {
@@ -98,7 +308,7 @@ impl GitRepo {
             session.project
         );
 
-        if let Some(duration) = session.duration {
+        if let Some(duration) = session.duration() {
             let minutes = duration.num_minutes();
             message.push_str(&format!(" | {}m", minutes));
         }
@@ -114,6 +324,130 @@ impl GitRepo {
         message
     }
 
+    /// `path` relative to the repository root, as `git show` wants it; logs filed under
+    /// project subdirectories keep their directory.
+    pub fn relative_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.repo_path)
+            .ok()
+            .or_else(|| path.file_name().map(Path::new))
+            .unwrap_or(path)
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Contents of `file` as of `rev`, i.e. `git show <rev>:<file>`.
+    pub fn show_file(&self, rev: &str, file: &str) -> Result<Vec<u8>> {
+        let output = Command::new("git")
+            .args(["show", &format!("{}:{}", rev, file)])
+            .current_dir(&self.repo_path)
+            .output()
+            .context("Failed to run git show")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!("Git show failed: {}", stderr.trim()));
+        }
+
+        Ok(output.stdout)
+    }
+
+    /// Most recent commit that added or modified `file`, even if it was deleted since.
+    pub fn last_commit_for(&self, file: &str) -> Result<Option<String>> {
+        let output = Command::new("git")
+            .args(["log", "-1", "--format=%H", "--diff-filter=AM", "--", file])
+            .current_dir(&self.repo_path)
+            .output()
+            .context("Failed to run git log")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!("Git log failed: {}", stderr.trim()));
+        }
+
+        let hash = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok((!hash.is_empty()).then_some(hash))
+    }
+
+    /// URL of remote `name`, if it's configured.
+    pub fn remote_url(&self, name: &str) -> Option<String> {
+        let output = Command::new("git")
+            .args(["remote", "get-url", name])
+            .current_dir(&self.repo_path)
+            .output()
+            .ok()?;
+        output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Add remote `name`, or with `replace` point it at `url` if it already exists.
+    pub fn set_remote(&self, name: &str, url: &str, replace: bool) -> Result<()> {
+        let action = match self.remote_url(name) {
+            None => "add",
+            Some(_) if replace => "set-url",
+            Some(existing) => {
+                return Err(anyhow::anyhow!(
+                    "Remote '{}' already points at {}; use `git remote set` to change it",
+                    name,
+                    existing
+                ));
+            }
+        };
+
+        let output = Command::new("git")
+            .args(["remote", action, name, url])
+            .current_dir(&self.repo_path)
+            .output()
+            .context("Failed to run git remote")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!("Git remote {} failed: {}", action, stderr.trim()));
+        }
+        Ok(())
+    }
+
+    /// Push the current branch to `remote`, tracking it there.
+    pub fn push(&self, remote: &str) -> Result<()> {
+        let output = Command::new("git")
+            .args(["push", "--set-upstream", remote, "HEAD"])
+            // Fail rather than wait for credentials nobody is there to type
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .current_dir(&self.repo_path)
+            .output()
+            .context("Failed to run git push")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!("Git push failed: {}", stderr.trim()));
+        }
+        Ok(())
+    }
+
+    /// `push`, tried up to `attempts` times with a growing pause between tries.
+    pub fn push_with_retry(&self, remote: &str, attempts: u32) -> Result<()> {
+        if self.remote_url(remote).is_none() {
+            return Err(anyhow::anyhow!(
+                "Remote '{}' is not configured; add it with `claude-logger git remote add URL`",
+                remote
+            ));
+        }
+
+        let mut delay = PUSH_RETRY_DELAY;
+        let mut attempt = 1;
+        loop {
+            match self.push(remote) {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt >= attempts.max(1) => {
+                    return Err(anyhow::anyhow!("Gave up pushing to {} after {} attempts: {:#}", remote, attempt, e));
+                }
+                Err(_) => {
+                    thread::sleep(delay);
+                    delay *= 2;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
     pub fn show_log(&self, count: usize) -> Result<()> {
         let output = Command::new("git")
             .args([
@@ -138,6 +472,34 @@ impl GitRepo {
         Ok(())
     }
 
+    /// The `count` most recent commits, newest first.
+    pub fn log(&self, count: usize) -> Result<Vec<LogEntry>> {
+        // Unit and record separators can't appear in a subject line
+        let output = Command::new("git")
+            .args(["log", "--format=%H%x1f%aI%x1f%an%x1f%s%x1e", &format!("-{}", count)])
+            .current_dir(&self.repo_path)
+            .output()
+            .context("Failed to run git log")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!("Git log failed: {}", stderr));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .split('\x1e')
+            .filter_map(|record| {
+                let mut fields = record.trim_start_matches('\n').splitn(4, '\x1f').map(str::to_string);
+                Some(LogEntry {
+                    hash: fields.next().filter(|hash| !hash.is_empty())?,
+                    date: fields.next()?,
+                    author: fields.next()?,
+                    subject: fields.next()?,
+                })
+            })
+            .collect())
+    }
+
     pub fn get_commit_count(&self) -> Result<usize> {
         let output = Command::new("git")
             .args(["rev-list", "--count", "HEAD"])
@@ -178,4 +540,100 @@ impl GitRepo {
     pub fn repo_path(&self) -> &Path {
         &self.repo_path
     }
-}
\ No newline at end of file
+}
+
+/// `git config <key>` as seen from `dir`, so a repository's own setting wins over the global one.
+pub fn config_value(dir: &Path, key: &str) -> Option<String> {
+    let output = Command::new("git").args(["config", "--get", key]).current_dir(dir).output().ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !value.is_empty()).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pushes_to_a_configured_remote() {
+        let dir = tempfile::tempdir().unwrap();
+        let logs = dir.path().join("logs");
+        let remote = dir.path().join("remote.git");
+        std::fs::create_dir_all(&logs).unwrap();
+        Command::new("git").args(["init", "-q", "--bare"]).arg(&remote).status().unwrap();
+        Command::new("git").args(["init", "-q"]).current_dir(&logs).status().unwrap();
+        for (key, value) in [("user.email", "test@example.com"), ("user.name", "Test")] {
+            Command::new("git").args(["config", key, value]).current_dir(&logs).status().unwrap();
+        }
+        let repo = GitRepo::init_or_open(&logs).unwrap();
+        std::fs::write(logs.join("a.log"), "hello").unwrap();
+        repo.commit_changes(&[], &[&logs.join("a.log")], "Add a.log").unwrap();
+
+        assert!(repo.push_with_retry("origin", 1).unwrap_err().to_string().contains("not configured"));
+        let url = remote.to_string_lossy();
+        repo.set_remote("origin", &url, false).unwrap();
+        assert!(repo.set_remote("origin", "elsewhere", false).is_err());
+        assert_eq!(repo.remote_url("origin").as_deref(), Some(url.as_ref()));
+
+        repo.push_with_retry("origin", 1).unwrap();
+        let pushed = Command::new("git").args(["log", "--format=%s", "-1"]).current_dir(&remote).output().unwrap();
+        assert_eq!(String::from_utf8_lossy(&pushed.stdout).trim(), "Add a.log");
+    }
+
+    #[test]
+    fn commits_as_a_configured_author() {
+        let dir = tempfile::tempdir().unwrap();
+        Command::new("git").args(["init", "-q"]).current_dir(dir.path()).status().unwrap();
+        for (key, value) in [("user.email", "test@example.com"), ("user.name", "Test")] {
+            Command::new("git").args(["config", key, value]).current_dir(dir.path()).status().unwrap();
+        }
+
+        // A configured author commits under their own name in a shared repository
+        let shared = GitRepo::init_or_open(dir.path())
+            .unwrap()
+            .with_identity(Identity { name: Some("Ada".to_string()), email: Some("ada@example.com".to_string()) });
+        std::fs::write(dir.path().join("a.log"), "hello").unwrap();
+        shared.commit_changes(&[], &[&dir.path().join("a.log")], "Add a.log").unwrap();
+        let log = GitRepo::init_or_open(dir.path()).unwrap().log(1).unwrap();
+        assert_eq!((log[0].author.as_str(), log[0].subject.as_str()), ("Ada", "Add a.log"));
+        assert_eq!(config_value(dir.path(), "user.name").as_deref(), Some("Test"));
+    }
+
+    #[test]
+    fn lists_commits_newest_first_for_git_log() {
+        let dir = tempfile::tempdir().unwrap();
+        Command::new("git").args(["init", "-q"]).current_dir(dir.path()).status().unwrap();
+        for (key, value) in [("user.email", "test@example.com"), ("user.name", "Test")] {
+            Command::new("git").args(["config", key, value]).current_dir(dir.path()).status().unwrap();
+        }
+        let repo = GitRepo::init_or_open(dir.path()).unwrap();
+        for name in ["a.log", "b.log"] {
+            std::fs::write(dir.path().join(name), "hello").unwrap();
+            repo.commit_changes(&[], &[&dir.path().join(name)], &format!("Add {}", name)).unwrap();
+        }
+
+        let log = repo.log(5).unwrap();
+        let subjects: Vec<&str> = log.iter().map(|entry| entry.subject.as_str()).collect();
+        assert_eq!(subjects, ["Add b.log", "Add a.log"]);
+        assert_eq!(repo.log(1).unwrap().len(), 1);
+
+        let json = serde_json::to_value(&log[0]).unwrap();
+        let mut keys: Vec<&str> = json.as_object().unwrap().keys().map(String::as_str).collect();
+        keys.sort();
+        assert_eq!(keys, ["author", "date", "hash", "subject"]);
+        assert_eq!(json["author"], "Test");
+        assert_eq!(json["hash"].as_str().unwrap().len(), 40);
+        assert!(chrono::DateTime::parse_from_rfc3339(json["date"].as_str().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn leaves_a_gitignore_block_without_an_end_marker_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = GitRepo::init_or_open(dir.path()).unwrap();
+        let content = format!("{}\n*.tmp\nnotes/\n", MANAGED_BLOCK_START);
+        std::fs::write(dir.path().join(".gitignore"), &content).unwrap();
+
+        let error = repo.sync_gitignore(&["*.tmp".to_string()]).unwrap_err();
+        assert!(error.to_string().contains(MANAGED_BLOCK_END), "{}", error);
+        assert_eq!(std::fs::read_to_string(dir.path().join(".gitignore")).unwrap(), content);
+    }
+}
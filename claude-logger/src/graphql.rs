@@ -0,0 +1,133 @@
+use crate::analyzer::SessionAnalyzer;
+use crate::patterns::analyze_session_quality;
+use crate::session::{Methodology, SessionMetadata};
+use juniper::{graphql_object, EmptyMutation, EmptySubscription, FieldResult, GraphQLObject, RootNode};
+
+pub struct GraphQLContext {
+    analyzer: SessionAnalyzer,
+}
+
+impl GraphQLContext {
+    pub fn new(analyzer: SessionAnalyzer) -> Self {
+        Self { analyzer }
+    }
+}
+
+impl juniper::Context for GraphQLContext {}
+
+pub type Schema = RootNode<'static, Query, EmptyMutation<GraphQLContext>, EmptySubscription<GraphQLContext>>;
+
+pub fn schema() -> Schema {
+    Schema::new(Query, EmptyMutation::new(), EmptySubscription::new())
+}
+
+pub struct Query;
+
+#[graphql_object(context = GraphQLContext)]
+impl Query {
+    /// Sessions newest first, optionally filtered and paginated
+    fn sessions(
+        context: &GraphQLContext,
+        methodology: Option<String>,
+        project: Option<String>,
+        first: Option<i32>,
+        offset: Option<i32>,
+    ) -> Vec<SessionNode> {
+        let methodology = methodology.as_deref().and_then(Methodology::from_name);
+        let offset = offset.unwrap_or(0).max(0) as usize;
+        let first = first.map(|n| n.max(0) as usize).unwrap_or(usize::MAX);
+
+        context
+            .analyzer
+            .metadata()
+            .recent_sessions(None, usize::MAX)
+            .into_iter()
+            .filter(|session| methodology.as_ref().is_none_or(|m| &session.methodology == m))
+            .filter(|session| project.as_ref().is_none_or(|p| &session.project == p))
+            .skip(offset)
+            .take(first)
+            .map(|session| SessionNode(session.clone()))
+            .collect()
+    }
+
+    fn session(context: &GraphQLContext, id: String) -> Option<SessionNode> {
+        context.analyzer.metadata().get_session(&id).cloned().map(SessionNode)
+    }
+}
+
+pub struct SessionNode(SessionMetadata);
+
+#[graphql_object(context = GraphQLContext)]
+impl SessionNode {
+    fn id(&self) -> &str {
+        &self.0.id
+    }
+
+    fn timestamp(&self) -> String {
+        self.0.timestamp.to_rfc3339()
+    }
+
+    fn project(&self) -> &str {
+        &self.0.project
+    }
+
+    fn methodology(&self) -> String {
+        self.0.methodology.to_string()
+    }
+
+    fn duration_minutes(&self) -> Option<i32> {
+        self.0.duration().map(|duration| duration.num_minutes() as i32)
+    }
+
+    fn creative_energy(&self) -> Option<i32> {
+        self.0.creative_energy.map(i32::from)
+    }
+
+    fn metrics(&self, context: &GraphQLContext) -> FieldResult<MetricsNode> {
+        let metrics = context.analyzer.analyze_session_log(&self.0)?;
+
+        Ok(MetricsNode {
+            exchanges: metrics.exchanges as i32,
+            code_blocks: metrics.code_blocks as i32,
+            file_edits: metrics.file_edits as i32,
+            questions_asked: metrics.questions_asked as i32,
+            enthusiasm_markers: metrics.enthusiasm_markers as i32,
+            confusion_markers: metrics.confusion_markers as i32,
+            compaction_indicators: metrics.compaction_indicators as i32,
+            error_events: metrics.error_events as i32,
+            retry_events: metrics.retry_events as i32,
+        })
+    }
+
+    fn quality(&self, context: &GraphQLContext) -> FieldResult<QualityNode> {
+        let quality = analyze_session_quality(&context.analyzer.read_session_log(&self.0)?);
+
+        Ok(QualityNode {
+            engagement_score: quality.engagement_score,
+            clarity_score: quality.clarity_score,
+            productivity_score: quality.productivity_score,
+            overall_score: quality.overall_score,
+        })
+    }
+}
+
+#[derive(GraphQLObject)]
+pub struct MetricsNode {
+    exchanges: i32,
+    code_blocks: i32,
+    file_edits: i32,
+    questions_asked: i32,
+    enthusiasm_markers: i32,
+    confusion_markers: i32,
+    compaction_indicators: i32,
+    error_events: i32,
+    retry_events: i32,
+}
+
+#[derive(GraphQLObject)]
+pub struct QualityNode {
+    engagement_score: f64,
+    clarity_score: f64,
+    productivity_score: f64,
+    overall_score: f64,
+}
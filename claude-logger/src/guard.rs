@@ -0,0 +1,151 @@
+use crate::config::{GuardAction, GuardConfig, PricingConfig};
+use crate::session::SessionMetadata;
+use crate::transcript::{self, Transcript};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Transcripts are re-read for the cost check at most this often.
+const COST_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A configured limit the running session went past.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Breach {
+    Duration { minutes: u64, limit: u64 },
+    Output { kb: u64, limit: u64 },
+    Cost { usd: f64, limit: f64 },
+}
+
+impl Breach {
+    fn same_limit(&self, other: &Breach) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+impl std::fmt::Display for Breach {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Breach::Duration { minutes, limit } => write!(f, "ran {} minutes (limit {})", minutes, limit),
+            Breach::Output { kb, limit } => write!(f, "wrote {} KB of output (limit {} KB)", kb, limit),
+            Breach::Cost { usd, limit } => write!(f, "estimated cost ${:.2} (limit ${:.2})", usd, limit),
+        }
+    }
+}
+
+/// Watches a wrapped claude process against the `[guard]` limits.
+pub struct SessionGuard {
+    config: GuardConfig,
+    pricing: PricingConfig,
+    session: SessionMetadata,
+    started: Instant,
+    transcript: Option<PathBuf>,
+    last_cost_check: Option<Instant>,
+    reported: Vec<Breach>,
+}
+
+impl SessionGuard {
+    /// `None` unless the guard is enabled and at least one limit is set.
+    pub fn new(config: &GuardConfig, pricing: &PricingConfig, session: &SessionMetadata) -> Option<Self> {
+        let any_limit = config.max_minutes.is_some() || config.max_output_kb.is_some() || config.max_cost.is_some();
+        (config.enabled && any_limit).then(|| Self {
+            config: config.clone(),
+            pricing: pricing.clone(),
+            session: session.clone(),
+            started: Instant::now(),
+            transcript: None,
+            last_cost_check: None,
+            reported: Vec::new(),
+        })
+    }
+
+    pub fn action(&self) -> GuardAction {
+        self.config.action
+    }
+
+    /// The first limit newly exceeded since the last call, if any.
+    pub fn check(&mut self) -> Option<Breach> {
+        let breaches = [self.duration_breach(), self.output_breach(), self.cost_breach()];
+        let breach = breaches
+            .into_iter()
+            .flatten()
+            .find(|breach| !self.reported.iter().any(|reported| reported.same_limit(breach)))?;
+        self.reported.push(breach);
+        Some(breach)
+    }
+
+    fn duration_breach(&self) -> Option<Breach> {
+        let limit = self.config.max_minutes?;
+        let minutes = self.started.elapsed().as_secs() / 60;
+        (minutes >= limit).then_some(Breach::Duration { minutes, limit })
+    }
+
+    fn output_breach(&self) -> Option<Breach> {
+        let limit = self.config.max_output_kb?;
+        let kb = fs::metadata(&self.session.log_file).ok()?.len() / 1024;
+        (kb >= limit).then_some(Breach::Output { kb, limit })
+    }
+
+    fn cost_breach(&mut self) -> Option<Breach> {
+        let limit = self.config.max_cost?;
+        if self.last_cost_check.is_some_and(|checked| checked.elapsed() < COST_CHECK_INTERVAL) {
+            return None;
+        }
+        self.last_cost_check = Some(Instant::now());
+
+        if self.transcript.is_none() {
+            self.transcript = transcript::projects_dir()
+                .and_then(|projects_dir| transcript::find_for_session(&projects_dir, &self.session));
+        }
+        let usd = Transcript::read(self.transcript.as_ref()?).ok()?.estimated_cost(&self.pricing);
+        (usd >= limit).then_some(Breach::Cost { usd, limit })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::{generate, sessions};
+    use chrono::Utc;
+
+    #[test]
+    fn is_off_unless_enabled_with_a_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let session = generate(&sessions(1, 5, "api"), dir.path(), Utc::now()).remove(0).session;
+        let pricing = PricingConfig::default();
+
+        let disabled = GuardConfig { max_minutes: Some(10), ..GuardConfig::default() };
+        assert!(SessionGuard::new(&disabled, &pricing, &session).is_none());
+        let unlimited = GuardConfig { enabled: true, ..GuardConfig::default() };
+        assert!(SessionGuard::new(&unlimited, &pricing, &session).is_none());
+        let enabled = GuardConfig { enabled: true, max_cost: Some(1.0), ..GuardConfig::default() };
+        assert!(SessionGuard::new(&enabled, &pricing, &session).is_some());
+    }
+
+    #[test]
+    fn reports_each_limit_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let session = generate(&sessions(1, 5, "api"), dir.path(), Utc::now()).remove(0).session;
+        fs::create_dir_all(session.log_file.parent().unwrap()).unwrap();
+        fs::write(&session.log_file, vec![b'x'; 1024]).unwrap();
+
+        let config = GuardConfig {
+            enabled: true,
+            action: GuardAction::Terminate,
+            max_minutes: Some(0),
+            max_output_kb: Some(2),
+            ..GuardConfig::default()
+        };
+        let mut guard = SessionGuard::new(&config, &PricingConfig::default(), &session).unwrap();
+        assert_eq!(guard.action(), GuardAction::Terminate);
+        assert_eq!(guard.check(), Some(Breach::Duration { minutes: 0, limit: 0 }));
+        assert_eq!(guard.check(), None);
+
+        // The output limit trips on the log's size on disk
+        fs::write(&session.log_file, vec![b'x'; 3 * 1024]).unwrap();
+        let breach = guard.check().unwrap();
+        assert_eq!(breach, Breach::Output { kb: 3, limit: 2 });
+        assert_eq!(breach.to_string(), "wrote 3 KB of output (limit 2 KB)");
+        fs::write(&session.log_file, vec![b'x'; 8 * 1024]).unwrap();
+        assert_eq!(guard.check(), None);
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::OnceLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::html::{IncludeBackground, styled_line_to_highlighted_html};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::{LinesWithEndings, as_24_bit_terminal_escaped};
+
+/// Dark theme, matching the code block background used in shared transcripts.
+const THEME: &str = "base16-ocean.dark";
+
+struct Assets {
+    syntaxes: SyntaxSet,
+    theme: Theme,
+}
+
+fn assets() -> &'static Assets {
+    static ASSETS: OnceLock<Assets> = OnceLock::new();
+    ASSETS.get_or_init(|| {
+        let mut themes = ThemeSet::load_defaults();
+        Assets {
+            syntaxes: SyntaxSet::load_defaults_newlines(),
+            theme: themes.themes.remove(THEME).expect("default theme set includes base16-ocean.dark"),
+        }
+    })
+}
+
+/// Syntax for a fence tag such as `rust`, `py` or `sh`, if one is known.
+fn syntax_for(lang: &str) -> Option<&'static SyntaxReference> {
+    let lang = lang.split([' ', ',', '{']).next().unwrap_or_default().trim();
+    if lang.is_empty() {
+        return None;
+    }
+    assets().syntaxes.find_syntax_by_token(lang)
+}
+
+/// Highlight code with 24-bit ANSI colours. Unknown languages come back unchanged.
+pub fn terminal(code: &str, lang: &str) -> String {
+    let Some(syntax) = syntax_for(lang) else {
+        return code.to_string();
+    };
+
+    let assets = assets();
+    let mut highlighter = HighlightLines::new(syntax, &assets.theme);
+    let mut output = String::with_capacity(code.len() * 2);
+    for line in LinesWithEndings::from(code) {
+        match highlighter.highlight_line(line, &assets.syntaxes) {
+            Ok(regions) => output.push_str(&as_24_bit_terminal_escaped(&regions, false)),
+            Err(_) => output.push_str(line),
+        }
+    }
+    output.push_str("\x1b[0m");
+    output
+}
+
+/// Highlight code as HTML spans with inline colours, or `None` for unknown languages.
+pub fn html(code: &str, lang: &str) -> Option<String> {
+    let syntax = syntax_for(lang)?;
+
+    let assets = assets();
+    let mut highlighter = HighlightLines::new(syntax, &assets.theme);
+    let mut output = String::with_capacity(code.len() * 4);
+    for line in LinesWithEndings::from(code) {
+        let regions = highlighter.highlight_line(line, &assets.syntaxes).ok()?;
+        output.push_str(&styled_line_to_highlighted_html(&regions, IncludeBackground::No).ok()?);
+    }
+    Some(output)
+}
+
+/// Highlight every fenced code block of a log for terminal display, leaving prose untouched.
+pub fn terminal_log(content: &str) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut fence: Option<(String, String)> = None;
+
+    for line in LinesWithEndings::from(content) {
+        let is_fence = line.trim_start().starts_with("```");
+        match fence.take() {
+            Some((lang, code)) if is_fence => {
+                output.push_str(&terminal(&code, &lang));
+                output.push_str(line);
+            }
+            Some((lang, mut code)) => {
+                code.push_str(line);
+                fence = Some((lang, code));
+            }
+            None if is_fence => {
+                output.push_str(line);
+                let lang = line.trim_start().trim_start_matches('`').trim().to_string();
+                fence = Some((lang, String::new()));
+            }
+            None => output.push_str(line),
+        }
+    }
+
+    // An unterminated fence at the end of a capture is still shown as code
+    if let Some((lang, code)) = fence {
+        output.push_str(&terminal(&code, &lang));
+    }
+    output
+}
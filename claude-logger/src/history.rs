@@ -0,0 +1,239 @@
+use crate::logger::ClaudeLogger;
+use crate::session::SessionsMetadata;
+use crate::timezone::DisplayZone;
+use crate::transcript::{self, Transcript, START_SLACK_SECS};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Tag carried by every session imported from Claude Code's transcripts.
+pub const IMPORTED_TAG: &str = "imported";
+
+/// A transcript no session accounts for yet.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub path: PathBuf,
+    pub working_directory: PathBuf,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub exchanges: usize,
+}
+
+/// What `scan` found under the projects directory.
+#[derive(Debug, Default)]
+pub struct Scan {
+    /// Oldest first
+    pub candidates: Vec<Candidate>,
+    /// Transcripts a session already links to or was captured alongside
+    pub known: usize,
+    /// Transcripts with no prompts, timestamps or working directory, with why
+    pub skipped: Vec<(PathBuf, &'static str)>,
+}
+
+/// Every `*.jsonl` transcript in each of Claude Code's project directories that no session
+/// links to and that doesn't overlap a session captured in the same directory.
+pub fn scan(projects_dir: &Path, metadata: &SessionsMetadata) -> Result<Scan> {
+    let mut scan = Scan::default();
+    let projects = fs::read_dir(projects_dir)
+        .with_context(|| format!("Failed to read the Claude Code projects directory: {}", projects_dir.display()))?;
+    let mut paths: Vec<PathBuf> = projects
+        .flatten()
+        .filter(|project| project.path().is_dir())
+        .filter_map(|project| fs::read_dir(project.path()).ok())
+        .flat_map(|entries| entries.flatten().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "jsonl"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        if metadata.sessions.values().any(|session| session.transcript_file.as_ref() == Some(&path)) {
+            scan.known += 1;
+            continue;
+        }
+        let transcript = match Transcript::read(&path) {
+            Ok(transcript) => transcript,
+            Err(e) => {
+                eprintln!("Warning: {:#}", e);
+                continue;
+            }
+        };
+        let candidate = match candidate(path.clone(), transcript) {
+            Ok(candidate) => candidate,
+            Err(reason) => {
+                scan.skipped.push((path, reason));
+                continue;
+            }
+        };
+        if overlaps_a_session(&candidate, metadata) {
+            scan.known += 1;
+            continue;
+        }
+        scan.candidates.push(candidate);
+    }
+    scan.candidates.sort_by_key(|candidate| candidate.started_at);
+    Ok(scan)
+}
+
+fn candidate(path: PathBuf, transcript: Transcript) -> std::result::Result<Candidate, &'static str> {
+    if transcript.exchanges == 0 {
+        return Err("no prompts");
+    }
+    let (Some(started_at), Some(ended_at)) = (transcript.started_at, transcript.ended_at) else {
+        return Err("no timestamps");
+    };
+    let working_directory = transcript.working_directory.ok_or("no working directory")?;
+    Ok(Candidate { path, working_directory, started_at, ended_at, exchanges: transcript.exchanges })
+}
+
+/// Whether a session captured through claude-logger in the same directory started while the
+/// transcript was being written; `transcripts link` is what joins those two.
+fn overlaps_a_session(candidate: &Candidate, metadata: &SessionsMetadata) -> bool {
+    let earliest = candidate.started_at - Duration::seconds(START_SLACK_SECS);
+    metadata.sessions.values().any(|session| {
+        session.working_directory == candidate.working_directory
+            && session.timestamp >= earliest
+            && session.timestamp <= candidate.ended_at
+    })
+}
+
+#[derive(Debug, Default)]
+pub struct ImportOutcome {
+    pub imported: Vec<String>,
+    pub failed: Vec<(PathBuf, String)>,
+    /// The logs repository commit adding the imported logs, if any
+    pub commit: Option<String>,
+}
+
+/// Record each candidate as a finished session started when its transcript did, with the
+/// transcript copied in as its log and the duration estimated from the message timestamps.
+pub fn import(logger: &mut ClaudeLogger, candidates: &[Candidate]) -> Result<ImportOutcome> {
+    let mut outcome = ImportOutcome::default();
+    let mut added = Vec::new();
+    for candidate in candidates {
+        match import_one(logger, candidate) {
+            Ok((id, files)) => {
+                outcome.imported.push(id);
+                added.extend(files);
+            }
+            Err(e) => outcome.failed.push((candidate.path.clone(), format!("{:#}", e))),
+        }
+    }
+    if outcome.imported.is_empty() {
+        return Ok(outcome);
+    }
+
+    logger.save_metadata()?;
+    let added: Vec<&Path> = added.iter().map(PathBuf::as_path).filter(|path| path.exists()).collect();
+    let message = format!("Import {} sessions from Claude Code history", outcome.imported.len());
+    outcome.commit = logger.git_repo().commit_changes(&[], &added, &message)?;
+    if outcome.commit.is_some() {
+        logger.push_if_enabled();
+    }
+    Ok(outcome)
+}
+
+/// Returns the new session's id and its log files.
+fn import_one(logger: &mut ClaudeLogger, candidate: &Candidate) -> Result<(String, Vec<PathBuf>)> {
+    let (log_file, mut session) = logger.create_session_log_at(&candidate.working_directory, &[], candidate.started_at)?;
+    fs::copy(&candidate.path, &log_file)
+        .with_context(|| format!("Failed to copy {} to {}", candidate.path.display(), log_file.display()))?;
+
+    // The terminal and any resumed session of the time aren't known
+    session.terminal = None;
+    session.parent_session_id = None;
+    session.set_duration(candidate.ended_at - candidate.started_at);
+    session.end_time = Some(candidate.ended_at);
+    session.duration_estimated = true;
+    session.transcript_file = Some(candidate.path.clone());
+    session.tags.push(IMPORTED_TAG.to_string());
+    // Refreshing keeps today's git branch out of the features worked on back then
+    logger.derive_from_log(&mut session, true);
+    if logger.config().encryption.enabled {
+        ClaudeLogger::seal_logs(&session)?;
+    }
+
+    let mut files = vec![session.log_file.clone()];
+    files.extend(session.clean_log_file.clone());
+    let id = session.id.clone();
+    logger.add_session(session);
+    Ok((id, files))
+}
+
+pub fn print_scan(scan: &Scan, projects_dir: &Path, zone: DisplayZone) {
+    println!(
+        "{}: {} transcripts to import, {} already known, {} skipped",
+        projects_dir.display(),
+        scan.candidates.len(),
+        scan.known,
+        scan.skipped.len()
+    );
+    for candidate in &scan.candidates {
+        println!(
+            "  {} | {} min | {} prompts | {}",
+            zone.format(candidate.started_at, "%Y-%m-%d %H:%M"),
+            (candidate.ended_at - candidate.started_at).num_minutes(),
+            candidate.exchanges,
+            candidate.working_directory.display()
+        );
+    }
+    for (path, reason) in &scan.skipped {
+        println!("  skipped {} ({})", path.display(), reason);
+    }
+}
+
+pub fn print_outcome(outcome: &ImportOutcome) {
+    for (path, error) in &outcome.failed {
+        eprintln!("Warning: Failed to import {}: {}", path.display(), error);
+    }
+    println!("Imported {} sessions, tagged `{}`", outcome.imported.len(), IMPORTED_TAG);
+    if let Some(commit) = &outcome.commit {
+        println!("Recorded in commit {}", &commit[..commit.len().min(7)]);
+    }
+}
+
+/// Where `import` looks, failing clearly when Claude Code's directory can't be found.
+pub fn projects_dir() -> Result<PathBuf> {
+    transcript::projects_dir().context("Could not find the Claude Code projects directory")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::SessionMetadata;
+    use crate::synth::{generate, sessions};
+
+    #[test]
+    fn finds_transcripts_no_session_accounts_for() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("-home-me-app");
+        fs::create_dir_all(&project).unwrap();
+        let line = |time: &str, text: &str| {
+            format!(
+                r#"{{"type":"user","cwd":"/home/me/app","timestamp":"{}","message":{{"role":"user","content":"{}"}}}}"#,
+                time, text
+            )
+        };
+        let write = |name: &str, lines: &[String]| fs::write(project.join(name), lines.join("\n")).unwrap();
+        write("old.jsonl", &[line("2025-01-01T10:00:00Z", "add a cache"), line("2025-01-01T10:45:00Z", "now test it")]);
+        write("captured.jsonl", &[line("2025-02-01T09:00:00Z", "fix the build"), line("2025-02-01T09:30:00Z", "thanks")]);
+        write("empty.jsonl", &[r#"{"type":"summary","summary":"nothing"}"#.to_string()]);
+        fs::write(project.join("notes.txt"), "not a transcript").unwrap();
+
+        let options = sessions(1, 3, "app");
+        let mut session: SessionMetadata = generate(&options, Path::new("/logs"), Utc::now()).remove(0).session;
+        session.working_directory = PathBuf::from("/home/me/app");
+        session.timestamp = "2025-02-01T09:01:00Z".parse().unwrap();
+        let mut metadata = SessionsMetadata::new();
+        metadata.add_session(session);
+
+        let scan = scan(dir.path(), &metadata).unwrap();
+        assert_eq!(scan.candidates.len(), 1);
+        assert_eq!(scan.known, 1);
+        assert_eq!(scan.skipped.len(), 1);
+        let candidate = &scan.candidates[0];
+        assert_eq!(candidate.path, project.join("old.jsonl"));
+        assert_eq!((candidate.ended_at - candidate.started_at).num_minutes(), 45);
+        assert_eq!(candidate.exchanges, 2);
+    }
+}
@@ -0,0 +1,155 @@
+use crate::logger::ClaudeLogger;
+use crate::session::{Hold, SessionMetadata, SessionsMetadata};
+use crate::timezone::DisplayZone;
+use anyhow::Result;
+use chrono::Utc;
+
+/// Put the given sessions under a legal hold. Returns the ids newly held; sessions already held
+/// keep their original hold.
+pub fn place(logger: &mut ClaudeLogger, session_ids: &[String], reason: &str) -> Result<Vec<String>> {
+    let reason = reason.trim();
+    if reason.is_empty() {
+        anyhow::bail!("A hold needs a reason");
+    }
+    ensure_exist(logger.metadata(), session_ids)?;
+
+    let mut held = Vec::new();
+    let now = Utc::now();
+    for session_id in session_ids {
+        let Some(session) = logger.metadata_mut().get_session_mut(session_id) else {
+            continue;
+        };
+        if let Some(hold) = &session.hold {
+            eprintln!("Warning: Session {} is already on hold ({})", session_id, hold.reason);
+            continue;
+        }
+        session.hold = Some(Hold { reason: reason.to_string(), placed_at: now });
+        held.push(session_id.clone());
+    }
+    if !held.is_empty() {
+        logger.save_metadata()?;
+    }
+    Ok(held)
+}
+
+/// Release the given sessions' holds. Returns the ids released.
+pub fn release(logger: &mut ClaudeLogger, session_ids: &[String]) -> Result<Vec<String>> {
+    ensure_exist(logger.metadata(), session_ids)?;
+
+    let mut released = Vec::new();
+    for session_id in session_ids {
+        if let Some(session) = logger.metadata_mut().get_session_mut(session_id)
+            && session.hold.take().is_some()
+        {
+            released.push(session_id.clone());
+        }
+    }
+    if !released.is_empty() {
+        logger.save_metadata()?;
+    }
+    Ok(released)
+}
+
+/// Checked up front, so a typo doesn't leave some of the sessions held and others not.
+fn ensure_exist(metadata: &SessionsMetadata, session_ids: &[String]) -> Result<()> {
+    match session_ids.iter().find(|id| metadata.get_session(id).is_none()) {
+        Some(missing) => Err(anyhow::anyhow!("Session not found: {}", missing)),
+        None => Ok(()),
+    }
+}
+
+/// Fail when `session` is under a hold, naming what was refused, e.g. "delete".
+pub fn ensure_released(session: &SessionMetadata, action: &str) -> Result<()> {
+    match &session.hold {
+        Some(hold) => Err(anyhow::anyhow!(
+            "Can't {} session {}: it is on hold ({}); release it first with `claude-logger hold release {}`",
+            action,
+            session.id,
+            hold.reason,
+            session.id
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Held sessions, longest held first.
+pub fn held(metadata: &SessionsMetadata) -> Vec<&SessionMetadata> {
+    let mut sessions: Vec<&SessionMetadata> = metadata.sessions.values().filter(|session| session.hold.is_some()).collect();
+    sessions.sort_by_key(|session| session.hold.as_ref().map(|hold| hold.placed_at));
+    sessions
+}
+
+pub fn print_list(sessions: &[&SessionMetadata], zone: DisplayZone) {
+    if sessions.is_empty() {
+        println!("No sessions are on hold.");
+        return;
+    }
+    for session in sessions {
+        if let Some(hold) = &session.hold {
+            println!(
+                "{} | {} | held since {} | {}",
+                session.id,
+                session.project,
+                zone.format(hold.placed_at, "%Y-%m-%d %H:%M"),
+                hold.reason
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{merge, prune};
+    use crate::synth::{generate, sessions};
+    use std::fs;
+
+    fn logger_with_sessions(dir: &std::path::Path) -> (ClaudeLogger, Vec<String>) {
+        let mut logger = ClaudeLogger::new_with_dir(dir).unwrap();
+        let mut ids = Vec::new();
+        for generated in generate(&sessions(2, 7, "api"), dir, Utc::now() - chrono::Duration::days(30)) {
+            fs::create_dir_all(generated.session.log_file.parent().unwrap()).unwrap();
+            fs::write(&generated.session.log_file, &generated.log).unwrap();
+            ids.push(generated.session.id.clone());
+            logger.add_session(generated.session);
+        }
+        ids.sort();
+        (logger, ids)
+    }
+
+    #[test]
+    fn places_holds_only_with_a_reason_and_known_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut logger, ids) = logger_with_sessions(dir.path());
+
+        assert!(place(&mut logger, &ids, "  ").is_err());
+        let unknown = vec![ids[0].clone(), "2000-01-01_00-00-00".to_string()];
+        let error = place(&mut logger, &unknown, "litigation").unwrap_err();
+        assert!(error.to_string().contains("2000-01-01_00-00-00"), "{}", error);
+        assert!(held(logger.metadata()).is_empty());
+
+        assert_eq!(place(&mut logger, &ids[..1], " litigation ").unwrap(), ids[..1]);
+        assert!(place(&mut logger, &ids[..1], "audit").unwrap().is_empty());
+        let reloaded = ClaudeLogger::new_with_dir(dir.path()).unwrap();
+        assert_eq!(reloaded.get_session(&ids[0]).unwrap().hold.as_ref().unwrap().reason, "litigation");
+        assert_eq!(release(&mut logger, &ids).unwrap(), ids[..1]);
+        assert!(held(logger.metadata()).is_empty());
+    }
+
+    #[test]
+    fn held_sessions_are_not_deleted_merged_or_archived() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut logger, ids) = logger_with_sessions(dir.path());
+        let cutoff = Utc::now() - chrono::Duration::days(7);
+        place(&mut logger, &ids[..1], "litigation").unwrap();
+
+        let error = prune::delete(&mut logger, &ids[0]).unwrap_err();
+        assert!(error.to_string().contains("Can't delete"), "{}", error);
+        let error = merge::merge(&mut logger, &ids[1], &ids[0]).unwrap_err();
+        assert!(error.to_string().contains("Can't merge"), "{}", error);
+        assert!(!prune::can_archive(logger.get_session(&ids[0]).unwrap(), cutoff));
+        assert!(prune::can_archive(logger.get_session(&ids[1]).unwrap(), cutoff));
+        assert!(logger.get_session(&ids[0]).unwrap().log_file.exists());
+        assert_eq!(logger.metadata().sessions.len(), 2);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::config::HooksConfig;
+use crate::session::SessionMetadata;
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A point in a session's life where `[hooks]` commands run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    /// Before claude starts; the session has an id and log file but no end time yet
+    PreSession,
+    /// Once the finished session's metadata is saved
+    PostSession,
+    /// Once the session's log is committed to the logs repository
+    PostCommit,
+}
+
+impl HookEvent {
+    pub fn name(self) -> &'static str {
+        match self {
+            HookEvent::PreSession => "pre_session",
+            HookEvent::PostSession => "post_session",
+            HookEvent::PostCommit => "post_commit",
+        }
+    }
+
+    fn commands(self, config: &HooksConfig) -> &[String] {
+        match self {
+            HookEvent::PreSession => &config.pre_session,
+            HookEvent::PostSession => &config.post_session,
+            HookEvent::PostCommit => &config.post_commit,
+        }
+    }
+}
+
+/// Run the commands configured for `event`, in order, for `session`.
+///
+/// Each gets the session's metadata as JSON on stdin and the basics in `CLAUDE_LOGGER_*`
+/// variables, and runs in the session's working directory. A failing hook is reported and
+/// never stops the session.
+pub fn run(config: &HooksConfig, event: HookEvent, session: &SessionMetadata) {
+    for command in event.commands(config) {
+        if let Err(e) = run_one(command, event, session, Duration::from_secs(config.timeout_secs)) {
+            eprintln!("Warning: {} hook `{}` failed: {:#}", event.name(), command, e);
+        }
+    }
+}
+
+fn run_one(command: &str, event: HookEvent, session: &SessionMetadata, timeout: Duration) -> Result<()> {
+    let input = serde_json::to_vec(session).context("Failed to serialize session")?;
+    let mut shell = shell(command);
+    shell.envs(environment(event, session)).stdin(Stdio::piped());
+    // Sessions from another machine may name a directory that doesn't exist here
+    if session.working_directory.is_dir() {
+        shell.current_dir(&session.working_directory);
+    }
+    let mut child = shell.spawn().context("Failed to start")?;
+
+    // Written from a thread, so a hook that never drains stdin still times out rather than
+    // blocking the write once the pipe fills; one that exits before reading isn't an error
+    if let Some(mut stdin) = child.stdin.take() {
+        thread::spawn(move || stdin.write_all(&input));
+    }
+
+    let status = wait_with_timeout(&mut child, timeout)?;
+    if !status.success() {
+        anyhow::bail!("exited with {}", status);
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+pub(crate) fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(not(unix))]
+pub(crate) fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+/// The variables a hook sees, besides the inherited environment.
+fn environment(event: HookEvent, session: &SessionMetadata) -> Vec<(&'static str, String)> {
+    let mut vars = vec![
+        ("CLAUDE_LOGGER_HOOK", event.name().to_string()),
+        ("CLAUDE_LOGGER_SESSION_ID", session.id.clone()),
+        ("CLAUDE_LOGGER_PROJECT", session.project.clone()),
+        ("CLAUDE_LOGGER_METHODOLOGY", session.methodology.to_string()),
+        ("CLAUDE_LOGGER_LOG_FILE", session.log_file.display().to_string()),
+        ("CLAUDE_LOGGER_WORKING_DIRECTORY", session.working_directory.display().to_string()),
+    ];
+    if let Some(duration_seconds) = session.duration_seconds {
+        vars.push(("CLAUDE_LOGGER_DURATION_SECS", duration_seconds.to_string()));
+    }
+    if let Some(commit) = &session.git_commit {
+        vars.push(("CLAUDE_LOGGER_GIT_COMMIT", commit.clone()));
+    }
+    vars
+}
+
+pub(crate) fn wait_with_timeout(child: &mut Child, timeout: Duration) -> Result<std::process::ExitStatus> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait().context("Failed to wait")? {
+            return Ok(status);
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            anyhow::bail!("timed out after {}s", timeout.as_secs());
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::synth::{generate, sessions};
+    use chrono::Utc;
+    use std::path::Path;
+
+    #[test]
+    fn hooks_get_the_session_on_stdin_and_in_the_environment() {
+        let options = sessions(1, 3, "api");
+        let session = generate(&options, Path::new("/logs"), Utc::now()).remove(0).session;
+
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out");
+        let config = HooksConfig {
+            post_session: vec![
+                format!("cat > '{}.json'", out.display()),
+                format!("echo \"$CLAUDE_LOGGER_HOOK $CLAUDE_LOGGER_PROJECT\" > '{}'", out.display()),
+                "exit 1".to_string(),
+            ],
+            ..HooksConfig::default()
+        };
+        run(&config, HookEvent::PostSession, &session);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(format!("{}.json", out.display())).unwrap()).unwrap();
+        assert_eq!(json["id"], session.id.as_str());
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "post_session api\n");
+
+        let error = run_one("sleep 5", HookEvent::PreSession, &session, Duration::from_millis(100)).unwrap_err();
+        assert!(error.to_string().contains("timed out"));
+    }
+
+    #[test]
+    fn a_hook_that_never_reads_a_large_session_times_out() {
+        let mut session = generate(&sessions(1, 3, "api"), Path::new("/logs"), Utc::now()).remove(0).session;
+        session.extra.insert("transcript".to_string(), serde_json::json!("x".repeat(1 << 20)));
+
+        let started = Instant::now();
+        let error = run_one("sleep 30", HookEvent::PostSession, &session, Duration::from_millis(500)).unwrap_err();
+        assert!(error.to_string().contains("timed out"), "{}", error);
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+}
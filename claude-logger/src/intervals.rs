@@ -0,0 +1,191 @@
+use crate::synth::Rng;
+use serde::Serialize;
+use std::fmt::Write;
+
+/// Resampled means drawn per interval.
+const RESAMPLES: usize = 2000;
+
+/// Fixed so a report run twice over the same sessions shows the same intervals.
+const SEED: u64 = 0x5eed_cafe;
+
+/// Share of resampled means left outside an interval, for 95% intervals.
+const ALPHA: f64 = 0.05;
+
+/// Longest arm label shown in plots before it is cut short.
+const LABEL_CHARS: usize = 24;
+
+/// A mean with its bootstrap confidence interval.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Interval {
+    pub mean: f64,
+    pub low: f64,
+    pub high: f64,
+    /// Values the mean was taken over
+    pub n: usize,
+}
+
+/// The mean of `values` with a 95% percentile-bootstrap interval; a single value gets a
+/// zero-width one.
+pub fn bootstrap(values: &[f64]) -> Option<Interval> {
+    if values.is_empty() {
+        return None;
+    }
+    let n = values.len();
+    let mean = values.iter().sum::<f64>() / n as f64;
+    if n == 1 {
+        return Some(Interval { mean, low: mean, high: mean, n });
+    }
+
+    let mut rng = Rng::new(SEED);
+    let mut means: Vec<f64> = (0..RESAMPLES)
+        .map(|_| (0..n).map(|_| values[rng.below(n as u64) as usize]).sum::<f64>() / n as f64)
+        .collect();
+    means.sort_by(f64::total_cmp);
+    let quantile = |q: f64| means[((RESAMPLES - 1) as f64 * q).round() as usize];
+    Some(Interval { mean, low: quantile(ALPHA / 2.0), high: quantile(1.0 - ALPHA / 2.0), n })
+}
+
+/// The lowest and highest bounds across `arms`, padded when they coincide up to rounding.
+fn axis(arms: &[(String, Interval)]) -> (f64, f64) {
+    let min = arms.iter().map(|(_, interval)| interval.low).fold(f64::INFINITY, f64::min);
+    let max = arms.iter().map(|(_, interval)| interval.high).fold(f64::NEG_INFINITY, f64::max);
+    if max - min > 1e-9 * max.abs().max(1.0) { (min, max) } else { (min - 1.0, max + 1.0) }
+}
+
+fn label(text: &str) -> String {
+    if text.chars().count() <= LABEL_CHARS {
+        return text.to_string();
+    }
+    let mut short: String = text.chars().take(LABEL_CHARS - 1).collect();
+    short.push('…');
+    short
+}
+
+/// One row per arm with its interval drawn on an axis shared by all arms, e.g.
+/// `v2  ├──●───┤  70.4 [66.2, 74.8] n=11`.
+pub fn ascii_plot(arms: &[(String, Interval)], width: usize) -> String {
+    if arms.is_empty() {
+        return String::new();
+    }
+    let (min, max) = axis(arms);
+    let column = |value: f64| ((value - min) / (max - min) * (width - 1) as f64).round() as usize;
+    let labels: Vec<String> = arms.iter().map(|(name, _)| label(name)).collect();
+    let label_width = labels.iter().map(|name| name.chars().count()).max().unwrap_or(0);
+
+    let mut text = String::new();
+    for (name, (_, interval)) in labels.iter().zip(arms) {
+        let mut row = vec![' '; width];
+        let (low, high) = (column(interval.low), column(interval.high));
+        row[low..=high].fill('─');
+        row[low] = '├';
+        row[high] = '┤';
+        row[column(interval.mean)] = '●';
+        let _ = writeln!(
+            text,
+            "  {:<label_width$}  {}  {:.1} [{:.1}, {:.1}] n={}",
+            name,
+            row.into_iter().collect::<String>(),
+            interval.mean,
+            interval.low,
+            interval.high,
+            interval.n
+        );
+    }
+    let (left, right) = (format!("{:.1}", min), format!("{:.1}", max));
+    let gap = width.saturating_sub(left.len() + right.len());
+    let _ = writeln!(text, "  {:<label_width$}  {}{}{}", "", left, " ".repeat(gap), right);
+    text
+}
+
+const SVG_WIDTH: usize = 640;
+const SVG_LABELS: usize = 170;
+const SVG_PLOT: usize = 300;
+const SVG_ROW: usize = 22;
+const SVG_INTERVAL_COLOR: &str = "#007ec6";
+
+/// The same plots as a standalone SVG, one titled panel per metric.
+pub fn svg_plot(panels: &[(&str, Vec<(String, Interval)>)]) -> String {
+    let mut body = String::new();
+    let mut y = 0;
+    for (title, arms) in panels.iter().filter(|(_, arms)| !arms.is_empty()) {
+        let (min, max) = axis(arms);
+        let x = |value: f64| SVG_LABELS as f64 + (value - min) / (max - min) * SVG_PLOT as f64;
+        y += 28;
+        let _ = writeln!(body, r#"  <text x="10" y="{}" font-weight="bold">{} (mean, 95% CI)</text>"#, y, escape(title));
+        for (name, interval) in arms {
+            y += SVG_ROW;
+            let (low, high, mean) = (x(interval.low), x(interval.high), x(interval.mean));
+            let _ = writeln!(body, r#"  <text x="10" y="{}">{}</text>"#, y + 4, escape(&label(name)));
+            let _ = writeln!(
+                body,
+                r#"  <g stroke="{color}" stroke-width="2"><line x1="{low:.1}" y1="{y}" x2="{high:.1}" y2="{y}"/><line x1="{low:.1}" y1="{top}" x2="{low:.1}" y2="{bottom}"/><line x1="{high:.1}" y1="{top}" x2="{high:.1}" y2="{bottom}"/></g>"#,
+                color = SVG_INTERVAL_COLOR,
+                top = y - 5,
+                bottom = y + 5,
+            );
+            let _ = writeln!(body, r#"  <circle cx="{:.1}" cy="{}" r="4" fill="{}"/>"#, mean, y, SVG_INTERVAL_COLOR);
+            let _ = writeln!(
+                body,
+                r##"  <text x="{}" y="{}" fill="#555">{:.1} [{:.1}, {:.1}] n={}</text>"##,
+                SVG_LABELS + SVG_PLOT + 15,
+                y + 4,
+                interval.mean,
+                interval.low,
+                interval.high,
+                interval.n
+            );
+        }
+        y += 18;
+        let _ = writeln!(
+            body,
+            r##"  <g fill="#9f9f9f"><text x="{}" y="{y}">{:.1}</text><text x="{}" y="{y}" text-anchor="end">{:.1}</text></g>"##,
+            SVG_LABELS,
+            min,
+            SVG_LABELS + SVG_PLOT,
+            max
+        );
+    }
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{SVG_WIDTH}" height="{}" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="12">
+  <rect width="100%" height="100%" fill="#fff"/>
+{body}</svg>
+"##,
+        y + 12
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bootstraps_repeatable_intervals_and_plots_them_on_one_axis() {
+        let values = [60.0, 64.0, 70.0, 58.0, 66.0, 72.0, 61.0, 69.0];
+        let interval = bootstrap(&values).unwrap();
+        assert_eq!(bootstrap(&values), Some(interval));
+        assert!((interval.mean - 65.0).abs() < 1e-9);
+        assert!(interval.low < interval.mean && interval.mean < interval.high);
+        assert!(interval.low >= 58.0 && interval.high <= 72.0);
+        assert_eq!(bootstrap(&[5.0]).map(|single| (single.low, single.high)), Some((5.0, 5.0)));
+        assert_eq!(bootstrap(&[]), None);
+
+        let arms = vec![
+            ("baseline".to_string(), Interval { mean: 50.0, low: 40.0, high: 60.0, n: 9 }),
+            ("terse CLAUDE.md".to_string(), Interval { mean: 70.0, low: 60.0, high: 80.0, n: 7 }),
+            ("examples & <rules>".to_string(), interval),
+        ];
+        let plot = ascii_plot(&arms, 21);
+        let rows: Vec<&str> = plot.lines().collect();
+        assert_eq!(rows[0], "  baseline            ├────●────┤            50.0 [40.0, 60.0] n=9");
+        assert_eq!(rows[1], "  terse CLAUDE.md               ├────●────┤  70.0 [60.0, 80.0] n=7");
+        assert!(rows[3].ends_with("40.0             80.0"), "{}", plot);
+
+        let svg = svg_plot(&[("Overall quality", arms)]);
+        assert!(svg.starts_with("<svg") && svg.contains("examples &amp; &lt;rules&gt;"));
+        assert_eq!(svg.matches("<circle").count(), 3);
+    }
+}
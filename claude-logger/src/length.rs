@@ -0,0 +1,210 @@
+use serde::Serialize;
+
+/// Upper bounds (inclusive) of the exchange-count buckets; the last bucket is open-ended.
+const EXCHANGE_BOUNDS: &[usize] = &[10, 20, 40, 80];
+
+/// Upper bounds (inclusive, in minutes) of the duration buckets.
+const DURATION_BOUNDS: &[usize] = &[15, 30, 60, 120];
+
+/// Buckets need this many sessions before they count towards a plateau.
+const MIN_BUCKET_SESSIONS: usize = 2;
+
+/// One session's size and outcome.
+#[derive(Debug, Clone, Copy)]
+pub struct LengthSample {
+    pub exchanges: usize,
+    pub minutes: Option<i64>,
+    pub quality: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LengthBucket {
+    pub label: String,
+    /// Inclusive upper bound, or `None` for the open-ended last bucket
+    pub upper: Option<usize>,
+    pub sessions: usize,
+    pub avg_quality: f64,
+}
+
+/// How session length relates to quality.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LengthAnalysis {
+    pub by_exchanges: Vec<LengthBucket>,
+    pub by_duration: Vec<LengthBucket>,
+    /// Pearson correlation of exchange count with overall quality
+    pub exchange_correlation: Option<f64>,
+    /// Pearson correlation of duration with overall quality
+    pub duration_correlation: Option<f64>,
+}
+
+impl LengthAnalysis {
+    pub fn from_samples(samples: &[LengthSample]) -> Self {
+        let exchanges: Vec<(f64, f64)> = samples
+            .iter()
+            .map(|sample| (sample.exchanges as f64, sample.quality))
+            .collect();
+        let durations: Vec<(f64, f64)> = samples
+            .iter()
+            .filter_map(|sample| sample.minutes.map(|minutes| (minutes as f64, sample.quality)))
+            .collect();
+
+        Self {
+            by_exchanges: bucket(&exchanges, EXCHANGE_BOUNDS, "exchanges"),
+            by_duration: bucket(&durations, DURATION_BOUNDS, "min"),
+            exchange_correlation: correlation(&exchanges),
+            duration_correlation: correlation(&durations),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_exchanges.is_empty()
+    }
+
+    /// Recommendations for where longer sessions stop paying off.
+    pub fn recommendations(&self) -> Vec<String> {
+        let mut recommendations = Vec::new();
+        if let Some(upper) = plateau(&self.by_exchanges) {
+            recommendations.push(format!(
+                "Quality plateaus after ~{} exchanges; consider splitting larger tasks into separate sessions",
+                upper
+            ));
+        }
+        if let Some(upper) = plateau(&self.by_duration) {
+            recommendations.push(format!(
+                "Sessions longer than ~{} minutes score no better; consider a break or a fresh session",
+                upper
+            ));
+        }
+        recommendations
+    }
+
+    pub fn print(&self) {
+        if self.is_empty() {
+            println!("No sessions found for analysis.");
+            return;
+        }
+
+        println!("Quality by exchange count:");
+        print_buckets(&self.by_exchanges);
+        if let Some(correlation) = self.exchange_correlation {
+            println!("  Correlation with quality: {:+.2}", correlation);
+        }
+
+        if !self.by_duration.is_empty() {
+            println!("Quality by duration:");
+            print_buckets(&self.by_duration);
+            if let Some(correlation) = self.duration_correlation {
+                println!("  Correlation with quality: {:+.2}", correlation);
+            }
+        }
+    }
+}
+
+fn print_buckets(buckets: &[LengthBucket]) {
+    for bucket in buckets {
+        println!("  {:<16} {:>3} sessions, average quality {:.1}/100",
+            bucket.label, bucket.sessions, bucket.avg_quality);
+    }
+}
+
+fn bucket(points: &[(f64, f64)], bounds: &[usize], unit: &str) -> Vec<LengthBucket> {
+    let mut buckets = Vec::new();
+    let mut lower = 0;
+
+    for upper in bounds.iter().copied().map(Some).chain([None]) {
+        let in_bucket: Vec<f64> = points
+            .iter()
+            .filter(|(value, _)| *value >= lower as f64 && upper.is_none_or(|upper| *value <= upper as f64))
+            .map(|(_, quality)| *quality)
+            .collect();
+
+        if !in_bucket.is_empty() {
+            buckets.push(LengthBucket {
+                label: match upper {
+                    Some(upper) => format!("{}-{} {}", lower, upper, unit),
+                    None => format!("{}+ {}", lower, unit),
+                },
+                upper,
+                sessions: in_bucket.len(),
+                avg_quality: in_bucket.iter().sum::<f64>() / in_bucket.len() as f64,
+            });
+        }
+        lower = upper.map_or(lower, |upper| upper + 1);
+    }
+
+    buckets
+}
+
+/// Upper bound of the best-scoring bucket when every longer bucket scores no higher.
+fn plateau(buckets: &[LengthBucket]) -> Option<usize> {
+    let populated: Vec<&LengthBucket> = buckets
+        .iter()
+        .filter(|bucket| bucket.sessions >= MIN_BUCKET_SESSIONS)
+        .collect();
+
+    let peak = populated
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.avg_quality.total_cmp(&b.avg_quality))
+        .map(|(index, _)| index)?;
+
+    let longer = &populated[peak + 1..];
+    if longer.is_empty() || longer.iter().any(|bucket| bucket.avg_quality > populated[peak].avg_quality) {
+        return None;
+    }
+    populated[peak].upper
+}
+
+fn correlation(points: &[(f64, f64)]) -> Option<f64> {
+    if points.len() < 3 {
+        return None;
+    }
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let spread_x: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum::<f64>().sqrt();
+    let spread_y: f64 = points.iter().map(|(_, y)| (y - mean_y).powi(2)).sum::<f64>().sqrt();
+
+    if spread_x == 0.0 || spread_y == 0.0 {
+        return None;
+    }
+    Some(covariance / (spread_x * spread_y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(exchanges: usize, quality: f64) -> LengthSample {
+        LengthSample {
+            exchanges,
+            minutes: None,
+            quality,
+        }
+    }
+
+    #[test]
+    fn detects_quality_plateau() {
+        let samples = [
+            sample(5, 50.0),
+            sample(8, 52.0),
+            sample(25, 70.0),
+            sample(35, 72.0),
+            sample(50, 65.0),
+            sample(60, 66.0),
+            sample(120, 60.0),
+        ];
+        let analysis = LengthAnalysis::from_samples(&samples);
+
+        assert_eq!(analysis.by_exchanges.len(), 4);
+        assert_eq!(analysis.by_exchanges[1].label, "21-40 exchanges");
+        assert_eq!(
+            analysis.recommendations(),
+            vec!["Quality plateaus after ~40 exchanges; consider splitting larger tasks into separate sessions"]
+        );
+        assert!(analysis.by_duration.is_empty());
+    }
+}
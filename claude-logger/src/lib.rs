@@ -1,13 +1,98 @@
+pub mod activity;
 pub mod analyzer;
+pub mod audit;
+pub mod backfill;
+pub mod badge;
+pub mod anomaly;
+pub mod batch;
+pub mod billing;
+pub mod annotate;
+pub mod calendar;
+pub mod changes;
 pub mod cli;
+pub mod compaction;
+pub mod config;
+pub mod conversation;
+pub mod cost;
+pub mod diff;
+pub mod digest;
+pub mod edits;
+pub mod embeddings;
+pub mod encryption;
+pub mod export;
+pub mod features;
+pub mod feed;
+pub mod fields;
+pub mod filter;
 pub mod git;
+pub mod guard;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+pub mod highlight;
+pub mod history;
+pub mod hold;
+pub mod hooks;
+pub mod intervals;
+pub mod length;
+pub mod live;
+pub mod locale;
+pub mod log_format;
 pub mod logger;
+pub mod merge;
+pub mod metadata;
+pub mod metadata_store;
+pub mod methodology;
+pub mod migrate;
+pub mod mirror;
+pub mod notify;
 pub mod patterns;
+pub mod phases;
+pub mod project;
+pub mod prune;
+pub mod pty;
+pub mod ranking;
+pub mod reanalyze;
+pub mod redact;
+pub mod reminders;
+pub mod resume;
+pub mod rhythm;
+pub mod report;
+pub mod review;
+pub mod self_stats;
+pub mod sentiment;
+pub mod serve;
 pub mod session;
+pub mod share;
+pub mod shell;
+pub mod stats;
+pub mod story;
+pub mod storage;
+pub mod sync;
+pub mod synth;
+pub mod task_type;
+pub mod template;
+pub mod terminal;
+pub mod timezone;
+pub mod tmux;
+pub mod transcript;
+pub mod todos;
+pub mod transition;
+pub mod trash;
+pub mod trends;
+pub mod tui;
+pub mod validate;
 
 pub use analyzer::{SessionAnalyzer, SessionSummary};
 pub use cli::{Cli, Commands};
+pub use config::Config;
+pub use cost::CostSummary;
 pub use git::GitRepo;
 pub use logger::ClaudeLogger;
 pub use patterns::{ConversationPatterns, SessionQuality};
-pub use session::{AnalysisMetrics, Methodology, MethodologyStats, SessionMetadata, SessionsMetadata};
\ No newline at end of file
+pub use report::Report;
+pub use serve::ApiServer;
+pub use storage::LogStore;
+pub use metadata_store::{MetadataStore, SessionFilter};
+pub use stats::{AggregateStats, Distribution};
+pub use session::{ActionItem, ActionState, AnalysisMetrics, Annotation, Methodology, MethodologyStats, Phase, Reminder, RemoteArchive, ReviewState, SessionMetadata, SessionsMetadata, TokenUsage};
+pub use terminal::TerminalInfo;
\ No newline at end of file
@@ -0,0 +1,159 @@
+use crate::log_format;
+use crate::logger::ClaudeLogger;
+use crate::patterns::get_patterns;
+use crate::project;
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Serialize;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+/// Logs untouched for longer than this are not offered as the active session.
+const ACTIVE_WINDOW_SECS: u64 = 15 * 60;
+
+/// A capture that is still being written.
+#[derive(Debug, Clone)]
+pub struct LiveSession {
+    pub id: String,
+    pub log_file: PathBuf,
+    pub started: DateTime<Utc>,
+}
+
+impl LiveSession {
+    /// Wrapped sessions only reach the metadata when they end, so fall back to the id's timestamp.
+    fn new(logger: &ClaudeLogger, id: &str, log_file: PathBuf) -> Self {
+        let started = logger
+            .get_session(id)
+            .map(|session| session.timestamp)
+            .or_else(|| {
+                let stamp = id.get(..19)?;
+                NaiveDateTime::parse_from_str(stamp, "%Y-%m-%d_%H-%M-%S").ok().map(|time| time.and_utc())
+            })
+            .unwrap_or_else(Utc::now);
+        Self { id: id.to_string(), log_file, started }
+    }
+}
+
+/// Wrapped sessions are only saved once they end; tmux captures are saved open.
+fn is_live(logger: &ClaudeLogger, session_id: &str) -> bool {
+    logger
+        .get_session(session_id)
+        .is_none_or(|session| session.tmux_pane.is_some() && session.end_time.is_none())
+}
+
+/// The named session, or the most recently written log of a session still running.
+pub fn find_session(logger: &ClaudeLogger, session_id: Option<&str>) -> Result<LiveSession> {
+    if let Some(id) = session_id {
+        let log_file = project::find_log(logger.logs_dir(), id)
+            .with_context(|| format!("No log for session {} in {}", id, logger.logs_dir().display()))?;
+        return Ok(LiveSession::new(logger, id, log_file));
+    }
+
+    let newest = project::log_files(logger.logs_dir())
+        .into_iter()
+        .filter_map(|path| {
+            let id = path.file_name()?.to_str()?.strip_suffix(".log")?.to_string();
+            if !is_live(logger, &id) {
+                return None;
+            }
+            let modified = fs::metadata(&path).ok()?.modified().ok()?;
+            let age = modified.elapsed().unwrap_or_default();
+            (age.as_secs() <= ACTIVE_WINDOW_SECS).then_some((modified, id, path))
+        })
+        .max_by_key(|(modified, _, _)| *modified);
+
+    match newest {
+        Some((_, id, path)) => Ok(LiveSession::new(logger, &id, path)),
+        None => anyhow::bail!("No active session found; start one with `claude-logger` or pass a session ID"),
+    }
+}
+
+/// A running session's metrics so far.
+#[derive(Debug, Clone, Serialize)]
+pub struct LiveMetrics {
+    pub session_id: String,
+    pub started: DateTime<Utc>,
+    pub elapsed_seconds: i64,
+    pub exchanges: usize,
+    pub code_blocks: usize,
+    pub file_edits: usize,
+    pub log_bytes: u64,
+}
+
+impl LiveMetrics {
+    pub fn new(session: &LiveSession, content: &str, size: u64, now: DateTime<Utc>) -> Self {
+        let metrics = get_patterns().analyze_content(content);
+        Self {
+            session_id: session.id.clone(),
+            started: session.started,
+            elapsed_seconds: (now - session.started).num_seconds().max(0),
+            exchanges: metrics.exchanges,
+            code_blocks: metrics.code_blocks,
+            file_edits: metrics.file_edits,
+            log_bytes: size,
+        }
+    }
+}
+
+/// One line of live metrics, refreshed while the session runs.
+pub fn status_line(metrics: &LiveMetrics) -> String {
+    let elapsed = metrics.elapsed_seconds;
+    format!(
+        "{} | {}:{:02}:{:02} | {} exchanges | {} code blocks | {} edits | {} KB",
+        metrics.session_id,
+        elapsed / 3600,
+        elapsed / 60 % 60,
+        elapsed % 60,
+        metrics.exchanges,
+        metrics.code_blocks,
+        metrics.file_edits,
+        metrics.log_bytes / 1024
+    )
+}
+
+/// Re-read a session's log every `interval_secs`, passing its metrics to `on_update`, until the
+/// session is finalized or `on_update` fails.
+pub fn follow(
+    logger: &mut ClaudeLogger,
+    session: &LiveSession,
+    interval_secs: u64,
+    mut on_update: impl FnMut(&LiveMetrics) -> Result<()>,
+) -> Result<()> {
+    let mut last_size = None;
+    let mut content = String::new();
+    loop {
+        let size = fs::metadata(&session.log_file).map(|metadata| metadata.len()).unwrap_or(0);
+        // Analysis covers the whole log, so only re-read it when it grew
+        if last_size != Some(size) {
+            let raw = fs::read(&session.log_file).unwrap_or_default();
+            content = log_format::to_text(&String::from_utf8_lossy(&raw)).1;
+            last_size = Some(size);
+        }
+
+        on_update(&LiveMetrics::new(session, &content, size, Utc::now()))?;
+
+        if let Err(e) = logger.reload_metadata() {
+            eprintln!("\nWarning: Failed to reload metadata: {:#}", e);
+        }
+        if !is_live(logger, &session.id) {
+            return Ok(());
+        }
+
+        thread::sleep(Duration::from_secs(interval_secs.max(1)));
+    }
+}
+
+/// Tail a session's log, redrawing its status line until the session is finalized.
+pub fn watch(logger: &mut ClaudeLogger, session: &LiveSession, interval_secs: u64) -> Result<()> {
+    println!("Watching {} (Ctrl-C to stop)", session.log_file.display());
+    follow(logger, session, interval_secs, |metrics| {
+        print!("\r\x1b[2K{}", status_line(metrics));
+        io::stdout().flush()?;
+        Ok(())
+    })?;
+    println!("\nSession ended.");
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use crate::config::DisplayConfig;
+use chrono::NaiveDate;
+use fluent::concurrent::FluentBundle;
+use fluent::{FluentArgs, FluentResource, FluentValue};
+use std::env;
+use std::sync::OnceLock;
+use unic_langid::LanguageIdentifier;
+
+/// Translations for report headings, by language.
+const TRANSLATIONS: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.ftl")),
+    ("de", include_str!("../locales/de.ftl")),
+    ("fr", include_str!("../locales/fr.ftl")),
+    ("es", include_str!("../locales/es.ftl")),
+];
+
+/// Number and date conventions, by language.
+struct Conventions {
+    decimal: char,
+    month_format: &'static str,
+}
+
+fn conventions(language: &str) -> Conventions {
+    match language {
+        "de" => Conventions { decimal: ',', month_format: "%m.%Y" },
+        "fr" | "es" => Conventions { decimal: ',', month_format: "%m/%Y" },
+        _ => Conventions { decimal: '.', month_format: "%B %Y" },
+    }
+}
+
+/// How reports are worded and how their numbers are written.
+///
+/// CSV, JSON and templates keep plain `.` decimals so other tools can read them.
+pub struct Locale {
+    language: String,
+    conventions: Conventions,
+    bundle: FluentBundle<FluentResource>,
+    /// English, for messages the chosen language lacks
+    fallback: FluentBundle<FluentResource>,
+}
+
+impl Locale {
+    /// Fall back to English for languages without translations.
+    pub fn new(language: &str) -> Self {
+        let language = if TRANSLATIONS.iter().any(|(code, _)| *code == language) { language } else { "en" };
+        Self {
+            language: language.to_string(),
+            conventions: conventions(language),
+            bundle: bundle(language),
+            fallback: bundle("en"),
+        }
+    }
+
+    /// The configured locale, else the one in `LC_ALL`, `LC_MESSAGES` or `LANG`.
+    pub fn from_config(config: &DisplayConfig) -> Self {
+        let requested = config
+            .locale
+            .clone()
+            .or_else(|| ["LC_ALL", "LC_MESSAGES", "LANG"].iter().find_map(|var| env::var(var).ok().filter(|value| !value.is_empty())));
+        let language = requested.as_deref().and_then(language_of);
+
+        if let (Some(locale), None) = (&config.locale, &language) {
+            eprintln!("Warning: Unrecognized locale '{}' in the config; using English", locale);
+        }
+        Self::new(language.as_deref().unwrap_or("en"))
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    pub fn text(&self, id: &str) -> String {
+        self.text_with(id, &[])
+    }
+
+    pub fn text_with(&self, id: &str, args: &[(&str, FluentValue)]) -> String {
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+        format_message(&self.bundle, id, &fluent_args)
+            .or_else(|| format_message(&self.fallback, id, &fluent_args))
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    /// A fraction with `digits` decimals and the locale's decimal separator.
+    pub fn number(&self, value: f64, digits: usize) -> String {
+        let formatted = format!("{:.*}", digits, value);
+        match self.conventions.decimal {
+            '.' => formatted,
+            decimal => formatted.replace('.', &decimal.to_string()),
+        }
+    }
+
+    pub fn minutes(&self, minutes: i64) -> String {
+        self.text_with("duration-minutes", &[("minutes", FluentValue::from(minutes))])
+    }
+
+    pub fn month(&self, month: NaiveDate) -> String {
+        month.format(self.conventions.month_format).to_string()
+    }
+}
+
+fn bundle(language: &str) -> FluentBundle<FluentResource> {
+    let source = TRANSLATIONS.iter().find(|(code, _)| *code == language).map_or(TRANSLATIONS[0].1, |(_, source)| source);
+    let langid: LanguageIdentifier = language.parse().unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // Terminal output has no use for bidi isolation marks
+    bundle.set_use_isolating(false);
+    let resource = FluentResource::try_new(source.to_string()).expect("bundled translations parse");
+    bundle.add_resource(resource).expect("bundled translations have unique ids");
+    bundle
+}
+
+fn format_message(bundle: &FluentBundle<FluentResource>, id: &str, args: &FluentArgs) -> Option<String> {
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = Vec::new();
+    Some(bundle.format_pattern(pattern, Some(args), &mut errors).into_owned())
+}
+
+/// The language of a POSIX (`de_DE.UTF-8`) or BCP 47 (`de-DE`) locale name.
+fn language_of(locale: &str) -> Option<String> {
+    let tag = locale.split(['.', '@']).next()?.replace('_', "-");
+    if tag == "C" || tag == "POSIX" {
+        return Some("en".to_string());
+    }
+    let langid: LanguageIdentifier = tag.parse().ok()?;
+    Some(langid.language.as_str().to_string())
+}
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// The locale chosen at startup, or the environment's when `init` was not called.
+pub fn current() -> &'static Locale {
+    LOCALE.get_or_init(|| Locale::from_config(&DisplayConfig::default()))
+}
+
+pub fn init(config: &DisplayConfig) {
+    let _ = LOCALE.set(Locale::from_config(config));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_numbers_and_headings_per_language() {
+        let german = Locale::new("de");
+        assert_eq!(german.number(1234.56, 1), "1234,6");
+        assert_eq!(german.text("heading-cost"), "Kosten");
+        assert_eq!(german.minutes(1), "1 Minute");
+
+        let english = Locale::new("xx");
+        assert_eq!(english.language(), "en");
+        assert_eq!(english.number(2.5, 2), "2.50");
+        assert_eq!(english.minutes(45), "45 minutes");
+
+        assert_eq!(language_of("fr_CA.UTF-8").as_deref(), Some("fr"));
+        assert_eq!(language_of("C").as_deref(), Some("en"));
+    }
+}
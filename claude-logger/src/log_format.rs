@@ -0,0 +1,154 @@
+use crate::conversation::{self, clean_log, ExchangeStyle};
+use serde::Serialize;
+
+/// How a session's log was captured, which decides how it is read into conversation text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LogFormat {
+    /// A terminal recording from `script`, escape sequences and all
+    ScriptCapture,
+    /// Plain text, already free of terminal noise
+    CleanText,
+    /// Claude Code's JSONL conversation file
+    ClaudeJsonl,
+    /// A transcript exported by `convert` or `show --format markdown`
+    Markdown,
+}
+
+impl LogFormat {
+    pub const ALL: [LogFormat; 4] =
+        [LogFormat::ScriptCapture, LogFormat::CleanText, LogFormat::ClaudeJsonl, LogFormat::Markdown];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LogFormat::ScriptCapture => "script-capture",
+            LogFormat::CleanText => "clean-text",
+            LogFormat::ClaudeJsonl => "claude-jsonl",
+            LogFormat::Markdown => "markdown",
+        }
+    }
+
+    /// Bumped whenever the format's parser changes what it produces, so text cached by an
+    /// older parser is read again from the log.
+    pub fn parser_version(self) -> u32 {
+        match self {
+            LogFormat::ScriptCapture => 1,
+            LogFormat::CleanText => 1,
+            LogFormat::ClaudeJsonl => 1,
+            LogFormat::Markdown => 1,
+        }
+    }
+
+    /// `name@version`, as recorded in `SessionMetadata::log_parser`.
+    pub fn parser_id(self) -> String {
+        format!("{}@{}", self.name(), self.parser_version())
+    }
+
+    /// Whether text produced by the parser `id` is what the current parser would produce.
+    pub fn is_current(id: &str) -> bool {
+        Self::ALL.iter().any(|format| format.parser_id() == id)
+    }
+
+    pub fn detect(raw: &str) -> Self {
+        if ExchangeStyle::detect(raw) == ExchangeStyle::Jsonl {
+            LogFormat::ClaudeJsonl
+        } else if raw.contains('\x1b') || raw.contains('\r') || raw.starts_with("Script started on") {
+            LogFormat::ScriptCapture
+        } else if raw.lines().any(|line| MARKDOWN_SPEAKERS.iter().any(|(heading, _)| line == *heading)) {
+            LogFormat::Markdown
+        } else {
+            LogFormat::CleanText
+        }
+    }
+
+    /// `raw` as conversation text with `Human:` / `Assistant:` markers, which every analysis reads.
+    pub fn parse(self, raw: &str) -> String {
+        match self {
+            LogFormat::ScriptCapture => conversation::normalize(&clean_log(raw)).into_owned(),
+            LogFormat::CleanText | LogFormat::ClaudeJsonl => conversation::normalize(raw.trim()).into_owned(),
+            LogFormat::Markdown => parse_markdown(raw),
+        }
+    }
+}
+
+/// Detect `raw`'s format and read it as conversation text.
+pub fn to_text(raw: &str) -> (LogFormat, String) {
+    let format = LogFormat::detect(raw);
+    (format, format.parse(raw))
+}
+
+/// The speaker headings `conversation::to_markdown` writes, and the markers they stand for.
+const MARKDOWN_SPEAKERS: [(&str, &str); 3] = [("**You**", "Human:"), ("**Claude**", "Assistant:"), ("**Terminal**", "")];
+
+/// Undo `conversation::to_markdown`: headings become markers again and folded tool
+/// output goes back to `⎿` lines.
+fn parse_markdown(raw: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut tool_output: Option<Vec<&str>> = None;
+
+    for line in raw.lines() {
+        if let Some(output) = tool_output.as_mut() {
+            if line.starts_with("</details>") {
+                let body: Vec<&str> = output.iter().copied().filter(|line| !line.starts_with("```")).collect();
+                for (index, text) in body.iter().enumerate() {
+                    lines.push(if index == 0 { format!("  ⎿  {}", text) } else { format!("     {}", text) });
+                }
+                tool_output = None;
+            } else if !line.is_empty() {
+                output.push(line);
+            }
+        } else if line.starts_with("<details>") {
+            tool_output = Some(Vec::new());
+        } else if let Some((_, marker)) = MARKDOWN_SPEAKERS.iter().find(|(heading, _)| line == *heading) {
+            if !marker.is_empty() {
+                lines.push(marker.to_string());
+            }
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+    lines.join("\n").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversation::{log_to_markdown, parse_turns, Speaker};
+    use crate::patterns::get_patterns;
+
+    #[test]
+    fn reads_every_format_into_the_same_conversation() {
+        let capture = "Script started on 2025-01-10\n\x1b[1mHuman:\x1b[0m fix the build\r\n\nAssistant: Done, great.\n⏺ Bash(cargo build)\n  ⎿  Finished\n";
+        let clean = clean_log(capture);
+        let markdown = log_to_markdown(capture);
+        let jsonl = concat!(
+            r#"{"type":"user","message":{"role":"user","content":"fix the build"}}"#, "\n",
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Done, great."},{"type":"tool_use","name":"Bash","input":{"command":"cargo build"}}]}}"#, "\n",
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","content":"Finished"}]}}"#, "\n",
+        );
+
+        let expected = [
+            (capture, LogFormat::ScriptCapture),
+            (clean.as_str(), LogFormat::CleanText),
+            (markdown.as_str(), LogFormat::Markdown),
+            (jsonl, LogFormat::ClaudeJsonl),
+        ];
+        for (raw, format) in expected {
+            let (detected, text) = to_text(raw);
+            assert_eq!(detected, format);
+
+            let turns = parse_turns(&text);
+            let speakers: Vec<Speaker> = turns.iter().map(|turn| turn.speaker).collect();
+            assert_eq!(speakers, [Speaker::Human, Speaker::Assistant], "{:?}: {}", format, text);
+            let metrics = get_patterns().analyze_content(&text);
+            assert_eq!((metrics.exchanges, metrics.enthusiasm_markers), (2, 1), "{:?}", format);
+            assert_eq!(metrics.tool_usage["Bash"], 1, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn older_parsers_are_stale() {
+        assert!(LogFormat::is_current(&LogFormat::Markdown.parser_id()));
+        assert!(!LogFormat::is_current("markdown@0"));
+    }
+}
@@ -1,81 +1,149 @@
-use crate::git::GitRepo;
-use crate::session::{Methodology, SessionMetadata, SessionsMetadata};
+use crate::activity::{self, Heartbeats};
+use crate::analyzer::SessionAnalyzer;
+use crate::audit::{self, AuditEntry};
+use crate::config::{expand_home, Config, GuardAction};
+use crate::embeddings::EmbeddingCache;
+use crate::encryption;
+use crate::log_format::{self, LogFormat};
+use crate::metadata::{self, MetadataLock};
+use crate::changes;
+use crate::compaction;
+use crate::cost;
+use crate::features;
+use crate::fields;
+use crate::git::{self, GitRepo, Identity};
+use crate::guard::SessionGuard;
+use crate::hooks::{self, HookEvent};
+use crate::metadata_store::{open_metadata_store, MetadataStore};
+use crate::methodology;
+use crate::mirror::{self, Mirrored};
+use crate::project;
+use crate::pty::{Capture, CaptureMode};
+use crate::redact::Redactor;
+use crate::resume;
+use crate::session::{Methodology, ReviewState, SessionMetadata, SessionsMetadata};
+use crate::storage::{open_store, LogStore};
+use crate::todos;
+use crate::transcript;
+use crate::terminal::TerminalInfo;
+use crate::timezone::DisplayZone;
 use anyhow::{Context, Result};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 use std::fs;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often a guarded session is checked against its limits.
+const GUARD_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often a running session is checked for exit and terminal resizes.
+const CAPTURE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Logs directory chosen at startup, see `ClaudeLogger::set_logs_directory`.
+static LOGS_DIR: OnceLock<PathBuf> = OnceLock::new();
+/// Names the logs directory ahead of the config file; `--logs-dir` still wins.
+const LOGS_DIR_ENV: &str = "CLAUDE_LOGGER_DIR";
 
 pub struct ClaudeLogger {
     logs_dir: PathBuf,
-    metadata_file: PathBuf,
+    metadata_store: Box<dyn MetadataStore>,
     metadata: SessionsMetadata,
+    /// The store as last loaded or saved, to tell what each save changes
+    saved: SessionsMetadata,
     git_repo: GitRepo,
+    log_store: Option<Box<dyn LogStore>>,
+    config: Config,
 }
 
 impl ClaudeLogger {
     pub fn new() -> Result<Self> {
         let logs_dir = Self::get_logs_directory()?;
-        Self::new_with_dir(&logs_dir)
+        let config = Config::load()?;
+        Self::new_with_config(&logs_dir, config)
     }
 
     pub fn new_with_dir(logs_dir: &Path) -> Result<Self> {
+        Self::new_with_config(logs_dir, Config::default())
+    }
+
+    pub fn new_with_config(logs_dir: &Path, config: Config) -> Result<Self> {
         // Ensure logs directory exists
         fs::create_dir_all(logs_dir)
             .with_context(|| format!("Failed to create logs directory: {}", logs_dir.display()))?;
 
-        let metadata_file = logs_dir.join("sessions_metadata.json");
-        
         // Load existing metadata or create new
-        let metadata = Self::load_metadata(&metadata_file)?;
+        let metadata_store = open_metadata_store(&config.metadata, logs_dir)?;
+        let metadata = metadata_store.load(config.load_mode())?;
         
         // Initialize git repository
-        let git_repo = GitRepo::init_or_open(logs_dir)?;
+        let identity = Identity { name: config.team.author.clone(), email: config.team.email.clone() };
+        let git_repo = GitRepo::init_or_open(logs_dir)?.with_identity(identity);
+        if let Err(e) = git_repo.sync_gitignore(&config.ignore_patterns()) {
+            eprintln!("Warning: Failed to update managed .gitignore: {}", e);
+        }
+
+        let log_store = open_store(&config.storage, logs_dir)?;
 
         Ok(Self {
             logs_dir: logs_dir.to_path_buf(),
-            metadata_file,
+            metadata_store,
+            saved: metadata.clone(),
             metadata,
             git_repo,
+            log_store,
+            config,
         })
     }
 
-    fn get_logs_directory() -> Result<PathBuf> {
-        let home_dir = dirs::home_dir()
-            .context("Failed to get home directory")?;
-        Ok(home_dir.join(".claude-logs"))
+    /// The logs directory: the one passed to `set_logs_directory`, else `CLAUDE_LOGGER_DIR`,
+    /// else `logs.directory` from the config file, else `~/.claude-logs`.
+    pub fn get_logs_directory() -> Result<PathBuf> {
+        choose_logs_directory(LOGS_DIR.get(), std::env::var_os(LOGS_DIR_ENV), || Ok(Config::load()?.logs.directory))
     }
 
-    fn load_metadata(metadata_file: &Path) -> Result<SessionsMetadata> {
-        if metadata_file.exists() {
-            let content = fs::read_to_string(metadata_file)
-                .with_context(|| format!("Failed to read metadata file: {}", metadata_file.display()))?;
-            
-            serde_json::from_str(&content)
-                .with_context(|| format!("Failed to parse metadata file: {}", metadata_file.display()))
-        } else {
-            Ok(SessionsMetadata::new())
-        }
+    /// Use `dir` as the logs directory for the rest of the process, ahead of `CLAUDE_LOGGER_DIR`
+    /// and the config file; for `--logs-dir`. Only the first call has any effect.
+    pub fn set_logs_directory(dir: &Path) {
+        let _ = LOGS_DIR.set(absolute_logs_directory(dir));
     }
 
     pub fn create_session_log(&self, args: &[String]) -> Result<(PathBuf, SessionMetadata)> {
-        let timestamp = Utc::now();
-        let session_id = timestamp.format("%Y-%m-%d_%H-%M-%S").to_string();
-        
         let project_dir = std::env::current_dir()
             .context("Failed to get current working directory")?;
+
+        self.create_session_log_in(&project_dir, args)
+    }
+
+    pub fn create_session_log_in(&self, project_dir: &Path, args: &[String]) -> Result<(PathBuf, SessionMetadata)> {
+        self.create_session_log_at(project_dir, args, Utc::now())
+    }
+
+    /// A new session started at `timestamp`, e.g. one imported from before claude-logger.
+    pub fn create_session_log_at(
+        &self,
+        project_dir: &Path,
+        args: &[String],
+        timestamp: DateTime<Utc>,
+    ) -> Result<(PathBuf, SessionMetadata)> {
+        let session_id = self.unique_session_id(&timestamp.format("%Y-%m-%d_%H-%M-%S").to_string());
         
-        let methodology = self.detect_methodology(&project_dir)
+        let methodology = methodology::detect(&self.config.methodology, project_dir)
             .context("Failed to detect development methodology")?;
         
-        let project_name = project_dir
-            .file_name()
-            .and_then(|name| name.to_str())
-            .unwrap_or("unknown")
-            .to_string();
+        let project_name = project::project_name(project_dir);
 
-        let log_file = self.logs_dir.join(format!("{}.log", session_id));
+        let log_dir = if self.config.logs.per_project {
+            self.logs_dir.join(project::directory_name(&project_name))
+        } else {
+            self.logs_dir.clone()
+        };
+        fs::create_dir_all(&log_dir)
+            .with_context(|| format!("Failed to create log directory: {}", log_dir.display()))?;
+        let log_file = log_dir.join(format!("{}.log", session_id));
         
         let command = if args.is_empty() {
             "claude".to_string()
@@ -83,96 +151,302 @@ impl ClaudeLogger {
             format!("claude {}", args.join(" "))
         };
 
-        let session = SessionMetadata {
+        let mut session = SessionMetadata {
             id: session_id,
             timestamp,
             project: project_name,
             methodology,
-            working_directory: project_dir,
+            working_directory: project_dir.to_path_buf(),
             command,
             log_file: log_file.clone(),
-            duration: None,
+            clean_log_file: None,
+            log_parser: None,
+            transcript_file: None,
+            duration_seconds: None,
             end_time: None,
             features_worked_on: Vec::new(),
             creative_energy: None,
+            terminal: Some(TerminalInfo::detect()),
+            tmux_pane: None,
+            capture_mode: CaptureMode::Pty,
+            hold: None,
+            archived_remote: None,
+            git_commit: None,
+            annotations: Vec::new(),
+            notes: Vec::new(),
+            project_changes: None,
+            compactions: Vec::new(),
+            author: self.config.team.author.clone().or_else(|| git::config_value(project_dir, "user.name")),
+            activity: None,
+            duration_estimated: false,
+            review_state: ReviewState::Unreviewed,
+            action_items: Vec::new(),
+            reminders: Vec::new(),
+            tags: Vec::new(),
+            termination_reason: None,
+            usage: None,
+            extra: HashMap::new(),
+            parent_session_id: None,
+            analysis: None,
         };
+        if let Some(resumption) = resume::detect(args) {
+            session.parent_session_id = resume::find_parent(&self.metadata, project_dir, &resumption, timestamp);
+        }
 
         Ok((log_file, session))
     }
 
-    fn detect_methodology(&self, project_dir: &Path) -> Result<Methodology> {
-        let claude_md_path = project_dir.join(".claude").join("CLAUDE.md");
-        
-        if claude_md_path.exists() {
-            let content = fs::read_to_string(&claude_md_path)
-                .with_context(|| format!("Failed to read CLAUDE.md: {}", claude_md_path.display()))?;
-            
-            if content.contains("Context-Driven") || content.contains("context-driven") {
-                return Ok(Methodology::ContextDriven);
-            } else if content.contains("Command-Based") || content.contains("command-based") {
-                return Ok(Methodology::CommandBased);
-            }
+    pub(crate) fn unique_session_id(&self, base_id: &str) -> String {
+        // Sessions started within the same second get a numeric suffix
+        let mut session_id = base_id.to_string();
+        let mut counter = 2;
+        while self.metadata.get_session(&session_id).is_some()
+            || project::find_log(&self.logs_dir, &session_id).is_some()
+        {
+            session_id = format!("{}-{}", base_id, counter);
+            counter += 1;
         }
-
-        Ok(Methodology::Unknown)
+        session_id
     }
 
-    pub fn run_logged_session(&mut self, claude_args: &[String], track_energy: bool) -> Result<()> {
+    pub fn run_logged_session(
+        &mut self,
+        claude_args: &[String],
+        track_energy: bool,
+        mut extra: HashMap<String, serde_json::Value>,
+        methodology: Option<Methodology>,
+    ) -> Result<()> {
+        // Reject bad --meta values before claude starts, not after the session
+        fields::validate(&self.config.fields, &mut extra)?;
+        self.ensure_encryption_key()?;
         let (log_file, mut session) = self.create_session_log(claude_args)?;
+        session.extra = extra;
+        if let Some(methodology) = methodology {
+            session.methodology = methodology;
+        }
         
         println!("Starting Claude session - logging to: {}", log_file.display());
+        if let Some(parent) = &session.parent_session_id {
+            println!("Resuming session {}", parent);
+        }
         
+        hooks::run(&self.config.hooks, HookEvent::PreSession, &session);
+        let project_snapshot = changes::snapshot(&session.working_directory).unwrap_or_else(|e| {
+            eprintln!("Warning: Failed to snapshot the project, its changes won't be recorded: {:#}", e);
+            None
+        });
         let start_time = Utc::now();
         
-        // Run Claude CLI through script command for full terminal capture
-        let exit_status = self.run_claude_with_logging(&log_file, claude_args)?;
-        
+        // Run Claude CLI on a pseudo-terminal for full terminal capture
+        let guard = SessionGuard::new(&self.config.guard, &self.config.pricing, &session);
+        let mut heartbeats = Heartbeats::new(&log_file);
+        let (exit_status, capture_mode, termination_reason) =
+            self.run_claude_with_logging(&log_file, claude_args, guard, &mut heartbeats)?;
+        session.capture_mode = capture_mode;
+        if let Some(reason) = &termination_reason {
+            println!("Session stopped by the guard: {}", reason);
+        }
+        session.termination_reason = termination_reason;
+
         let end_time = Utc::now();
-        session.duration = Some(end_time.signed_duration_since(start_time));
+        session.set_duration(end_time.signed_duration_since(start_time));
         session.end_time = Some(end_time);
+        session.activity = heartbeats.breakdown(start_time, end_time);
+        if let Some(snapshot) = &project_snapshot {
+            match changes::since(snapshot) {
+                Ok(changes) => session.project_changes = Some(changes),
+                Err(e) => eprintln!("Warning: Failed to record the project's changes: {:#}", e),
+            }
+        }
 
         // Get creative energy if requested
         if track_energy {
             session.creative_energy = Self::get_creative_energy()?;
         }
+        fields::prompt(&self.config.fields, &mut session.extra)?;
+        if self.config.features.confirm {
+            let content = project::read_log(&log_file).map(|raw| log_format::to_text(&String::from_utf8_lossy(&raw)).1);
+            let branch = features::current_branch(&session.working_directory);
+            let detected = content.map(|content| features::detect(&content, branch.as_deref())).unwrap_or_default();
+            session.features_worked_on = features::confirm(detected)?;
+        }
 
-        // Save session metadata
-        self.metadata.add_session(session.clone());
-        self.save_metadata()?;
-
-        // Commit to git
-        self.git_repo.commit_session(&session, &log_file)?;
+        self.complete_session(&session)?;
 
         println!("Session completed. Exit status: {}", exit_status);
         if let Some(energy) = session.creative_energy {
             println!("Creative energy level: {}/3", energy);
         }
+        if let Some(changes) = &session.project_changes {
+            println!("Project changes: {}", changes.describe());
+        }
 
         Ok(())
     }
 
-    fn run_claude_with_logging(&self, log_file: &Path, claude_args: &[String]) -> Result<i32> {
-        let mut cmd = Command::new("script");
-        cmd.arg("-q")  // Quiet mode
-            .arg(&log_file)
-            .arg("claude");
-        
-        // Add claude arguments
-        for arg in claude_args {
-            cmd.arg(arg);
+    /// Persist a finished session's metadata and commit its log to git.
+    pub fn complete_session(&mut self, session: &SessionMetadata) -> Result<()> {
+        let mut session = session.clone();
+        self.derive_from_log(&mut session, false);
+        let sealed = if self.config.encryption.enabled { Self::seal_logs(&session).map(|_| ()) } else { Ok(()) };
+
+        // Save session metadata
+        self.save_session(session.clone())?;
+        // Never commit a log that should have been encrypted
+        sealed.with_context(|| format!("Session {} was saved, but its logs weren't encrypted or committed", session.id))?;
+        hooks::run(&self.config.hooks, HookEvent::PostSession, &session);
+        if self.config.mirror.enabled {
+            self.mirror_session(&session);
         }
+        if self.config.embeddings.enabled {
+            self.update_embeddings(&session);
+        }
+
+        // Commit to git and remember where the log lives in history
+        let mut log_files = vec![session.log_file.as_path()];
+        log_files.extend(session.clean_log_file.as_deref());
+        let commit = self.git_repo.commit_session(&session, &log_files)?;
+        session.git_commit = Some(commit);
+        self.save_session(session.clone())?;
+        hooks::run(&self.config.hooks, HookEvent::PostCommit, &session);
+        self.push_if_enabled();
 
-        let mut child = cmd
-            .stdout(Stdio::inherit())
-            .stderr(Stdio::inherit())
-            .stdin(Stdio::inherit())
-            .spawn()
-            .context("Failed to start script command")?;
+        Ok(())
+    }
+
+    /// With encryption enabled, fail before a capture starts rather than when it ends if there is
+    /// no key to encrypt its logs with.
+    pub fn ensure_encryption_key(&self) -> Result<()> {
+        if self.config.encryption.enabled {
+            encryption::ensure_key()?;
+        }
+        Ok(())
+    }
 
-        let exit_status = child.wait()
-            .context("Failed to wait for script command")?;
+    /// Encrypt a finished session's log and its cleaned copy in place.
+    pub fn seal_logs(session: &SessionMetadata) -> Result<usize> {
+        let mut sealed = 0;
+        for path in std::iter::once(&session.log_file).chain(&session.clean_log_file) {
+            if path.exists() && encryption::seal_file(path)? {
+                sealed += 1;
+            }
+        }
+        Ok(sealed)
+    }
+
+    /// Add a finished session's new chunks to the embeddings cache.
+    fn update_embeddings(&self, session: &SessionMetadata) {
+        let result = project::read_text(session.clean_log_file.as_ref().unwrap_or(&session.log_file)).and_then(|content| {
+            let mut cache = EmbeddingCache::load(&self.logs_dir)?;
+            cache.update(&session.id, &content, Utc::now());
+            cache.enforce_limit(self.config.embeddings.max_mb * 1024 * 1024);
+            cache.save(&self.logs_dir)
+        });
+        if let Err(e) = result {
+            eprintln!("Warning: Failed to update the embeddings cache: {:#}", e);
+        }
+    }
+
+    /// Fill in what is worked out from the captured log: the cleaned copy, the transcript link,
+    /// action items, features worked on, token usage, context compactions, active time and the analysis snapshot. With `refresh`, usage is recomputed even when already known,
+    /// e.g. after the pricing changed.
+    pub fn derive_from_log(&self, session: &mut SessionMetadata, refresh: bool) {
+        match Self::write_clean_log(session) {
+            Ok((path, format)) => {
+                session.clean_log_file = Some(path);
+                session.log_parser = Some(format.parser_id());
+            }
+            Err(e) => eprintln!("Warning: Failed to write cleaned log for {}: {:#}", session.id, e),
+        }
+        if session.transcript_file.is_none() {
+            session.transcript_file = transcript::projects_dir()
+                .and_then(|projects_dir| transcript::find_for_session(&projects_dir, session));
+        }
+
+        let clean_log = session.clean_log_file.as_ref().and_then(|path| project::read_text(path).ok());
+        if session.action_items.is_empty()
+            && let Some(content) = &clean_log
+        {
+            session.action_items = todos::new_items(todos::extract_action_items(content));
+        }
+        if refresh || session.usage.is_none() {
+            session.usage = cost::session_usage(session, clean_log.as_deref(), &self.config.pricing);
+        }
+        if session.features_worked_on.is_empty()
+            && let Some(content) = &clean_log
+        {
+            // The branch checked out now is only the session's own as it finishes
+            let branch = if refresh { None } else { features::current_branch(&session.working_directory) };
+            session.features_worked_on = features::detect(content, branch.as_deref());
+        }
+        let transcript = session.transcript_file.as_ref().and_then(|path| fs::read_to_string(path).ok());
+        // Heartbeats sampled during the capture beat the transcript, which only has message times
+        if session.activity.is_none()
+            && let Some(transcript) = &transcript
+        {
+            session.activity = activity::from_transcript(session, transcript);
+        }
+        if let Some(content) = &clean_log {
+            session.compactions = compaction::detect(transcript.as_deref(), content);
+            session.analysis = Some(SessionAnalyzer::snapshot(session, content));
+        }
+    }
 
-        Ok(exit_status.code().unwrap_or(-1))
+    /// Read the raw log as conversation text in whatever format it is, keeping the raw log
+    /// untouched beside the copy.
+    pub(crate) fn write_clean_log(session: &SessionMetadata) -> Result<(PathBuf, LogFormat)> {
+        let stored = project::read_stored_log(&session.log_file)?;
+        let encrypted = encryption::is_encrypted(&stored);
+        let raw = encryption::open(stored)?;
+        let path = session.clean_log_path();
+        let (format, text) = log_format::to_text(&String::from_utf8_lossy(&raw));
+        // The cleaned copy of an encrypted log is encrypted too
+        let text = if encrypted { encryption::seal(text.as_bytes())? } else { text.into_bytes() };
+        fs::write(&path, text).with_context(|| format!("Failed to write cleaned log: {}", path.display()))?;
+        Ok((path, format))
+    }
+
+    /// Returns the exit code, how the output was captured and, if the guard stopped claude, why.
+    fn run_claude_with_logging(
+        &self,
+        log_file: &Path,
+        claude_args: &[String],
+        mut guard: Option<SessionGuard>,
+        heartbeats: &mut Heartbeats,
+    ) -> Result<(i32, CaptureMode, Option<String>)> {
+        let mut capture = Capture::spawn("claude", claude_args, log_file)?;
+        let mut last_guard_check = Instant::now();
+        let mut termination_reason = None;
+        loop {
+            if let Some(exit_code) = capture.try_wait()? {
+                let mode = capture.mode();
+                capture.finish();
+                return Ok((exit_code, mode, termination_reason));
+            }
+            capture.follow_resize();
+            heartbeats.poll();
+
+            if termination_reason.is_none()
+                && last_guard_check.elapsed() >= GUARD_POLL_INTERVAL
+                && let Some(guard) = guard.as_mut()
+            {
+                last_guard_check = Instant::now();
+                if let Some(breach) = guard.check() {
+                    match guard.action() {
+                        GuardAction::Warn => eprintln!("\r\nWarning: Session {}\r", breach),
+                        GuardAction::Terminate => {
+                            eprintln!("\r\nStopping session: {}\r", breach);
+                            if let Err(e) = capture.hang_up() {
+                                eprintln!("\r\nWarning: {:#}\r", e);
+                            }
+                            termination_reason = Some(breach.to_string());
+                        }
+                    }
+                }
+            }
+
+            thread::sleep(CAPTURE_POLL_INTERVAL);
+        }
     }
 
     pub fn get_creative_energy() -> Result<Option<u8>> {
@@ -197,53 +471,208 @@ impl ClaudeLogger {
         }
     }
 
-    pub fn save_metadata(&self) -> Result<()> {
-        let json = serde_json::to_string_pretty(&self.metadata)
-            .context("Failed to serialize metadata to JSON")?;
-        
-        fs::write(&self.metadata_file, json)
-            .with_context(|| format!("Failed to write metadata file: {}", self.metadata_file.display()))?;
-        
+    /// Re-read metadata from disk so a long-running daemon sees changes made by
+    /// other commands before it writes.
+    pub fn reload_metadata(&mut self) -> Result<()> {
+        self.metadata = self.metadata_store.load(self.config.load_mode())?;
+        self.saved = self.metadata.clone();
+        Ok(())
+    }
+
+    /// Save this process's changes since the metadata was loaded on top of the store as it is
+    /// now, so sessions saved meanwhile by another `claude-logger` aren't overwritten.
+    pub fn save_metadata(&mut self) -> Result<()> {
+        let _lock = self.lock_metadata()?;
+        let current = self.metadata_store.load(self.config.load_mode())?;
+        let merged = metadata::merge(&self.saved, &self.metadata, current);
+        self.metadata_store.save(&merged)?;
+        self.record(&audit::changes(&self.saved, &self.metadata));
+        self.metadata = merged;
+        self.saved = self.metadata.clone();
+        Ok(())
+    }
+
+    /// Store one session, updating it in place where the backend allows.
+    pub fn save_session(&mut self, session: SessionMetadata) -> Result<()> {
+        let _lock = self.lock_metadata()?;
+        let mut current = self.metadata_store.load(self.config.load_mode())?;
+        current.add_session(session.clone());
+        self.metadata_store.save_session(&current, &session)?;
+        self.metadata.add_session(session.clone());
+        let previous = self.saved.sessions.insert(session.id.clone(), session.clone());
+        if let Some(entry) = audit::session_change(previous.as_ref(), Some(&session)) {
+            self.record(&[entry]);
+        }
         Ok(())
     }
 
+    /// Hold the metadata store's lock, for stores kept in a file.
+    fn lock_metadata(&self) -> Result<Option<MetadataLock>> {
+        self.metadata_store.file().map(MetadataLock::acquire).transpose()
+    }
+
+    /// Commit the metadata store's file to the logs repository, pushing when `[git] auto_push` is on.
+    pub fn commit_metadata(&self, message: &str) -> Result<Option<String>> {
+        let Some(file) = self.metadata_store.file() else {
+            return Ok(None);
+        };
+        let commit = self.git_repo.commit_changes(&[], &[file], message)?;
+        if commit.is_some() {
+            self.push_if_enabled();
+        }
+        Ok(commit)
+    }
+
+    /// Write `[mirror]`'s copy of a finished session into its project, reporting rather than
+    /// failing when it can't.
+    fn mirror_session(&self, session: &SessionMetadata) {
+        let content = session
+            .clean_log_file
+            .as_ref()
+            .and_then(|path| project::read_text(path).ok())
+            .or_else(|| project::read_log(&session.log_file).ok().map(|raw| log_format::to_text(&String::from_utf8_lossy(&raw)).1));
+        let Some(content) = content else {
+            eprintln!("Warning: Not mirroring {}: its log couldn't be read", session.id);
+            return;
+        };
+
+        let mirrored = Redactor::new(&self.config.redaction).and_then(|redactor| {
+            mirror::write(&self.config.mirror, self.config.mirror.format, &redactor, session, &content, DisplayZone::Local)
+        });
+        match mirrored {
+            Ok(Mirrored::Written(path)) => println!("Mirrored session to {}", path.display()),
+            Ok(Mirrored::Ignored(path)) => println!("Not mirroring: the project ignores {}", path.display()),
+            Ok(Mirrored::NoProject) => {}
+            Err(e) => eprintln!("Warning: Failed to mirror session {}: {:#}", session.id, e),
+        }
+    }
+
+    /// Push the logs repository when `[git] auto_push` is on. The commits are safe locally, so a
+    /// failed push is only reported.
+    pub fn push_if_enabled(&self) {
+        let git = &self.config.git;
+        if !git.auto_push {
+            return;
+        }
+        if let Err(e) = self.git_repo.push_with_retry(&git.remote, git.push_attempts) {
+            eprintln!("Warning: {:#}", e);
+            eprintln!("Warning: Session history is committed locally only; run `claude-logger git push` to retry.");
+        }
+    }
+
+    /// Add `entries` to the audit log; the change is already saved, so failing to is only reported.
+    fn record(&self, entries: &[AuditEntry]) {
+        if let Err(e) = audit::append(&self.logs_dir, entries) {
+            eprintln!("Warning: {:#}", e);
+        }
+    }
+
     pub fn get_session(&self, session_id: &str) -> Option<&SessionMetadata> {
         self.metadata.get_session(session_id)
     }
 
     pub fn list_sessions(&self, methodology_filter: Option<&str>, limit: usize) -> Vec<&SessionMetadata> {
-        let mut sessions: Vec<_> = self.metadata.sessions.values().collect();
-        
-        // Filter by methodology if specified
-        if let Some(methodology_str) = methodology_filter {
-            let methodology = match methodology_str.to_lowercase().as_str() {
-                "context-driven" | "contextdriven" => Some(Methodology::ContextDriven),
-                "command-based" | "commandbased" => Some(Methodology::CommandBased),
-                "unknown" => Some(Methodology::Unknown),
-                _ => None,
-            };
-            
-            if let Some(method) = methodology {
-                sessions.retain(|session| session.methodology == method);
-            }
-        }
-
-        // Sort by timestamp (newest first)
-        sessions.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-        
-        // Apply limit
-        sessions.into_iter().take(limit).collect()
+        self.metadata.recent_sessions(methodology_filter, limit)
     }
 
     pub fn metadata(&self) -> &SessionsMetadata {
         &self.metadata
     }
 
+    pub fn metadata_mut(&mut self) -> &mut SessionsMetadata {
+        &mut self.metadata
+    }
+
     pub fn add_session(&mut self, session: SessionMetadata) {
         self.metadata.add_session(session);
     }
 
+    pub fn logs_dir(&self) -> &Path {
+        &self.logs_dir
+    }
+
+    pub fn log_store(&self) -> Option<&dyn LogStore> {
+        self.log_store.as_deref()
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
     pub fn git_repo(&self) -> &GitRepo {
         &self.git_repo
     }
-}
\ No newline at end of file
+}
+
+/// `get_logs_directory` given what was set, the environment variable's value and a way to read
+/// the config file, which is only loaded when neither of the others names a directory.
+fn choose_logs_directory(
+    set: Option<&PathBuf>,
+    env: Option<std::ffi::OsString>,
+    configured: impl FnOnce() -> Result<Option<PathBuf>>,
+) -> Result<PathBuf> {
+    if let Some(dir) = set {
+        return Ok(dir.clone());
+    }
+    if let Some(dir) = env.filter(|dir| !dir.is_empty()) {
+        return Ok(absolute_logs_directory(Path::new(&dir)));
+    }
+    if let Some(dir) = configured()? {
+        return Ok(expand_home(&dir));
+    }
+    let home_dir = dirs::home_dir()
+        .context("Failed to get home directory")?;
+    Ok(home_dir.join(".claude-logs"))
+}
+
+fn absolute_logs_directory(dir: &Path) -> PathBuf {
+    std::path::absolute(expand_home(dir)).unwrap_or_else(|_| dir.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::{generate, sessions};
+
+    #[test]
+    fn concurrent_loggers_keep_each_others_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = sessions(2, 8, "api");
+        let sessions = generate(&options, dir.path(), Utc::now());
+
+        // Both load the empty store before either saves, like two terminals started together
+        let mut first = ClaudeLogger::new_with_dir(dir.path()).unwrap();
+        let mut second = ClaudeLogger::new_with_dir(dir.path()).unwrap();
+        first.add_session(sessions[0].session.clone());
+        first.save_metadata().unwrap();
+        second.add_session(sessions[1].session.clone());
+        second.save_metadata().unwrap();
+
+        let stored = ClaudeLogger::new_with_dir(dir.path()).unwrap();
+        assert_eq!(stored.metadata().sessions.len(), 2);
+        assert_eq!(second.metadata().sessions.len(), 2);
+
+        // A deletion removes only the deleted session, even from a logger that never saw the other
+        first.metadata_mut().sessions.remove(&sessions[0].session.id);
+        first.save_metadata().unwrap();
+        let ids: Vec<&String> = first.metadata().sessions.keys().collect();
+        assert_eq!(ids, [&sessions[1].session.id]);
+    }
+
+    #[test]
+    fn picks_the_logs_directory_by_flag_then_environment_then_config() {
+        let flag = PathBuf::from("/flag");
+        let configured = || Ok(Some(PathBuf::from("/configured")));
+        let unreadable = || -> Result<Option<PathBuf>> { anyhow::bail!("config file not read") };
+
+        assert_eq!(choose_logs_directory(Some(&flag), Some("/env".into()), unreadable).unwrap(), flag);
+        assert_eq!(choose_logs_directory(None, Some("/env".into()), unreadable).unwrap(), PathBuf::from("/env"));
+        assert_eq!(choose_logs_directory(None, Some("".into()), configured).unwrap(), PathBuf::from("/configured"));
+        assert_eq!(choose_logs_directory(None, None, configured).unwrap(), PathBuf::from("/configured"));
+        assert!(choose_logs_directory(None, None, unreadable).is_err());
+        assert!(choose_logs_directory(None, None, || Ok(None)).unwrap().ends_with(".claude-logs"));
+
+        let relative = choose_logs_directory(None, Some("logs".into()), unreadable).unwrap();
+        assert!(relative.is_absolute() && relative.ends_with("logs"));
+    }
+}
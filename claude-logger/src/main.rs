@@ -1,26 +1,162 @@
-use claude_logger::{Cli, ClaudeLogger, Commands, SessionAnalyzer};
-use clap::Parser;
+use claude_logger::calendar::{self, Period};
+use claude_logger::cli::{CacheAction, ExportFormat, GitAction, HoldAction, ListFormat, PhaseAction, RemoteAction, ReminderAction, ReviewAction, ShowFormat, TmuxAction, TrashAction, TodoAction, TranscriptAction};
+use claude_logger::metadata::LoadMode;
+use claude_logger::metadata_store::{import_json, open_metadata_store, SessionFilter};
+use claude_logger::mirror::{self, Mirrored};
+use claude_logger::embeddings::EmbeddingCache;
+use claude_logger::redact::Redactor;
+use claude_logger::serve::TlsFiles;
+use claude_logger::timezone::DisplayZone;
+use claude_logger::{
+    annotate, audit, backfill, badge, batch, billing, conversation, cost, diff, digest, edits, embeddings, encryption, export, feed, fields, filter, highlight, history, hold, intervals, live, locale, merge, migrate, patterns, phases, project, prune, reanalyze, reminders, report, resume, review, rhythm, self_stats, sentiment, share, shell, story, sync, synth, template, tmux, todos, transcript, transition, trash, trends, tui, validate, ActionState, ApiServer, Cli, ClaudeLogger, Commands, Config, CostSummary, Methodology,
+    SessionAnalyzer,
+};
+use anyhow::Context;
+use chrono::Datelike;
+use clap::{CommandFactory, FromArgMatches};
+use std::io::{IsTerminal, Write};
 use std::process;
 
 fn main() {
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let invocation = self_stats::invocation(&Cli::command(), &matches);
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
 
-    if let Err(e) = run_cli(cli) {
+    if let Err(e) = run_cli(cli, invocation) {
         eprintln!("Error: {}", e);
         process::exit(1);
     }
 }
 
-fn run_cli(cli: Cli) -> anyhow::Result<()> {
+fn run_cli(cli: Cli, invocation: String) -> anyhow::Result<()> {
+    let zone = DisplayZone::from_flags(cli.utc);
+    if let Some(dir) = &cli.logs_dir {
+        ClaudeLogger::set_logs_directory(dir);
+    }
+
+    // Config errors surface from the command itself; here they only mean the defaults
+    let config = Config::load().unwrap_or_default();
+    locale::init(&config.display);
+    let logs_dir = ClaudeLogger::get_logs_directory()?;
+    if let Err(e) = patterns::init(&logs_dir, &config.analysis.sentiment) {
+        // A bad patterns file must not stop claude from starting
+        if cli.command.is_some() {
+            return Err(anyhow::anyhow!("{:#}", e));
+        }
+        eprintln!("Warning: {:#}; using the built-in patterns", e);
+    }
+
+    // Only the command and flag names, locally, for `self-stats`
+    if logs_dir.is_dir()
+        && let Err(e) = audit::append(&logs_dir, &[audit::AuditEntry::ran(invocation)])
+    {
+        eprintln!("Warning: {:#}", e);
+    }
+
+    let json = cli.json;
+    if json
+        && !matches!(
+            cli.command,
+            Some(Commands::List { .. } | Commands::Show { .. } | Commands::Analyze { .. } | Commands::GitLog { .. })
+        )
+    {
+        anyhow::bail!("--json is supported by list, show, analyze and git-log");
+    }
+
     match cli.command {
-        Some(Commands::Analyze { methodology, comparative }) => {
-            let analyzer = SessionAnalyzer::new()?;
+        Some(Commands::Analyze { methodology, project, author, filter: query, comparative, length, task_types, transition, switch_date, window, billing, month, output, template }) => {
+            let mut analyzer = report_analyzer(cli.include_excluded)?;
+            if let Some(project) = &project
+                && analyzer.retain_project(project) == 0
+            {
+                anyhow::bail!("No sessions for project '{}'; see `claude-logger projects`", project);
+            }
+            if let Some(author) = &author
+                && analyzer.retain_author(author) == 0
+            {
+                anyhow::bail!("No sessions recorded by '{}'", author);
+            }
+            let query = filter::Query::parse(&query, zone)?;
+            if !query.is_empty() && analyzer.retain_matching(&query) == 0 {
+                anyhow::bail!("No sessions match the filter");
+            }
             
-            if comparative {
-                analyzer.generate_report()?;
+            if billing {
+                let config = Config::load()?.billing;
+                let month = month.unwrap_or_else(|| zone.date(chrono::Utc::now()).with_day(1).unwrap());
+                let filter = SessionFilter {
+                    from: Some(zone.start_of_day(month)),
+                    to: Some(zone.start_of_day(billing::next_month(month))),
+                    ..SessionFilter::default()
+                };
+                let bills = billing::bills(&analyzer.filtered_sessions(&filter), &config);
+
+                if let Some(template) = template {
+                    print!("{}", template::render(&template, &billing::BillingSummary::new(&bills, month, &config))?);
+                    return Ok(());
+                }
+                if json {
+                    print_json(&billing::BillingSummary::new(&bills, month, &config))?;
+                } else {
+                    billing::print(&bills, month, &config);
+                }
+                if let Some(path) = output {
+                    std::fs::write(&path, billing::to_csv(&bills, &config))?;
+                    if !json {
+                        println!("\nCSV written to {}", path.display());
+                    }
+                }
+            } else if let Some(template) = template {
+                print!("{}", template::render(&template, &analyzer.build_report()?)?);
+            } else if transition {
+                let config = Config::load()?.analysis;
+                let switch_date = switch_date.or(config.transition_date).ok_or_else(|| {
+                    anyhow::anyhow!("No switch date: pass --switch-date or set analysis.transition_date in the config")
+                })?;
+                let window = window.unwrap_or(config.transition_window);
+
+                let report = transition::transition_report(&analyzer, switch_date, window, zone);
+                if json {
+                    print_json(&report)?;
+                } else {
+                    report.print();
+                }
+            } else if length {
+                let analysis = analyzer.length_analysis();
+                if json {
+                    return print_json(&with_recommendations(&analysis, analysis.recommendations())?);
+                }
+                println!("=== Conversation Length vs Quality ===");
+                analysis.print();
+                for recommendation in analysis.recommendations() {
+                    println!("\nRecommendation: {}", recommendation);
+                }
+            } else if task_types {
+                let analysis = analyzer.task_type_analysis();
+                if json {
+                    return print_json(&with_recommendations(&analysis, analysis.recommendations())?);
+                }
+                println!("=== Methodology by Task Type ===");
+                analysis.print();
+                for recommendation in analysis.recommendations() {
+                    println!("\nRecommendation: {}", recommendation);
+                }
+            } else if json && methodology.is_none() {
+                print_json(&analyzer.build_report()?)?;
+            } else if comparative {
+                analyzer.generate_report(zone)?;
             } else if let Some(method_filter) = methodology {
-                println!("Analyzing sessions with methodology: {}", method_filter);
                 let stats = analyzer.compare_methodologies()?;
+                if json {
+                    // Keyed by methodology, like the HTTP API's /stats
+                    let matching: std::collections::BTreeMap<String, _> = stats
+                        .into_iter()
+                        .filter(|(method, _)| method.to_string().to_lowercase().contains(&method_filter.to_lowercase()))
+                        .map(|(method, stat)| (method.to_string(), stat))
+                        .collect();
+                    return print_json(&matching);
+                }
+                println!("Analyzing sessions with methodology: {}", method_filter);
                 
                 // Find matching methodology and display its stats
                 for (method, stat) in stats {
@@ -32,17 +168,38 @@ fn run_cli(cli: Cli) -> anyhow::Result<()> {
                         }
                         println!("Total Exchanges: {}", stat.metrics.exchanges);
                         println!("Code Blocks: {}", stat.metrics.code_blocks);
+                        println!("File Edits: {}", stat.metrics.file_edits);
                         break;
                     }
                 }
             } else {
-                analyzer.generate_report()?;
+                analyzer.generate_report(zone)?;
             }
         }
         
-        Some(Commands::List { methodology, limit }) => {
-            let logger = ClaudeLogger::new()?;
-            let sessions = logger.list_sessions(methodology.as_deref(), limit);
+        Some(Commands::List { methodology, project, author, filter: query, since, until, limit, format }) => {
+            // Query the store directly so a database backend need not load every session
+            let config = Config::load()?;
+            let store = open_metadata_store(&config.metadata, &ClaudeLogger::get_logs_directory()?)?;
+            let query = filter::Query::parse(&query, zone)?;
+            let filter = SessionFilter {
+                methodology: methodology.as_deref().and_then(Methodology::from_name),
+                project,
+                author,
+                from: since.map(|day| zone.start_of_day(day)),
+                to: until.and_then(|day| day.succ_opt()).map(|day| zone.start_of_day(day)),
+                // A filter query is applied after loading, so the limit must wait for it
+                limit: query.is_empty().then_some(limit),
+            };
+            let mut sessions = store.query(&filter, config.load_mode())?;
+            sessions.retain(|session| query.matches(session));
+            sessions.truncate(limit);
+
+            match format {
+                ListFormat::Table if !json => {}
+                ListFormat::Table | ListFormat::Json => return print_json(&sessions),
+                ListFormat::Tsv => return print_for_scripts(&export::to_tsv(&sessions)),
+            }
             
             if sessions.is_empty() {
                 println!("No sessions found.");
@@ -55,10 +212,10 @@ fn run_cli(cli: Cli) -> anyhow::Result<()> {
                     session.id, 
                     session.methodology, 
                     session.project,
-                    session.timestamp.format("%Y-%m-%d %H:%M")
+                    zone.format(session.timestamp, "%Y-%m-%d %H:%M")
                 );
                 
-                if let Some(duration) = session.duration {
+                if let Some(duration) = session.duration() {
                     print!(" | {}m", duration.num_minutes());
                 }
                 
@@ -70,30 +227,1268 @@ fn run_cli(cli: Cli) -> anyhow::Result<()> {
             }
         }
         
+        Some(Commands::Projects { since, until }) => {
+            let config = Config::load()?;
+            let store = open_metadata_store(&config.metadata, &ClaudeLogger::get_logs_directory()?)?;
+            let filter = SessionFilter {
+                from: since.map(|day| zone.start_of_day(day)),
+                to: until.and_then(|day| day.succ_opt()).map(|day| zone.start_of_day(day)),
+                ..SessionFilter::default()
+            };
+            let sessions = store.query(&filter, config.load_mode())?;
+            project::print(&project::summaries(&sessions), zone);
+        }
+
         Some(Commands::GitLog { count }) => {
             let logger = ClaudeLogger::new()?;
+            if json {
+                return print_json(&logger.git_repo().log(count)?);
+            }
             logger.git_repo().show_log(count)?;
         }
         
-        Some(Commands::Show { session_id, full }) => {
+        Some(Commands::Show { session_id, format, full, no_highlight, edits: show_edits }) => {
             let analyzer = SessionAnalyzer::new()?;
             let summary = analyzer.get_session_summary(&session_id)?;
+
+            let format = format.or(full.then_some(ShowFormat::Raw));
+            if let Some(format) = format {
+                let content = match format {
+                    ShowFormat::Raw => analyzer.read_raw_session_log(&summary.session)?,
+                    _ => analyzer.read_session_log(&summary.session)?,
+                };
+                // Only colour output headed for a terminal, so piping to a file stays clean
+                let highlight = !no_highlight && std::io::stdout().is_terminal();
+                match format {
+                    ShowFormat::Raw | ShowFormat::Clean if highlight => println!("{}", highlight::terminal_log(&content)),
+                    ShowFormat::Raw | ShowFormat::Clean => println!("{}", content),
+                    ShowFormat::Markdown => {
+                        println!("# {} ({})\n", summary.session.project, summary.session.id);
+                        print!("{}", conversation::to_markdown(&conversation::parse_turns(&content)));
+                    }
+                    ShowFormat::Json => {
+                        let conversation = conversation::Conversation {
+                            session_id: summary.session.id.clone(),
+                            turns: conversation::parse_turns(&content),
+                        };
+                        println!("{}", serde_json::to_string_pretty(&conversation)?);
+                    }
+                }
+                return Ok(());
+            }
+
+            if json {
+                return print_json(&analyzer.summary_json(&summary, show_edits)?);
+            }
+
+            summary.print_summary(zone);
+
+            let conversation = resume::chain(analyzer.metadata(), &session_id);
+            if conversation.len() > 1 {
+                println!("\n=== Conversation ({} sessions) ===", conversation.len());
+                for session in conversation {
+                    let marker = if session.id == session_id { "*" } else { " " };
+                    let minutes = session.duration().map(|duration| format!("{} min", duration.num_minutes()));
+                    println!(
+                        "{} {}  {}  {}",
+                        marker,
+                        session.id,
+                        zone.format(session.timestamp, "%Y-%m-%d %H:%M"),
+                        minutes.unwrap_or_else(|| "unfinished".to_string()),
+                    );
+                }
+            }
             
-            summary.print_summary();
-            
-            if full {
-                println!("\n=== Full Log Content ===");
-                let content = std::fs::read_to_string(&summary.session.log_file)?;
-                println!("{}", content);
+            if show_edits {
+                let content = analyzer.read_session_log(&summary.session)?;
+                let file_edits = edits::parse_edits(&content);
+                println!("\n=== File Edits ({}) ===", file_edits.len());
+                edits::print_edits(&file_edits);
             }
         }
         
+        Some(Commands::Story { project, output }) => {
+            let analyzer = report_analyzer(cli.include_excluded)?;
+            let chapters = story::storyline(&analyzer, &project)?;
+            let markdown = story::render_markdown(&project, &chapters, zone);
+
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, markdown)?;
+                    println!("Storyline written to {}", path.display());
+                }
+                None => print!("{}", markdown),
+            }
+        }
+
+        Some(Commands::Convert { log_file, output }) => {
+            let raw = project::read_log(&log_file)?;
+            let markdown = conversation::log_to_markdown(&String::from_utf8_lossy(&raw));
+
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, markdown)?;
+                    println!("Converted to {}", path.display());
+                }
+                None => print!("{}", markdown),
+            }
+        }
+
+        Some(Commands::Mirror { session_ids, format, preview }) => {
+            let analyzer = SessionAnalyzer::new()?;
+            let config = Config::load()?;
+            let redactor = Redactor::new(&config.redaction)?;
+            let format = format.unwrap_or(config.mirror.format);
+            for session_id in &session_ids {
+                let session = analyzer
+                    .metadata()
+                    .get_session(session_id)
+                    .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+                let content = analyzer.read_session_log(session)?;
+                if preview {
+                    redactor.preview(&content).print(session_id);
+                    println!();
+                    continue;
+                }
+                match mirror::write(&config.mirror, format, &redactor, session, &content, zone)? {
+                    Mirrored::Written(path) => println!("Mirrored {} to {}", session_id, path.display()),
+                    Mirrored::Ignored(path) => println!("Skipped {}: the project ignores {}", session_id, path.display()),
+                    Mirrored::NoProject => println!(
+                        "Skipped {}: {} is not in a git repository",
+                        session_id,
+                        session.working_directory.display()
+                    ),
+                }
+            }
+            if preview {
+                println!("Preview only: nothing written.");
+            }
+        }
+
+        Some(Commands::Share { session_id, output, no_highlight, preview }) => {
+            let analyzer = SessionAnalyzer::new()?;
+            let redactor = Redactor::new(&Config::load()?.redaction)?;
+            if preview {
+                let session = analyzer
+                    .metadata()
+                    .get_session(&session_id)
+                    .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+                redactor.preview(&analyzer.read_session_log(session)?).print(&session_id);
+                println!("\nPreview only: nothing written.");
+                return Ok(());
+            }
+
+            let transcript = share::share_session(&analyzer, &session_id, &redactor, zone, !no_highlight)?;
+            let path = output.unwrap_or_else(|| format!("{}.html", session_id).into());
+            std::fs::write(&path, transcript.html)?;
+            println!("Shared transcript written to {} ({} item(s) redacted)", path.display(), transcript.redactions);
+        }
+
+        Some(Commands::Export { format, limit, methodology, project, since, until, filter: query, output }) => {
+            let mut analyzer = report_analyzer(cli.include_excluded)?;
+            analyzer.retain_matching(&filter::Query::parse(&query, zone)?);
+            let methodology = methodology
+                .map(|name| Methodology::from_name(&name).ok_or_else(|| anyhow::anyhow!("Unknown methodology: {}", name)))
+                .transpose()?;
+            let filter = SessionFilter {
+                methodology,
+                project,
+                author: None,
+                from: since.map(|day| zone.start_of_day(day)),
+                to: until.and_then(|day| day.succ_opt()).map(|day| zone.start_of_day(day)),
+                limit: Some(limit),
+            };
+            let sessions = analyzer.filtered_sessions(&filter);
+
+            let content = match format {
+                ExportFormat::Atom => feed::render_feed(&analyzer, &sessions, None, zone),
+                ExportFormat::Json => export::to_json(&export::summaries(&analyzer, &sessions))? + "\n",
+                ExportFormat::Csv => export::to_csv(&export::summaries(&analyzer, &sessions), zone),
+                ExportFormat::Markdown => export::to_markdown(&export::summaries(&analyzer, &sessions), zone),
+            };
+
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, content)?;
+                    println!("Exported to {}", path.display());
+                }
+                None => print!("{}", content),
+            }
+        }
+
+        Some(Commands::Cost { session_id, methodology, project, since, until, refresh }) => {
+            if refresh {
+                let config = Config::load()?;
+                let mut logger = ClaudeLogger::new()?;
+                let recorded = cost::record_all(&mut logger, &config.pricing)?;
+                println!("Recorded usage for {} session(s)\n", recorded);
+            }
+
+            let analyzer = report_analyzer(cli.include_excluded)?;
+            if let Some(session_id) = session_id {
+                let session = analyzer
+                    .metadata()
+                    .get_session(&session_id)
+                    .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+                match &session.usage {
+                    Some(usage) => cost::print_usage(&format!("Session {}", session.id), usage),
+                    None => println!("No usage recorded for {}; run: claude-logger cost --refresh", session.id),
+                }
+                return Ok(());
+            }
+
+            let methodology = methodology
+                .map(|name| Methodology::from_name(&name).ok_or_else(|| anyhow::anyhow!("Unknown methodology: {}", name)))
+                .transpose()?;
+            let filter = SessionFilter {
+                methodology,
+                project,
+                author: None,
+                from: since.map(|day| zone.start_of_day(day)),
+                to: until.and_then(|day| day.succ_opt()).map(|day| zone.start_of_day(day)),
+                limit: None,
+            };
+            let sessions = analyzer.filtered_sessions(&filter);
+
+            println!("=== {} ===", locale::current().text("heading-cost"));
+            CostSummary::from_sessions(sessions.iter().copied()).print();
+
+            let mut priciest: Vec<_> = sessions.iter().filter_map(|session| Some((*session, session.usage.as_ref()?))).collect();
+            priciest.sort_by(|a, b| b.1.cost.total_cmp(&a.1.cost));
+            if !priciest.is_empty() {
+                println!("\nMost expensive sessions:");
+                for (session, usage) in priciest.iter().take(5) {
+                    println!("  ${:>8.2}  {} | {} | {}", usage.cost, session.id, session.methodology, session.project);
+                }
+            }
+        }
+
+        Some(Commands::Serve { bind, token, tls_cert, tls_key, allow_unauthenticated }) => {
+            let analyzer = SessionAnalyzer::new()?;
+            let config = Config::load()?;
+
+            let tls = match (tls_cert.or(config.serve.tls_cert.clone()), tls_key.or(config.serve.tls_key.clone())) {
+                (Some(cert), Some(key)) => Some(TlsFiles { cert, key }),
+                _ => None,
+            };
+
+            let token = token.or(config.serve.token.clone());
+
+            ApiServer::new(analyzer.logs_dir())
+                .with_config(config)
+                .with_token(token)
+                .with_tls(tls)
+                .run(&bind, allow_unauthenticated)?;
+        }
+
+        Some(Commands::Annotate { session_id, lines, category, note }) => {
+            let range = annotate::parse_line_range(&lines)?;
+            let analyzer = SessionAnalyzer::new()?;
+            let session = analyzer.metadata().get_session(&session_id)
+                .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+            let content = analyzer.read_session_log(session)?;
+            let annotation = annotate::new_annotation(&content, range, &category, &note)?;
+
+            let mut logger = ClaudeLogger::new()?;
+            let summary = format!("Annotated {} lines {}-{} [{}]",
+                session_id, annotation.start_line, annotation.end_line, annotation.category);
+            annotate::add_annotation(&mut logger, &session_id, annotation)?;
+            println!("{}", summary);
+        }
+
+        Some(Commands::Note { session_id, text }) => {
+            let mut logger = ClaudeLogger::new()?;
+            let commit = annotate::add_note(&mut logger, &session_id, &text)?;
+            println!("Noted {}", session_id);
+            if let Some(commit) = commit {
+                println!("Recorded in commit {}", &commit[..commit.len().min(7)]);
+            }
+        }
+
+        Some(Commands::Annotations { query, category, project, excerpts }) => {
+            let analyzer = SessionAnalyzer::new()?;
+            let matches = annotate::search(analyzer.metadata(), query.as_deref(), category.as_deref(), project.as_deref());
+
+            if matches.is_empty() {
+                println!("No annotations found.");
+                return Ok(());
+            }
+
+            for (session, annotation) in matches {
+                println!("{} | {} | lines {}-{} | [{}] {}",
+                    session.id,
+                    session.project,
+                    annotation.start_line,
+                    annotation.end_line,
+                    annotation.category,
+                    annotation.note);
+                if excerpts {
+                    for line in annotation.excerpt.lines() {
+                        println!("    > {}", line);
+                    }
+                }
+            }
+        }
+
+        Some(Commands::Review { action }) => match action {
+            ReviewAction::Queue { flagged } => {
+                let analyzer = SessionAnalyzer::new()?;
+                let pending = review::queue(analyzer.metadata(), flagged);
+
+                if pending.is_empty() {
+                    println!("Review queue is empty.");
+                    return Ok(());
+                }
+
+                println!("=== Review Queue ({}) ===", pending.len());
+                for session in pending {
+                    println!("{} | {} | {} | {} | {}",
+                        session.id,
+                        session.review_state,
+                        session.methodology,
+                        session.project,
+                        zone.format(session.timestamp, "%Y-%m-%d %H:%M"));
+                }
+            }
+            ReviewAction::Walk => {
+                let analyzer = SessionAnalyzer::new()?;
+                let mut logger = ClaudeLogger::new()?;
+                review::walk(&mut logger, &analyzer, zone)?;
+            }
+            ReviewAction::Mark { session_id, state } => {
+                let mut logger = ClaudeLogger::new()?;
+                review::mark(&mut logger, &session_id, state)?;
+                println!("Marked {} as {}", session_id, state);
+            }
+        },
+
+        Some(Commands::Todos { action, all }) => match action {
+            None => {
+                let analyzer = SessionAnalyzer::new()?;
+                let states: &[ActionState] = if all {
+                    &[ActionState::Open, ActionState::Done, ActionState::Dismissed]
+                } else {
+                    &[ActionState::Open]
+                };
+                let items = todos::list(analyzer.metadata(), states);
+
+                if items.is_empty() {
+                    println!("No action items.");
+                    return Ok(());
+                }
+
+                for (session, number, item) in items {
+                    print!("{} #{} | {}", session.id, number, item.text);
+                    if all {
+                        print!(" | {}", item.state);
+                    }
+                    println!();
+                }
+            }
+            Some(TodoAction::Done { session_id, number }) => {
+                let mut logger = ClaudeLogger::new()?;
+                let item = todos::set_state(&mut logger, &session_id, number, ActionState::Done)?;
+                println!("Done: {}", item.text);
+            }
+            Some(TodoAction::Dismiss { session_id, number }) => {
+                let mut logger = ClaudeLogger::new()?;
+                let item = todos::set_state(&mut logger, &session_id, number, ActionState::Dismissed)?;
+                println!("Dismissed: {}", item.text);
+            }
+            Some(TodoAction::Scan) => {
+                let analyzer = SessionAnalyzer::new()?;
+                let mut logger = ClaudeLogger::new()?;
+                let found = todos::scan(&mut logger, &analyzer)?;
+                println!("Found {} new action items.", found);
+            }
+        },
+
+        Some(Commands::Remind { session_id, delay, note }) => {
+            let delay = reminders::parse_delay(&delay)?;
+            let mut logger = ClaudeLogger::new()?;
+            let reminder = reminders::schedule(&mut logger, &session_id, delay, &note)?;
+            println!("Reminder set for {} on {}", session_id, zone.format(reminder.due_at, "%Y-%m-%d %H:%M"));
+        }
+
+        Some(Commands::Tag { session_id, tags, remove }) => {
+            let mut logger = ClaudeLogger::new()?;
+            let mut session = logger
+                .get_session(&session_id)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+
+            if remove {
+                session.tags.retain(|tag| !tags.contains(tag));
+            } else {
+                for tag in tags {
+                    if !session.tags.contains(&tag) {
+                        session.tags.push(tag);
+                    }
+                }
+            }
+
+            println!("Tags for {}: {}", session_id, if session.tags.is_empty() { "(none)".to_string() } else { session.tags.join(", ") });
+            logger.add_session(session);
+            logger.save_metadata()?;
+        }
+
+        Some(Commands::Edit { session_id, meta, unset, parent }) => {
+            if meta.is_empty() && unset.is_empty() && parent.is_none() {
+                anyhow::bail!("Nothing to change: pass --meta key=value, --unset key or --parent SESSION_ID");
+            }
+            let mut logger = ClaudeLogger::new()?;
+            let mut session = logger
+                .get_session(&session_id)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+
+            let edits_fields = !meta.is_empty() || !unset.is_empty();
+            for key in &unset {
+                if session.extra.remove(key).is_none() {
+                    eprintln!("Warning: {} has no field '{}'", session_id, key);
+                }
+            }
+            session.extra.extend(meta);
+            let specs = Config::load()?.fields;
+            fields::validate(&specs, &mut session.extra)?;
+            let removed_required: Vec<&str> = fields::missing_required(&specs, &session.extra)
+                .into_iter()
+                .filter(|name| unset.iter().any(|key| key == name))
+                .collect();
+            if !removed_required.is_empty() {
+                anyhow::bail!("Required fields cannot be removed: {}", removed_required.join(", "));
+            }
+
+            match parent.as_deref() {
+                Some("") => {
+                    session.parent_session_id = None;
+                    println!("{} no longer resumes another session", session_id);
+                }
+                Some(parent) => {
+                    if logger.get_session(parent).is_none() {
+                        anyhow::bail!("Session not found: {}", parent);
+                    }
+                    if resume::would_cycle(logger.metadata(), &session_id, parent) {
+                        anyhow::bail!("{} cannot resume {}: the sessions would form a loop", session_id, parent);
+                    }
+                    session.parent_session_id = Some(parent.to_string());
+                    println!("{} now resumes {}", session_id, parent);
+                }
+                None => {}
+            }
+
+            if edits_fields {
+                let mut fields: Vec<_> = session.extra.iter().collect();
+                fields.sort_by_key(|(key, _)| *key);
+                println!("Custom fields for {}:", session_id);
+                if fields.is_empty() {
+                    println!("  (none)");
+                }
+                for (key, value) in fields {
+                    println!("  {} = {}", key, value);
+                }
+            }
+            logger.save_session(session)?;
+        }
+
+        Some(Commands::MigrateMetadata) => {
+            let config = Config::load()?;
+            let logs_dir = ClaudeLogger::get_logs_directory()?;
+            let (path, metadata) = import_json(&logs_dir, config.load_mode())?;
+            let mut imported: Vec<&String> = metadata.sessions.keys().collect();
+            imported.sort();
+            let entries: Vec<audit::AuditEntry> = imported.into_iter().map(|id| audit::AuditEntry::imported(id)).collect();
+            if let Err(e) = audit::append(&logs_dir, &entries) {
+                eprintln!("Warning: {:#}", e);
+            }
+            println!("Imported {} sessions and {} phases into {}", metadata.sessions.len(), metadata.phases.len(), path.display());
+            println!("Set `backend = \"sqlite\"` under [metadata] in the config file to use it.");
+        }
+
+        Some(Commands::Migrate { dry_run }) => {
+            let config = Config::load()?;
+            let logs_dir = ClaudeLogger::get_logs_directory()?;
+            migrate::run(&logs_dir, &config, dry_run)?;
+        }
+
+        Some(Commands::Transcripts { action }) => match action {
+            TranscriptAction::Link => {
+                let mut logger = ClaudeLogger::new()?;
+                let linked = transcript::link_all(&mut logger)?;
+                println!("Linked {} session(s) to Claude Code transcripts", linked);
+            }
+            TranscriptAction::Show { session_id } => {
+                let analyzer = SessionAnalyzer::new()?;
+                let session = analyzer
+                    .metadata()
+                    .get_session(&session_id)
+                    .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+                let path = session.transcript_file.as_ref().ok_or_else(|| {
+                    anyhow::anyhow!("Session {} has no linked transcript; run: claude-logger transcripts link", session_id)
+                })?;
+                println!("=== Transcript: {} ===", path.display());
+                transcript::Transcript::read(path)?.print();
+            }
+        },
+
+        Some(Commands::Import { dry_run }) => {
+            let projects_dir = history::projects_dir()?;
+            let mut logger = ClaudeLogger::new()?;
+            let scan = history::scan(&projects_dir, logger.metadata())?;
+            history::print_scan(&scan, &projects_dir, zone);
+            if !dry_run && !scan.candidates.is_empty() {
+                logger.ensure_encryption_key()?;
+                history::print_outcome(&history::import(&mut logger, &scan.candidates)?);
+            }
+        }
+
+        Some(Commands::Hold { action }) => match action {
+            None => {
+                let analyzer = SessionAnalyzer::new()?;
+                hold::print_list(&hold::held(analyzer.metadata()), zone);
+            }
+            Some(HoldAction::Place { session_ids, reason }) => {
+                let mut logger = ClaudeLogger::new()?;
+                for session_id in hold::place(&mut logger, &session_ids, &reason)? {
+                    println!("Placed a hold on {}", session_id);
+                }
+            }
+            Some(HoldAction::Release { session_ids }) => {
+                let mut logger = ClaudeLogger::new()?;
+                let released = hold::release(&mut logger, &session_ids)?;
+                for session_id in &session_ids {
+                    if released.contains(session_id) {
+                        println!("Released the hold on {}", session_id);
+                    } else {
+                        println!("{} was not on hold", session_id);
+                    }
+                }
+            }
+        },
+
+        Some(Commands::Phase { action }) => match action {
+            None => {
+                let analyzer = SessionAnalyzer::new()?;
+                phases::print_list(&analyzer.metadata().phases, zone);
+            }
+            Some(PhaseAction::Start { name }) => {
+                let mut logger = ClaudeLogger::new()?;
+                let (phase, ended) = phases::start(&mut logger, &name)?;
+                if let Some(ended) = ended {
+                    println!("Ended phase: {}", ended.name);
+                }
+                println!("Started phase: {}", phase.name);
+            }
+            Some(PhaseAction::End) => {
+                let mut logger = ClaudeLogger::new()?;
+                let phase = phases::end(&mut logger)?;
+                println!("Ended phase: {}", phase.name);
+            }
+            Some(PhaseAction::Compare { svg }) => {
+                let analyzer = report_analyzer(cli.include_excluded)?;
+                let summaries = phases::compare(&analyzer);
+                println!("=== Phase Comparison ===");
+                phases::print_comparison(&summaries);
+                if let Some(path) = svg {
+                    std::fs::write(&path, intervals::svg_plot(&phases::interval_panels(&summaries)))
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    println!("\nWrote interval plots to {}", path.display());
+                }
+            }
+        },
+
+        Some(Commands::Reminders { action }) => match action {
+            None => {
+                let analyzer = SessionAnalyzer::new()?;
+                let pending = reminders::pending(analyzer.metadata());
+
+                if pending.is_empty() {
+                    println!("No pending reminders.");
+                    return Ok(());
+                }
+
+                let now = chrono::Utc::now();
+                for (session, number, reminder) in pending {
+                    println!("{} #{} | {}{} | {}",
+                        session.id,
+                        number,
+                        zone.format(reminder.due_at, "%Y-%m-%d %H:%M"),
+                        if reminder.due_at <= now { " (due)" } else { "" },
+                        reminder.note);
+                }
+            }
+            Some(ReminderAction::Done { session_id, number }) => {
+                let mut logger = ClaudeLogger::new()?;
+                let reminder = reminders::complete(&mut logger, &session_id, number)?;
+                println!("Done: {}", reminder.note);
+            }
+        },
+
+        Some(Commands::Status) => {
+            let logger = ClaudeLogger::new()?;
+            let metadata = logger.metadata();
+            let now = chrono::Utc::now();
+
+            println!("=== Status ===");
+            print!("Sessions: {}", metadata.sessions.len());
+            if let Some(latest) = metadata.recent_sessions(None, 1).first() {
+                print!(" (latest {} on {})", latest.id, zone.format(latest.timestamp, "%Y-%m-%d %H:%M"));
+            }
+            println!();
+
+            let unreviewed = review::queue(metadata, false).len();
+            let flagged = review::queue(metadata, true).len() - unreviewed;
+            println!("Review queue: {} unreviewed, {} flagged", unreviewed, flagged);
+            println!("Open action items: {}", todos::list(metadata, &[ActionState::Open]).len());
+            println!("Active tmux captures: {}", tmux::active_captures(&logger).len());
+
+            let due = reminders::due(metadata, now);
+            let upcoming = reminders::pending(metadata).len() - due.len();
+            println!("Reminders: {} due, {} upcoming", due.len(), upcoming);
+            for (session, number, reminder) in due {
+                println!("  {} #{} | {} | {}",
+                    session.id, number, zone.format(reminder.due_at, "%Y-%m-%d %H:%M"), reminder.note);
+            }
+        }
+
+        Some(Commands::Hotspots { project, limit }) => {
+            let analyzer = report_analyzer(cli.include_excluded)?;
+            let hotspots = analyzer.edit_hotspots(project.as_deref(), limit);
+
+            if hotspots.is_empty() {
+                println!("No file edits found.");
+            } else {
+                println!("=== Edit Hotspots ===");
+                report::print_hotspots(&hotspots);
+            }
+        }
+
+        Some(Commands::Trends { period, count, csv, output }) => {
+            let config = Config::load()?;
+            let analyzer = report_analyzer(cli.include_excluded)?;
+            let points = trends::trends(&analyzer, period, &config.calendar, zone, count);
+            if !csv {
+                trends::print(&points);
+                return Ok(());
+            }
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, trends::to_csv(&points))?;
+                    println!("Trends written to {}", path.display());
+                }
+                None => print!("{}", trends::to_csv(&points)),
+            }
+        }
+
+        Some(Commands::InstallShellAlias { shell, rc_file, print, uninstall }) => {
+            if print {
+                print!("{}", shell::snippet());
+                return Ok(());
+            }
+            let rc_file = match rc_file {
+                Some(path) => path,
+                None => shell
+                    .or_else(shell::Shell::detect)
+                    .context("Couldn't tell your shell from SHELL; pass --shell bash or --shell zsh")?
+                    .rc_file()?,
+            };
+
+            if uninstall {
+                if shell::uninstall(&rc_file)? {
+                    println!("Removed the claude alias from {}", rc_file.display());
+                } else {
+                    println!("No claude alias found in {}", rc_file.display());
+                }
+            } else if shell::install(&rc_file)? {
+                println!("Installed the claude alias in {}", rc_file.display());
+                println!("Open a new shell or run `source {}` to start logging every claude session.", rc_file.display());
+                println!("Run `{}=1 claude` to skip logging once.", shell::OPT_OUT_VAR);
+            } else {
+                println!("The claude alias in {} is already up to date", rc_file.display());
+            }
+        }
+
+        Some(Commands::Diff { session_a, session_b, prompts }) => {
+            let analyzer = SessionAnalyzer::new()?;
+            let find = |id: &str| analyzer.metadata().get_session(id).with_context(|| format!("Session not found: {}", id));
+            let comparison = diff::compare(&analyzer, find(&session_a)?, find(&session_b)?, prompts)?;
+            diff::print(&comparison, zone);
+        }
+
+        Some(Commands::Badge { session_id, project, metric, label, output }) => {
+            let badge = match session_id {
+                Some(session_id) => badge::for_session(&SessionAnalyzer::new()?, &session_id, metric, label.as_deref())?,
+                None => {
+                    let project = project.context("Give a session ID or --project")?;
+                    badge::for_project(&report_analyzer(cli.include_excluded)?, &project, metric, label.as_deref())?
+                }
+            };
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, badge.to_svg())
+                        .with_context(|| format!("Failed to write badge: {}", path.display()))?;
+                    println!("Badge written to {} ({}: {})", path.display(), badge.label, badge.value);
+                }
+                None => print!("{}", badge.to_svg()),
+            }
+        }
+
+        Some(Commands::SelfStats) => {
+            let entries = audit::read(&logs_dir)?;
+            let stats = self_stats::SelfStats::from_entries(&entries, &Cli::command(), &Config::load()?, zone, chrono::Utc::now());
+            stats.print(zone);
+        }
+
+        Some(Commands::Rhythm { project }) => {
+            let analyzer = report_analyzer(cli.include_excluded)?;
+            let sessions = analyzer
+                .metadata()
+                .sessions
+                .values()
+                .filter(|session| project.as_ref().is_none_or(|project| &session.project == project));
+            rhythm::Rhythm::from_sessions(sessions).print(zone);
+        }
+
+        Some(Commands::Digest { period, count, write }) => {
+            let config = Config::load()?;
+            let analyzer = report_analyzer(cli.include_excluded)?;
+            let buckets = calendar::bucket_sessions(
+                analyzer.metadata().sessions.values(),
+                period,
+                &config.calendar,
+                zone,
+            );
+
+            let Some((&latest, sessions)) = buckets.last_key_value() else {
+                println!("No sessions found.");
+                return Ok(());
+            };
+
+            // The latest period with sessions, told against the one before it
+            let before = buckets.get(&digest::previous(latest, period, &config.calendar)).map(Vec::as_slice).unwrap_or_default();
+            let summary = digest::render(
+                period,
+                latest,
+                &digest::PeriodSummary::new(sessions),
+                &digest::PeriodSummary::new(before),
+                zone,
+            );
+            println!("{}", summary);
+            if write {
+                let logger = ClaudeLogger::new()?;
+                let path = logger.logs_dir().join(digest::file_name(period, latest));
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&path, &summary).with_context(|| format!("Failed to write {}", path.display()))?;
+                println!("Digest written to {}", path.display());
+                let message = format!("Add digest for {}", latest.label());
+                if logger.git_repo().commit_changes(&[], &[path.as_path()], &message)?.is_some() {
+                    logger.push_if_enabled();
+                }
+                println!();
+            }
+
+            println!("=== {} Digest ===", match period {
+                Period::Week => "Weekly",
+                Period::Month => "Monthly",
+            });
+            for (bucket, sessions) in buckets.iter().rev().take(count) {
+                let minutes: i64 = sessions
+                    .iter()
+                    .filter_map(|session| session.duration())
+                    .map(|duration| duration.num_minutes())
+                    .sum();
+                print!("{} | {} sessions | {}m", bucket.label(), sessions.len(), minutes);
+
+                let energies: Vec<u8> = sessions.iter().filter_map(|session| session.creative_energy).collect();
+                if !energies.is_empty() {
+                    let avg = energies.iter().map(|&e| e as f64).sum::<f64>() / energies.len() as f64;
+                    print!(" | Energy: {:.1}/3", avg);
+                }
+
+                println!();
+
+                // Mark phase boundaries falling inside this period
+                let (start, end) = (zone.start_of_day(bucket.start), zone.start_of_day(bucket.end));
+                for phase in &analyzer.metadata().phases {
+                    if (start..end).contains(&phase.started_at) {
+                        println!("  > phase started: {}", phase.name);
+                    }
+                    if phase.ended_at.is_some_and(|ended_at| (start..end).contains(&ended_at)) {
+                        println!("  < phase ended: {}", phase.name);
+                    }
+                }
+            }
+
+            // Follow-ups falling due before the current period ends
+            let now = chrono::Utc::now();
+            let period_end = zone.start_of_day(period.bucket(zone.date(now), &config.calendar).end);
+            let due = reminders::due(analyzer.metadata(), period_end);
+            if !due.is_empty() {
+                println!("\nReminders due this {}:", match period {
+                    Period::Week => "week",
+                    Period::Month => "month",
+                });
+                for (session, number, reminder) in due {
+                    println!("  {} #{} | {} | {}",
+                        session.id, number, zone.format(reminder.due_at, "%Y-%m-%d %H:%M"), reminder.note);
+                }
+            }
+        }
+
+        Some(Commands::Sync { once, interval, prune_local }) => {
+            let mut logger = ClaudeLogger::new()?;
+
+            if once {
+                let outcome = sync::sync_once(&mut logger, prune_local)?;
+                if outcome.archived.is_empty() && outcome.failed.is_empty() {
+                    println!("Nothing to sync.");
+                }
+                sync::print_outcome(&outcome);
+            } else {
+                sync::run_daemon(&mut logger, interval, prune_local)?;
+            }
+        }
+
+        Some(Commands::Validate { print_schema, strict }) => {
+            if print_schema {
+                print!("{}", validate::METADATA_SCHEMA);
+                return Ok(());
+            }
+
+            // Read the files directly so broken metadata is reported rather than fatal
+            let mode = if strict { LoadMode::Strict } else { Config::load()?.load_mode() };
+            let issues = validate::validate_logs_dir(&ClaudeLogger::get_logs_directory()?, mode)?;
+            if issues.is_empty() {
+                println!("All session metadata is valid.");
+            } else {
+                for issue in &issues {
+                    println!("{}", issue);
+                }
+                return Err(anyhow::anyhow!("Found {} problems in stored session data", issues.len()));
+            }
+        }
+
+        Some(Commands::Batch { filter, apply, dry_run }) => {
+            let query = filter::Query::parse(&filter, zone)?;
+            let mut logger = ClaudeLogger::new()?;
+            let outcome = batch::run(&mut logger, &query, &apply, dry_run)?;
+            batch::print_outcome(&outcome, dry_run);
+        }
+
+        Some(Commands::Delete { session_id, yes }) => {
+            let mut logger = ClaudeLogger::new()?;
+            let session = logger
+                .get_session(&session_id)
+                .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+            hold::ensure_released(session, "delete")?;
+            if !yes {
+                print!(
+                    "Move session {} ({}, {}) and its logs to the trash? [y/N] ",
+                    session.id,
+                    session.project,
+                    zone.format(session.timestamp, "%Y-%m-%d %H:%M")
+                );
+                std::io::Write::flush(&mut std::io::stdout())?;
+                let mut answer = String::new();
+                std::io::stdin().read_line(&mut answer).context("Failed to read confirmation")?;
+                if !answer.trim().eq_ignore_ascii_case("y") {
+                    println!("Nothing deleted.");
+                    return Ok(());
+                }
+            }
+
+            prune::delete(&mut logger, &session_id)?;
+            println!(
+                "Moved session {} to the trash; `trash restore {}` brings it back within {} days",
+                session_id,
+                session_id,
+                logger.config().trash.retention_days
+            );
+        }
+
+        Some(Commands::MergeSessions { first, second }) => {
+            let mut logger = ClaudeLogger::new()?;
+            let outcome = merge::merge(&mut logger, &first, &second)?;
+            let session = &outcome.session;
+            print!("Merged {} and {} into {}", outcome.originals[0], outcome.originals[1], session.id);
+            if let Some(duration) = session.duration() {
+                print!(" ({}m)", duration.num_minutes());
+            }
+            println!();
+            if let Some(commit) = &outcome.commit {
+                println!("Recorded in commit {}", &commit[..commit.len().min(7)]);
+            }
+            println!(
+                "The originals are in the trash; `trash restore` brings them back within {} days",
+                logger.config().trash.retention_days
+            );
+        }
+
+        Some(Commands::Trash { action }) => {
+            let mut logger = ClaudeLogger::new()?;
+            let retention_days = logger.config().trash.retention_days;
+            for session_id in trash::purge_expired(logger.logs_dir(), retention_days, chrono::Utc::now())? {
+                println!("Purged {} (deleted more than {} days ago)", session_id, retention_days);
+            }
+
+            match action {
+                TrashAction::List => {
+                    let entries = trash::list(logger.logs_dir())?;
+                    if entries.is_empty() {
+                        println!("The trash is empty.");
+                    }
+                    for entry in entries {
+                        println!(
+                            "{} | {} | deleted {} | purged after {}",
+                            entry.session.id,
+                            entry.session.project,
+                            zone.format(entry.deleted_at, "%Y-%m-%d %H:%M"),
+                            zone.format(entry.expires_at(retention_days), "%Y-%m-%d")
+                        );
+                    }
+                }
+                TrashAction::Restore { session_id } => {
+                    let session = trash::restore(&mut logger, &session_id)?;
+                    println!("Restored session {} ({})", session.id, session.project);
+                }
+            }
+        }
+
+        Some(Commands::Archive { older_than, dry_run }) => {
+            let cutoff = chrono::Utc::now()
+                .checked_sub_signed(reminders::parse_delay(&older_than)?)
+                .with_context(|| format!("--older-than {} reaches before the earliest date that can be stored", older_than))?;
+            let mut logger = ClaudeLogger::new()?;
+            let sessions: Vec<String> =
+                prune::archivable(logger.metadata(), cutoff).iter().map(|session| session.id.clone()).collect();
+
+            if sessions.is_empty() {
+                println!("No uncompressed logs older than {}.", older_than);
+            } else if dry_run {
+                for session_id in &sessions {
+                    println!("Would archive {}", session_id);
+                }
+                println!("\nDry run: nothing changed.");
+            } else {
+                let outcome = prune::archive(&mut logger, &sessions)?;
+                prune::print_outcome(&outcome);
+            }
+        }
+
+        Some(Commands::EncryptExisting { dry_run }) => {
+            let mut logger = ClaudeLogger::new()?;
+            let sessions: Vec<String> =
+                encryption::unencrypted(logger.metadata()).iter().map(|session| session.id.clone()).collect();
+
+            if sessions.is_empty() {
+                println!("No unencrypted session logs.");
+            } else if dry_run {
+                for session_id in &sessions {
+                    println!("Would encrypt {}", session_id);
+                }
+                println!("\nDry run: nothing changed.");
+            } else {
+                let outcome = encryption::encrypt_existing(&mut logger, &sessions)?;
+                encryption::print_outcome(&outcome);
+            }
+            if !logger.config().encryption.enabled {
+                println!("New sessions stay unencrypted until `[encryption] enabled = true` is set.");
+            }
+        }
+
+        Some(Commands::Cache { action }) => {
+            let config = Config::load()?;
+            let limit = config.embeddings.max_mb * 1024 * 1024;
+            match action {
+                CacheAction::Status => {
+                    let cache = EmbeddingCache::load(&logs_dir)?;
+                    let analyzer = SessionAnalyzer::new()?;
+                    let sessions = analyzer.metadata().sessions.len();
+                    let embedded = analyzer.metadata().sessions.keys().filter(|id| cache.sessions.contains_key(*id)).count();
+                    let size = std::fs::metadata(logs_dir.join(embeddings::CACHE_FILE)).map_or(0, |metadata| metadata.len());
+                    println!("Model: {}", cache.model);
+                    println!("Sessions: {} of {} embedded ({} chunks)", embedded, sessions, cache.chunks());
+                    println!("Size: {} KB of {} MB allowed", size / 1024, config.embeddings.max_mb);
+                    println!(
+                        "New sessions: {}",
+                        if config.embeddings.enabled { "embedded as they finish" } else { "not embedded; set `[embeddings] enabled = true`" }
+                    );
+                }
+                CacheAction::Clear => {
+                    if EmbeddingCache::remove(&logs_dir)? {
+                        println!("Embeddings cache removed.");
+                    } else {
+                        println!("No embeddings cache.");
+                    }
+                }
+                CacheAction::Rebuild => {
+                    let analyzer = SessionAnalyzer::new()?;
+                    let mut cache = EmbeddingCache::default();
+                    let now = chrono::Utc::now();
+                    let mut sessions: Vec<_> = analyzer.metadata().sessions.values().collect();
+                    sessions.sort_by_key(|session| session.timestamp);
+                    for session in sessions {
+                        match analyzer.read_session_log(session) {
+                            Ok(content) => {
+                                cache.update(&session.id, &content, now);
+                            }
+                            Err(e) => eprintln!("Warning: Skipping {}: {:#}", session.id, e),
+                        }
+                    }
+                    let evicted = cache.enforce_limit(limit);
+                    let size = cache.save(&logs_dir)?;
+                    println!("Embedded {} chunks from {} sessions ({} KB)", cache.chunks(), cache.sessions.len(), size / 1024);
+                    if evicted > 0 {
+                        println!("Left out {} sessions over the {} MB limit", evicted, config.embeddings.max_mb);
+                    }
+                }
+            }
+        }
+
+        Some(Commands::CalibrateSentiment { limit }) => {
+            let patterns = patterns::get_patterns();
+            let sentiment = patterns
+                .sentiment()
+                .context("Enthusiasm and confusion come from the built-in patterns; pick a `backend` under [analysis.sentiment] to calibrate")?;
+            let analyzer = SessionAnalyzer::new()?;
+            let calibration = sentiment::calibrate(&analyzer, patterns, sentiment, limit)?;
+            let backend = sentiment.backend().id();
+            sentiment::save_calibration(&logs_dir, &backend, calibration)?;
+            println!(
+                "Calibrated `{}` against the built-in patterns over {} sessions: enthusiasm x{:.2}, confusion x{:.2}",
+                backend, calibration.sessions, calibration.enthusiasm, calibration.confusion
+            );
+            println!("Run `claude-logger reanalyze` to rescore sessions with the calibrated backend.");
+        }
+
+        Some(Commands::Audit { session_id, action, since, limit }) => {
+            let entries = audit::read(&ClaudeLogger::get_logs_directory()?)?;
+            let from = since.map(|day| zone.start_of_day(day));
+            let matching: Vec<&audit::AuditEntry> = entries
+                .iter()
+                .filter(|entry| session_id.is_none() || entry.session_id == session_id)
+                // Runs are for `self-stats`; listed only when asked for
+                .filter(|entry| action.map_or(entry.action != audit::Action::Ran, |action| entry.action == action))
+                .filter(|entry| from.is_none_or(|from| entry.timestamp >= from))
+                .collect();
+            audit::print(&matching[matching.len().saturating_sub(limit)..], zone);
+        }
+
+        Some(Commands::Reanalyze { filter, dry_run }) => {
+            let query = filter::Query::parse(&filter, zone)?;
+            let analyzer = SessionAnalyzer::new()?;
+            let result = reanalyze::reanalyze(&analyzer, analyzer.metadata().sessions.values().filter(|session| query.matches(session)));
+            reanalyze::print(&result);
+
+            if dry_run {
+                println!("\nDry run: nothing saved.");
+            } else if !result.updated.is_empty() {
+                let mut logger = ClaudeLogger::new()?;
+                for session in result.updated {
+                    if let Some(stored) = logger.metadata_mut().get_session_mut(&session.id) {
+                        stored.analysis = session.analysis;
+                    }
+                }
+                logger.save_metadata()?;
+            }
+        }
+
+        Some(Commands::BackfillDurations { dry_run }) => {
+            let analyzer = SessionAnalyzer::new()?;
+            let estimates = backfill::estimate_all(&analyzer);
+            let missing = analyzer.metadata().sessions.values().filter(|session| backfill::missing_duration(session)).count();
+            if missing == 0 {
+                println!("Every session has a duration.");
+                return Ok(());
+            }
+
+            for (session_id, estimate) in &estimates {
+                println!("{}: {} minutes (from {})", session_id, estimate.duration().num_minutes(), estimate.source.name());
+            }
+            if estimates.len() < missing {
+                println!("{} sessions had nothing to estimate from.", missing - estimates.len());
+            }
+
+            if dry_run {
+                println!("\nDry run: nothing saved.");
+            } else {
+                let mut logger = ClaudeLogger::new()?;
+                let applied = backfill::apply(&mut logger, &estimates)?;
+                println!("\nEstimated durations for {} sessions.", applied);
+            }
+        }
+
+        Some(Commands::Tmux { action }) => {
+            let mut logger = ClaudeLogger::new()?;
+
+            match action {
+                TmuxAction::Attach => {
+                    let attached = tmux::attach_claude_panes(&mut logger)?;
+                    if attached.is_empty() {
+                        println!("No uncaptured claude panes found.");
+                    }
+                    for session in attached {
+                        println!("Capturing {} -> {}",
+                            session.tmux_pane.as_deref().unwrap_or("?"),
+                            session.log_file.display());
+                    }
+                }
+                TmuxAction::Detach => {
+                    for session in tmux::finalize_captures(&mut logger, true)? {
+                        println!("Finalized session {} ({})", session.id, session.project);
+                    }
+                }
+                TmuxAction::Watch { interval } => {
+                    tmux::watch(&mut logger, interval)?;
+                }
+                TmuxAction::Status => {
+                    let captures = tmux::active_captures(&logger);
+                    if captures.is_empty() {
+                        println!("No active tmux captures.");
+                    }
+                    for session in captures {
+                        println!("{} | {} | {} | {}",
+                            session.tmux_pane.as_deref().unwrap_or("?"),
+                            session.id,
+                            session.project,
+                            zone.format(session.timestamp, "%Y-%m-%d %H:%M"));
+                    }
+                }
+            }
+        }
+
+        Some(Commands::Git { action }) => {
+            let logger = ClaudeLogger::new()?;
+            let git = &logger.config().git;
+
+            match action {
+                GitAction::Remote { action: RemoteAction::Add { url, name } } => {
+                    let name = name.unwrap_or_else(|| git.remote.clone());
+                    logger.git_repo().set_remote(&name, &url, false)?;
+                    println!("Added remote {} -> {}", name, url);
+                    if !git.auto_push {
+                        println!("Set `auto_push = true` under [git] in the config file to push after every session.");
+                    }
+                }
+                GitAction::Remote { action: RemoteAction::Set { url, name } } => {
+                    let name = name.unwrap_or_else(|| git.remote.clone());
+                    logger.git_repo().set_remote(&name, &url, true)?;
+                    println!("Remote {} -> {}", name, url);
+                }
+                GitAction::Remote { action: RemoteAction::Show { name } } => {
+                    let name = name.unwrap_or_else(|| git.remote.clone());
+                    match logger.git_repo().remote_url(&name) {
+                        Some(url) => println!("{} -> {}", name, url),
+                        None => println!("Remote {} is not configured.", name),
+                    }
+                    println!("Auto-push: {}", if git.auto_push { "on" } else { "off" });
+                }
+                GitAction::Push { remote } => {
+                    let remote = remote.unwrap_or_else(|| git.remote.clone());
+                    logger.git_repo().push_with_retry(&remote, git.push_attempts)?;
+                    println!("Pushed the logs repository to {}", remote);
+                }
+            }
+        }
+
+        Some(Commands::Watch { session_id, interval }) => {
+            let mut logger = ClaudeLogger::new()?;
+            let session = live::find_session(&logger, session_id.as_deref())?;
+            live::watch(&mut logger, &session, interval)?;
+        }
+
+        Some(Commands::Synth { count, profile, set, jitter, seed, methodology, project, days, dir, force }) => {
+            if !(0.0..=1.0).contains(&jitter) {
+                anyhow::bail!("--jitter must be between 0 and 1, got {}", jitter);
+            }
+            let mut profile = synth::Profile::preset(profile);
+            for assignment in &set {
+                profile.set(assignment).map_err(|e| anyhow::anyhow!("--set {}: {}", assignment, e))?;
+            }
+            let methodology = methodology
+                .map(|name| Methodology::from_name(&name).ok_or_else(|| anyhow::anyhow!("Unknown methodology: {}", name)))
+                .transpose()?;
+
+            let dir = match dir {
+                Some(dir) => dir,
+                None => ClaudeLogger::get_logs_directory()?,
+            };
+            let mut logger = ClaudeLogger::new_with_config(&dir, Config::load()?)?;
+            let real_sessions = logger
+                .metadata()
+                .sessions
+                .values()
+                .filter(|session| !session.tags.iter().any(|tag| tag == synth::SYNTHETIC_TAG))
+                .count();
+            if real_sessions > 0 && !force {
+                anyhow::bail!(
+                    "{} already holds {} real sessions; pass --dir for a separate directory, or --force to mix them in",
+                    dir.display(),
+                    real_sessions
+                );
+            }
+
+            let options = synth::SynthOptions { count, profile, jitter, seed, methodology, projects: project, days };
+            let sessions = synth::generate(&options, &dir, chrono::Utc::now());
+            synth::write(&mut logger, sessions)?;
+            println!("Generated {} sessions in {} (tagged `{}`)", count, dir.display(), synth::SYNTHETIC_TAG);
+        }
+
+        Some(Commands::Tui) => {
+            let analyzer = report_analyzer(cli.include_excluded)?;
+            let mut logger = ClaudeLogger::new()?;
+            tui::run(&analyzer, &mut logger, zone)?;
+        }
+
         None => {
             // Run Claude with logging
             let mut logger = ClaudeLogger::new()?;
-            logger.run_logged_session(&cli.claude_args, cli.track_energy)?;
+            logger.run_logged_session(&cli.claude_args, cli.track_energy, cli.meta.into_iter().collect(), cli.methodology)?;
         }
     }
 
     Ok(())
 }
+
+/// Pretty-printed JSON on stdout, for `--json`.
+fn print_json<T: serde::Serialize>(value: &T) -> anyhow::Result<()> {
+    let json = serde_json::to_string_pretty(value).context("Failed to serialize output to JSON")?;
+    print_for_scripts(&(json + "\n"))
+}
+
+/// Write machine-readable output; scripts often stop reading early (`| head`), which isn't an error.
+fn print_for_scripts(output: &str) -> anyhow::Result<()> {
+    match std::io::stdout().write_all(output.as_bytes()) {
+        Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => Err(e).context("Failed to write output"),
+        _ => Ok(()),
+    }
+}
+
+/// An analysis with its recommendations alongside, as the text output shows them.
+fn with_recommendations<T: serde::Serialize>(analysis: &T, recommendations: Vec<String>) -> anyhow::Result<serde_json::Value> {
+    let mut value = serde_json::to_value(analysis).context("Failed to serialize analysis to JSON")?;
+    value["recommendations"] = serde_json::json!(recommendations);
+    Ok(value)
+}
+
+/// Analyzer for report commands, leaving out sessions matched by the configured exclusions.
+fn report_analyzer(include_excluded: bool) -> anyhow::Result<SessionAnalyzer> {
+    let config = Config::load()?;
+    let mut analyzer = SessionAnalyzer::new_with_config(&ClaudeLogger::get_logs_directory()?, &config)?;
+
+    if !include_excluded {
+        let excluded = analyzer.apply_exclusions(&config.analysis.exclude);
+        if excluded > 0 {
+            eprintln!("Note: {} excluded sessions left out (pass --include-excluded to count them)", excluded);
+        }
+    }
+    if config.analysis.merge_resumed {
+        let merged = analyzer.merge_resumed();
+        if merged > 0 {
+            eprintln!("Note: {} resumed sessions merged into their conversations", merged);
+        }
+    }
+    Ok(analyzer)
+}
@@ -0,0 +1,254 @@
+use crate::hold;
+use crate::log_format::{self, LogFormat};
+use crate::logger::ClaudeLogger;
+use crate::project;
+use crate::session::{ProjectChanges, SessionMetadata};
+use crate::trash::{self, TrashEntry};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The session two split captures were merged into.
+#[derive(Debug, Clone)]
+pub struct MergeOutcome {
+    pub session: SessionMetadata,
+    /// The originals, earlier first, now in the trash
+    pub originals: [String; 2],
+    pub commit: Option<String>,
+}
+
+/// Join two captures of one logical session, e.g. split by a crash, into a new session.
+///
+/// The logs are concatenated in timestamp order and the metadata combined with the durations
+/// summed. The merged session gets the earlier id with a numeric suffix; the originals move to
+/// the trash, so `trash restore` can undo the merge, and their removal is committed together with
+/// the combined log.
+pub fn merge(logger: &mut ClaudeLogger, first_id: &str, second_id: &str) -> Result<MergeOutcome> {
+    if first_id == second_id {
+        anyhow::bail!("Can't merge session {} with itself", first_id);
+    }
+    let find = |id: &str| logger.get_session(id).cloned().ok_or_else(|| anyhow::anyhow!("Session not found: {}", id));
+    let (first, second) = (find(first_id)?, find(second_id)?);
+    let (earlier, later) = if first.timestamp <= second.timestamp { (first, second) } else { (second, first) };
+
+    for session in [&earlier, &later] {
+        hold::ensure_released(session, "merge")?;
+        if session.tmux_pane.is_some() && session.end_time.is_none() {
+            anyhow::bail!("Session {} is still being captured; stop it before merging", session.id);
+        }
+    }
+
+    if earlier.project != later.project {
+        eprintln!(
+            "Warning: Merging sessions from different projects ({} and {}); the merged session is filed under {}",
+            earlier.project, later.project, earlier.project
+        );
+    }
+
+    let read = |session: &SessionMetadata| {
+        project::read_log(&session.log_file).with_context(|| {
+            format!("Failed to read the log of session {}; both logs must be on disk to merge", session.id)
+        })
+    };
+    let (mut log, later_log) = (read(&earlier)?, read(&later)?);
+    let formats = [&log, &later_log].map(|raw| LogFormat::detect(&String::from_utf8_lossy(raw)));
+    if formats[0] != formats[1] {
+        anyhow::bail!(
+            "Session {} is a {} log and {} a {} log; only captures in the same format can be merged",
+            earlier.id,
+            formats[0].name(),
+            later.id,
+            formats[1].name()
+        );
+    }
+    // Annotations on the later log move down by the lines before it
+    let offset = text_lines(&log);
+    if !log.is_empty() && !log.ends_with(b"\n") {
+        log.push(b'\n');
+    }
+    log.extend_from_slice(&later_log);
+
+    let id = logger.unique_session_id(&earlier.id);
+    let log_dir = earlier.log_file.parent().map(Path::to_path_buf).unwrap_or_else(|| logger.logs_dir().to_path_buf());
+    let log_file = log_dir.join(format!("{}.log", id));
+    fs::write(&log_file, &log).with_context(|| format!("Failed to write merged log: {}", log_file.display()))?;
+
+    let mut session = combine(&earlier, &later, offset);
+    session.id = id;
+    session.log_file = log_file;
+    logger.derive_from_log(&mut session, false);
+
+    let deleted_at = Utc::now();
+    let mut removed: Vec<PathBuf> = Vec::new();
+    for original in [&earlier, &later] {
+        let mut files = vec![original.log_file.clone(), original.clean_log_path()];
+        files.extend(original.clean_log_file.clone());
+        files.sort();
+        files.dedup();
+        files.retain(|file| file.exists());
+        let entry = TrashEntry { deleted_at, session: original.clone(), relinked: Vec::new(), files: files.clone() };
+        trash::put(logger.logs_dir(), &entry)?;
+        removed.extend(files);
+    }
+
+    let metadata = logger.metadata_mut();
+    metadata.remove_session(&earlier.id);
+    metadata.remove_session(&later.id);
+    for other in metadata.sessions.values_mut() {
+        if other.parent_session_id.as_deref().is_some_and(|parent| parent == earlier.id || parent == later.id) {
+            other.parent_session_id = Some(session.id.clone());
+        }
+    }
+    metadata.add_session(session.clone());
+    logger.save_metadata()?;
+
+    let mut added = vec![session.log_file.as_path()];
+    added.extend(session.clean_log_file.as_deref());
+    let removed: Vec<&Path> = removed.iter().map(PathBuf::as_path).collect();
+    let message = format!("Merge sessions {} and {} into {} | {}", earlier.id, later.id, session.id, session.project);
+    let commit = logger.git_repo().commit_changes(&removed, &added, &message)?;
+    if commit.is_some() {
+        session.git_commit = commit.clone();
+        logger.save_session(session.clone())?;
+        logger.push_if_enabled();
+    }
+
+    Ok(MergeOutcome { session, originals: [earlier.id, later.id], commit })
+}
+
+/// Lines of conversation text the raw log reads as, the numbering annotations use.
+fn text_lines(raw: &[u8]) -> usize {
+    log_format::to_text(&String::from_utf8_lossy(raw)).1.lines().count()
+}
+
+/// `earlier`'s metadata with `later`'s folded in. What is worked out from the log (the cleaned
+/// copy, the analysis) is left for `derive_from_log` to redo on the merged log.
+fn combine(earlier: &SessionMetadata, later: &SessionMetadata, offset: usize) -> SessionMetadata {
+    let mut session = earlier.clone();
+    session.duration_seconds = match (earlier.duration_seconds, later.duration_seconds) {
+        (None, None) => None,
+        (a, b) => Some(a.unwrap_or(0) + b.unwrap_or(0)),
+    };
+    session.duration_estimated = earlier.duration_estimated || later.duration_estimated;
+    // Part of the log captured without a terminal makes the whole a degraded capture
+    session.capture_mode = earlier.capture_mode.max(later.capture_mode);
+    session.end_time = later.end_time.or(earlier.end_time);
+    session.creative_energy = earlier.creative_energy.or(later.creative_energy);
+    session.transcript_file = earlier.transcript_file.clone().or_else(|| later.transcript_file.clone());
+    session.termination_reason = later.termination_reason.clone();
+    session.clean_log_file = None;
+    session.log_parser = None;
+    session.git_commit = None;
+    session.archived_remote = None;
+    session.tmux_pane = None;
+    session.analysis = None;
+
+    for feature in &later.features_worked_on {
+        if !session.features_worked_on.contains(feature) {
+            session.features_worked_on.push(feature.clone());
+        }
+    }
+    for tag in &later.tags {
+        if !session.tags.contains(tag) {
+            session.tags.push(tag.clone());
+        }
+    }
+    session.annotations.extend(later.annotations.iter().cloned().map(|mut annotation| {
+        annotation.start_line += offset;
+        annotation.end_line += offset;
+        annotation
+    }));
+    session.notes.extend(later.notes.iter().cloned());
+    session.notes.sort_by_key(|note| note.created_at);
+    session.action_items.extend(later.action_items.iter().cloned());
+    session.reminders.extend(later.reminders.iter().cloned());
+    for (key, value) in &later.extra {
+        session.extra.entry(key.clone()).or_insert_with(|| value.clone());
+    }
+
+    session.usage = match (&earlier.usage, &later.usage) {
+        (Some(a), Some(b)) => {
+            let mut usage = a.clone();
+            usage.add(b);
+            Some(usage)
+        }
+        (a, b) => a.clone().or_else(|| b.clone()),
+    };
+    session.activity = match (&earlier.activity, &later.activity) {
+        (Some(a), Some(b)) => Some(a.combine(b)),
+        (a, b) => a.or(*b),
+    };
+    session.project_changes = match (&earlier.project_changes, &later.project_changes) {
+        (Some(a), Some(b)) => {
+            let mut files = a.files.clone();
+            files.extend(b.files.iter().filter(|file| !a.files.contains(file)).cloned());
+            Some(ProjectChanges {
+                files_touched: files.len(),
+                lines_added: a.lines_added + b.lines_added,
+                lines_removed: a.lines_removed + b.lines_removed,
+                files,
+            })
+        }
+        (a, b) => a.clone().or_else(|| b.clone()),
+    };
+    session
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::synth::{generate, sessions};
+    use chrono::Duration;
+    use std::process::Command;
+
+    #[test]
+    fn merges_split_captures_and_trashes_the_originals() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut logger = ClaudeLogger::new_with_config(dir.path(), Config::default()).unwrap();
+        for (key, value) in [("user.email", "test@example.com"), ("user.name", "Test")] {
+            Command::new("git").args(["config", key, value]).current_dir(dir.path()).status().unwrap();
+        }
+
+        let options = sessions(3, 11, "api");
+        let mut generated = generate(&options, dir.path(), Utc::now() - Duration::days(2));
+        let mut logs = Vec::new();
+        for generated in &mut generated {
+            fs::create_dir_all(generated.session.log_file.parent().unwrap()).unwrap();
+            fs::write(&generated.session.log_file, &generated.log).unwrap();
+            logs.push(generated.log.clone());
+        }
+        let [earlier, later, child] = [0, 1, 2].map(|index| generated[index].session.clone());
+        let mut child = child;
+        child.parent_session_id = Some(later.id.clone());
+        for session in [&earlier, &later, &child] {
+            logger.complete_session(session).unwrap();
+        }
+
+        // Order of the arguments doesn't matter
+        let outcome = merge(&mut logger, &later.id, &earlier.id).unwrap();
+        let merged = &outcome.session;
+        assert_eq!(outcome.originals, [earlier.id.clone(), later.id.clone()]);
+        assert_eq!(merged.id, format!("{}-2", earlier.id));
+        assert_eq!(merged.timestamp, earlier.timestamp);
+        assert_eq!(merged.duration_seconds, Some(earlier.duration_seconds.unwrap() + later.duration_seconds.unwrap()));
+        assert!(outcome.commit.is_some() && merged.git_commit == outcome.commit);
+
+        let log = fs::read_to_string(&merged.log_file).unwrap();
+        assert!(log.starts_with(logs[0].trim_end()) && log.ends_with(&logs[1]));
+        assert!(merged.clean_log_file.as_ref().is_some_and(|path| path.exists()));
+
+        assert!(logger.get_session(&earlier.id).is_none() && logger.get_session(&later.id).is_none());
+        assert_eq!(logger.get_session(&child.id).unwrap().parent_session_id.as_ref(), Some(&merged.id));
+        assert!(!earlier.log_file.exists() && !later.log_file.exists());
+        assert_eq!(trash::list(dir.path()).unwrap().len(), 2);
+
+        let tracked = Command::new("git").args(["ls-files"]).current_dir(dir.path()).output().unwrap();
+        let tracked = String::from_utf8_lossy(&tracked.stdout);
+        assert!(tracked.contains(&format!("{}.log", merged.id)));
+        assert!(!tracked.contains(&format!("{}.log", later.id)));
+
+        assert!(merge(&mut logger, &merged.id, &merged.id).is_err());
+    }
+}
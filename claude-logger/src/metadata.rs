@@ -0,0 +1,443 @@
+use crate::migrate::CURRENT_VERSION;
+use crate::session::{SessionMetadata, SessionsMetadata};
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Directory under the logs directory where unreadable metadata is set aside.
+pub const CORRUPT_DIR: &str = "corrupt";
+
+/// How long to wait for another claude-logger to finish saving before giving up.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How to treat metadata entries that don't match the current format.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LoadMode {
+    /// Load every readable session, quarantine the rest and warn about unknown fields
+    #[default]
+    Lenient,
+    /// Refuse to load if any entry is malformed or has unknown fields
+    Strict,
+}
+
+/// Load `sessions_metadata.json` in the given mode.
+///
+/// Entries in an older format are upgraded as they are read. In lenient mode, entries that fail
+/// to deserialize are copied to `corrupt/` and left out; they disappear from the main file the
+/// next time metadata is saved.
+pub fn load_metadata(metadata_file: &Path, mode: LoadMode) -> Result<SessionsMetadata> {
+    if !metadata_file.exists() {
+        return Ok(SessionsMetadata::new());
+    }
+
+    let content = fs::read_to_string(metadata_file)
+        .with_context(|| format!("Failed to read metadata file: {}", metadata_file.display()))?;
+
+    let mut value: Value = match serde_json::from_str(&content) {
+        Ok(value) => value,
+        Err(e) if mode == LoadMode::Lenient => {
+            let quarantined = quarantine(metadata_file, "sessions_metadata", content.as_bytes())?;
+            eprintln!(
+                "Warning: {} is not valid JSON ({}); copied it to {} and starting empty",
+                metadata_file.display(),
+                e,
+                quarantined.display()
+            );
+            return Ok(SessionsMetadata::new());
+        }
+        Err(e) => {
+            return Err(e)
+                .with_context(|| format!("Failed to parse metadata file: {}", metadata_file.display()));
+        }
+    };
+
+    let version = schema_version(&value);
+    if version > CURRENT_VERSION {
+        return Err(anyhow::anyhow!(
+            "{} is at layout version {}, newer than this claude-logger reads ({}); upgrade claude-logger",
+            metadata_file.display(),
+            version,
+            CURRENT_VERSION
+        ));
+    }
+
+    // Upgraded in memory only: loading never writes, so read-only commands don't race a saving
+    // process. The next locked save persists the upgrade, as does `migrate`, after a backup.
+    migrate(&mut value);
+
+    let entries = value
+        .get("sessions")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+
+    // Kept as found, so saving never claims migrations that haven't run
+    let mut metadata = SessionsMetadata { schema_version: version, ..SessionsMetadata::new() };
+    let mut problems = Vec::new();
+
+    for (key, raw) in entries {
+        let session = match serde_json::from_value::<SessionMetadata>(raw.clone()) {
+            Ok(session) => session,
+            Err(e) => {
+                problems.push(format!("session {}: {}", key, e));
+                if mode == LoadMode::Lenient {
+                    let bytes = serde_json::to_vec_pretty(&raw).context("Failed to serialize corrupt entry")?;
+                    let quarantined = quarantine(metadata_file, &key, &bytes)?;
+                    eprintln!(
+                        "Warning: Skipping unreadable session {} ({}); copy kept in {}",
+                        key,
+                        e,
+                        quarantined.display()
+                    );
+                }
+                continue;
+            }
+        };
+
+        let unknown = unknown_fields(&raw, &session);
+        if !unknown.is_empty() {
+            problems.push(format!("session {}: unknown fields {}", key, unknown.join(", ")));
+            if mode == LoadMode::Lenient {
+                eprintln!(
+                    "Warning: Session {} has unknown fields that will be dropped on save: {}",
+                    key,
+                    unknown.join(", ")
+                );
+            }
+        }
+
+        metadata.sessions.insert(key, session);
+    }
+
+    if let Some(raw) = value.get("phases") {
+        match serde_json::from_value(raw.clone()) {
+            Ok(phases) => metadata.phases = phases,
+            Err(e) => {
+                problems.push(format!("phases: {}", e));
+                if mode == LoadMode::Lenient {
+                    let bytes = serde_json::to_vec_pretty(raw).context("Failed to serialize corrupt phases")?;
+                    let quarantined = quarantine(metadata_file, "phases", &bytes)?;
+                    eprintln!("Warning: Skipping unreadable phases ({}); copy kept in {}", e, quarantined.display());
+                }
+            }
+        }
+    }
+
+    if mode == LoadMode::Strict && !problems.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Metadata file {} has {} problems:\n  {}",
+            metadata_file.display(),
+            problems.len(),
+            problems.join("\n  ")
+        ));
+    }
+
+    Ok(metadata)
+}
+
+/// The `schema_version` a raw metadata document records, 0 when it predates the field.
+pub fn schema_version(value: &Value) -> u32 {
+    value.get("schema_version").and_then(Value::as_u64).map_or(0, |version| version as u32)
+}
+
+/// Upgrade every session entry in a raw metadata document to the current format, returning how
+/// many changed.
+pub fn migrate(value: &mut Value) -> usize {
+    value
+        .get_mut("sessions")
+        .and_then(Value::as_object_mut)
+        .map(|sessions| sessions.values_mut().map(migrate_entry).filter(|changed| *changed).count())
+        .unwrap_or(0)
+}
+
+/// Rewrite legacy fields of a single session entry in place.
+///
+/// Older versions stored `duration` as chrono's `[secs, nanos]` pair, then as `duration_secs`;
+/// it is now `duration_seconds`, a plain integer.
+pub fn migrate_entry(entry: &mut Value) -> bool {
+    let Some(fields) = entry.as_object_mut() else {
+        return false;
+    };
+    let secs = match (fields.remove("duration"), fields.remove("duration_secs")) {
+        (None, None) => return false,
+        (_, Some(secs)) => secs.as_i64(),
+        (Some(Value::Array(parts)), None) => parts.first().and_then(Value::as_i64),
+        (Some(Value::Number(secs)), None) => secs.as_i64(),
+        (Some(_), None) => None,
+    };
+    if !fields.contains_key("duration_seconds") {
+        fields.insert("duration_seconds".to_string(), secs.map(Value::from).unwrap_or(Value::Null));
+    }
+    true
+}
+
+/// Fields present in the stored entry that the current `SessionMetadata` doesn't know.
+pub fn unknown_fields(raw: &Value, session: &SessionMetadata) -> Vec<String> {
+    let known: Map<String, Value> = match serde_json::to_value(session) {
+        Ok(Value::Object(map)) => map,
+        _ => return Vec::new(),
+    };
+
+    raw.as_object()
+        .map(|fields| {
+            fields
+                .keys()
+                .filter(|field| !known.contains_key(*field))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// An advisory lock on a metadata store, held from re-reading the store to writing it back so
+/// processes saving at the same time take turns. Released when dropped.
+pub struct MetadataLock {
+    _file: File,
+}
+
+impl MetadataLock {
+    /// Lock `<metadata_file>.lock`, waiting while another process holds it.
+    pub fn acquire(metadata_file: &Path) -> Result<Self> {
+        let name = metadata_file.file_name().context("Metadata file has no file name")?;
+        let path = metadata_file.with_file_name(format!("{}.lock", name.to_string_lossy()));
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open lock file: {}", path.display()))?;
+
+        let deadline = Instant::now() + LOCK_TIMEOUT;
+        loop {
+            match file.try_lock() {
+                Ok(()) => return Ok(Self { _file: file }),
+                Err(TryLockError::WouldBlock) if Instant::now() < deadline => thread::sleep(Duration::from_millis(50)),
+                Err(TryLockError::WouldBlock) => anyhow::bail!(
+                    "Timed out after {}s waiting for another claude-logger to release {}",
+                    LOCK_TIMEOUT.as_secs(),
+                    path.display()
+                ),
+                Err(TryLockError::Error(e)) => {
+                    return Err(e).with_context(|| format!("Failed to lock {}", path.display()));
+                }
+            }
+        }
+    }
+}
+
+/// The changes made since `base` was loaded (`base` to `ours`) applied on top of `theirs`, the
+/// store as it is now, so sessions another process saved in the meantime survive. Where both
+/// changed the same session, ours wins field by field.
+pub fn merge(base: &SessionsMetadata, ours: &SessionsMetadata, mut theirs: SessionsMetadata) -> SessionsMetadata {
+    let ids: BTreeSet<&String> = base.sessions.keys().chain(ours.sessions.keys()).collect();
+    for id in ids {
+        match (base.sessions.get(id), ours.sessions.get(id)) {
+            (_, None) => {
+                theirs.sessions.remove(id);
+            }
+            (None, Some(session)) => {
+                theirs.sessions.insert(id.clone(), session.clone());
+            }
+            (Some(before), Some(after)) => {
+                let (before, after_value) = (to_object(before), to_object(after));
+                if before == after_value {
+                    continue;
+                }
+                let merged = theirs
+                    .sessions
+                    .get(id)
+                    .and_then(|current| {
+                        let mut current = to_object(current);
+                        for key in before.keys().chain(after_value.keys()).collect::<BTreeSet<_>>() {
+                            match after_value.get(key) {
+                                Some(value) if before.get(key) != Some(value) => {
+                                    current.insert(key.clone(), value.clone());
+                                }
+                                None => {
+                                    current.remove(key);
+                                }
+                                _ => {}
+                            }
+                        }
+                        serde_json::from_value(Value::Object(current)).ok()
+                    })
+                    .unwrap_or_else(|| after.clone());
+                theirs.sessions.insert(id.clone(), merged);
+            }
+        }
+    }
+
+    if serde_json::to_value(&base.phases).ok() != serde_json::to_value(&ours.phases).ok() {
+        theirs.phases = ours.phases.clone();
+    }
+    theirs
+}
+
+fn to_object(session: &SessionMetadata) -> Map<String, Value> {
+    match serde_json::to_value(session) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+fn quarantine(metadata_file: &Path, name: &str, data: &[u8]) -> Result<PathBuf> {
+    let dir = metadata_file
+        .parent()
+        .map(|parent| parent.join(CORRUPT_DIR))
+        .context("Metadata file has no parent directory")?;
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+
+    // Names are derived from the content so repeated loads don't pile up copies
+    let digest: String = Sha256::digest(data).iter().take(6).map(|byte| format!("{:02x}", byte)).collect();
+    let file_name = format!("{}-{}.json", name.replace(['/', '\\'], "_"), digest);
+    let path = dir.join(file_name);
+    fs::write(&path, data).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn migrates_legacy_duration_pairs() {
+        let mut value = json!({
+            "sessions": {
+                "old": { "duration": [1800, 0] },
+                "unfinished": { "duration": null },
+                "renamed": { "duration_secs": 90 },
+                "current": { "duration_seconds": 60 }
+            }
+        });
+
+        assert_eq!(schema_version(&value), 0);
+        assert_eq!(migrate(&mut value), 3);
+        assert_eq!(value["sessions"]["old"], json!({ "duration_seconds": 1800 }));
+        assert_eq!(value["sessions"]["unfinished"], json!({ "duration_seconds": null }));
+        assert_eq!(value["sessions"]["renamed"], json!({ "duration_seconds": 90 }));
+        assert_eq!(migrate(&mut value), 0);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions_metadata.json");
+        fs::write(&path, json!({ "schema_version": CURRENT_VERSION + 1, "sessions": {} }).to_string()).unwrap();
+        let error = load_metadata(&path, LoadMode::Lenient).unwrap_err();
+        assert!(error.to_string().contains("upgrade claude-logger"), "{}", error);
+    }
+
+    #[test]
+    fn loading_upgrades_legacy_entries_without_writing() {
+        let session = crate::synth::generate(&crate::synth::sessions(1, 2, "api"), Path::new("/logs"), chrono::Utc::now())
+            .remove(0)
+            .session;
+        let mut entry = serde_json::to_value(&session).unwrap();
+        entry.as_object_mut().unwrap().remove("duration_seconds");
+        entry["duration"] = json!([1800, 0]);
+        let content = json!({ "sessions": { session.id.clone(): entry } }).to_string();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions_metadata.json");
+        fs::write(&path, &content).unwrap();
+        let metadata = load_metadata(&path, LoadMode::Strict).unwrap();
+        assert_eq!(metadata.get_session(&session.id).unwrap().duration_seconds, Some(1800));
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+    }
+
+    #[test]
+    fn quarantines_unreadable_entries_or_refuses_them_in_strict_mode() {
+        let session = crate::synth::generate(&crate::synth::sessions(1, 6, "api"), Path::new("/logs"), chrono::Utc::now())
+            .remove(0)
+            .session;
+        let mut extra = serde_json::to_value(&session).unwrap();
+        extra["mood"] = json!("sunny");
+        let content = json!({
+            "sessions": {
+                session.id.clone(): session,
+                "broken": { "id": 42 },
+                "extra": extra
+            }
+        })
+        .to_string();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions_metadata.json");
+        fs::write(&path, &content).unwrap();
+
+        let error = load_metadata(&path, LoadMode::Strict).unwrap_err().to_string();
+        assert!(error.contains("has 2 problems"), "{}", error);
+        assert!(error.contains("session broken:"), "{}", error);
+        assert!(error.contains("session extra: unknown fields mood"), "{}", error);
+        assert!(!dir.path().join(CORRUPT_DIR).exists());
+
+        let metadata = load_metadata(&path, LoadMode::Lenient).unwrap();
+        let mut ids: Vec<&String> = metadata.sessions.keys().collect();
+        ids.sort();
+        assert_eq!(ids, vec![&session.id, "extra"]);
+        let quarantined: Vec<PathBuf> = fs::read_dir(dir.path().join(CORRUPT_DIR))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(quarantined.len(), 1);
+        assert!(quarantined[0].file_name().unwrap().to_string_lossy().starts_with("broken-"));
+        let copy: Value = serde_json::from_slice(&fs::read(&quarantined[0]).unwrap()).unwrap();
+        assert_eq!(copy, json!({ "id": 42 }));
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+    }
+
+    #[test]
+    fn a_metadata_file_that_is_not_json_starts_empty_only_in_lenient_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions_metadata.json");
+        fs::write(&path, "{ \"sessions\": ").unwrap();
+
+        let error = load_metadata(&path, LoadMode::Strict).unwrap_err();
+        assert!(error.to_string().contains("Failed to parse metadata file"), "{}", error);
+
+        assert!(load_metadata(&path, LoadMode::Lenient).unwrap().sessions.is_empty());
+        let quarantined: Vec<PathBuf> = fs::read_dir(dir.path().join(CORRUPT_DIR))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(fs::read_to_string(&quarantined[0]).unwrap(), "{ \"sessions\": ");
+    }
+
+    #[test]
+    fn merging_keeps_what_another_process_saved() {
+        let options = crate::synth::sessions(3, 4, "api");
+        let sessions: Vec<SessionMetadata> = crate::synth::generate(&options, Path::new("/logs"), chrono::Utc::now())
+            .into_iter()
+            .map(|generated| generated.session)
+            .collect();
+        let (running, other, shared) = (&sessions[0], &sessions[1], &sessions[2]);
+
+        let mut base = SessionsMetadata::new();
+        base.add_session(shared.clone());
+
+        // We finish our session and tag the shared one while another process adds its own
+        // session and annotates the shared one
+        let mut ours = base.clone();
+        ours.add_session(running.clone());
+        ours.get_session_mut(&shared.id).unwrap().tags.push("reviewed".to_string());
+        let mut theirs = base.clone();
+        theirs.add_session(other.clone());
+        theirs.get_session_mut(&shared.id).unwrap().features_worked_on.push("upload".to_string());
+
+        let merged = merge(&base, &ours, theirs);
+        assert_eq!(merged.sessions.len(), 3);
+        let shared = merged.get_session(&shared.id).unwrap();
+        assert!(shared.tags.contains(&"reviewed".to_string()));
+        assert!(shared.features_worked_on.contains(&"upload".to_string()));
+
+        // Deleting our own copy removes it, whatever the other process has
+        let mut ours = base.clone();
+        ours.sessions.remove(&sessions[2].id);
+        assert!(merge(&base, &ours, base.clone()).sessions.is_empty());
+    }
+}
@@ -0,0 +1,420 @@
+use crate::config::{MetadataBackend, MetadataConfig};
+use crate::metadata::{load_metadata, LoadMode};
+use crate::session::{Methodology, SessionMetadata, SessionsMetadata};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name of the JSON metadata store in the logs directory.
+pub const JSON_FILE: &str = "sessions_metadata.json";
+
+/// File name of the SQLite metadata store in the logs directory.
+pub const SQLITE_FILE: &str = "sessions.db";
+
+/// Which sessions a query returns; unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct SessionFilter {
+    pub methodology: Option<Methodology>,
+    pub project: Option<String>,
+    /// Sessions recorded by this author
+    pub author: Option<String>,
+    /// Sessions starting at or after this time
+    pub from: Option<DateTime<Utc>>,
+    /// Sessions starting before this time
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<usize>,
+}
+
+impl SessionFilter {
+    pub fn matches(&self, session: &SessionMetadata) -> bool {
+        self.methodology.as_ref().is_none_or(|methodology| session.methodology == *methodology)
+            && self.project.as_ref().is_none_or(|project| session.project == *project)
+            && self.author.as_ref().is_none_or(|author| session.author.as_ref() == Some(author))
+            && self.from.is_none_or(|from| session.timestamp >= from)
+            && self.to.is_none_or(|to| session.timestamp < to)
+    }
+}
+
+/// Where session metadata and phases are persisted.
+pub trait MetadataStore: Send + Sync {
+    fn name(&self) -> &str;
+    fn load(&self, mode: LoadMode) -> Result<SessionsMetadata>;
+    fn save(&self, metadata: &SessionsMetadata) -> Result<()>;
+
+    /// The file the store lives in, for committing it to the logs repository.
+    fn file(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Persist one changed session; stores that can update in place avoid a full rewrite.
+    fn save_session(&self, metadata: &SessionsMetadata, _session: &SessionMetadata) -> Result<()> {
+        self.save(metadata)
+    }
+
+    /// Matching sessions, newest first.
+    fn query(&self, filter: &SessionFilter, mode: LoadMode) -> Result<Vec<SessionMetadata>> {
+        let mut sessions: Vec<SessionMetadata> = self
+            .load(mode)?
+            .sessions
+            .into_values()
+            .filter(|session| filter.matches(session))
+            .collect();
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.timestamp));
+        sessions.truncate(filter.limit.unwrap_or(usize::MAX));
+        Ok(sessions)
+    }
+}
+
+/// `sessions_metadata.json`, rewritten in full on every save.
+pub struct JsonMetadataStore {
+    path: PathBuf,
+}
+
+impl JsonMetadataStore {
+    pub fn new(logs_dir: &Path) -> Self {
+        Self {
+            path: logs_dir.join(JSON_FILE),
+        }
+    }
+}
+
+impl MetadataStore for JsonMetadataStore {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn file(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    fn load(&self, mode: LoadMode) -> Result<SessionsMetadata> {
+        load_metadata(&self.path, mode)
+    }
+
+    fn save(&self, metadata: &SessionsMetadata) -> Result<()> {
+        let json = serde_json::to_string_pretty(metadata)
+            .context("Failed to serialize metadata to JSON")?;
+
+        // Written beside the file and renamed over it, so readers never see half a file
+        let temporary = self.path.with_extension("json.tmp");
+        fs::write(&temporary, json)
+            .with_context(|| format!("Failed to write metadata file: {}", temporary.display()))?;
+        fs::rename(&temporary, &self.path)
+            .with_context(|| format!("Failed to replace metadata file: {}", self.path.display()))
+    }
+}
+
+/// Open the metadata store selected in the config.
+pub fn open_metadata_store(config: &MetadataConfig, logs_dir: &Path) -> Result<Box<dyn MetadataStore>> {
+    match config.backend {
+        MetadataBackend::Json => Ok(Box::new(JsonMetadataStore::new(logs_dir))),
+        #[cfg(feature = "sqlite")]
+        MetadataBackend::Sqlite => Ok(Box::new(sqlite::SqliteMetadataStore::open(&logs_dir.join(SQLITE_FILE))?)),
+        #[allow(unreachable_patterns)]
+        ref backend => Err(anyhow::anyhow!(
+            "Metadata backend '{}' is not compiled in; rebuild with `--features {}`",
+            backend,
+            backend
+        )),
+    }
+}
+
+/// Copy `sessions_metadata.json` into a fresh SQLite store, returning the database path.
+#[cfg(feature = "sqlite")]
+pub fn import_json(logs_dir: &Path, mode: LoadMode) -> Result<(PathBuf, SessionsMetadata)> {
+    let metadata = JsonMetadataStore::new(logs_dir).load(mode)?;
+    let path = logs_dir.join(SQLITE_FILE);
+    sqlite::SqliteMetadataStore::open(&path)?.save(&metadata)?;
+    Ok((path, metadata))
+}
+
+#[cfg(not(feature = "sqlite"))]
+pub fn import_json(_logs_dir: &Path, _mode: LoadMode) -> Result<(PathBuf, SessionsMetadata)> {
+    Err(anyhow::anyhow!("SQLite support is not compiled in; rebuild with `--features sqlite`"))
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::{MetadataStore, SessionFilter};
+    use crate::metadata::LoadMode;
+    use crate::session::{Methodology, Phase, SessionMetadata, SessionsMetadata};
+    use anyhow::{Context, Result};
+    use chrono::{DateTime, Utc};
+    use rusqlite::{params, Connection};
+    use std::collections::HashSet;
+    use std::path::{Path, PathBuf};
+
+    const SCHEMA: &str = "
+        CREATE TABLE IF NOT EXISTS sessions (
+            id TEXT PRIMARY KEY,
+            timestamp TEXT NOT NULL,
+            project TEXT NOT NULL,
+            methodology TEXT NOT NULL,
+            data TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS sessions_timestamp ON sessions (timestamp);
+        CREATE INDEX IF NOT EXISTS sessions_project ON sessions (project);
+        CREATE INDEX IF NOT EXISTS sessions_methodology ON sessions (methodology);
+        CREATE TABLE IF NOT EXISTS phases (
+            position INTEGER PRIMARY KEY,
+            data TEXT NOT NULL
+        );
+    ";
+
+    /// Session metadata in `sessions.db`, one row per session with the indexed fields
+    /// broken out beside the full JSON record.
+    ///
+    /// A connection is opened per operation so the store can be shared across threads.
+    pub struct SqliteMetadataStore {
+        path: PathBuf,
+    }
+
+    impl SqliteMetadataStore {
+        pub fn open(path: &Path) -> Result<Self> {
+            let store = Self {
+                path: path.to_path_buf(),
+            };
+            store.connect()?;
+            Ok(store)
+        }
+
+        fn connect(&self) -> Result<Connection> {
+            let connection = Connection::open(&self.path)
+                .with_context(|| format!("Failed to open metadata database: {}", self.path.display()))?;
+            connection.execute_batch(SCHEMA).context("Failed to create metadata tables")?;
+            Ok(connection)
+        }
+
+        fn rows(&self, sql: &str, params: &[&dyn rusqlite::ToSql], mode: LoadMode) -> Result<Vec<SessionMetadata>> {
+            let connection = self.connect()?;
+            let mut statement = connection.prepare(sql).context("Failed to prepare session query")?;
+            let rows = statement
+                .query_map(params, |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+                .context("Failed to query sessions")?;
+
+            let mut sessions = Vec::new();
+            for row in rows {
+                let (id, data) = row.context("Failed to read session row")?;
+                match serde_json::from_str::<SessionMetadata>(&data) {
+                    Ok(session) => sessions.push(session),
+                    Err(e) if mode == LoadMode::Lenient => {
+                        eprintln!("Warning: Skipping unreadable session {} in {}: {}", id, self.path.display(), e)
+                    }
+                    Err(e) => return Err(e).with_context(|| format!("Failed to parse session {}", id)),
+                }
+            }
+            Ok(sessions)
+        }
+    }
+
+    /// Fixed-width UTC timestamps, so text order in SQLite is time order.
+    fn sortable(timestamp: DateTime<Utc>) -> String {
+        timestamp.format("%Y-%m-%dT%H:%M:%S%.9fZ").to_string()
+    }
+
+    fn methodology_key(methodology: &Methodology) -> String {
+        methodology.name().to_string()
+    }
+
+    fn upsert(connection: &Connection, session: &SessionMetadata) -> Result<()> {
+        let data = serde_json::to_string(session).context("Failed to serialize session")?;
+        connection
+            .execute(
+                "INSERT INTO sessions (id, timestamp, project, methodology, data) VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT (id) DO UPDATE SET timestamp = ?2, project = ?3, methodology = ?4, data = ?5",
+                params![
+                    session.id,
+                    sortable(session.timestamp),
+                    session.project,
+                    methodology_key(&session.methodology),
+                    data
+                ],
+            )
+            .with_context(|| format!("Failed to save session {}", session.id))?;
+        Ok(())
+    }
+
+    impl MetadataStore for SqliteMetadataStore {
+        fn name(&self) -> &str {
+            "sqlite"
+        }
+
+        fn file(&self) -> Option<&Path> {
+            Some(&self.path)
+        }
+
+        fn load(&self, mode: LoadMode) -> Result<SessionsMetadata> {
+            let mut metadata = SessionsMetadata::new();
+            for session in self.rows("SELECT id, data FROM sessions", &[], mode)? {
+                metadata.add_session(session);
+            }
+
+            let connection = self.connect()?;
+            let mut statement = connection
+                .prepare("SELECT data FROM phases ORDER BY position")
+                .context("Failed to prepare phase query")?;
+            let phases = statement
+                .query_map([], |row| row.get::<_, String>(0))
+                .context("Failed to query phases")?;
+            for data in phases {
+                let data = data.context("Failed to read phase row")?;
+                match serde_json::from_str::<Phase>(&data) {
+                    Ok(phase) => metadata.phases.push(phase),
+                    Err(e) if mode == LoadMode::Lenient => eprintln!("Warning: Skipping unreadable phase: {}", e),
+                    Err(e) => return Err(e).context("Failed to parse phase"),
+                }
+            }
+            Ok(metadata)
+        }
+
+        fn save(&self, metadata: &SessionsMetadata) -> Result<()> {
+            let mut connection = self.connect()?;
+            let transaction = connection.transaction().context("Failed to start metadata transaction")?;
+
+            let stored: Vec<String> = transaction
+                .prepare("SELECT id FROM sessions")?
+                .query_map([], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()
+                .context("Failed to list stored sessions")?;
+            let current: HashSet<&str> = metadata.sessions.keys().map(String::as_str).collect();
+            for id in stored.iter().filter(|id| !current.contains(id.as_str())) {
+                transaction.execute("DELETE FROM sessions WHERE id = ?1", params![id])?;
+            }
+            for session in metadata.sessions.values() {
+                upsert(&transaction, session)?;
+            }
+
+            transaction.execute("DELETE FROM phases", [])?;
+            for (position, phase) in metadata.phases.iter().enumerate() {
+                let data = serde_json::to_string(phase).context("Failed to serialize phase")?;
+                transaction.execute("INSERT INTO phases (position, data) VALUES (?1, ?2)", params![position as i64, data])?;
+            }
+
+            transaction.commit().context("Failed to commit metadata")
+        }
+
+        fn save_session(&self, _metadata: &SessionsMetadata, session: &SessionMetadata) -> Result<()> {
+            upsert(&self.connect()?, session)
+        }
+
+        fn query(&self, filter: &SessionFilter, mode: LoadMode) -> Result<Vec<SessionMetadata>> {
+            let mut sql = String::from("SELECT id, data FROM sessions WHERE 1 = 1");
+            let mut values: Vec<String> = Vec::new();
+            if let Some(methodology) = &filter.methodology {
+                values.push(methodology_key(methodology));
+                sql.push_str(&format!(" AND methodology = ?{}", values.len()));
+            }
+            if let Some(project) = &filter.project {
+                values.push(project.clone());
+                sql.push_str(&format!(" AND project = ?{}", values.len()));
+            }
+            if let Some(author) = &filter.author {
+                values.push(author.clone());
+                sql.push_str(&format!(" AND json_extract(data, '$.author') = ?{}", values.len()));
+            }
+            if let Some(from) = filter.from {
+                values.push(sortable(from));
+                sql.push_str(&format!(" AND timestamp >= ?{}", values.len()));
+            }
+            if let Some(to) = filter.to {
+                values.push(sortable(to));
+                sql.push_str(&format!(" AND timestamp < ?{}", values.len()));
+            }
+            sql.push_str(" ORDER BY timestamp DESC");
+            if let Some(limit) = filter.limit {
+                sql.push_str(&format!(" LIMIT {}", limit));
+            }
+
+            let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|value| value as &dyn rusqlite::ToSql).collect();
+            self.rows(&sql, &params, mode)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::pty::CaptureMode;
+        use crate::session::ReviewState;
+        use chrono::TimeZone;
+
+        fn session(id: &str, project: &str, day: u32) -> SessionMetadata {
+            SessionMetadata {
+                id: id.to_string(),
+                timestamp: Utc.with_ymd_and_hms(2025, 1, day, 10, 0, 0).unwrap(),
+                project: project.to_string(),
+                methodology: Methodology::CONTEXT_DRIVEN,
+                working_directory: PathBuf::from("/tmp"),
+                command: "claude".to_string(),
+                log_file: PathBuf::from(format!("/tmp/{}.log", id)),
+                clean_log_file: None,
+                log_parser: None,
+                transcript_file: None,
+                duration_seconds: None,
+                end_time: None,
+                features_worked_on: Vec::new(),
+                creative_energy: None,
+                terminal: None,
+                tmux_pane: None,
+                capture_mode: CaptureMode::Pty,
+                hold: None,
+                archived_remote: None,
+                git_commit: None,
+                annotations: Vec::new(),
+                notes: Vec::new(),
+                project_changes: None,
+                compactions: Vec::new(),
+                author: None,
+                activity: None,
+                duration_estimated: false,
+                review_state: ReviewState::Unreviewed,
+                action_items: Vec::new(),
+                reminders: Vec::new(),
+                tags: Vec::new(),
+                termination_reason: None,
+                usage: None,
+                extra: Default::default(),
+                parent_session_id: None,
+                analysis: None,
+            }
+        }
+
+        #[test]
+        fn saves_and_queries_by_project_author_and_date() {
+            let dir = tempfile::tempdir().unwrap();
+            let store = SqliteMetadataStore::open(&dir.path().join("sessions.db")).unwrap();
+
+            let mut metadata = SessionsMetadata::new();
+            metadata.add_session(session("a", "alpha", 1));
+            metadata.add_session(session("b", "alpha", 5));
+            let mut by_ada = session("c", "beta", 9);
+            by_ada.author = Some("Ada".to_string());
+            metadata.add_session(by_ada);
+            store.save(&metadata).unwrap();
+
+            let filter = SessionFilter {
+                project: Some("alpha".to_string()),
+                from: Some(Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap()),
+                ..SessionFilter::default()
+            };
+            let found = store.query(&filter, LoadMode::Strict).unwrap();
+            assert_eq!(found.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(), ["b"]);
+
+            let filter = SessionFilter { author: Some("Ada".to_string()), ..SessionFilter::default() };
+            let found = store.query(&filter, LoadMode::Strict).unwrap();
+            assert_eq!(found.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(), ["c"]);
+            assert!(filter.matches(&found[0]) && !filter.matches(&metadata.sessions["a"]));
+
+            metadata.sessions.remove("a");
+            store.save(&metadata).unwrap();
+            let mut renamed = session("c", "gamma", 9);
+            renamed.tags.push("x".to_string());
+            store.save_session(&metadata, &renamed).unwrap();
+
+            let loaded = store.load(LoadMode::Strict).unwrap();
+            assert_eq!(loaded.sessions.len(), 2);
+            assert_eq!(loaded.sessions["c"].project, "gamma");
+            assert!(store.query(&SessionFilter::default(), LoadMode::Strict).unwrap()[0].id == "c");
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use crate::config::{MethodologyConfig, MethodologyRule};
+use crate::session::Methodology;
+use anyhow::{Context, Result};
+use glob::{MatchOptions, Pattern};
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// `*` stays within a directory; `**` crosses them.
+const MATCH_OPTIONS: MatchOptions =
+    MatchOptions { case_sensitive: true, require_literal_separator: true, require_literal_leading_dot: false };
+
+/// Methodology rules ready to run against a project directory.
+pub struct Rules(Vec<Rule>);
+
+struct Rule {
+    methodology: Methodology,
+    paths: Vec<Pattern>,
+    content: Option<(Regex, Vec<Pattern>)>,
+}
+
+impl Rules {
+    /// Check and compile `[methodology]` rules from the config.
+    pub fn compile(rules: &[MethodologyRule]) -> Result<Self> {
+        rules
+            .iter()
+            .map(|rule| {
+                let methodology =
+                    Methodology::from_name(&rule.name).context("a methodology rule has an empty name")?;
+                if rule.paths.is_empty() && rule.content.is_none() {
+                    anyhow::bail!("rule '{}' needs paths, content or both", rule.name);
+                }
+                let content = rule
+                    .content
+                    .as_deref()
+                    .map(|content| -> Result<_> {
+                        let regex = Regex::new(content)
+                            .with_context(|| format!("rule '{}' has an invalid content pattern", rule.name))?;
+                        Ok((regex, patterns(&rule.name, &rule.files)?))
+                    })
+                    .transpose()?;
+                Ok(Rule { methodology, paths: patterns(&rule.name, &rule.paths)?, content })
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+
+    /// The checks every project gets: the methodology named in `.claude/CLAUDE.md`.
+    pub fn built_in() -> Self {
+        let claude_md = || vec![Pattern::new(".claude/CLAUDE.md").expect("valid glob")];
+        Self(vec![
+            Rule {
+                methodology: Methodology::CONTEXT_DRIVEN,
+                paths: Vec::new(),
+                content: Some((Regex::new("Context-Driven|context-driven").expect("valid regex"), claude_md())),
+            },
+            Rule {
+                methodology: Methodology::COMMAND_BASED,
+                paths: Vec::new(),
+                content: Some((Regex::new("Command-Based|command-based").expect("valid regex"), claude_md())),
+            },
+        ])
+    }
+
+    /// The first rule that matches `project_dir`.
+    pub fn detect(&self, project_dir: &Path) -> Result<Option<Methodology>> {
+        for rule in &self.0 {
+            if rule.matches(project_dir)? {
+                return Ok(Some(rule.methodology.clone()));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Rule {
+    fn matches(&self, project_dir: &Path) -> Result<bool> {
+        if !self.paths.is_empty() && !self.paths.iter().any(|pattern| expand(project_dir, pattern).next().is_some()) {
+            return Ok(false);
+        }
+        let Some((regex, files)) = &self.content else {
+            return Ok(true);
+        };
+        for path in files.iter().flat_map(|pattern| expand(project_dir, pattern)).filter(|path| path.is_file()) {
+            let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+            if regex.is_match(&content) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+fn patterns(rule: &str, globs: &[String]) -> Result<Vec<Pattern>> {
+    globs
+        .iter()
+        .map(|glob| Pattern::new(glob).with_context(|| format!("rule '{}' has an invalid glob '{}'", rule, glob)))
+        .collect()
+}
+
+/// Existing paths under `project_dir` matching `pattern`.
+fn expand(project_dir: &Path, pattern: &Pattern) -> impl Iterator<Item = PathBuf> {
+    let full = format!("{}/{}", Pattern::escape(&project_dir.to_string_lossy()), pattern.as_str());
+    glob::glob_with(&full, MATCH_OPTIONS).into_iter().flatten().flatten()
+}
+
+/// A new session's methodology: the configured environment variable, else the first
+/// `[methodology]` rule that matches, else the built-in checks, else `Unknown`.
+/// `--methodology` on the command line overrides all of these.
+pub fn detect(config: &MethodologyConfig, project_dir: &Path) -> Result<Methodology> {
+    if let Some(methodology) = std::env::var(&config.env_var).ok().as_deref().and_then(Methodology::from_name) {
+        return Ok(methodology);
+    }
+    if let Some(methodology) = Rules::compile(&config.rules)?.detect(project_dir)? {
+        return Ok(methodology);
+    }
+    Ok(Rules::built_in().detect(project_dir)?.unwrap_or(Methodology::UNKNOWN))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, paths: &[&str], content: Option<&str>) -> MethodologyRule {
+        MethodologyRule {
+            name: name.to_string(),
+            paths: paths.iter().map(|path| path.to_string()).collect(),
+            content: content.map(str::to_string),
+            files: vec!["docs/*.md".to_string()],
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_names_the_methodology() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("tests/unit")).unwrap();
+        fs::write(dir.path().join("tests/unit/parser_test.rs"), "").unwrap();
+        fs::create_dir_all(dir.path().join("docs")).unwrap();
+        fs::write(dir.path().join("docs/process.md"), "We write the test first (TDD).").unwrap();
+        fs::create_dir_all(dir.path().join(".claude")).unwrap();
+        fs::write(dir.path().join(".claude/CLAUDE.md"), "Use the Command-Based workflow.").unwrap();
+
+        let config = |rules| MethodologyConfig { env_var: "CLAUDE_LOGGER_TEST_UNSET_METHODOLOGY".to_string(), rules };
+        let detect = |rules| detect(&config(rules), dir.path()).unwrap();
+
+        // `*` stops at directories, `**` does not; content alone or paths and content together
+        assert_eq!(detect(vec![rule("tdd", &["tests/*_test.rs"], None)]), Methodology::COMMAND_BASED);
+        assert_eq!(detect(vec![rule("tdd", &["tests/**/*_test.rs"], None)]).name(), "tdd");
+        assert_eq!(detect(vec![rule("tdd", &[], Some(r"\bTDD\b"))]).name(), "tdd");
+        assert_eq!(detect(vec![rule("tdd", &["Makefile"], Some(r"\bTDD\b"))]), Methodology::COMMAND_BASED);
+        assert_eq!(
+            detect(vec![rule("spike", &["docs/*.md"], None), rule("tdd", &["tests/**"], None)]).name(),
+            "spike"
+        );
+        // A rule may name a built-in methodology
+        assert_eq!(detect(vec![rule("context-driven", &["docs"], None)]), Methodology::CONTEXT_DRIVEN);
+
+        fs::remove_file(dir.path().join(".claude/CLAUDE.md")).unwrap();
+        assert_eq!(detect(Vec::new()), Methodology::UNKNOWN);
+
+        assert!(Rules::compile(&[rule("empty", &[], None)]).is_err());
+        assert!(Rules::compile(&[rule("bad", &["[z-a"], None)]).is_err());
+        assert!(Rules::compile(&[rule("bad", &[], Some("("))]).is_err());
+    }
+
+    #[test]
+    fn built_in_names_parse_in_any_form() {
+        assert_eq!(Methodology::from_name("context-driven"), Some(Methodology::CONTEXT_DRIVEN));
+        assert_eq!(Methodology::from_name("CommandBased"), Some(Methodology::COMMAND_BASED));
+        assert_eq!(Methodology::from_name(" Command Based "), Some(Methodology::COMMAND_BASED));
+        assert_eq!(Methodology::from_name("pair-programming").unwrap().to_string(), "pair-programming");
+        assert_eq!(Methodology::from_name("  "), None);
+
+        let stored: Methodology = serde_json::from_str("\"ContextDriven\"").unwrap();
+        assert_eq!(stored, Methodology::CONTEXT_DRIVEN);
+        assert_eq!(stored.to_string(), "Context-Driven");
+        assert_eq!(serde_json::to_string(&Methodology::from_name("tdd").unwrap()).unwrap(), "\"tdd\"");
+    }
+}
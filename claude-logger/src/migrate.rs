@@ -0,0 +1,476 @@
+use crate::config::{Config, MetadataBackend};
+use crate::git::GitRepo;
+use crate::log_format::LogFormat;
+use crate::logger::ClaudeLogger;
+use crate::metadata::{self, MetadataLock};
+use crate::metadata_store::{open_metadata_store, JSON_FILE};
+use crate::project;
+use crate::session::SessionMetadata;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde_json::{Map, Value};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// File in the logs directory holding the layout version the data was last migrated to, for
+/// metadata stores other than `sessions_metadata.json`, which records it as `schema_version`.
+pub const VERSION_FILE: &str = "layout_version";
+
+/// Directory under the logs directory where `migrate` copies the metadata before changing it.
+pub const BACKUP_DIR: &str = "backups";
+
+/// One upgrade of the stored data; applying it brings the data to `version`.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// Every migration, oldest first. Each is safe to apply to data that doesn't need it.
+pub const MIGRATIONS: [Migration; 4] = [
+    Migration {
+        version: 1,
+        name: "duration-seconds",
+        description: "store durations as whole seconds instead of [secs, nanos] pairs",
+    },
+    Migration {
+        version: 2,
+        name: "metadata-fields",
+        description: "write out metadata fields added since the sessions were stored",
+    },
+    Migration {
+        version: 3,
+        name: "per-project-logs",
+        description: "move logs kept flat in the logs directory into a directory per project",
+    },
+    Migration {
+        version: 4,
+        name: "clean-logs",
+        description: "write cleaned copies of logs that have none or one from an older parser",
+    },
+];
+
+/// The layout this version of claude-logger writes.
+pub const CURRENT_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// The stored data as `migrate` finds it, read without changing anything.
+pub struct Stored {
+    logs_dir: PathBuf,
+    per_project: bool,
+    /// `sessions_metadata.json` as written, when it is the metadata store
+    raw: Option<Value>,
+    sessions: Vec<SessionMetadata>,
+}
+
+impl Stored {
+    pub fn read(logs_dir: &Path, config: &Config) -> Result<Self> {
+        let (raw, sessions) = if config.metadata.backend == MetadataBackend::Json {
+            let path = logs_dir.join(JSON_FILE);
+            let raw = match fs::read_to_string(&path) {
+                Ok(content) => Some(
+                    serde_json::from_str::<Value>(&content)
+                        .with_context(|| format!("Failed to parse metadata file: {}", path.display()))?,
+                ),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => return Err(e).with_context(|| format!("Failed to read metadata file: {}", path.display())),
+            };
+            let sessions = raw.as_ref().map(readable_sessions).unwrap_or_default();
+            (raw, sessions)
+        } else {
+            let store = open_metadata_store(&config.metadata, logs_dir)?;
+            (None, store.load(config.load_mode())?.sessions.into_values().collect())
+        };
+        Ok(Self { logs_dir: logs_dir.to_path_buf(), per_project: config.logs.per_project, raw, sessions })
+    }
+
+    /// The version recorded by the last `migrate`, else the one before the first migration with
+    /// work to do.
+    pub fn version(&self) -> Result<u32> {
+        let in_metadata = self.raw.as_ref().map_or(0, metadata::schema_version);
+        if in_metadata > 0 {
+            return Ok(in_metadata);
+        }
+        if let Some(version) = recorded_version(&self.logs_dir)? {
+            return Ok(version);
+        }
+        Ok(MIGRATIONS
+            .iter()
+            .find(|migration| self.pending(migration.version) > 0)
+            .map(|migration| migration.version - 1)
+            .unwrap_or(CURRENT_VERSION))
+    }
+
+    /// How many sessions migration `version` would change.
+    pub fn pending(&self, version: u32) -> usize {
+        match version {
+            1 => self.entries().filter(|entry| entry.get("duration").is_some() || entry.get("duration_secs").is_some()).count(),
+            2 => self.entries().filter(|entry| !missing_fields(entry).is_empty()).count(),
+            3 => self.sessions.iter().filter(|session| self.per_project && is_flat(session, &self.logs_dir)).count(),
+            4 => self.sessions.iter().filter(|session| needs_clean_log(session)).count(),
+            _ => 0,
+        }
+    }
+
+    fn entries(&self) -> impl Iterator<Item = &Value> {
+        self.raw.iter().filter_map(|raw| raw.get("sessions")).filter_map(Value::as_object).flat_map(|sessions| sessions.values())
+    }
+}
+
+/// Bring the data in `logs_dir` up to `CURRENT_VERSION`, after copying the metadata to
+/// `backups/`. With `dry_run`, only report what would change.
+pub fn run(logs_dir: &Path, config: &Config, dry_run: bool) -> Result<()> {
+    let stored = Stored::read(logs_dir, config)?;
+    let version = stored.version()?;
+    if version > CURRENT_VERSION {
+        anyhow::bail!(
+            "{} is at layout version {}, newer than this claude-logger understands ({}); upgrade claude-logger",
+            logs_dir.display(),
+            version,
+            CURRENT_VERSION
+        );
+    }
+    println!("Stored data is at layout version {} (current is {})", version, CURRENT_VERSION);
+    let pending: Vec<&Migration> = MIGRATIONS.iter().filter(|migration| migration.version > version).collect();
+    if pending.is_empty() {
+        println!("Nothing to migrate.");
+        return Ok(());
+    }
+    for migration in &pending {
+        println!(
+            "  {}. {}: {} ({} sessions)",
+            migration.version,
+            migration.name,
+            migration.description,
+            stored.pending(migration.version)
+        );
+    }
+    if dry_run {
+        println!("Dry run: nothing was changed.");
+        return Ok(());
+    }
+
+    let backup = backup(logs_dir, config)?;
+    println!("Backed up metadata to {}", backup.display());
+
+    let mut logger: Option<ClaudeLogger> = None;
+    for migration in pending {
+        let changed = match migration.version {
+            1 => rewrite_metadata(logs_dir, config, metadata::migrate)?.unwrap_or(0),
+            2 => rewrite_metadata(logs_dir, config, fill_missing_fields)?.unwrap_or(0),
+            version => {
+                let logger = match &mut logger {
+                    Some(logger) => logger,
+                    None => logger.insert(ClaudeLogger::new_with_config(logs_dir, config.clone())?),
+                };
+                if version == 3 { move_flat_logs(logger, &backup)? } else { write_clean_logs(logger)? }
+            }
+        };
+        // Recorded step by step, so an interrupted run picks up where it stopped
+        record_version(logs_dir, config, migration.version)?;
+        println!("Applied {}: {} sessions changed", migration.name, changed);
+    }
+
+    let version_file = logs_dir.join(VERSION_FILE);
+    let mut files = Vec::new();
+    files.extend(open_metadata_store(&config.metadata, logs_dir)?.file().map(Path::to_path_buf));
+    let (removed, added) = if version_file.exists() { (None, Some(&version_file)) } else { (Some(&version_file), None) };
+    files.extend(added.cloned());
+    let files: Vec<&Path> = files.iter().map(PathBuf::as_path).collect();
+    let removed: Vec<&Path> = removed.into_iter().map(PathBuf::as_path).collect();
+    let message = format!("Migrate stored data to layout version {}", CURRENT_VERSION);
+    GitRepo::init_or_open(logs_dir)?.commit_changes(&removed, &files, &message)?;
+    println!("Stored data is now at layout version {}", CURRENT_VERSION);
+    Ok(())
+}
+
+/// Record `version` as `sessions_metadata.json`'s `schema_version`, dropping the version file
+/// earlier releases wrote, or in the version file for other metadata stores.
+fn record_version(logs_dir: &Path, config: &Config, version: u32) -> Result<()> {
+    let version_file = logs_dir.join(VERSION_FILE);
+    let stamped = rewrite_metadata(logs_dir, config, |value| {
+        value.as_object_mut().map_or(0, |document| {
+            document.insert("schema_version".to_string(), Value::from(version));
+            1
+        })
+    })?;
+    if stamped.is_none() {
+        return fs::write(&version_file, format!("{}\n", version)).with_context(|| format!("Failed to write {}", VERSION_FILE));
+    }
+    if version_file.exists() {
+        fs::remove_file(&version_file).with_context(|| format!("Failed to remove {}", version_file.display()))?;
+    }
+    Ok(())
+}
+
+fn recorded_version(logs_dir: &Path) -> Result<Option<u32>> {
+    let path = logs_dir.join(VERSION_FILE);
+    match fs::read_to_string(&path) {
+        Ok(content) => content
+            .trim()
+            .parse()
+            .map(Some)
+            .with_context(|| format!("{} does not hold a version number", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+/// Copy the metadata store and the version file into a new directory under `backups/`.
+fn backup(logs_dir: &Path, config: &Config) -> Result<PathBuf> {
+    let dir = logs_dir.join(BACKUP_DIR).join(format!("migrate-{}", Utc::now().format("%Y-%m-%d_%H-%M-%S")));
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create backup directory: {}", dir.display()))?;
+
+    let mut files = vec![logs_dir.join(JSON_FILE), logs_dir.join(VERSION_FILE)];
+    files.extend(open_metadata_store(&config.metadata, logs_dir)?.file().map(Path::to_path_buf));
+    files.dedup();
+    for file in files.iter().filter(|file| file.exists()) {
+        let name = file.file_name().context("Backup source has no file name")?;
+        fs::copy(file, dir.join(name)).with_context(|| format!("Failed to back up {}", file.display()))?;
+    }
+    Ok(dir)
+}
+
+/// Edit `sessions_metadata.json` as raw JSON, so entries this version can't read are kept as they
+/// are, returning how many `edit` changed. `None` for other metadata stores, which have only ever
+/// held the current format.
+fn rewrite_metadata(logs_dir: &Path, config: &Config, edit: impl FnOnce(&mut Value) -> usize) -> Result<Option<usize>> {
+    let path = logs_dir.join(JSON_FILE);
+    if config.metadata.backend != MetadataBackend::Json || !path.exists() {
+        return Ok(None);
+    }
+    let _lock = MetadataLock::acquire(&path)?;
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read metadata file: {}", path.display()))?;
+    let mut value: Value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse metadata file: {}", path.display()))?;
+    let changed = edit(&mut value);
+    if changed > 0 {
+        let json = serde_json::to_string_pretty(&value).context("Failed to serialize metadata")?;
+        let temporary = path.with_extension("json.tmp");
+        fs::write(&temporary, json)
+            .with_context(|| format!("Failed to write metadata file: {}", temporary.display()))?;
+        fs::rename(&temporary, &path)
+            .with_context(|| format!("Failed to replace metadata file: {}", path.display()))?;
+    }
+    Ok(Some(changed))
+}
+
+/// Sessions in a raw metadata document that this version can read.
+fn readable_sessions(raw: &Value) -> Vec<SessionMetadata> {
+    let mut raw = raw.clone();
+    metadata::migrate(&mut raw);
+    raw.get("sessions")
+        .and_then(Value::as_object)
+        .map(|sessions| sessions.values().filter_map(|entry| serde_json::from_value(entry.clone()).ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Fields `entry` leaves to their defaults, with the values they default to.
+fn missing_fields(entry: &Value) -> Map<String, Value> {
+    let mut current = entry.clone();
+    metadata::migrate_entry(&mut current);
+    let Ok(session) = serde_json::from_value::<SessionMetadata>(current.clone()) else {
+        return Map::new();
+    };
+    let Ok(Value::Object(fields)) = serde_json::to_value(&session) else {
+        return Map::new();
+    };
+    fields.into_iter().filter(|(field, _)| current.get(field).is_none()).collect()
+}
+
+fn fill_missing_fields(value: &mut Value) -> usize {
+    let Some(sessions) = value.get_mut("sessions").and_then(Value::as_object_mut) else {
+        return 0;
+    };
+    let mut changed = 0;
+    for entry in sessions.values_mut() {
+        let missing = missing_fields(entry);
+        if let Some(fields) = entry.as_object_mut()
+            && !missing.is_empty()
+        {
+            fields.extend(missing);
+            changed += 1;
+        }
+    }
+    changed
+}
+
+fn is_flat(session: &SessionMetadata, logs_dir: &Path) -> bool {
+    session.log_file.parent() == Some(logs_dir) && session.log_file.exists()
+}
+
+/// A log still on disk uncompressed whose cleaned copy is missing or from an older parser.
+/// Compressed logs have their cleaned copy dropped on purpose.
+fn needs_clean_log(session: &SessionMetadata) -> bool {
+    !project::is_compressed(&session.log_file)
+        && session.log_file.exists()
+        && (session.clean_log_file.as_ref().is_none_or(|path| !path.exists())
+            || session.log_parser.as_deref().is_none_or(|parser| !LogFormat::is_current(parser)))
+}
+
+/// Move flat logs and their cleaned copies under their project's directory, listing each move in
+/// `moves.tsv` in the backup.
+fn move_flat_logs(logger: &mut ClaudeLogger, backup: &Path) -> Result<usize> {
+    if !logger.config().logs.per_project {
+        return Ok(0);
+    }
+    let logs_dir = logger.logs_dir().to_path_buf();
+    let flat: Vec<SessionMetadata> =
+        logger.metadata().sessions.values().filter(|session| is_flat(session, &logs_dir)).cloned().collect();
+    let manifest_path = backup.join("moves.tsv");
+    let mut manifest = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&manifest_path)
+        .with_context(|| format!("Failed to open {}", manifest_path.display()))?;
+
+    let (mut moved, mut removed, mut added) = (Vec::new(), Vec::new(), Vec::new());
+    for mut session in flat {
+        let dir = logs_dir.join(project::directory_name(&session.project));
+        let clean = session.clean_log_file.clone().unwrap_or_else(|| session.clean_log_path());
+        let mut files = vec![session.log_file.clone()];
+        if clean.parent() == Some(logs_dir.as_path()) && clean.exists() {
+            files.push(clean);
+        }
+        let targets: Vec<PathBuf> = files.iter().filter_map(|file| file.file_name()).map(|name| dir.join(name)).collect();
+        if let Some(taken) = targets.iter().find(|target| target.exists()) {
+            eprintln!("Warning: Not moving the logs of {}: {} already exists", session.id, taken.display());
+            continue;
+        }
+
+        fs::create_dir_all(&dir).with_context(|| format!("Failed to create log directory: {}", dir.display()))?;
+        for (from, to) in files.iter().zip(&targets) {
+            fs::rename(from, to).with_context(|| format!("Failed to move {} to {}", from.display(), to.display()))?;
+            writeln!(manifest, "{}\t{}", from.display(), to.display())
+                .with_context(|| format!("Failed to write {}", manifest_path.display()))?;
+            removed.push(from.clone());
+            added.push(to.clone());
+        }
+        session.log_file = targets[0].clone();
+        if let Some(clean) = targets.get(1) {
+            session.clean_log_file = Some(clean.clone());
+        }
+        moved.push(session);
+    }
+    if moved.is_empty() {
+        return Ok(0);
+    }
+
+    // Saved before committing, so the metadata follows the files even if the commit fails
+    let count = moved.len();
+    for session in &moved {
+        logger.metadata_mut().add_session(session.clone());
+    }
+    logger.save_metadata()?;
+
+    let removed: Vec<&Path> = removed.iter().map(PathBuf::as_path).collect();
+    let added: Vec<&Path> = added.iter().map(PathBuf::as_path).collect();
+    if let Some(commit) = logger.git_repo().commit_changes(&removed, &added, "Move session logs into per-project directories")? {
+        // The log is read back from history at its new path once it's gone
+        for mut session in moved.into_iter().filter(|session| session.git_commit.is_some()) {
+            session.git_commit = Some(commit.clone());
+            logger.metadata_mut().add_session(session);
+        }
+        logger.save_metadata()?;
+    }
+    Ok(count)
+}
+
+fn write_clean_logs(logger: &mut ClaudeLogger) -> Result<usize> {
+    let stale: Vec<SessionMetadata> =
+        logger.metadata().sessions.values().filter(|session| needs_clean_log(session)).cloned().collect();
+    let mut written = Vec::new();
+    for mut session in stale {
+        match ClaudeLogger::write_clean_log(&session) {
+            Ok((path, format)) => {
+                session.clean_log_file = Some(path.clone());
+                session.log_parser = Some(format.parser_id());
+                written.push(path);
+                logger.metadata_mut().add_session(session);
+            }
+            Err(e) => eprintln!("Warning: Failed to write cleaned log for {}: {:#}", session.id, e),
+        }
+    }
+    if written.is_empty() {
+        return Ok(0);
+    }
+
+    logger.save_metadata()?;
+    let written: Vec<&Path> = written.iter().map(PathBuf::as_path).collect();
+    logger.git_repo().commit_changes(&[], &written, "Write cleaned copies of session logs")?;
+    Ok(written.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::process::Command;
+
+    #[test]
+    fn upgrades_legacy_data_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let logs_dir = dir.path();
+        GitRepo::init_or_open(logs_dir).unwrap();
+        for (key, value) in [("user.email", "test@example.com"), ("user.name", "Test")] {
+            Command::new("git").args(["config", key, value]).current_dir(logs_dir).status().unwrap();
+        }
+        let log_file = logs_dir.join("claude_api_context_2024-06-01.log");
+        fs::write(&log_file, "Human: Add a health check\n\nAssistant: Added /health.\n").unwrap();
+        // As an early version wrote it: a [secs, nanos] duration, flat log, none of the later fields
+        let legacy = json!({ "sessions": { "2024-06-01_09-00-00": {
+            "id": "2024-06-01_09-00-00",
+            "timestamp": "2024-06-01T09:00:00Z",
+            "project": "api",
+            "methodology": "ContextDriven",
+            "working_directory": "/work/api",
+            "command": "claude",
+            "log_file": log_file,
+            "duration": [1800, 0],
+            "end_time": "2024-06-01T09:30:00Z",
+            "features_worked_on": [],
+            "creative_energy": null
+        } } });
+        fs::write(logs_dir.join(JSON_FILE), legacy.to_string()).unwrap();
+
+        let config = Config::default();
+        let stored = Stored::read(logs_dir, &config).unwrap();
+        assert_eq!(stored.version().unwrap(), 0);
+        assert_eq!((1..=4).map(|version| stored.pending(version)).collect::<Vec<_>>(), [1, 1, 1, 1]);
+
+        run(logs_dir, &config, true).unwrap();
+        assert!(!logs_dir.join(VERSION_FILE).exists());
+
+        // An earlier release recorded the version in a file of its own
+        fs::write(logs_dir.join(VERSION_FILE), "0\n").unwrap();
+        run(logs_dir, &config, false).unwrap();
+        assert!(!logs_dir.join(VERSION_FILE).exists());
+        let metadata: Value = serde_json::from_str(&fs::read_to_string(logs_dir.join(JSON_FILE)).unwrap()).unwrap();
+        assert_eq!(metadata::schema_version(&metadata), CURRENT_VERSION);
+        let entry = &metadata["sessions"]["2024-06-01_09-00-00"];
+        assert_eq!((entry["duration_seconds"].as_i64(), entry.get("duration")), (Some(1800), None));
+        assert_eq!(entry["tags"], json!([]));
+
+        let moved = logs_dir.join("api/claude_api_context_2024-06-01.log");
+        assert!(moved.exists() && !log_file.exists());
+        assert_eq!(entry["log_file"], json!(moved));
+        assert!(logs_dir.join("api/claude_api_context_2024-06-01.clean.log").exists());
+
+        let backups: Vec<PathBuf> = fs::read_dir(logs_dir.join(BACKUP_DIR)).unwrap().map(|entry| entry.unwrap().path()).collect();
+        assert_eq!(backups.len(), 1);
+        let saved: Value = serde_json::from_str(&fs::read_to_string(backups[0].join(JSON_FILE)).unwrap()).unwrap();
+        assert_eq!(saved, legacy);
+        assert!(fs::read_to_string(backups[0].join("moves.tsv")).unwrap().contains("api/claude_api_context_2024-06-01.log"));
+
+        let stored = Stored::read(logs_dir, &config).unwrap();
+        assert_eq!(stored.version().unwrap(), CURRENT_VERSION);
+        assert!((1..=4).all(|version| stored.pending(version) == 0));
+
+        let mut newer = metadata.clone();
+        newer["schema_version"] = json!(CURRENT_VERSION + 1);
+        fs::write(logs_dir.join(JSON_FILE), newer.to_string()).unwrap();
+        assert!(run(logs_dir, &config, false).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+use crate::config::{MirrorConfig, MirrorFormat};
+use crate::conversation::{parse_turns, to_markdown};
+use crate::edits::parse_edits;
+use crate::redact::Redactor;
+use crate::session::{ActionState, SessionMetadata};
+use crate::story;
+use crate::timezone::DisplayZone;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// What became of a session's mirror.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mirrored {
+    Written(PathBuf),
+    /// The session didn't run inside a git repository
+    NoProject,
+    /// The project's `.gitignore` excludes the mirror directory
+    Ignored(PathBuf),
+}
+
+/// Write a redacted copy of `session`, whose cleaned log is `content`, into the repository
+/// it ran in, under `config.directory`.
+pub fn write(
+    config: &MirrorConfig,
+    format: MirrorFormat,
+    redactor: &Redactor,
+    session: &SessionMetadata,
+    content: &str,
+    zone: DisplayZone,
+) -> Result<Mirrored> {
+    let Some(root) = project_root(&session.working_directory) else {
+        return Ok(Mirrored::NoProject);
+    };
+    let path = root.join(&config.directory).join(format!("{}.md", session.id));
+    if is_ignored(&root, &path) {
+        return Ok(Mirrored::Ignored(path));
+    }
+
+    let (content, _) = redactor.apply(content);
+    let markdown = match format {
+        MirrorFormat::Summary => render_summary(redactor, session, &content, zone),
+        MirrorFormat::Transcript => format!(
+            "{}\n{}",
+            render_header(session, &content, zone),
+            to_markdown(&parse_turns(&content))
+        ),
+    };
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    fs::write(&path, markdown).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(Mirrored::Written(path))
+}
+
+fn project_root(dir: &Path) -> Option<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+fn is_ignored(root: &Path, path: &Path) -> bool {
+    Command::new("git")
+        .args(["check-ignore", "-q", "--no-index"])
+        .arg(path)
+        .current_dir(root)
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+fn render_header(session: &SessionMetadata, content: &str, zone: DisplayZone) -> String {
+    let mut details = vec![zone.format(session.timestamp, "%Y-%m-%d %H:%M"), session.methodology.to_string()];
+    if let Some(duration) = session.duration() {
+        details.push(format!("{}m", duration.num_minutes()));
+    }
+    if let Some(analysis) = &session.analysis {
+        details.push(format!("quality {:.0}/100", analysis.quality.overall_score));
+    }
+    format!(
+        "# {}\n\nSession `{}` · {}\n",
+        story::title(content, session),
+        session.id,
+        details.join(" · ")
+    )
+}
+
+fn render_summary(redactor: &Redactor, session: &SessionMetadata, content: &str, zone: DisplayZone) -> String {
+    let mut markdown = render_header(session, content, zone);
+
+    if let Some(analysis) = &session.analysis {
+        let metrics = &analysis.metrics;
+        markdown.push_str(&format!(
+            "\n{} exchanges, {} code blocks, {} file edits.\n",
+            metrics.exchanges, metrics.code_blocks, metrics.file_edits
+        ));
+    }
+
+    let mut files: Vec<String> = Vec::new();
+    for edit in parse_edits(content) {
+        if !files.contains(&edit.file) {
+            files.push(edit.file);
+        }
+    }
+    if !files.is_empty() {
+        markdown.push_str("\n## Files edited\n\n");
+        for file in &files {
+            markdown.push_str(&format!("- `{}`\n", file));
+        }
+    }
+
+    if !session.action_items.is_empty() {
+        markdown.push_str("\n## Follow-ups\n\n");
+        for item in &session.action_items {
+            let mark = if item.state == ActionState::Open { " " } else { "x" };
+            markdown.push_str(&format!("- [{}] {}\n", mark, redactor.apply(&item.text).0));
+        }
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RedactionConfig;
+    use crate::synth::{generate, sessions};
+    use chrono::Utc;
+
+    #[test]
+    fn writes_redacted_copies_where_the_project_allows() {
+        let project = tempfile::tempdir().unwrap();
+        Command::new("git").args(["init", "-q"]).current_dir(project.path()).status().unwrap();
+
+        let options = sessions(1, 2, "api");
+        let mut session = generate(&options, Path::new("/logs"), Utc::now()).remove(0).session;
+        session.working_directory = project.path().join("src");
+        fs::create_dir_all(&session.working_directory).unwrap();
+        let content = "Human: deploy with token=hunter2hunter2 please\n\nAssistant: Done.\n";
+
+        let config = MirrorConfig::default();
+        let redactor = Redactor::new(&RedactionConfig::default()).unwrap();
+        let zone = DisplayZone::Utc;
+        let Mirrored::Written(path) =
+            write(&config, MirrorFormat::Transcript, &redactor, &session, content, zone).unwrap()
+        else {
+            panic!("expected the mirror to be written");
+        };
+        assert!(path.starts_with(project.path().canonicalize().unwrap().join("docs/ai-sessions")));
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.contains("deploy with") && !written.contains("hunter2"));
+
+        fs::write(project.path().join(".gitignore"), "docs/ai-sessions/\n").unwrap();
+        let outcome = write(&config, MirrorFormat::Summary, &redactor, &session, content, zone).unwrap();
+        assert!(matches!(outcome, Mirrored::Ignored(_)));
+
+        session.working_directory = std::env::temp_dir();
+        let outcome = write(&config, MirrorFormat::Summary, &redactor, &session, content, zone).unwrap();
+        assert_eq!(outcome, Mirrored::NoProject);
+    }
+}
@@ -0,0 +1,382 @@
+use crate::analyzer::SessionAnalyzer;
+use crate::calendar::{self, Bucket, Period};
+use crate::config::NotificationsConfig;
+use crate::logger::ClaudeLogger;
+use crate::reminders;
+use crate::session::SessionMetadata;
+use crate::timezone::DisplayZone;
+use anyhow::Result;
+use chrono::Utc;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::mem;
+use std::time::Instant;
+
+/// Number of earlier sessions averaged for the quality regression baseline.
+const REGRESSION_BASELINE_SESSIONS: usize = 10;
+
+/// Something the daemons report, on stdout and optionally as a desktop notification.
+#[derive(Debug, Clone)]
+pub enum Event {
+    SessionFinalized { session_id: String, project: String },
+    QualityRegression { session_id: String, score: f64, baseline: f64 },
+    DigestReady { period: String, sessions: usize, minutes: i64 },
+    ReminderDue { session_id: String, note: String },
+    /// A live capture growing far faster than sessions normally do, e.g. a tool loop
+    RunawayOutput { session_id: String, rate: f64, baseline: f64 },
+    /// A live capture that has written nothing for a while
+    SessionStalled { session_id: String, minutes: i64 },
+}
+
+impl Event {
+    fn enabled(&self, config: &NotificationsConfig) -> bool {
+        match self {
+            Event::SessionFinalized { .. } => config.session_finalized,
+            Event::QualityRegression { .. } => config.quality_regression,
+            Event::DigestReady { .. } => config.digest_ready,
+            Event::ReminderDue { .. } => config.reminder_due,
+            Event::RunawayOutput { .. } | Event::SessionStalled { .. } => config.anomalies,
+        }
+    }
+
+    pub fn summary(&self) -> &'static str {
+        match self {
+            Event::SessionFinalized { .. } => "Session finalized",
+            Event::QualityRegression { .. } => "Quality regression",
+            Event::DigestReady { .. } => "Weekly digest ready",
+            Event::ReminderDue { .. } => "Reminder due",
+            Event::RunawayOutput { .. } => "Runaway output",
+            Event::SessionStalled { .. } => "Session stalled",
+        }
+    }
+
+    /// Heading for several events of this kind, after their count.
+    fn plural(&self) -> &'static str {
+        match self {
+            Event::SessionFinalized { .. } => "sessions finalized",
+            Event::QualityRegression { .. } => "quality regressions",
+            Event::DigestReady { .. } => "digests ready",
+            Event::ReminderDue { .. } => "reminders due",
+            Event::RunawayOutput { .. } => "sessions with runaway output",
+            Event::SessionStalled { .. } => "sessions stalled",
+        }
+    }
+
+    pub fn body(&self) -> String {
+        match self {
+            Event::SessionFinalized { session_id, project } => format!("{} ({})", session_id, project),
+            Event::QualityRegression { session_id, score, baseline } => format!(
+                "{} scored {:.1}/100 against a recent average of {:.1}",
+                session_id, score, baseline
+            ),
+            Event::DigestReady { period, sessions, minutes } => {
+                format!("{}: {} sessions, {}m (claude-logger digest)", period, sessions, minutes)
+            }
+            Event::ReminderDue { session_id, note } => format!("{}: {}", session_id, note),
+            Event::RunawayOutput { session_id, rate, baseline } => format!(
+                "{} is writing {:.0} bytes/min, {:.0}x the usual {:.0}",
+                session_id, rate, rate / baseline, baseline
+            ),
+            Event::SessionStalled { session_id, minutes } => {
+                format!("{} has produced no output for {} minutes", session_id, minutes)
+            }
+        }
+    }
+}
+
+/// Where notifications are delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Channel {
+    Console,
+    Desktop,
+    Webhook,
+}
+
+/// One delivery: a single event, or every queued event of one kind.
+#[derive(Debug, Clone, PartialEq)]
+struct Message {
+    summary: String,
+    body: String,
+}
+
+impl Message {
+    /// Bodies listed in a batched message before the rest are only counted.
+    const BATCH_BODIES: usize = 3;
+
+    fn from_batch(events: &[Event]) -> Self {
+        let [event] = events else {
+            let bodies: Vec<String> = events.iter().take(Self::BATCH_BODIES).map(Event::body).collect();
+            let mut body = bodies.join("; ");
+            if events.len() > Self::BATCH_BODIES {
+                body.push_str(&format!("; and {} more", events.len() - Self::BATCH_BODIES));
+            }
+            return Self { summary: format!("{} {}", events.len(), events[0].plural()), body };
+        };
+        Self { summary: event.summary().to_string(), body: event.body() }
+    }
+}
+
+/// Events waiting for a channel's rate limit, a token bucket refilled at `per_minute`.
+struct ChannelQueue {
+    channel: Channel,
+    per_minute: u32,
+    tokens: f64,
+    refilled: Instant,
+    pending: VecDeque<Event>,
+}
+
+impl ChannelQueue {
+    fn new(channel: Channel, per_minute: u32, now: Instant) -> Self {
+        Self { channel, per_minute, tokens: per_minute as f64, refilled: now, pending: VecDeque::new() }
+    }
+
+    /// The messages the channel may send at `now`, oldest first; events of the kind at the front
+    /// of the queue go out together. With `force` the limit is ignored, e.g. when shutting down.
+    fn ready(&mut self, now: Instant, force: bool) -> Vec<Message> {
+        let per_minute = self.per_minute as f64;
+        let elapsed = now.saturating_duration_since(self.refilled).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * per_minute / 60.0).min(per_minute);
+        self.refilled = now;
+
+        let mut messages = Vec::new();
+        while let Some(front) = self.pending.front() {
+            if !force && self.per_minute > 0 {
+                if self.tokens < 1.0 {
+                    break;
+                }
+                self.tokens -= 1.0;
+            }
+            let kind = mem::discriminant(front);
+            let (batch, rest): (Vec<Event>, Vec<Event>) =
+                self.pending.drain(..).partition(|event| mem::discriminant(event) == kind);
+            self.pending = rest.into();
+            messages.push(Message::from_batch(&batch));
+        }
+        messages
+    }
+}
+
+/// Delivers events to stdout and the configured channels, each throttled by its
+/// `[notifications.rate_limits]` entry so an import or a backlog of finished captures doesn't
+/// fire a storm. Call `flush` regularly to deliver what waited; the rest goes out on drop.
+pub struct Notifier {
+    config: NotificationsConfig,
+    queues: RefCell<Vec<ChannelQueue>>,
+}
+
+impl Notifier {
+    pub fn new(config: NotificationsConfig) -> Self {
+        let now = Instant::now();
+        let limits = &config.rate_limits;
+        let mut queues = vec![ChannelQueue::new(Channel::Console, limits.console, now)];
+        if config.desktop {
+            if cfg!(feature = "notifications") {
+                queues.push(ChannelQueue::new(Channel::Desktop, limits.desktop, now));
+            } else {
+                eprintln!("Warning: Desktop notifications need the `notifications` feature; printing events only");
+            }
+        }
+        if config.webhook.is_some() {
+            if cfg!(feature = "notifications") {
+                queues.push(ChannelQueue::new(Channel::Webhook, limits.webhook, now));
+            } else {
+                eprintln!("Warning: Webhook notifications need the `notifications` feature; printing events only");
+            }
+        }
+        Self { config, queues: RefCell::new(queues) }
+    }
+
+    pub fn emit(&self, event: &Event) {
+        if !event.enabled(&self.config) {
+            return;
+        }
+        for queue in self.queues.borrow_mut().iter_mut() {
+            queue.pending.push_back(event.clone());
+        }
+        self.flush();
+    }
+
+    /// Deliver the events each channel's rate limit now allows.
+    pub fn flush(&self) {
+        self.deliver(false);
+    }
+
+    fn deliver(&self, force: bool) {
+        let now = Instant::now();
+        for queue in self.queues.borrow_mut().iter_mut() {
+            for message in queue.ready(now, force) {
+                self.send(queue.channel, &message);
+            }
+        }
+    }
+
+    fn send(&self, channel: Channel, message: &Message) {
+        match channel {
+            Channel::Console => println!("{}: {}", message.summary, message.body),
+            Channel::Desktop => self.show_desktop(message),
+            Channel::Webhook => self.post_webhook(message),
+        }
+    }
+
+    #[cfg(feature = "notifications")]
+    fn show_desktop(&self, message: &Message) {
+        let result = notify_rust::Notification::new()
+            .appname("claude-logger")
+            .summary(&message.summary)
+            .body(&message.body)
+            .show();
+        if let Err(e) = result {
+            eprintln!("Warning: Failed to show desktop notification: {}", e);
+        }
+    }
+
+    #[cfg(not(feature = "notifications"))]
+    fn show_desktop(&self, _message: &Message) {}
+
+    #[cfg(feature = "notifications")]
+    fn post_webhook(&self, message: &Message) {
+        let Some(url) = &self.config.webhook else {
+            return;
+        };
+        let payload = serde_json::json!({ "text": format!("{}: {}", message.summary, message.body) });
+        let result = ureq::post(url)
+            .header("Content-Type", "application/json")
+            .send(payload.to_string().as_bytes());
+        if let Err(e) = result {
+            eprintln!("Warning: Failed to post webhook notification: {}", e);
+        }
+    }
+
+    #[cfg(not(feature = "notifications"))]
+    fn post_webhook(&self, _message: &Message) {}
+}
+
+impl Drop for Notifier {
+    fn drop(&mut self) {
+        self.deliver(true);
+    }
+}
+
+/// Compare a just-finished session with the average of the sessions before it.
+pub fn quality_regression(analyzer: &SessionAnalyzer, session: &SessionMetadata, threshold: f64) -> Option<Event> {
+    let (_, quality) = analyzer.analyze_session(&session.id).ok()?;
+
+    let earlier: Vec<f64> = analyzer
+        .metadata()
+        .recent_sessions(None, usize::MAX)
+        .into_iter()
+        .filter(|other| other.id != session.id && other.timestamp < session.timestamp)
+        .take(REGRESSION_BASELINE_SESSIONS)
+        .filter_map(|other| analyzer.analyze_session(&other.id).ok())
+        .map(|(_, quality)| quality.overall_score)
+        .collect();
+    if earlier.is_empty() {
+        return None;
+    }
+
+    let baseline = earlier.iter().sum::<f64>() / earlier.len() as f64;
+    (quality.overall_score < baseline - threshold).then(|| Event::QualityRegression {
+        session_id: session.id.clone(),
+        score: quality.overall_score,
+        baseline,
+    })
+}
+
+/// Emit the events for a session the watcher just finalized.
+pub fn session_finalized(logger: &ClaudeLogger, notifier: &Notifier, session: &SessionMetadata) {
+    notifier.emit(&Event::SessionFinalized {
+        session_id: session.id.clone(),
+        project: session.project.clone(),
+    });
+
+    if !notifier.config.quality_regression {
+        return;
+    }
+    match SessionAnalyzer::new_with_config(logger.logs_dir(), logger.config()) {
+        Ok(analyzer) => {
+            let threshold = notifier.config.regression_threshold;
+            if let Some(event) = quality_regression(&analyzer, session, threshold) {
+                notifier.emit(&event);
+            }
+        }
+        Err(e) => eprintln!("Warning: Failed to check quality regression: {:#}", e),
+    }
+}
+
+/// Time-based checks shared by the daemons: due reminders and the weekly digest.
+#[derive(Default)]
+pub struct PeriodicChecks {
+    current_week: Option<Bucket>,
+}
+
+impl PeriodicChecks {
+    pub fn run(&mut self, logger: &mut ClaudeLogger, notifier: &Notifier) -> Result<()> {
+        for (session_id, reminder) in reminders::take_due_notifications(logger)? {
+            notifier.emit(&Event::ReminderDue {
+                session_id,
+                note: reminder.note,
+            });
+        }
+
+        // Announce last week's digest the first time we notice a new week has begun
+        let zone = DisplayZone::Local;
+        let calendar = &logger.config().calendar;
+        let week = Period::Week.bucket(zone.date(Utc::now()), calendar);
+        let previous = self.current_week.replace(week);
+        if let Some(previous) = previous
+            && previous != week
+        {
+            let buckets = calendar::bucket_sessions(logger.metadata().sessions.values(), Period::Week, calendar, zone);
+            let sessions = buckets.get(&previous).map(Vec::as_slice).unwrap_or_default();
+            notifier.emit(&Event::DigestReady {
+                period: previous.label(),
+                sessions: sessions.len(),
+                minutes: sessions
+                    .iter()
+                    .filter_map(|session| session.duration())
+                    .map(|duration| duration.num_minutes())
+                    .sum(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn finalized(n: usize) -> Event {
+        Event::SessionFinalized { session_id: format!("s{}", n), project: "api".to_string() }
+    }
+
+    #[test]
+    fn throttles_channels_and_batches_what_waited() {
+        let start = Instant::now();
+        let mut queue = ChannelQueue::new(Channel::Webhook, 2, start);
+        queue.pending.extend((1..=34).map(finalized));
+        queue.pending.push_back(Event::ReminderDue { session_id: "s1".to_string(), note: "follow up".to_string() });
+
+        // The first delivery takes every waiting finalized session; the reminder uses the second token
+        let messages = queue.ready(start, false);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].summary, "34 sessions finalized");
+        assert_eq!(messages[0].body, "s1 (api); s2 (api); s3 (api); and 31 more");
+        assert_eq!(messages[1], Message { summary: "Reminder due".to_string(), body: "s1: follow up".to_string() });
+
+        // Out of tokens until the bucket refills at 2 a minute
+        queue.pending.extend((35..=36).map(finalized));
+        assert!(queue.ready(start + Duration::from_secs(10), false).is_empty());
+        let messages = queue.ready(start + Duration::from_secs(30), false);
+        assert_eq!(messages[0].summary, "2 sessions finalized");
+
+        queue.pending.push_back(finalized(37));
+        assert_eq!(queue.ready(start + Duration::from_secs(31), true)[0].summary, "Session finalized");
+
+        let mut unlimited = ChannelQueue::new(Channel::Console, 0, start);
+        unlimited.pending.push_back(finalized(1));
+        assert_eq!(unlimited.ready(start, false).len(), 1);
+    }
+}
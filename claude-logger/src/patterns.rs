@@ -1,37 +1,240 @@
+use crate::config::SentimentConfig;
+use crate::conversation;
+use crate::edits;
+use crate::sentiment::{self, RawSentiment, Sentiment};
 use crate::session::AnalysisMetrics;
+use anyhow::{Context, Result};
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
 use std::sync::OnceLock;
 
+/// User pattern definitions, read from the logs directory.
+pub const PATTERNS_FILE: &str = "patterns.toml";
+
+const ENTHUSIASM: &str = r"(?i)(excellent|great|perfect|amazing|awesome|fantastic|wonderful|brilliant|outstanding|superb|terrific|love it|exactly|precisely)";
+const CONFUSION: &str = r"(?i)(confused|unclear|not sure|don't understand|what do you mean|can you clarify|help me understand|i'm lost|not following)";
+const COMPACTION: &str = r"(?i)(concise|brief|short|summarize|compact|terse|reduce|minimize|streamline)";
+const ERRORS: &str = r"(?m)(^\s*error(\[E\d{4}\])?:|test result: FAILED|^FAIL\b|\b\d+ (failed|failing)\b|panicked at|Traceback \(most recent call last\)|npm ERR!|\b(Syntax|Type|Reference|Assertion)Error\b)";
+const RETRIES: &str = r"(?i)(i apologi[sz]e|my apologies|my mistake|sorry about that|let me fix (that|this)|let me try (again|a different)|that didn't work|i made an error)";
+
+/// Largest amount custom categories together can move one score.
+const CUSTOM_SCORE_CAP: f64 = 30.0;
+
 pub struct ConversationPatterns {
     enthusiasm: Regex,
     confusion: Regex,
     compaction: Regex,
+    errors: Regex,
+    retries: Regex,
     code_blocks: Regex,
     exchanges: Regex,
     questions: Regex,
+    tools: Regex,
+    weights: Weights,
+    custom: Vec<CustomCategory>,
+    /// Replaces the enthusiasm and confusion patterns when `[analysis.sentiment]` picks a backend
+    sentiment: Option<Sentiment>,
+}
+
+/// Multipliers on the built-in categories' contribution to the quality scores.
+#[derive(Debug, Clone, Copy)]
+pub struct Weights {
+    pub enthusiasm: f64,
+    pub confusion: f64,
+    pub compaction: f64,
+    pub errors: f64,
+    pub retries: f64,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Self { enthusiasm: 1.0, confusion: 1.0, compaction: 1.0, errors: 1.0, retries: 1.0 }
+    }
+}
+
+/// A user-defined category, counted into `AnalysisMetrics::custom_markers`.
+pub struct CustomCategory {
+    pub name: String,
+    regex: Regex,
+    /// Points per match, negative for a penalty
+    pub points: f64,
+    pub score: ScoreKind,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScoreKind {
+    #[default]
+    Engagement,
+    Clarity,
+    Productivity,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PatternsFile {
+    #[serde(default)]
+    categories: BTreeMap<String, CategoryDef>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CategoryDef {
+    #[serde(default)]
+    patterns: Vec<String>,
+    /// Built-in categories only: drop the built-in regex instead of adding to it
+    #[serde(default)]
+    replace: bool,
+    /// Built-in categories only: multiplier on the category's score contribution
+    weight: Option<f64>,
+    /// Custom categories only
+    points: Option<f64>,
+    score: Option<ScoreKind>,
 }
 
 impl ConversationPatterns {
     pub fn new() -> Self {
         Self {
-            enthusiasm: Regex::new(r"(?i)(excellent|great|perfect|amazing|awesome|fantastic|wonderful|brilliant|outstanding|superb|terrific|love it|exactly|precisely)").unwrap(),
-            confusion: Regex::new(r"(?i)(confused|unclear|not sure|don't understand|what do you mean|can you clarify|help me understand|i'm lost|not following)").unwrap(),
-            compaction: Regex::new(r"(?i)(concise|brief|short|summarize|compact|terse|reduce|minimize|streamline)").unwrap(),
+            enthusiasm: Regex::new(ENTHUSIASM).unwrap(),
+            confusion: Regex::new(CONFUSION).unwrap(),
+            compaction: Regex::new(COMPACTION).unwrap(),
+            errors: Regex::new(ERRORS).unwrap(),
+            retries: Regex::new(RETRIES).unwrap(),
             code_blocks: Regex::new(r"```[\s\S]*?```").unwrap(),
             exchanges: Regex::new(r"^(Human:|Assistant:)").unwrap(),
             questions: Regex::new(r"\?").unwrap(),
+            tools: Regex::new(r"(?m)^\s*[⏺●]\s*([A-Z][A-Za-z]+)\(").unwrap(),
+            weights: Weights::default(),
+            custom: Vec::new(),
+            sentiment: None,
+        }
+    }
+
+    /// Built-in patterns adjusted by `patterns.toml` in the logs directory, when there is one.
+    pub fn load(logs_dir: &Path) -> Result<Self> {
+        let path = logs_dir.join(PATTERNS_FILE);
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read pattern definitions: {}", path.display()))?;
+        Self::from_toml(&content).with_context(|| format!("Invalid pattern definitions in {}", path.display()))
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let file: PatternsFile = toml::from_str(content).context("Failed to parse TOML")?;
+        let mut patterns = Self::new();
+
+        for (name, def) in file.categories {
+            let builtin = match name.as_str() {
+                "enthusiasm" => Some((&mut patterns.enthusiasm, &mut patterns.weights.enthusiasm, ENTHUSIASM)),
+                "confusion" => Some((&mut patterns.confusion, &mut patterns.weights.confusion, CONFUSION)),
+                "compaction" => Some((&mut patterns.compaction, &mut patterns.weights.compaction, COMPACTION)),
+                "errors" => Some((&mut patterns.errors, &mut patterns.weights.errors, ERRORS)),
+                "retries" => Some((&mut patterns.retries, &mut patterns.weights.retries, RETRIES)),
+                _ => None,
+            };
+
+            match builtin {
+                Some((regex, weight, default)) => {
+                    if def.points.is_some() || def.score.is_some() {
+                        anyhow::bail!("category '{}': `points` and `score` are only for custom categories; use `weight`", name);
+                    }
+                    if def.replace && def.patterns.is_empty() {
+                        anyhow::bail!("category '{}': `replace = true` needs at least one pattern", name);
+                    }
+                    *regex = combine(&name, (!def.replace).then_some(default), &def.patterns)?;
+                    if let Some(value) = def.weight {
+                        if !value.is_finite() || value < 0.0 {
+                            anyhow::bail!("category '{}': `weight` must be zero or more, got {}", name, value);
+                        }
+                        *weight = value;
+                    }
+                }
+                None => {
+                    if def.replace || def.weight.is_some() {
+                        anyhow::bail!("category '{}': `replace` and `weight` are only for the built-in categories; use `points`", name);
+                    }
+                    if def.patterns.is_empty() {
+                        anyhow::bail!("category '{}': needs at least one pattern", name);
+                    }
+                    let points = def.points.with_context(|| {
+                        format!("category '{}': missing `points` (score change per match, negative for a penalty)", name)
+                    })?;
+                    patterns.custom.push(CustomCategory {
+                        regex: combine(&name, None, &def.patterns)?,
+                        name,
+                        points,
+                        score: def.score.unwrap_or_default(),
+                    });
+                }
+            }
         }
+        Ok(patterns)
+    }
+
+    pub fn weights(&self) -> Weights {
+        self.weights
+    }
+
+    pub fn custom_categories(&self) -> &[CustomCategory] {
+        &self.custom
+    }
+
+    pub fn sentiment(&self) -> Option<&Sentiment> {
+        self.sentiment.as_ref()
+    }
+
+    /// Enthusiasm and confusion by the patterns alone, whatever backend is configured.
+    pub fn pattern_sentiment(&self, content: &str) -> RawSentiment {
+        RawSentiment {
+            enthusiasm: self.count_matches(&self.enthusiasm, content) as f64,
+            confusion: self.count_matches(&self.confusion, content) as f64,
+        }
+    }
+
+    fn sentiment_markers(&self, content: &str) -> (usize, usize) {
+        if let Some(sentiment) = &self.sentiment {
+            match sentiment.markers(content) {
+                Ok(markers) => return markers,
+                Err(e) => eprintln!("Warning: {:#}; counting enthusiasm and confusion with the built-in patterns", e),
+            }
+        }
+        (self.count_matches(&self.enthusiasm, content), self.count_matches(&self.confusion, content))
     }
 
     pub fn analyze_content(&self, content: &str) -> AnalysisMetrics {
+        let content = &*conversation::normalize(content);
+        let (enthusiasm_markers, confusion_markers) = self.sentiment_markers(content);
         AnalysisMetrics {
             exchanges: self.count_exchanges(content),
             code_blocks: self.count_code_blocks(content),
+            file_edits: edits::parse_edits(content).len(),
             questions_asked: self.count_questions(content),
-            enthusiasm_markers: self.count_matches(&self.enthusiasm, content),
-            confusion_markers: self.count_matches(&self.confusion, content),
+            enthusiasm_markers,
+            confusion_markers,
             compaction_indicators: self.count_matches(&self.compaction, content),
+            error_events: self.count_matches(&self.errors, content),
+            retry_events: self.count_matches(&self.retries, content),
+            custom_markers: self
+                .custom
+                .iter()
+                .map(|category| (category.name.clone(), self.count_matches(&category.regex, content)))
+                .collect(),
+            tool_usage: self.count_tools(content),
+            ..AnalysisMetrics::default()
+        }
+    }
+
+    fn count_tools(&self, content: &str) -> BTreeMap<String, usize> {
+        let mut tools = BTreeMap::new();
+        for captures in self.tools.captures_iter(content) {
+            *tools.entry(captures[1].to_string()).or_default() += 1;
         }
+        tools
     }
 
     fn count_matches(&self, regex: &Regex, content: &str) -> usize {
@@ -60,7 +263,7 @@ impl ConversationPatterns {
             }
             
             if !in_code_block {
-                question_count += line.matches('?').count();
+                question_count += self.questions.find_iter(line).count();
             }
         }
         
@@ -74,6 +277,16 @@ impl Default for ConversationPatterns {
     }
 }
 
+/// One regex matching the built-in pattern, if kept, or any user pattern; names the user pattern that fails to compile.
+fn combine(category: &str, builtin: Option<&str>, user: &[String]) -> Result<Regex> {
+    for (i, source) in user.iter().enumerate() {
+        Regex::new(source).with_context(|| format!("category '{}': pattern {} ('{}') is not a valid regex", category, i + 1, source))?;
+    }
+    let alternation: Vec<String> =
+        builtin.into_iter().chain(user.iter().map(String::as_str)).map(|source| format!("(?:{})", source)).collect();
+    Regex::new(&alternation.join("|")).with_context(|| format!("category '{}': patterns could not be combined", category))
+}
+
 // Global instance using OnceLock for thread-safe lazy initialization
 static PATTERNS: OnceLock<ConversationPatterns> = OnceLock::new();
 
@@ -81,6 +294,17 @@ pub fn get_patterns() -> &'static ConversationPatterns {
     PATTERNS.get_or_init(ConversationPatterns::new)
 }
 
+/// Install the user's pattern definitions and sentiment backend; call before any analysis.
+///
+/// The built-in patterns stay in place when loading fails.
+pub fn init(logs_dir: &Path, sentiment: &SentimentConfig) -> Result<()> {
+    let mut patterns = ConversationPatterns::load(logs_dir)?;
+    patterns.sentiment = sentiment::load(sentiment, logs_dir)?;
+    // Already initialized means analysis ran first; keep using what it saw
+    let _ = PATTERNS.set(patterns);
+    Ok(())
+}
+
 // Specialized pattern analysis functions
 pub fn analyze_session_quality(content: &str) -> SessionQuality {
     let patterns = get_patterns();
@@ -89,7 +313,7 @@ pub fn analyze_session_quality(content: &str) -> SessionQuality {
     SessionQuality::from_metrics(&metrics)
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionQuality {
     pub engagement_score: f64,
     pub clarity_score: f64,
@@ -99,9 +323,19 @@ pub struct SessionQuality {
 
 impl SessionQuality {
     pub fn from_metrics(metrics: &AnalysisMetrics) -> Self {
-        let engagement_score = Self::calculate_engagement_score(metrics);
-        let clarity_score = Self::calculate_clarity_score(metrics);
-        let productivity_score = Self::calculate_productivity_score(metrics);
+        Self::score(metrics, get_patterns())
+    }
+
+    /// Scores for `metrics` under `patterns`' weights and custom categories rather than the
+    /// installed ones, so the formulas can be checked against any configuration.
+    ///
+    /// Each score stays within 0-100. With the weights `patterns.toml` allows (zero or more),
+    /// more enthusiasm never lowers engagement, more confusion never raises engagement or clarity,
+    /// more code blocks or compaction never lower productivity, and more errors or retries never raise it.
+    pub fn score(metrics: &AnalysisMetrics, patterns: &ConversationPatterns) -> Self {
+        let engagement_score = Self::calculate_engagement_score(metrics, patterns);
+        let clarity_score = Self::calculate_clarity_score(metrics, patterns);
+        let productivity_score = Self::calculate_productivity_score(metrics, patterns);
         let overall_score = (engagement_score + clarity_score + productivity_score) / 3.0;
 
         Self {
@@ -112,33 +346,58 @@ impl SessionQuality {
         }
     }
 
-    fn calculate_engagement_score(metrics: &AnalysisMetrics) -> f64 {
+    fn calculate_engagement_score(metrics: &AnalysisMetrics, patterns: &ConversationPatterns) -> f64 {
+        let weights = patterns.weights();
         let base_score = 50.0;
-        let enthusiasm_bonus = (metrics.enthusiasm_markers as f64 * 10.0).min(30.0);
-        let confusion_penalty = (metrics.confusion_markers as f64 * 5.0).min(20.0);
+        let enthusiasm_bonus = (metrics.enthusiasm_markers as f64 * 10.0).min(30.0) * weights.enthusiasm;
+        let confusion_penalty = (metrics.confusion_markers as f64 * 5.0).min(20.0) * weights.confusion;
         let exchange_bonus = ((metrics.exchanges as f64 / 10.0) * 20.0).min(20.0);
 
-        (base_score + enthusiasm_bonus + exchange_bonus - confusion_penalty).clamp(0.0, 100.0)
+        (base_score + enthusiasm_bonus + exchange_bonus - confusion_penalty
+            + Self::custom_adjustment(metrics, patterns, ScoreKind::Engagement))
+            .clamp(0.0, 100.0)
     }
 
-    fn calculate_clarity_score(metrics: &AnalysisMetrics) -> f64 {
+    fn calculate_clarity_score(metrics: &AnalysisMetrics, patterns: &ConversationPatterns) -> f64 {
+        let weights = patterns.weights();
         let base_score = 70.0;
-        let confusion_penalty = (metrics.confusion_markers as f64 * 10.0).min(40.0);
+        let confusion_penalty = (metrics.confusion_markers as f64 * 10.0).min(40.0) * weights.confusion;
         let question_penalty = if metrics.questions_asked > metrics.exchanges {
             ((metrics.questions_asked - metrics.exchanges) as f64 * 2.0).min(20.0)
         } else {
             0.0
         };
 
-        (base_score - confusion_penalty - question_penalty).clamp(0.0, 100.0)
+        (base_score - confusion_penalty - question_penalty + Self::custom_adjustment(metrics, patterns, ScoreKind::Clarity))
+            .clamp(0.0, 100.0)
     }
 
-    fn calculate_productivity_score(metrics: &AnalysisMetrics) -> f64 {
+    fn calculate_productivity_score(metrics: &AnalysisMetrics, patterns: &ConversationPatterns) -> f64 {
+        let weights = patterns.weights();
         let base_score = 40.0;
-        let code_bonus = (metrics.code_blocks as f64 * 15.0).min(40.0);
-        let compaction_bonus = (metrics.compaction_indicators as f64 * 5.0).min(20.0);
+        // Real file edits show output better than fences, which may just quote existing code;
+        // logs captured without the edit tools still fall back to code blocks
+        let produced = if metrics.file_edits > 0 { metrics.file_edits } else { metrics.code_blocks };
+        let code_bonus = (produced as f64 * 15.0).min(40.0);
+        let compaction_bonus = (metrics.compaction_indicators as f64 * 5.0).min(20.0) * weights.compaction;
+        // Friction: output that failed, and turns spent undoing it
+        let error_penalty = (metrics.error_events as f64 * 3.0).min(15.0) * weights.errors;
+        let retry_penalty = (metrics.retry_events as f64 * 5.0).min(15.0) * weights.retries;
+
+        (base_score + code_bonus + compaction_bonus - error_penalty - retry_penalty
+            + Self::custom_adjustment(metrics, patterns, ScoreKind::Productivity))
+            .clamp(0.0, 100.0)
+    }
 
-        (base_score + code_bonus + compaction_bonus).clamp(0.0, 100.0)
+    /// Points from the custom categories that feed `score`, capped either way.
+    fn custom_adjustment(metrics: &AnalysisMetrics, patterns: &ConversationPatterns, score: ScoreKind) -> f64 {
+        patterns
+            .custom_categories()
+            .iter()
+            .filter(|category| category.score == score)
+            .map(|category| metrics.custom_markers.get(&category.name).copied().unwrap_or(0) as f64 * category.points)
+            .sum::<f64>()
+            .clamp(-CUSTOM_SCORE_CAP, CUSTOM_SCORE_CAP)
     }
 }
 
@@ -164,4 +423,161 @@ Assistant: Sure\! This code creates a simple Hello World program.
         assert_eq!(metrics.code_blocks, 1);
         assert!(metrics.enthusiasm_markers > 0);
     }
+
+    #[test]
+    fn counts_errors_and_retries() {
+        let content = "Human: run it\n\nAssistant: Building.\nerror[E0308]: mismatched types\nI apologize, let me fix that.\n\
+                       test result: FAILED. 3 passed; 1 failed\nSorry, no error here: it was the config.\n";
+        let metrics = ConversationPatterns::new().analyze_content(content);
+        assert_eq!(metrics.error_events, 3);
+        assert_eq!(metrics.retry_events, 2);
+
+        let calm = AnalysisMetrics { error_events: 0, retry_events: 0, ..metrics.clone() };
+        assert!(SessionQuality::from_metrics(&metrics).productivity_score < SessionQuality::from_metrics(&calm).productivity_score);
+    }
+
+    #[test]
+    fn counts_tool_calls_by_type() {
+        let content = "Human: Run the tests\n\n⏺ Bash(cargo test)\n  ⎿  ok\n\n⏺ Read(src/lib.rs)\n\n\
+                       ● Update(src/lib.rs)\n\n⏺ Bash(cargo test)\n\n⏺ The tests pass (all of them).\n";
+        let metrics = ConversationPatterns::new().analyze_content(content);
+
+        assert_eq!(metrics.tool_usage.len(), 3);
+        assert_eq!(metrics.tool_usage["Bash"], 2);
+        let distribution = metrics.tool_distribution();
+        assert_eq!(distribution[0], ("Bash", 2, 50.0));
+        assert_eq!(distribution[1].0, "Read");
+    }
+
+    #[test]
+    fn user_patterns_extend_replace_and_add_categories() {
+        let patterns = ConversationPatterns::from_toml(r#"
+[categories.enthusiasm]
+patterns = ["(?i)genial", "(?i)super"]
+
+[categories.confusion]
+patterns = ["(?i)no entiendo"]
+replace = true
+weight = 0.5
+
+[categories.frustration]
+patterns = ['(?i)still broken', '(?i)again\?']
+points = -5.0
+score = "clarity"
+"#).unwrap();
+
+        let metrics = patterns.analyze_content("Great, genial! I'm confused. No entiendo. Still broken again?");
+        assert_eq!(metrics.enthusiasm_markers, 2);
+        assert_eq!(metrics.confusion_markers, 1);
+        assert_eq!(metrics.custom_markers["frustration"], 2);
+        assert_eq!(patterns.weights().confusion, 0.5);
+
+        let error = ConversationPatterns::from_toml("[categories.enthusiasm]\npatterns = [\"(unclosed\"]\n").err().unwrap();
+        assert!(format!("{:#}", error).contains("category 'enthusiasm': pattern 1 ('(unclosed')"));
+        assert!(ConversationPatterns::from_toml("[categories.rage]\npatterns = [\"argh\"]\n").is_err());
+    }
+
+    mod quality_invariants {
+        use super::*;
+        use proptest::prelude::*;
+
+        /// `patterns.toml` with arbitrary allowed weights and a custom category feeding each score.
+        fn patterns_file() -> impl Strategy<Value = String> {
+            (0.0..3.0f64, 0.0..3.0f64, 0.0..3.0f64, prop::array::uniform3(-40.0..40.0f64)).prop_map(
+                |(enthusiasm, confusion, compaction, points)| {
+                    let mut toml = format!(
+                        "[categories.enthusiasm]\nweight = {enthusiasm}\n\
+                         [categories.confusion]\nweight = {confusion}\n\
+                         [categories.compaction]\nweight = {compaction}\n"
+                    );
+                    for (score, points) in ["engagement", "clarity", "productivity"].iter().zip(points) {
+                        toml.push_str(&format!(
+                            "[categories.{score}_custom]\npatterns = [\"{score}\"]\npoints = {points}\nscore = \"{score}\"\n"
+                        ));
+                    }
+                    toml
+                },
+            )
+        }
+
+        fn metrics() -> impl Strategy<Value = AnalysisMetrics> {
+            (prop::array::uniform7(0..60usize), prop::array::uniform3(0..20usize)).prop_map(|(counts, custom)| {
+                let [exchanges, code_blocks, file_edits, questions_asked, enthusiasm_markers, confusion_markers, compaction_indicators] =
+                    counts;
+                AnalysisMetrics {
+                    exchanges,
+                    code_blocks,
+                    file_edits,
+                    questions_asked,
+                    enthusiasm_markers,
+                    confusion_markers,
+                    compaction_indicators,
+                    custom_markers: ["engagement", "clarity", "productivity"]
+                        .iter()
+                        .map(|score| format!("{score}_custom"))
+                        .zip(custom)
+                        .collect(),
+                    ..AnalysisMetrics::default()
+                }
+            })
+        }
+
+        proptest! {
+            // Every case compiles a patterns file, so keep the run short
+            #![proptest_config(ProptestConfig::with_cases(64))]
+
+            #[test]
+            fn scores_stay_within_bounds(metrics in metrics(), file in patterns_file()) {
+                let patterns = ConversationPatterns::from_toml(&file).unwrap();
+                let quality = SessionQuality::score(&metrics, &patterns);
+                for score in [quality.engagement_score, quality.clarity_score, quality.productivity_score, quality.overall_score] {
+                    prop_assert!((0.0..=100.0).contains(&score), "score {} out of range", score);
+                }
+            }
+
+            #[test]
+            fn more_enthusiasm_never_lowers_engagement(metrics in metrics(), file in patterns_file(), extra in 1..20usize) {
+                let patterns = ConversationPatterns::from_toml(&file).unwrap();
+                let before = SessionQuality::score(&metrics, &patterns);
+                let more = AnalysisMetrics { enthusiasm_markers: metrics.enthusiasm_markers + extra, ..metrics.clone() };
+                let after = SessionQuality::score(&more, &patterns);
+                prop_assert!(after.engagement_score >= before.engagement_score);
+                prop_assert!(after.overall_score >= before.overall_score);
+            }
+
+            #[test]
+            fn more_confusion_never_helps(metrics in metrics(), file in patterns_file(), extra in 1..20usize) {
+                let patterns = ConversationPatterns::from_toml(&file).unwrap();
+                let before = SessionQuality::score(&metrics, &patterns);
+                let more = AnalysisMetrics { confusion_markers: metrics.confusion_markers + extra, ..metrics.clone() };
+                let after = SessionQuality::score(&more, &patterns);
+                prop_assert!(after.engagement_score <= before.engagement_score);
+                prop_assert!(after.clarity_score <= before.clarity_score);
+            }
+
+            #[test]
+            fn more_output_never_lowers_productivity(metrics in metrics(), file in patterns_file(), extra in 1..20usize) {
+                let patterns = ConversationPatterns::from_toml(&file).unwrap();
+                let before = SessionQuality::score(&metrics, &patterns);
+                let more = AnalysisMetrics {
+                    code_blocks: metrics.code_blocks + extra,
+                    compaction_indicators: metrics.compaction_indicators + extra,
+                    ..metrics.clone()
+                };
+                prop_assert!(SessionQuality::score(&more, &patterns).productivity_score >= before.productivity_score);
+            }
+
+            #[test]
+            fn more_friction_never_raises_productivity(metrics in metrics(), file in patterns_file(), extra in 1..20usize) {
+                let patterns = ConversationPatterns::from_toml(&file).unwrap();
+                let before = SessionQuality::score(&metrics, &patterns);
+                let more = AnalysisMetrics {
+                    error_events: metrics.error_events + extra,
+                    retry_events: metrics.retry_events + extra,
+                    ..metrics.clone()
+                };
+                prop_assert!(SessionQuality::score(&more, &patterns).productivity_score <= before.productivity_score);
+            }
+        }
+    }
 }
@@ -0,0 +1,180 @@
+use crate::analyzer::SessionAnalyzer;
+use crate::intervals::{self, Interval};
+use crate::logger::ClaudeLogger;
+use crate::patterns::{analyze_session_quality, get_patterns};
+use crate::session::{Phase, SessionsMetadata};
+use crate::timezone::DisplayZone;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Start a phase now, ending the running one first. Returns the new phase and the one it closed.
+pub fn start(logger: &mut ClaudeLogger, name: &str) -> Result<(Phase, Option<Phase>)> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(anyhow::anyhow!("Phase name cannot be empty"));
+    }
+
+    let now = Utc::now();
+    let phases = &mut logger.metadata_mut().phases;
+    let ended = phases.last_mut().filter(|phase| phase.ended_at.is_none()).map(|phase| {
+        phase.ended_at = Some(now);
+        phase.clone()
+    });
+
+    let phase = Phase {
+        name: name.to_string(),
+        started_at: now,
+        ended_at: None,
+    };
+    phases.push(phase.clone());
+
+    logger.save_metadata()?;
+    Ok((phase, ended))
+}
+
+/// End the running phase.
+pub fn end(logger: &mut ClaudeLogger) -> Result<Phase> {
+    let phase = logger
+        .metadata_mut()
+        .phases
+        .last_mut()
+        .filter(|phase| phase.ended_at.is_none())
+        .ok_or_else(|| anyhow::anyhow!("No phase is running"))?;
+    phase.ended_at = Some(Utc::now());
+    let phase = phase.clone();
+
+    logger.save_metadata()?;
+    Ok(phase)
+}
+
+/// The phase a timestamp falls in, if any.
+pub fn phase_at(metadata: &SessionsMetadata, timestamp: DateTime<Utc>) -> Option<&Phase> {
+    metadata.phases.iter().rev().find(|phase| phase.contains(timestamp))
+}
+
+/// Phases compared at once from which intervals are plotted instead of phase-over-phase changes.
+const PLOTTED_PHASES: usize = 3;
+
+/// Width in characters of the interval plots.
+const PLOT_WIDTH: usize = 41;
+
+/// Per-phase averages with their confidence intervals, in phase order.
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseSummary {
+    pub phase: Phase,
+    pub sessions: usize,
+    pub overall: Option<Interval>,
+    pub exchanges: Option<Interval>,
+    pub confusion_markers: Option<Interval>,
+    pub minutes: Option<Interval>,
+}
+
+impl PhaseSummary {
+    fn metrics(&self) -> [(&'static str, Option<Interval>); 4] {
+        [
+            ("Overall quality", self.overall),
+            ("Exchanges", self.exchanges),
+            ("Confusion markers", self.confusion_markers),
+            ("Duration (min)", self.minutes),
+        ]
+    }
+}
+
+pub fn compare(analyzer: &SessionAnalyzer) -> Vec<PhaseSummary> {
+    analyzer
+        .metadata()
+        .phases
+        .iter()
+        .map(|phase| {
+            let mut overall = Vec::new();
+            let mut exchanges = Vec::new();
+            let mut confusion = Vec::new();
+            let mut minutes = Vec::new();
+
+            for session in analyzer.metadata().sessions.values().filter(|session| phase.contains(session.timestamp)) {
+                minutes.extend(session.duration().map(|duration| duration.num_minutes() as f64));
+                let Ok(content) = analyzer.read_session_log(session) else {
+                    continue;
+                };
+                let metrics = get_patterns().analyze_content(&content);
+                overall.push(analyze_session_quality(&content).overall_score);
+                exchanges.push(metrics.exchanges as f64);
+                confusion.push(metrics.confusion_markers as f64);
+            }
+
+            PhaseSummary {
+                phase: phase.clone(),
+                sessions: overall.len().max(minutes.len()),
+                overall: intervals::bootstrap(&overall),
+                exchanges: intervals::bootstrap(&exchanges),
+                confusion_markers: intervals::bootstrap(&confusion),
+                minutes: intervals::bootstrap(&minutes),
+            }
+        })
+        .collect()
+}
+
+/// Each metric with the phases that have a value for it, as plot arms.
+pub fn interval_panels(summaries: &[PhaseSummary]) -> Vec<(&'static str, Vec<(String, Interval)>)> {
+    let Some(first) = summaries.first() else {
+        return Vec::new();
+    };
+    (0..first.metrics().len())
+        .map(|metric| {
+            let arms = summaries
+                .iter()
+                .filter_map(|summary| Some((summary.phase.name.clone(), summary.metrics()[metric].1?)))
+                .collect();
+            (first.metrics()[metric].0, arms)
+        })
+        .collect()
+}
+
+pub fn print_list(phases: &[Phase], zone: DisplayZone) {
+    if phases.is_empty() {
+        println!("No phases recorded. Start one with: claude-logger phase start \"name\"");
+        return;
+    }
+
+    for phase in phases {
+        let ended = phase
+            .ended_at
+            .map_or_else(|| "running".to_string(), |ended_at| zone.format(ended_at, "%Y-%m-%d %H:%M"));
+        println!("{} -> {} | {}", zone.format(phase.started_at, "%Y-%m-%d %H:%M"), ended, phase.name);
+    }
+}
+
+/// Print each phase with its change from the phase before it or, with three phases or more,
+/// each metric's per-phase intervals.
+pub fn print_comparison(summaries: &[PhaseSummary]) {
+    if summaries.is_empty() {
+        println!("No phases recorded.");
+        return;
+    }
+    if summaries.len() >= PLOTTED_PHASES {
+        for (metric, arms) in interval_panels(summaries) {
+            if !arms.is_empty() {
+                println!("\n{} (mean, 95% CI)", metric);
+                print!("{}", intervals::ascii_plot(&arms, PLOT_WIDTH));
+            }
+        }
+        return;
+    }
+
+    let mut previous: Option<&PhaseSummary> = None;
+    for summary in summaries {
+        println!("\n{} ({} sessions)", summary.phase.name, summary.sessions);
+        let before = previous.map(PhaseSummary::metrics);
+        for (index, (label, value)) in summary.metrics().into_iter().enumerate() {
+            let Some(value) = value.map(|interval| interval.mean) else {
+                continue;
+            };
+            match before.and_then(|before| before[index].1) {
+                Some(before) => println!("  {:<18} {:>7.1} ({:+.1} vs previous phase)", label, value, value - before.mean),
+                None => println!("  {:<18} {:>7.1}", label, value),
+            }
+        }
+        previous = Some(summary);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::encryption;
+use crate::locale;
+use crate::session::SessionMetadata;
+use crate::timezone::DisplayZone;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The project a directory belongs to: its `origin` remote's repository name, so clones
+/// in differently named directories share one project, else the directory's own name.
+pub fn project_name(project_dir: &Path) -> String {
+    remote_url(project_dir)
+        .as_deref()
+        .and_then(repo_name_from_url)
+        .or_else(|| project_dir.file_name().and_then(|name| name.to_str()).map(str::to_string))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn remote_url(project_dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["remote", "get-url", "origin"])
+        .current_dir(project_dir)
+        .output()
+        .ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `repo` from `git@host:owner/repo.git`, `https://host/owner/repo` and the like.
+fn repo_name_from_url(url: &str) -> Option<String> {
+    let path = url.trim_end_matches('/');
+    let name = path.rsplit(['/', ':']).next()?;
+    let name = name.strip_suffix(".git").unwrap_or(name);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// A directory name for a project's logs: anything but letters, digits, `.`, `_` and `-`
+/// becomes `-`, and leading dots are dropped so the directory isn't hidden.
+pub fn directory_name(project: &str) -> String {
+    let name: String = project
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '.' | '_' | '-') { c } else { '-' })
+        .collect();
+    let name = name.trim_start_matches('.');
+    if name.is_empty() { "unknown".to_string() } else { name.to_string() }
+}
+
+/// Session logs in the logs directory and its project subdirectories.
+///
+/// Includes logs compressed by `archive`. Cleaned copies are skipped; hidden directories such as `.git` are not searched.
+pub fn log_files(logs_dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![logs_dir.to_path_buf()];
+    if let Ok(entries) = fs::read_dir(logs_dir) {
+        dirs.extend(
+            entries
+                .flatten()
+                .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+                .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+                .map(|entry| entry.path()),
+        );
+    }
+
+    dirs.iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    (name.ends_with(".log") || name.ends_with(".log.gz")) && !name.ends_with(".clean.log")
+                })
+        })
+        .collect()
+}
+
+/// The log of session `id`, wherever it was filed.
+pub fn find_log(logs_dir: &Path, id: &str) -> Option<PathBuf> {
+    let file_names = [format!("{}.log", id), format!("{}.log.gz", id)];
+    log_files(logs_dir).into_iter().find(|path| {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| file_names.iter().any(|file_name| file_name == name))
+    })
+}
+
+/// Whether `path` names a log compressed by `archive`.
+pub fn is_compressed(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "gz")
+}
+
+/// A session log as captured, decompressed if `archive` compressed it and decrypted if it
+/// was encrypted.
+pub fn read_log(path: &Path) -> Result<Vec<u8>> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read log file: {}", path.display()))?;
+    decode_log(path, bytes)
+}
+
+/// A log, raw or cleaned, as text.
+pub fn read_text(path: &Path) -> Result<String> {
+    Ok(String::from_utf8_lossy(&read_log(path)?).into_owned())
+}
+
+/// A session log as stored: decompressed, but still encrypted if it was, for copying elsewhere.
+pub fn read_stored_log(path: &Path) -> Result<Vec<u8>> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read log file: {}", path.display()))?;
+    decompress(path, bytes)
+}
+
+/// The contents of the log at `path`, however obtained, decompressed if the path is a compressed
+/// log's and decrypted if encrypted.
+pub fn decode_log(path: &Path, bytes: Vec<u8>) -> Result<Vec<u8>> {
+    encryption::open(decompress(path, bytes)?).map_err(|e| anyhow::anyhow!("Failed to read {}: {:#}", path.display(), e))
+}
+
+fn decompress(path: &Path, bytes: Vec<u8>) -> Result<Vec<u8>> {
+    if !is_compressed(path) {
+        return Ok(bytes);
+    }
+    let mut raw = Vec::new();
+    GzDecoder::new(bytes.as_slice())
+        .read_to_end(&mut raw)
+        .with_context(|| format!("Failed to decompress {}", path.display()))?;
+    Ok(raw)
+}
+
+/// Session count and logged time for one project.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProjectSummary {
+    pub project: String,
+    pub sessions: usize,
+    /// Sessions without an end time, which contribute no time
+    pub unfinished_sessions: usize,
+    pub minutes: i64,
+    pub first_session: Option<DateTime<Utc>>,
+    pub last_session: Option<DateTime<Utc>>,
+}
+
+/// One summary per project, most logged time first.
+pub fn summaries<'a>(sessions: impl IntoIterator<Item = &'a SessionMetadata>) -> Vec<ProjectSummary> {
+    let mut by_project: BTreeMap<&str, ProjectSummary> = BTreeMap::new();
+    for session in sessions {
+        let summary = by_project.entry(&session.project).or_insert_with(|| ProjectSummary {
+            project: session.project.clone(),
+            ..ProjectSummary::default()
+        });
+
+        summary.sessions += 1;
+        match session.duration() {
+            Some(duration) => summary.minutes += duration.num_minutes(),
+            None => summary.unfinished_sessions += 1,
+        }
+        summary.first_session = Some(summary.first_session.map_or(session.timestamp, |first| first.min(session.timestamp)));
+        summary.last_session = summary.last_session.max(Some(session.timestamp));
+    }
+
+    let mut summaries: Vec<ProjectSummary> = by_project.into_values().collect();
+    summaries.sort_by_key(|summary| std::cmp::Reverse(summary.minutes));
+    summaries
+}
+
+pub fn print(summaries: &[ProjectSummary], zone: DisplayZone) {
+    if summaries.is_empty() {
+        println!("No sessions found.");
+        return;
+    }
+
+    let locale = locale::current();
+    let width = summaries.iter().map(|summary| summary.project.len()).max().unwrap_or(0).max(7);
+    println!("{:<width$}  {:>8}  {:>8}  Last session", "Project", "Sessions", "Hours");
+    for summary in summaries {
+        println!(
+            "{:<width$}  {:>8}  {:>8}  {}",
+            summary.project,
+            summary.sessions,
+            locale.number(summary.minutes as f64 / 60.0, 1),
+            summary.last_session.map(|time| zone.format(time, "%Y-%m-%d")).unwrap_or_default(),
+        );
+    }
+
+    let unfinished: usize = summaries.iter().map(|summary| summary.unfinished_sessions).sum();
+    if unfinished > 0 {
+        println!("\nNote: {} session(s) have no end time and add no hours", unfinished);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_projects_from_remotes_and_directories() {
+        assert_eq!(repo_name_from_url("git@github.com:owner/api.git").as_deref(), Some("api"));
+        assert_eq!(repo_name_from_url("https://gitlab.com/group/sub/web-app/").as_deref(), Some("web-app"));
+        assert_eq!(repo_name_from_url("/srv/git/tools.git").as_deref(), Some("tools"));
+
+        assert_eq!(directory_name("my project/v2"), "my-project-v2");
+        assert_eq!(directory_name(".."), "unknown");
+
+        let dir = tempfile::tempdir().unwrap();
+        let project_dir = dir.path().join("widgets");
+        fs::create_dir(&project_dir).unwrap();
+        assert_eq!(project_name(&project_dir), "widgets");
+    }
+}
@@ -0,0 +1,244 @@
+use crate::hold;
+use crate::logger::ClaudeLogger;
+use crate::project;
+use crate::session::{SessionMetadata, SessionsMetadata};
+use crate::trash::{self, TrashEntry};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default)]
+pub struct ArchiveOutcome {
+    /// Sessions whose logs were compressed
+    pub archived: Vec<String>,
+    pub failed: Vec<(String, String)>,
+    /// Bytes the archived sessions' logs took before and after
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    /// The logs repository commit recording the change, if any of the logs were tracked
+    pub commit: Option<String>,
+}
+
+/// Whether `session` started before `cutoff`, has finished, isn't on hold, and its log is still
+/// uncompressed on disk.
+pub fn can_archive(session: &SessionMetadata, cutoff: DateTime<Utc>) -> bool {
+    session.timestamp < cutoff
+        && session.hold.is_none()
+        && (session.end_time.is_some() || session.duration_seconds.is_some())
+        && !project::is_compressed(&session.log_file)
+        && session.log_file.exists()
+}
+
+/// The sessions `archive` would compress for `cutoff`, oldest first.
+pub fn archivable(metadata: &SessionsMetadata, cutoff: DateTime<Utc>) -> Vec<&SessionMetadata> {
+    let mut sessions: Vec<&SessionMetadata> =
+        metadata.sessions.values().filter(|session| can_archive(session, cutoff)).collect();
+    sessions.sort_by_key(|session| session.timestamp);
+    sessions
+}
+
+/// Compress the given sessions' logs in place, save their metadata and commit the change.
+pub fn archive(logger: &mut ClaudeLogger, session_ids: &[String]) -> Result<ArchiveOutcome> {
+    let mut outcome = ArchiveOutcome::default();
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+
+    for session_id in session_ids {
+        let Some(session) = logger.metadata_mut().get_session_mut(session_id) else {
+            continue;
+        };
+        match compress_log(session) {
+            Ok(compressed) => {
+                outcome.bytes_before += compressed.bytes_before;
+                outcome.bytes_after += compressed.bytes_after;
+                removed.extend(compressed.removed);
+                added.push(session.log_file.clone());
+                outcome.archived.push(session_id.clone());
+            }
+            Err(e) => outcome.failed.push((session_id.clone(), format!("{:#}", e))),
+        }
+    }
+    if outcome.archived.is_empty() {
+        return Ok(outcome);
+    }
+
+    logger.save_metadata()?;
+    let removed: Vec<&Path> = removed.iter().map(PathBuf::as_path).collect();
+    let added: Vec<&Path> = added.iter().map(PathBuf::as_path).collect();
+    let message = format!("Archive {} session logs: {}", outcome.archived.len(), outcome.archived.join(", "));
+    outcome.commit = logger.git_repo().commit_changes(&removed, &added, &message)?;
+    if outcome.commit.is_some() {
+        logger.push_if_enabled();
+    }
+    Ok(outcome)
+}
+
+struct Compressed {
+    removed: Vec<PathBuf>,
+    bytes_before: u64,
+    bytes_after: u64,
+}
+
+/// Replace `session`'s log with `<id>.log.gz` and drop its cleaned copy, which is
+/// regenerated from the log whenever it's needed.
+fn compress_log(session: &mut SessionMetadata) -> Result<Compressed> {
+    let log_file = session.log_file.clone();
+    let mut archive_path = log_file.clone().into_os_string();
+    archive_path.push(".gz");
+    let archive_path = PathBuf::from(archive_path);
+
+    if let Err(e) = write_compressed(&log_file, &archive_path) {
+        let _ = fs::remove_file(&archive_path);
+        return Err(e);
+    }
+
+    let mut removed = vec![log_file];
+    removed.extend(session.clean_log_file.take());
+    let mut bytes_before = 0;
+    for file in &removed {
+        if let Ok(metadata) = fs::metadata(file) {
+            bytes_before += metadata.len();
+            fs::remove_file(file).with_context(|| format!("Failed to remove {}", file.display()))?;
+        }
+    }
+
+    let bytes_after = fs::metadata(&archive_path).map(|metadata| metadata.len()).unwrap_or(0);
+    session.log_file = archive_path;
+    Ok(Compressed { removed, bytes_before, bytes_after })
+}
+
+fn write_compressed(source: &Path, destination: &Path) -> Result<()> {
+    let mut input = File::open(source).with_context(|| format!("Failed to read log file: {}", source.display()))?;
+    let output = File::create(destination).with_context(|| format!("Failed to create {}", destination.display()))?;
+    let mut encoder = GzEncoder::new(output, Compression::best());
+    io::copy(&mut input, &mut encoder).with_context(|| format!("Failed to compress {}", source.display()))?;
+    encoder
+        .finish()
+        .and_then(|output| output.sync_all())
+        .with_context(|| format!("Failed to write {}", destination.display()))
+}
+
+pub fn print_outcome(outcome: &ArchiveOutcome) {
+    for (session_id, error) in &outcome.failed {
+        eprintln!("Warning: Failed to archive {}: {}", session_id, error);
+    }
+    if outcome.archived.is_empty() {
+        return;
+    }
+    println!(
+        "Compressed {} session logs ({} KB -> {} KB)",
+        outcome.archived.len(),
+        outcome.bytes_before / 1024,
+        outcome.bytes_after / 1024
+    );
+    if let Some(commit) = &outcome.commit {
+        println!("Recorded in commit {}", &commit[..commit.len().min(7)]);
+    }
+}
+
+/// Move `session_id`'s logs and metadata to the trash, committing the removal to the logs repository.
+///
+/// Sessions that resumed it are relinked to the session it resumed. Sessions deleted longer
+/// ago than `[trash] retention_days` are purged on the way.
+pub fn delete(logger: &mut ClaudeLogger, session_id: &str) -> Result<Option<String>> {
+    let session = logger
+        .get_session(session_id)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+    hold::ensure_released(&session, "delete")?;
+    trash::purge_expired(logger.logs_dir(), logger.config().trash.retention_days, Utc::now())?;
+
+    let mut files = vec![session.log_file.clone(), session.clean_log_path()];
+    files.extend(session.clean_log_file.clone());
+    files.sort();
+    files.dedup();
+    files.retain(|file| file.exists());
+    let relinked: Vec<String> = logger
+        .metadata()
+        .sessions
+        .values()
+        .filter(|other| other.parent_session_id.as_deref() == Some(session_id))
+        .map(|other| other.id.clone())
+        .collect();
+    let entry = TrashEntry {
+        deleted_at: Utc::now(),
+        session: session.clone(),
+        relinked,
+        files: files.clone(),
+    };
+    trash::put(logger.logs_dir(), &entry)?;
+
+    let metadata = logger.metadata_mut();
+    metadata.remove_session(session_id);
+    for other_id in &entry.relinked {
+        if let Some(other) = metadata.get_session_mut(other_id) {
+            other.parent_session_id = session.parent_session_id.clone();
+        }
+    }
+    logger.save_metadata()?;
+
+    let files: Vec<&Path> = files.iter().map(PathBuf::as_path).collect();
+    let commit =
+        logger.git_repo().commit_changes(&files, &[], &format!("Delete session {} | {}", session.id, session.project))?;
+    if commit.is_some() {
+        logger.push_if_enabled();
+    }
+    Ok(commit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::SessionAnalyzer;
+    use crate::config::Config;
+    use crate::synth::{generate, sessions};
+    use std::process::Command;
+
+    #[test]
+    fn archives_and_deletes_with_commits() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut logger = ClaudeLogger::new_with_config(dir.path(), Config::default()).unwrap();
+        for (key, value) in [("user.email", "test@example.com"), ("user.name", "Test")] {
+            Command::new("git").args(["config", key, value]).current_dir(dir.path()).status().unwrap();
+        }
+
+        let options = sessions(2, 5, "api");
+        let now = Utc::now();
+        let mut ids = Vec::new();
+        for generated in generate(&options, dir.path(), now - chrono::Duration::days(30)) {
+            fs::create_dir_all(generated.session.log_file.parent().unwrap()).unwrap();
+            fs::write(&generated.session.log_file, &generated.log).unwrap();
+            ids.push(generated.session.id.clone());
+            logger.complete_session(&generated.session).unwrap();
+        }
+        ids.sort();
+
+        let cutoff = now - chrono::Duration::days(7);
+        assert_eq!(archivable(logger.metadata(), cutoff).len(), 2);
+        let outcome = archive(&mut logger, &ids[..1]).unwrap();
+        assert_eq!(outcome.archived, [ids[0].clone()]);
+        assert!(outcome.commit.is_some());
+
+        let archived = logger.get_session(&ids[0]).unwrap().clone();
+        assert!(project::is_compressed(&archived.log_file) && archived.clean_log_file.is_none());
+        assert_eq!(project::find_log(logger.logs_dir(), &ids[0]), Some(archived.log_file.clone()));
+        let analyzer = SessionAnalyzer::new_with_dir(dir.path()).unwrap();
+        assert!(!analyzer.read_session_log(&archived).unwrap().is_empty());
+        assert_eq!(archivable(logger.metadata(), cutoff).len(), 1);
+
+        logger.metadata_mut().get_session_mut(&ids[1]).unwrap().parent_session_id = Some(ids[0].clone());
+        assert!(delete(&mut logger, &ids[0]).unwrap().is_some());
+        assert!(logger.get_session(&ids[0]).is_none() && !archived.log_file.exists());
+        assert_eq!(logger.get_session(&ids[1]).unwrap().parent_session_id, None);
+        assert!(delete(&mut logger, &ids[0]).is_err());
+
+        assert_eq!(trash::list(logger.logs_dir()).unwrap()[0].session.id, ids[0]);
+        trash::restore(&mut logger, &ids[0]).unwrap();
+        assert!(archived.log_file.exists() && trash::list(logger.logs_dir()).unwrap().is_empty());
+        assert_eq!(logger.get_session(&ids[1]).unwrap().parent_session_id.as_deref(), Some(ids[0].as_str()));
+    }
+}
@@ -0,0 +1,325 @@
+use anyhow::{Context, Result};
+use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtyPair, PtySize};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How long output still arriving after the command exits is given to drain.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How a session's output was captured.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureMode {
+    /// On a pseudo-terminal, exactly as claude drew it
+    #[default]
+    Pty,
+    /// Without one, e.g. in CI or a container with no terminal devices: stdout and stderr piped
+    /// through the logger. The content is kept, but claude sees no terminal and draws no UI.
+    Pipe,
+}
+
+impl std::fmt::Display for CaptureMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaptureMode::Pty => write!(f, "pty"),
+            CaptureMode::Pipe => write!(f, "pipe"),
+        }
+    }
+}
+
+/// A command running on a pseudo-terminal, its output shown on ours and recorded to a log,
+/// our keystrokes forwarded to it. Works the same on Linux, macOS and Windows. Where no
+/// pseudo-terminal can be opened, the command's output is piped through instead.
+pub struct Capture {
+    process: Process,
+    output: Vec<JoinHandle<()>>,
+    stop_input: Arc<AtomicBool>,
+}
+
+enum Process {
+    Pty {
+        child: Box<dyn Child + Send + Sync>,
+        master: Box<dyn MasterPty + Send>,
+        size: PtySize,
+        raw_mode: Option<RawMode>,
+    },
+    Pipe(std::process::Child),
+}
+
+impl Capture {
+    /// Start `program` in the current directory, logging everything it prints to `log_file`.
+    pub fn spawn(program: &str, args: &[String], log_file: &Path) -> Result<Self> {
+        let size = terminal_size();
+        match native_pty_system().openpty(size) {
+            Ok(pair) => Self::spawn_pty(program, args, pair, size, log_file),
+            Err(e) => {
+                eprintln!("Warning: No pseudo-terminal available ({}); capturing {}'s output through pipes", e, program);
+                Self::spawn_pipe(program, args, log_file)
+            }
+        }
+    }
+
+    fn spawn_pty(program: &str, args: &[String], pair: PtyPair, size: PtySize, log_file: &Path) -> Result<Self> {
+        let mut command = CommandBuilder::new(program);
+        command.args(args);
+        command.cwd(std::env::current_dir().context("Failed to get current working directory")?);
+        let child = pair
+            .slave
+            .spawn_command(command)
+            .map_err(|e| anyhow::anyhow!("Failed to start {}: {}", program, e))?;
+        // Only the child may hold the terminal open, so reads end once it exits
+        drop(pair.slave);
+
+        let log = open_log(log_file)?;
+        let reader = pair.master.try_clone_reader().map_err(|e| anyhow::anyhow!("Failed to read from the pseudo-terminal: {}", e))?;
+        let writer = pair.master.take_writer().map_err(|e| anyhow::anyhow!("Failed to write to the pseudo-terminal: {}", e))?;
+
+        // Keystrokes go to claude untouched, so our terminal must stop interpreting them
+        let raw_mode = if io::stdin().is_terminal() { Some(RawMode::enable()?) } else { None };
+        let stop_input = Arc::new(AtomicBool::new(false));
+        let input_stop = Arc::clone(&stop_input);
+        thread::spawn(move || forward_input(writer, &input_stop));
+        let output = thread::spawn(move || record_output(reader, io::stdout(), &log));
+
+        Ok(Self {
+            process: Process::Pty { child, master: pair.master, size, raw_mode },
+            output: vec![output],
+            stop_input,
+        })
+    }
+
+    /// Run `program` with our stdin and its stdout and stderr teed to ours and the log.
+    fn spawn_pipe(program: &str, args: &[String], log_file: &Path) -> Result<Self> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to start {}", program))?;
+
+        let log = open_log(log_file)?;
+        let stdout = child.stdout.take().context("Failed to read the command's output")?;
+        let stderr = child.stderr.take().context("Failed to read the command's errors")?;
+        let stderr_log = Arc::clone(&log);
+        let output = vec![
+            thread::spawn(move || record_output(Box::new(stdout), io::stdout(), &log)),
+            thread::spawn(move || record_output(Box::new(stderr), io::stderr(), &stderr_log)),
+        ];
+
+        Ok(Self { process: Process::Pipe(child), output, stop_input: Arc::new(AtomicBool::new(false)) })
+    }
+
+    pub fn mode(&self) -> CaptureMode {
+        match self.process {
+            Process::Pty { .. } => CaptureMode::Pty,
+            Process::Pipe(_) => CaptureMode::Pipe,
+        }
+    }
+
+    /// The exit code once the command has finished.
+    pub fn try_wait(&mut self) -> Result<Option<i32>> {
+        match &mut self.process {
+            Process::Pty { child, .. } => {
+                let status = child.try_wait().context("Failed to wait for claude")?;
+                Ok(status.map(|status| status.exit_code() as i32))
+            }
+            // Killed by a signal reads as 1, as it does on a pseudo-terminal
+            Process::Pipe(child) => {
+                let status = child.try_wait().context("Failed to wait for claude")?;
+                Ok(status.map(|status| status.code().unwrap_or(1)))
+            }
+        }
+    }
+
+    /// Pass a change in our terminal's size on to the command.
+    pub fn follow_resize(&mut self) {
+        let Process::Pty { master, size: current, .. } = &mut self.process else {
+            return;
+        };
+        let size = terminal_size();
+        if (size.rows, size.cols) != (current.rows, current.cols) && master.resize(size).is_ok() {
+            *current = size;
+        }
+    }
+
+    /// Hang up the command, as closing its terminal would.
+    pub fn hang_up(&mut self) -> Result<()> {
+        match &mut self.process {
+            Process::Pty { child, .. } => child.kill(),
+            Process::Pipe(child) => child.kill(),
+        }
+        .context("Failed to stop claude")
+    }
+
+    /// Wait for the command's last output to be logged and give the terminal back.
+    pub fn finish(mut self) {
+        self.stop_input.store(true, Ordering::Relaxed);
+        // A background process may keep the terminal open; don't wait on it forever
+        let deadline = Instant::now() + DRAIN_TIMEOUT;
+        for output in self.output.drain(..) {
+            while !output.is_finished() && Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(10));
+            }
+            if output.is_finished() {
+                let _ = output.join();
+            }
+        }
+        if let Process::Pty { raw_mode, .. } = &mut self.process {
+            raw_mode.take();
+        }
+    }
+}
+
+impl Drop for Capture {
+    fn drop(&mut self) {
+        self.stop_input.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Our terminal in raw mode, restored when dropped.
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> Result<Self> {
+        crossterm::terminal::enable_raw_mode().context("Failed to put the terminal in raw mode")?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = crossterm::terminal::disable_raw_mode();
+    }
+}
+
+fn open_log(log_file: &Path) -> Result<Arc<Mutex<File>>> {
+    let log = File::create(log_file).with_context(|| format!("Failed to create log file: {}", log_file.display()))?;
+    Ok(Arc::new(Mutex::new(log)))
+}
+
+fn terminal_size() -> PtySize {
+    let (cols, rows) = crossterm::terminal::size().unwrap_or((80, 24));
+    PtySize { rows, cols, pixel_width: 0, pixel_height: 0 }
+}
+
+/// Copy the command's output to our terminal and the log until the terminal or pipe closes.
+fn record_output(mut reader: Box<dyn Read + Send>, mut terminal: impl Write, log: &Mutex<File>) {
+    let mut buffer = [0u8; 8192];
+    loop {
+        match reader.read(&mut buffer) {
+            // Linux reports the closed terminal as an error rather than end of file
+            Ok(0) | Err(_) => break,
+            Ok(read) => {
+                let _ = terminal.write_all(&buffer[..read]);
+                let _ = terminal.flush();
+                let mut log = log.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                if let Err(e) = log.write_all(&buffer[..read]) {
+                    eprintln!("\r\nWarning: Failed to write session log: {}\r", e);
+                }
+            }
+        }
+    }
+    let _ = log.lock().map(|mut log| log.flush());
+}
+
+/// Forward our keystrokes to the command until `stop` is set.
+#[cfg(unix)]
+fn forward_input(mut writer: Box<dyn Write + Send>, stop: &AtomicBool) {
+    use std::os::fd::AsRawFd;
+
+    // Poll rather than block, so no keystroke meant for the prompts after the session is taken
+    let fd = io::stdin().as_raw_fd();
+    let mut buffer = [0u8; 1024];
+    while !stop.load(Ordering::Relaxed) {
+        let mut poll_fd = libc::pollfd { fd, events: libc::POLLIN, revents: 0 };
+        // SAFETY: one valid pollfd is passed with a matching count
+        let ready = unsafe { libc::poll(&mut poll_fd, 1, 50) };
+        if ready <= 0 || stop.load(Ordering::Relaxed) {
+            continue;
+        }
+        // SAFETY: reads at most the buffer's length into it; stdin stays open for the process
+        let read = unsafe { libc::read(fd, buffer.as_mut_ptr().cast(), buffer.len()) };
+        if read <= 0 {
+            break;
+        }
+        if writer.write_all(&buffer[..read as usize]).and_then(|_| writer.flush()).is_err() {
+            break;
+        }
+    }
+}
+
+/// Forward our keystrokes to the command until `stop` is set.
+///
+/// The console offers no way to wait for input with a timeout here, so the read blocks and
+/// the keystroke typed just as the session ends may be lost.
+#[cfg(not(unix))]
+fn forward_input(mut writer: Box<dyn Write + Send>, stop: &AtomicBool) {
+    let mut stdin = io::stdin();
+    let mut buffer = [0u8; 1024];
+    while !stop.load(Ordering::Relaxed) {
+        match stdin.read(&mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(read) => {
+                if stop.load(Ordering::Relaxed)
+                    || writer.write_all(&buffer[..read]).and_then(|_| writer.flush()).is_err()
+                {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn wait_for_exit(capture: &mut Capture) -> i32 {
+        loop {
+            if let Some(code) = capture.try_wait().unwrap() {
+                break code;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn records_output_and_exit_code_on_a_pty() {
+        let dir = tempfile::tempdir().unwrap();
+        let log_file = dir.path().join("session.log");
+        let mut capture =
+            Capture::spawn("sh", &["-c".to_string(), "printf 'hello from the pty'; exit 3".to_string()], &log_file).unwrap();
+
+        let code = wait_for_exit(&mut capture);
+        let capture_mode = capture.mode();
+        capture.finish();
+
+        assert_eq!(code, 3);
+        assert_eq!(capture_mode, CaptureMode::Pty);
+        assert!(std::fs::read_to_string(&log_file).unwrap().contains("hello from the pty"));
+    }
+
+    #[test]
+    fn interleaves_stdout_and_stderr_through_pipes() {
+        let dir = tempfile::tempdir().unwrap();
+        let log_file = dir.path().join("session.log");
+        // Pauses between writes, so each stream's reader has logged its line before the next
+        let script = "printf 'one\\n'; sleep 0.2; printf 'two\\n' >&2; sleep 0.2; printf 'three\\n'; exit 2";
+        let mut capture = Capture::spawn_pipe("sh", &["-c".to_string(), script.to_string()], &log_file).unwrap();
+        assert_eq!(capture.mode(), CaptureMode::Pipe);
+
+        let code = wait_for_exit(&mut capture);
+        capture.finish();
+
+        assert_eq!(code, 2);
+        assert_eq!(std::fs::read_to_string(&log_file).unwrap(), "one\ntwo\nthree\n");
+    }
+}
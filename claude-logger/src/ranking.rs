@@ -0,0 +1,88 @@
+use crate::patterns::SessionQuality;
+use serde::Serialize;
+
+/// Fewer earlier analyses than this and a percentile says more about chance than about the session.
+const MIN_HISTORY: usize = 5;
+
+/// Where one of a session's quality scores falls among the other analyzed sessions.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QualityRank {
+    /// `engagement`, `clarity`, `productivity` or `overall`
+    pub score: &'static str,
+    pub value: f64,
+    /// Share of the other sessions scoring lower, ties counting half, 0-100
+    pub percentile: u32,
+    /// Sessions compared against
+    pub out_of: usize,
+}
+
+impl QualityRank {
+    /// e.g. `91st percentile of your 214 sessions`.
+    pub fn describe(&self) -> String {
+        format!("{} percentile of your {} sessions", ordinal(self.percentile), self.out_of)
+    }
+}
+
+/// Rank each of `quality`'s scores against `history`, the cached quality of the other sessions
+/// (their last `reanalyze` snapshots). Empty when there is too little history to compare with.
+pub fn rank<'a>(quality: &SessionQuality, history: impl IntoIterator<Item = &'a SessionQuality>) -> Vec<QualityRank> {
+    let history: Vec<[(&str, f64); 4]> = history.into_iter().map(scores).collect();
+    if history.len() < MIN_HISTORY {
+        return Vec::new();
+    }
+
+    scores(quality)
+        .into_iter()
+        .enumerate()
+        .map(|(index, (score, value))| {
+            let below = history.iter().filter(|other| other[index].1 < value).count() as f64;
+            let tied = history.iter().filter(|other| other[index].1 == value).count() as f64;
+            let percentile = ((below + tied / 2.0) / history.len() as f64 * 100.0).round() as u32;
+            QualityRank { score, value, percentile, out_of: history.len() }
+        })
+        .collect()
+}
+
+fn scores(quality: &SessionQuality) -> [(&'static str, f64); 4] {
+    [
+        ("engagement", quality.engagement_score),
+        ("clarity", quality.clarity_score),
+        ("productivity", quality.productivity_score),
+        ("overall", quality.overall_score),
+    ]
+}
+
+/// `1st`, `22nd`, `13th`.
+fn ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quality(overall: f64) -> SessionQuality {
+        SessionQuality { engagement_score: 50.0, clarity_score: 50.0, productivity_score: overall, overall_score: overall }
+    }
+
+    #[test]
+    fn ranks_scores_against_earlier_sessions() {
+        let history: Vec<SessionQuality> = (1..=10).map(|score| quality(score as f64 * 10.0)).collect();
+        let ranks = rank(&quality(85.0), &history);
+        let overall = ranks.iter().find(|rank| rank.score == "overall").unwrap();
+        assert_eq!((overall.percentile, overall.out_of), (80, 10));
+        assert_eq!(overall.describe(), "80th percentile of your 10 sessions");
+        // Everyone ties on engagement, which puts the session in the middle
+        assert_eq!(ranks[0].percentile, 50);
+
+        assert!(rank(&quality(85.0), &history[..4]).is_empty());
+        assert_eq!([1, 2, 3, 11, 12, 13, 21, 91, 100].map(ordinal), ["1st", "2nd", "3rd", "11th", "12th", "13th", "21st", "91st", "100th"]);
+    }
+}
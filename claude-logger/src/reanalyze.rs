@@ -0,0 +1,156 @@
+use crate::analyzer::SessionAnalyzer;
+use crate::patterns::SessionQuality;
+use crate::session::{AnalysisSnapshot, SessionMetadata};
+
+/// Overall score moves smaller than this are rounding, not a change.
+const SCORE_TOLERANCE: f64 = 0.05;
+
+/// How many of the largest overall-score moves are listed.
+const TOP_MOVES: usize = 5;
+
+pub(crate) type Score = fn(&SessionQuality) -> f64;
+
+pub(crate) const SCORES: [(&str, Score); 4] = [
+    ("Engagement", |quality| quality.engagement_score),
+    ("Clarity", |quality| quality.clarity_score),
+    ("Productivity", |quality| quality.productivity_score),
+    ("Overall", |quality| quality.overall_score),
+];
+
+/// How fresh analysis under the current patterns compares with each session's previous snapshot.
+#[derive(Debug, Default)]
+pub struct Reanalysis {
+    /// Sessions with a new snapshot, ready to be saved
+    pub updated: Vec<SessionMetadata>,
+    /// Sessions analyzed for the first time, so with nothing to compare against
+    pub first_analysis: usize,
+    /// Sessions whose log couldn't be read, left untouched
+    pub unreadable: Vec<(String, String)>,
+    /// Previously analyzed sessions whose overall score moved
+    pub changed: usize,
+    /// Average score before and after, over the previously analyzed sessions
+    pub averages: Vec<(&'static str, f64, f64)>,
+    /// Session id with its overall score before and after, largest moves first
+    pub biggest_moves: Vec<(String, f64, f64)>,
+}
+
+impl Reanalysis {
+    fn compared(&self) -> usize {
+        self.updated.len() - self.first_analysis
+    }
+}
+
+/// Analyze `sessions` afresh, comparing each with its stored snapshot.
+pub fn reanalyze<'a>(analyzer: &SessionAnalyzer, sessions: impl IntoIterator<Item = &'a SessionMetadata>) -> Reanalysis {
+    let mut result = Reanalysis::default();
+    let mut pairs = Vec::new();
+
+    for session in sessions {
+        let content = match analyzer.read_session_log(session) {
+            Ok(content) => content,
+            Err(e) => {
+                result.unreadable.push((session.id.clone(), format!("{:#}", e)));
+                continue;
+            }
+        };
+        let mut session = session.clone();
+        let snapshot = SessionAnalyzer::snapshot(&session, &content);
+        match session.analysis.replace(snapshot.clone()) {
+            Some(previous) => pairs.push((session.id.clone(), previous, snapshot)),
+            None => result.first_analysis += 1,
+        }
+        result.updated.push(session);
+    }
+
+    compare(&mut result, &pairs);
+    result
+}
+
+fn compare(result: &mut Reanalysis, pairs: &[(String, AnalysisSnapshot, AnalysisSnapshot)]) {
+    if !pairs.is_empty() {
+        let count = pairs.len() as f64;
+        result.averages = SCORES
+            .iter()
+            .map(|(name, score)| {
+                let before = pairs.iter().map(|(_, before, _)| score(&before.quality)).sum::<f64>() / count;
+                let after = pairs.iter().map(|(_, _, after)| score(&after.quality)).sum::<f64>() / count;
+                (*name, before, after)
+            })
+            .collect();
+    }
+
+    let mut moves: Vec<(String, f64, f64)> = pairs
+        .iter()
+        .map(|(id, before, after)| (id.clone(), before.quality.overall_score, after.quality.overall_score))
+        .filter(|(_, before, after)| (after - before).abs() >= SCORE_TOLERANCE)
+        .collect();
+    result.changed = moves.len();
+    moves.sort_by(|a, b| (b.2 - b.1).abs().total_cmp(&(a.2 - a.1).abs()));
+    moves.truncate(TOP_MOVES);
+    result.biggest_moves = moves;
+}
+
+pub fn print(result: &Reanalysis) {
+    println!(
+        "Re-analyzed {} sessions ({} for the first time)",
+        result.updated.len(),
+        result.first_analysis
+    );
+    for (session_id, error) in &result.unreadable {
+        eprintln!("Warning: Skipped {}: {}", session_id, error);
+    }
+    if result.compared() == 0 {
+        return;
+    }
+
+    println!("Overall score changed for {} of {} previously analyzed sessions", result.changed, result.compared());
+    println!("\n{:<14} {:>7} {:>7} {:>7}", "Average", "Before", "After", "Change");
+    for (name, before, after) in &result.averages {
+        println!("{:<14} {:>7.1} {:>7.1} {:>+7.1}", name, before, after, after - before);
+    }
+
+    if !result.biggest_moves.is_empty() {
+        println!("\nBiggest changes (overall):");
+        for (session_id, before, after) in &result.biggest_moves {
+            println!("  {}  {:.1} -> {:.1} ({:+.1})", session_id, before, after, after - before);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::AnalysisMetrics;
+    use chrono::Utc;
+
+    fn snapshot(overall: f64) -> AnalysisSnapshot {
+        AnalysisSnapshot {
+            analyzed_at: Utc::now(),
+            metrics: AnalysisMetrics::default(),
+            quality: SessionQuality {
+                engagement_score: overall,
+                clarity_score: overall,
+                productivity_score: overall,
+                overall_score: overall,
+            },
+        }
+    }
+
+    #[test]
+    fn reports_average_shifts_and_biggest_moves() {
+        let pairs = vec![
+            ("a".to_string(), snapshot(50.0), snapshot(50.0)),
+            ("b".to_string(), snapshot(40.0), snapshot(60.0)),
+            ("c".to_string(), snapshot(70.0), snapshot(65.0)),
+        ];
+        let mut result = Reanalysis::default();
+        compare(&mut result, &pairs);
+
+        assert_eq!(result.changed, 2);
+        assert_eq!(result.biggest_moves[0].0, "b");
+        assert_eq!(result.biggest_moves[1].0, "c");
+        let (name, before, after) = result.averages[3];
+        assert_eq!(name, "Overall");
+        assert!((before - 160.0 / 3.0).abs() < 1e-9 && (after - 175.0 / 3.0).abs() < 1e-9);
+    }
+}
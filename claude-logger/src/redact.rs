@@ -0,0 +1,250 @@
+use crate::config::RedactionConfig;
+use anyhow::{Context, Result};
+use regex::Regex;
+
+/// Secrets that should never leave the machine, as (rule name, pattern).
+const BUILTIN_RULES: &[(&str, &str)] = &[
+    (
+        "private-key",
+        r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----",
+    ),
+    ("aws-access-key", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b"),
+    ("github-token", r"\b(?:gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{22,})\b"),
+    ("api-key", r"\bsk-(?:ant-|proj-)?[A-Za-z0-9_-]{20,}"),
+    ("slack-token", r"\bxox[abprs]-[A-Za-z0-9-]{10,}"),
+    ("bearer-token", r"(?i)\bbearer\s+[A-Za-z0-9._~+/-]{16,}=*"),
+    (
+        "assignment",
+        r#"(?i)\b(?:password|passwd|secret|token|api[_-]?key|access[_-]?key)\b["']?\s*[:=]\s*["']?[^\s"']{6,}"#,
+    ),
+];
+
+const EMAIL_RULE: (&str, &str) = ("email", r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b");
+
+/// A span of text a rule would scrub, as byte offsets into the original.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedactionMatch {
+    pub start: usize,
+    pub end: usize,
+    pub rule: String,
+}
+
+pub struct Redactor {
+    rules: Vec<(String, Regex)>,
+    home: Option<String>,
+}
+
+impl Redactor {
+    pub fn new(config: &RedactionConfig) -> Result<Self> {
+        let mut rules = Vec::new();
+        for (name, pattern) in BUILTIN_RULES {
+            rules.push((name.to_string(), Regex::new(pattern).expect("built-in redaction pattern is valid")));
+        }
+        if !config.keep_emails {
+            rules.push((EMAIL_RULE.0.to_string(), Regex::new(EMAIL_RULE.1).expect("email pattern is valid")));
+        }
+        for pattern in &config.patterns {
+            let regex = Regex::new(pattern).with_context(|| format!("Invalid redaction pattern: {}", pattern))?;
+            rules.push(("custom".to_string(), regex));
+        }
+
+        let home = dirs::home_dir()
+            .map(|home| home.to_string_lossy().into_owned())
+            .filter(|home| home.len() > 1);
+
+        Ok(Self { rules, home })
+    }
+
+    /// Every span that would be scrubbed, in order and without overlaps.
+    pub fn find(&self, text: &str) -> Vec<RedactionMatch> {
+        let mut matches: Vec<RedactionMatch> = self
+            .rules
+            .iter()
+            .flat_map(|(name, regex)| {
+                regex.find_iter(text).map(move |found| RedactionMatch {
+                    start: found.start(),
+                    end: found.end(),
+                    rule: name.clone(),
+                })
+            })
+            .collect();
+        matches.sort_by_key(|found| (found.start, std::cmp::Reverse(found.end)));
+
+        let mut kept: Vec<RedactionMatch> = Vec::new();
+        for found in matches {
+            match kept.last_mut() {
+                Some(last) if found.start < last.end => last.end = last.end.max(found.end),
+                _ => kept.push(found),
+            }
+        }
+        kept
+    }
+
+    /// Replace secrets with `[REDACTED:<rule>]` and the home directory with `~`.
+    /// Returns the scrubbed text and how many secrets were removed.
+    pub fn apply(&self, text: &str) -> (String, usize) {
+        let matches = self.find(text);
+        let mut output = String::with_capacity(text.len());
+        let mut position = 0;
+        for found in &matches {
+            output.push_str(&text[position..found.start]);
+            output.push_str(&format!("[REDACTED:{}]", found.rule));
+            position = found.end;
+        }
+        output.push_str(&text[position..]);
+
+        if let Some(home) = &self.home {
+            output = output.replace(home.as_str(), "~");
+        }
+        (output, matches.len())
+    }
+
+    /// What `apply` would change in `text`, without changing it.
+    pub fn preview(&self, text: &str) -> RedactionPreview {
+        let spans = self
+            .find(text)
+            .into_iter()
+            .map(|found| PreviewSpan {
+                line: text[..found.start].matches('\n').count() + 1,
+                before: context_before(text, found.start),
+                original: text[found.start..found.end].to_string(),
+                after: context_after(text, found.end),
+                rule: found.rule,
+            })
+            .collect();
+        let home_paths = self.home.as_ref().map_or(0, |home| text.matches(home.as_str()).count());
+        RedactionPreview { spans, home_paths }
+    }
+}
+
+/// Characters of the surrounding line shown on each side of a previewed span.
+const PREVIEW_CONTEXT: usize = 30;
+/// Longest stretch of a secret shown before it is cut short.
+const PREVIEW_ORIGINAL: usize = 60;
+
+fn context_before(text: &str, start: usize) -> String {
+    let line = &text[text[..start].rfind('\n').map_or(0, |newline| newline + 1)..start];
+    let skip = line.chars().count().saturating_sub(PREVIEW_CONTEXT);
+    let context: String = line.chars().skip(skip).collect();
+    if skip > 0 { format!("…{}", context) } else { context }
+}
+
+fn context_after(text: &str, end: usize) -> String {
+    let line = &text[end..text[end..].find('\n').map_or(text.len(), |newline| end + newline)];
+    let context: String = line.chars().take(PREVIEW_CONTEXT).collect();
+    if context.len() < line.len() { format!("{}…", context) } else { context }
+}
+
+/// A span `apply` would replace, with the text around it on its line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviewSpan {
+    /// 1-based line the span starts on
+    pub line: usize,
+    pub rule: String,
+    pub original: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// Everything `apply` would change in one text.
+#[derive(Debug, Clone, Default)]
+pub struct RedactionPreview {
+    pub spans: Vec<PreviewSpan>,
+    /// Occurrences of the home directory that would become `~`
+    pub home_paths: usize,
+}
+
+impl RedactionPreview {
+    /// Spans per rule, most frequent first.
+    pub fn counts(&self) -> Vec<(&str, usize)> {
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+        for span in &self.spans {
+            match counts.iter_mut().find(|(rule, _)| *rule == span.rule) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((&span.rule, 1)),
+            }
+        }
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        counts
+    }
+
+    /// Each span with its context, the secret marked `«…»`, then the totals.
+    pub fn print(&self, label: &str) {
+        println!("=== Redaction preview: {} ===", label);
+        for span in &self.spans {
+            let mut original = span.original.lines().next().unwrap_or_default().to_string();
+            let lines = span.original.lines().count();
+            if original.chars().count() > PREVIEW_ORIGINAL {
+                original = format!("{}…", original.chars().take(PREVIEW_ORIGINAL).collect::<String>());
+            }
+            if lines > 1 {
+                original.push_str(&format!(" (+{} lines)", lines - 1));
+            }
+            println!("  line {:>5}  {}«{}»{}", span.line, span.before, original, span.after);
+            println!("              -> [REDACTED:{}]", span.rule);
+        }
+
+        if self.spans.is_empty() {
+            println!("Nothing would be redacted.");
+        } else {
+            let counts: Vec<String> = self.counts().iter().map(|(rule, count)| format!("{} {}", rule, count)).collect();
+            println!("{} spans would be redacted: {}", self.spans.len(), counts.join(", "));
+        }
+        if self.home_paths > 0 {
+            println!("{} home directory paths would be shortened to ~", self.home_paths);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrubs_builtin_and_custom_patterns() {
+        let config = RedactionConfig {
+            patterns: vec![r"internal-\d+".to_string()],
+            keep_emails: false,
+        };
+        let redactor = Redactor::new(&config).unwrap();
+
+        let text = "export API_KEY=abcdef123456\nmail dev@example.com about internal-42\nAKIAABCDEFGHIJKLMNOP ok";
+        let (scrubbed, count) = redactor.apply(text);
+
+        assert_eq!(count, 4);
+        assert_eq!(
+            scrubbed,
+            "export [REDACTED:assignment]\nmail [REDACTED:email] about [REDACTED:custom]\n[REDACTED:aws-access-key] ok"
+        );
+    }
+
+    #[test]
+    fn previews_spans_with_their_context() {
+        let redactor = Redactor::new(&RedactionConfig::default()).unwrap();
+        let text = "first line\nplease mail dev@example.com and ops@example.com today\npassword = hunter22";
+        let preview = redactor.preview(text);
+
+        assert_eq!(preview.spans.len(), 3);
+        assert_eq!(
+            preview.spans[0],
+            PreviewSpan {
+                line: 2,
+                rule: "email".to_string(),
+                original: "dev@example.com".to_string(),
+                before: "please mail ".to_string(),
+                after: " and ops@example.com today".to_string(),
+            }
+        );
+        assert_eq!((preview.spans[2].line, preview.spans[2].after.as_str()), (3, ""));
+        assert_eq!(preview.counts(), vec![("email", 2), ("assignment", 1)]);
+        assert_eq!(redactor.apply(text).1, preview.spans.len());
+    }
+
+    #[test]
+    fn overlapping_matches_are_merged() {
+        let redactor = Redactor::new(&RedactionConfig::default()).unwrap();
+        let matches = redactor.find("token: sk-ant-REDACTED");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].rule, "assignment");
+    }
+}
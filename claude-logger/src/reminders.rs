@@ -0,0 +1,155 @@
+use crate::logger::ClaudeLogger;
+use crate::session::{Reminder, SessionMetadata, SessionsMetadata};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+
+/// Parse a relative delay such as `30m`, `12h`, `3d` or `2w`.
+pub fn parse_delay(delay: &str) -> Result<Duration> {
+    let delay = delay.trim();
+    let split = delay.find(|c: char| !c.is_ascii_digit()).unwrap_or(delay.len());
+    let (amount, unit) = delay.split_at(split);
+
+    let amount: i64 = amount
+        .parse()
+        .with_context(|| format!("Invalid delay '{}': expected e.g. 3d or 12h", delay))?;
+
+    let duration = match unit {
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" | "" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        _ => return Err(anyhow::anyhow!("Invalid delay '{}': use m, h, d or w", delay)),
+    };
+    duration.with_context(|| format!("Invalid delay '{}': too long", delay))
+}
+
+pub fn schedule(logger: &mut ClaudeLogger, session_id: &str, delay: Duration, note: &str) -> Result<Reminder> {
+    let mut session = logger
+        .get_session(session_id)
+        .cloned()
+        .with_context(|| format!("Session not found: {}", session_id))?;
+
+    let now = Utc::now();
+    let due_at = now
+        .checked_add_signed(delay)
+        .context("The reminder would be due beyond the latest date that can be stored")?;
+    let reminder = Reminder {
+        note: note.trim().to_string(),
+        due_at,
+        created_at: now,
+        notified: false,
+        done: false,
+    };
+    session.reminders.push(reminder.clone());
+
+    logger.add_session(session);
+    logger.save_metadata()?;
+    Ok(reminder)
+}
+
+/// Pending reminders due before `until`, soonest first, with their 1-based number
+/// within the session.
+pub fn due(metadata: &SessionsMetadata, until: DateTime<Utc>) -> Vec<(&SessionMetadata, usize, &Reminder)> {
+    let mut due: Vec<_> = pending(metadata)
+        .into_iter()
+        .filter(|(_, _, reminder)| reminder.due_at <= until)
+        .collect();
+    due.sort_by_key(|(_, _, reminder)| reminder.due_at);
+    due
+}
+
+/// All reminders not yet marked done, soonest first.
+pub fn pending(metadata: &SessionsMetadata) -> Vec<(&SessionMetadata, usize, &Reminder)> {
+    let mut pending: Vec<_> = metadata
+        .sessions
+        .values()
+        .flat_map(|session| {
+            session
+                .reminders
+                .iter()
+                .enumerate()
+                .map(move |(index, reminder)| (session, index + 1, reminder))
+        })
+        .filter(|(_, _, reminder)| !reminder.done)
+        .collect();
+    pending.sort_by_key(|(_, _, reminder)| reminder.due_at);
+    pending
+}
+
+pub fn complete(logger: &mut ClaudeLogger, session_id: &str, number: usize) -> Result<Reminder> {
+    let mut session = logger
+        .get_session(session_id)
+        .cloned()
+        .with_context(|| format!("Session not found: {}", session_id))?;
+
+    let reminder = number
+        .checked_sub(1)
+        .and_then(|index| session.reminders.get_mut(index))
+        .with_context(|| format!("Session {} has no reminder {}", session_id, number))?;
+    reminder.done = true;
+    let reminder = reminder.clone();
+
+    logger.add_session(session);
+    logger.save_metadata()?;
+    Ok(reminder)
+}
+
+/// Mark due reminders as notified and return them, so the daemon reports each once.
+pub fn take_due_notifications(logger: &mut ClaudeLogger) -> Result<Vec<(String, Reminder)>> {
+    let now = Utc::now();
+    let mut fired = Vec::new();
+
+    let sessions: Vec<SessionMetadata> = logger
+        .metadata()
+        .sessions
+        .values()
+        .filter(|session| {
+            session
+                .reminders
+                .iter()
+                .any(|reminder| !reminder.done && !reminder.notified && reminder.due_at <= now)
+        })
+        .cloned()
+        .collect();
+
+    for mut session in sessions {
+        for reminder in session.reminders.iter_mut() {
+            if !reminder.done && !reminder.notified && reminder.due_at <= now {
+                reminder.notified = true;
+                fired.push((session.id.clone(), reminder.clone()));
+            }
+        }
+        logger.add_session(session);
+    }
+
+    if !fired.is_empty() {
+        logger.save_metadata()?;
+    }
+    Ok(fired)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::{generate, sessions};
+
+    #[test]
+    fn rejects_delays_out_of_range() {
+        assert_eq!(parse_delay("90m").unwrap(), Duration::minutes(90));
+        assert_eq!(parse_delay(" 2w ").unwrap(), Duration::days(14));
+        assert_eq!(parse_delay("3").unwrap(), Duration::days(3));
+        assert!(parse_delay("3y").is_err());
+        for delay in ["99999999999999999d", "9999999999999999w", "99999999999999999999m"] {
+            assert!(parse_delay(delay).is_err(), "{}", delay);
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut logger = ClaudeLogger::new_with_dir(dir.path()).unwrap();
+        let session = generate(&sessions(1, 4, "api"), dir.path(), Utc::now()).remove(0).session;
+        let id = session.id.clone();
+        logger.add_session(session);
+        let error = schedule(&mut logger, &id, parse_delay("99999999d").unwrap(), "later").unwrap_err();
+        assert!(error.to_string().contains("latest date"), "{}", error);
+        assert!(logger.get_session(&id).unwrap().reminders.is_empty());
+    }
+}
@@ -0,0 +1,217 @@
+use crate::cost::CostSummary;
+use crate::length::LengthAnalysis;
+use crate::locale;
+use crate::session::{Methodology, MethodologyStats};
+use crate::task_type::TaskTypeAnalysis;
+use crate::timezone::DisplayZone;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Structured form of the analysis report, shared by the CLI and the HTTP API.
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub generated_at: DateTime<Utc>,
+    pub total_sessions: usize,
+    pub methodologies: Vec<MethodologyReport>,
+    pub terminal: Vec<TerminalBucket>,
+    /// Each author's sessions side by side, when more than one has recorded
+    pub authors: Vec<AuthorReport>,
+    pub hotspots: Vec<EditHotspot>,
+    pub length: LengthAnalysis,
+    pub task_types: TaskTypeAnalysis,
+    pub cost: CostSummary,
+    pub recommendations: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MethodologyReport {
+    pub methodology: Methodology,
+    pub stats: MethodologyStats,
+    pub quality: Option<QualityAverages>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QualityAverages {
+    pub sampled_sessions: usize,
+    pub engagement: f64,
+    pub clarity: f64,
+    pub productivity: f64,
+    pub overall: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TerminalBucket {
+    pub label: String,
+    pub sessions: usize,
+    pub confusion_per_session: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthorReport {
+    pub author: String,
+    pub sessions: usize,
+    pub hours: f64,
+    pub exchanges_per_session: f64,
+    /// Average overall quality of the sessions whose logs could be read
+    pub overall_quality: Option<f64>,
+}
+
+/// A file Claude keeps coming back to, aggregated from parsed edit records.
+#[derive(Debug, Clone, Serialize)]
+pub struct EditHotspot {
+    pub project: String,
+    pub file: String,
+    pub edits: usize,
+    pub sessions: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+impl EditHotspot {
+    /// Hotspots edited this often, across this many sessions, are worth a recommendation.
+    pub fn is_churning(&self) -> bool {
+        self.edits >= 5 && self.sessions >= 3
+    }
+}
+
+pub fn print_hotspots(hotspots: &[EditHotspot]) {
+    for hotspot in hotspots {
+        println!("  {:>4} edits in {:>3} sessions  +{:<5} -{:<5} {} / {}",
+            hotspot.edits, hotspot.sessions, hotspot.lines_added, hotspot.lines_removed,
+            hotspot.project, hotspot.file);
+    }
+}
+
+impl Report {
+    pub fn print(&self, zone: DisplayZone) {
+        let locale = locale::current();
+        println!("=== {} ===", locale.text("report-title"));
+        println!("{}\n", locale.text_with("report-generated", &[("time", zone.format_full(self.generated_at).into())]));
+
+        if self.methodologies.is_empty() {
+            println!("{}", locale.text("report-no-sessions"));
+            return;
+        }
+
+        // Overall statistics
+        println!("{}\n", locale.text_with("report-total-sessions", &[("count", self.total_sessions.into())]));
+
+        // Methodology comparison
+        println!("=== {} ===", locale.text("heading-methodology-comparison"));
+        for entry in &self.methodologies {
+            let stats = &entry.stats;
+
+            println!("\n{} Sessions:", entry.methodology);
+            println!("  Sessions: {}", stats.sessions);
+
+            if stats.avg_duration.num_minutes() > 0 {
+                println!("  Average Duration: {}", locale.minutes(stats.avg_duration.num_minutes()));
+                println!("  Total Duration: {}", locale.minutes(stats.total_duration.num_minutes()));
+            }
+
+            if let Some(idle) = stats.idle_percent() {
+                println!("  Active Time: {} ({}% idle; measured in {} of {} sessions)",
+                    locale.minutes(stats.active_duration.num_minutes()), locale.number(idle, 0),
+                    stats.activity_sessions, stats.sessions);
+            }
+
+            if let Some(avg_energy) = stats.avg_energy {
+                println!("  Average Creative Energy: {}/3", locale.number(avg_energy, 1));
+            }
+
+            println!("  Conversation Metrics:");
+            println!("    Total Exchanges: {}", stats.metrics.exchanges);
+            println!("    Code Blocks: {}", stats.metrics.code_blocks);
+            println!("    File Edits: {}", stats.metrics.file_edits);
+            println!("    Questions Asked: {}", stats.metrics.questions_asked);
+            println!("    Enthusiasm Markers: {}", stats.metrics.enthusiasm_markers);
+            println!("    Confusion Markers: {}", stats.metrics.confusion_markers);
+            println!("    Compaction Indicators: {}", stats.metrics.compaction_indicators);
+            println!("    Error Events: {}", stats.metrics.error_events);
+            println!("    Retry Events: {}", stats.metrics.retry_events);
+            if stats.metrics.tool_calls > 0 {
+                println!("    Tool Calls: {}", stats.metrics.tool_calls);
+                println!("    Tokens: {} in, {} out", stats.metrics.input_tokens, stats.metrics.output_tokens);
+            }
+
+            let tools = stats.metrics.tool_distribution();
+            if !tools.is_empty() {
+                println!("  Tool Usage:");
+                for (tool, calls, share) in tools {
+                    println!("    {}: {} ({}%)", tool, calls, locale.number(share, 0));
+                }
+            }
+
+            // Calculate derived metrics
+            if stats.sessions > 0 {
+                let avg_exchanges = stats.metrics.exchanges as f64 / stats.sessions as f64;
+                let avg_code_blocks = stats.metrics.code_blocks as f64 / stats.sessions as f64;
+                println!("  Average per Session:");
+                println!("    Exchanges: {}", locale.number(avg_exchanges, 1));
+                println!("    Code Blocks: {}", locale.number(avg_code_blocks, 1));
+            }
+        }
+
+        // Quality analysis
+        println!("\n=== {} ===", locale.text("heading-quality"));
+        for entry in &self.methodologies {
+            println!("\n{} Quality Metrics:", entry.methodology);
+
+            if let Some(quality) = &entry.quality {
+                println!("  Average Engagement Score: {}/100", locale.number(quality.engagement, 1));
+                println!("  Average Clarity Score: {}/100", locale.number(quality.clarity, 1));
+                println!("  Average Productivity Score: {}/100", locale.number(quality.productivity, 1));
+                println!("  Average Overall Score: {}/100", locale.number(quality.overall, 1));
+            }
+        }
+
+        // Terminal environment analysis
+        if !self.terminal.is_empty() {
+            println!("\n=== {} ===", locale.text("heading-terminal"));
+            for bucket in &self.terminal {
+                println!("  {}: {} sessions, {} confusion markers per session",
+                    bucket.label, bucket.sessions, locale.number(bucket.confusion_per_session, 1));
+            }
+        }
+
+        if !self.authors.is_empty() {
+            println!("\n=== {} ===", locale.text("heading-authors"));
+            for entry in &self.authors {
+                let quality = entry.overall_quality.map_or("-".to_string(), |quality| format!("{}/100", locale.number(quality, 1)));
+                println!("  {}: {} sessions, {} hours, {} exchanges per session, quality {}",
+                    entry.author, entry.sessions, locale.number(entry.hours, 1),
+                    locale.number(entry.exchanges_per_session, 1), quality);
+            }
+        }
+
+        if !self.length.is_empty() {
+            println!("\n=== {} ===", locale.text("heading-length"));
+            self.length.print();
+        }
+
+        if !self.task_types.is_empty() {
+            println!("\n=== {} ===", locale.text("heading-task-types"));
+            self.task_types.print();
+        }
+
+        if self.cost.priced_sessions > 0 {
+            println!("\n=== {} ===", locale.text("heading-cost"));
+            self.cost.print();
+        }
+
+        if !self.hotspots.is_empty() {
+            println!("\n=== {} ===", locale.text("heading-hotspots"));
+            print_hotspots(&self.hotspots);
+        }
+
+        // Recommendations
+        println!("\n=== {} ===", locale.text("heading-recommendations"));
+        if self.recommendations.is_empty() {
+            println!("{}", locale.text("report-no-recommendations"));
+        } else {
+            for (i, recommendation) in self.recommendations.iter().enumerate() {
+                println!("{}. {}", i + 1, recommendation);
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use crate::session::{SessionMetadata, SessionsMetadata};
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// How claude was asked to pick up an earlier conversation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resumption {
+    /// `--continue`: the most recent conversation in the directory
+    Continue,
+    /// `--resume [ID]`; without an id claude lets the user pick one
+    Resume(Option<String>),
+}
+
+/// The resumption requested by claude's arguments, if any.
+pub fn detect(args: &[String]) -> Option<Resumption> {
+    let mut args = args.iter().peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-c" | "--continue" => return Some(Resumption::Continue),
+            "-r" | "--resume" => {
+                // The id is optional, so only take a following argument that looks like one
+                let id = args.next_if(|next| is_session_id(next)).cloned();
+                return Some(Resumption::Resume(id));
+            }
+            _ => {
+                if let Some(id) = arg.strip_prefix("--resume=") {
+                    return Some(Resumption::Resume(Some(id.to_string())));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Claude Code session ids are UUIDs.
+fn is_session_id(text: &str) -> bool {
+    text.len() >= 8 && text.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+}
+
+/// The logged session a new one in `working_directory` resumes.
+///
+/// `--continue` picks the latest session started there before `started`; `--resume ID` the
+/// session whose transcript has that id. `--resume` without an id is left unlinked, since
+/// only claude knows which conversation was picked; link it with `edit --parent`.
+pub fn find_parent(
+    metadata: &SessionsMetadata,
+    working_directory: &Path,
+    resumption: &Resumption,
+    started: DateTime<Utc>,
+) -> Option<String> {
+    match resumption {
+        Resumption::Continue => metadata
+            .sessions
+            .values()
+            .filter(|session| session.working_directory == working_directory && session.timestamp < started)
+            .max_by_key(|session| session.timestamp)
+            .map(|session| session.id.clone()),
+        Resumption::Resume(Some(id)) => metadata
+            .sessions
+            .values()
+            .filter(|session| {
+                session.transcript_file.as_ref().and_then(|path| path.file_stem()).is_some_and(|stem| stem == id.as_str())
+            })
+            .max_by_key(|session| session.timestamp)
+            .map(|session| session.id.clone()),
+        Resumption::Resume(None) => None,
+    }
+}
+
+/// The first session of the conversation `session_id` belongs to, following parents that are present.
+pub fn root_of<'a>(metadata: &'a SessionsMetadata, session_id: &'a str) -> &'a str {
+    let mut current = session_id;
+    let mut seen = HashSet::from([current]);
+    while let Some(parent) = metadata.get_session(current).and_then(|session| session.parent_session_id.as_deref()) {
+        // A missing parent (deleted, or excluded from a report) ends the chain
+        if metadata.get_session(parent).is_none() || !seen.insert(parent) {
+            break;
+        }
+        current = parent;
+    }
+    current
+}
+
+/// Every session of the conversation `session_id` belongs to, oldest first.
+pub fn chain<'a>(metadata: &'a SessionsMetadata, session_id: &str) -> Vec<&'a SessionMetadata> {
+    let Some(session) = metadata.get_session(session_id) else {
+        return Vec::new();
+    };
+    let root = root_of(metadata, &session.id);
+    let mut sessions: Vec<&SessionMetadata> =
+        metadata.sessions.values().filter(|session| root_of(metadata, &session.id) == root).collect();
+    sessions.sort_by_key(|session| session.timestamp);
+    sessions
+}
+
+/// Whether making `parent` the parent of `session_id` would create a loop.
+pub fn would_cycle(metadata: &SessionsMetadata, session_id: &str, parent: &str) -> bool {
+    parent == session_id || is_ancestor(metadata, session_id, parent)
+}
+
+/// Whether `ancestor` is reached by following `session_id`'s parents.
+fn is_ancestor(metadata: &SessionsMetadata, ancestor: &str, session_id: &str) -> bool {
+    let mut current = session_id;
+    let mut seen = HashSet::new();
+    while let Some(parent) = metadata.get_session(current).and_then(|session| session.parent_session_id.as_deref()) {
+        if parent == ancestor {
+            return true;
+        }
+        if !seen.insert(parent) {
+            return false;
+        }
+        current = parent;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::{generate, sessions, SynthOptions};
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn detects_resumption_and_follows_chains() {
+        assert_eq!(detect(&args("--continue")), Some(Resumption::Continue));
+        assert_eq!(detect(&args("-r fix-the-bug")), Some(Resumption::Resume(None)));
+        let id = "0f8b6d3e-1c2a-4e5f-9a7b-3c4d5e6f7a8b";
+        assert_eq!(detect(&args(&format!("--model opus -r {} more", id))), Some(Resumption::Resume(Some(id.to_string()))));
+        assert_eq!(detect(&args("-p hello")), None);
+
+        let options = SynthOptions { days: 3, ..sessions(4, 1, "api") };
+        let mut metadata = SessionsMetadata::new();
+        for generated in generate(&options, Path::new("/logs"), Utc::now()) {
+            metadata.add_session(generated.session);
+        }
+        let mut ids: Vec<String> = metadata.sessions.keys().cloned().collect();
+        ids.sort();
+
+        let latest = metadata.get_session(&ids[3]).unwrap();
+        let parent = find_parent(&metadata, &latest.working_directory, &Resumption::Continue, latest.timestamp);
+        assert_eq!(parent.as_deref(), Some(ids[2].as_str()));
+
+        metadata.get_session_mut(&ids[2]).unwrap().parent_session_id = Some(ids[1].clone());
+        metadata.get_session_mut(&ids[3]).unwrap().parent_session_id = Some(ids[2].clone());
+        let linked: Vec<&str> = chain(&metadata, &ids[2]).iter().map(|session| session.id.as_str()).collect();
+        assert_eq!(linked, [ids[1].as_str(), ids[2].as_str(), ids[3].as_str()]);
+        assert_eq!(root_of(&metadata, &ids[3]), ids[1]);
+
+        assert!(would_cycle(&metadata, &ids[1], &ids[3]));
+        assert!(!would_cycle(&metadata, &ids[3], &ids[0]));
+    }
+}
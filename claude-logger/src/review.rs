@@ -0,0 +1,89 @@
+use crate::analyzer::SessionAnalyzer;
+use crate::logger::ClaudeLogger;
+use crate::session::{ReviewState, SessionMetadata, SessionsMetadata};
+use crate::timezone::DisplayZone;
+use anyhow::{Context, Result};
+use std::io::{self, Write};
+
+/// Sessions still waiting for review, oldest first so the queue is worked in order.
+pub fn queue(metadata: &SessionsMetadata, include_flagged: bool) -> Vec<&SessionMetadata> {
+    let mut sessions: Vec<&SessionMetadata> = metadata
+        .sessions
+        .values()
+        .filter(|session| match session.review_state {
+            ReviewState::Unreviewed => true,
+            ReviewState::Flagged => include_flagged,
+            ReviewState::Reviewed => false,
+        })
+        .collect();
+    sessions.sort_by_key(|session| session.timestamp);
+    sessions
+}
+
+pub fn mark(logger: &mut ClaudeLogger, session_id: &str, state: ReviewState) -> Result<()> {
+    let mut session = logger
+        .get_session(session_id)
+        .cloned()
+        .with_context(|| format!("Session not found: {}", session_id))?;
+    session.review_state = state;
+    logger.add_session(session);
+    logger.save_metadata()
+}
+
+/// Step through the unreviewed queue, showing each summary and asking for a verdict.
+pub fn walk(logger: &mut ClaudeLogger, analyzer: &SessionAnalyzer, zone: DisplayZone) -> Result<()> {
+    let pending: Vec<String> = queue(analyzer.metadata(), false)
+        .into_iter()
+        .map(|session| session.id.clone())
+        .collect();
+
+    if pending.is_empty() {
+        println!("Review queue is empty.");
+        return Ok(());
+    }
+
+    for (index, session_id) in pending.iter().enumerate() {
+        println!("\n[{}/{}]", index + 1, pending.len());
+        match analyzer.get_session_summary(session_id) {
+            Ok(summary) => summary.print_summary(zone),
+            Err(e) => eprintln!("Warning: Failed to summarize session {}: {:#}", session_id, e),
+        }
+
+        match prompt_verdict()? {
+            Verdict::Mark(state) => {
+                mark(logger, session_id, state)?;
+                println!("Marked {} as {}", session_id, state);
+            }
+            Verdict::Skip => {}
+            Verdict::Quit => break,
+        }
+    }
+
+    Ok(())
+}
+
+enum Verdict {
+    Mark(ReviewState),
+    Skip,
+    Quit,
+}
+
+fn prompt_verdict() -> Result<Verdict> {
+    loop {
+        print!("\n[r]eviewed, [f]lagged, [s]kip, [q]uit: ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).context("Failed to read review input")? == 0 {
+            return Ok(Verdict::Quit);
+        }
+
+        match input.trim().to_lowercase().as_str() {
+            "r" => return Ok(Verdict::Mark(ReviewState::Reviewed)),
+            "f" => return Ok(Verdict::Mark(ReviewState::Flagged)),
+            "s" | "" => return Ok(Verdict::Skip),
+            "q" => return Ok(Verdict::Quit),
+            _ => println!("Invalid input. Please enter r, f, s or q."),
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use crate::session::SessionMetadata;
+use crate::timezone::DisplayZone;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+/// Sessions this close together count as one uninterrupted streak.
+const STREAK_GAP_MINUTES: i64 = 15;
+/// Longer gaps are the end of a working day rather than a break.
+const BREAK_LIMIT_HOURS: i64 = 4;
+/// Streaks at least this long are hyperfocus.
+const HYPERFOCUS_MINUTES: i64 = 120;
+
+/// Sessions worked with no break longer than `STREAK_GAP_MINUTES`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Streak {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub sessions: usize,
+}
+
+impl Streak {
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+}
+
+/// Work rhythm across sessions: how long they run, how long the breaks between them are and
+/// how long the stretches without a real break get.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Rhythm {
+    /// Sessions with a duration, which everything else is measured from
+    pub sessions: usize,
+    /// Sessions left out for want of a duration
+    pub without_duration: usize,
+    pub median_session_minutes: Option<i64>,
+    pub longest_session_minutes: Option<i64>,
+    /// Gaps between sessions up to `BREAK_LIMIT_HOURS`; overlapping sessions have none
+    pub breaks: usize,
+    pub median_break_minutes: Option<i64>,
+    /// Oldest first
+    pub streaks: Vec<Streak>,
+}
+
+impl Rhythm {
+    pub fn from_sessions<'a>(sessions: impl IntoIterator<Item = &'a SessionMetadata>) -> Self {
+        let mut rhythm = Self::default();
+        let mut spans: Vec<(DateTime<Utc>, DateTime<Utc>)> = Vec::new();
+        for session in sessions {
+            match session.duration() {
+                Some(duration) => spans.push((session.timestamp, session.timestamp + duration)),
+                None => rhythm.without_duration += 1,
+            }
+        }
+        spans.sort();
+        rhythm.sessions = spans.len();
+
+        let mut lengths: Vec<i64> = spans.iter().map(|(start, end)| (*end - *start).num_minutes()).collect();
+        lengths.sort();
+        rhythm.median_session_minutes = median(&lengths);
+        rhythm.longest_session_minutes = lengths.last().copied();
+
+        let mut breaks: Vec<i64> = Vec::new();
+        for (start, end) in spans {
+            match rhythm.streaks.last_mut() {
+                Some(streak) if start - streak.end <= Duration::minutes(STREAK_GAP_MINUTES) => {
+                    if start > streak.end {
+                        breaks.push((start - streak.end).num_minutes());
+                    }
+                    streak.end = streak.end.max(end);
+                    streak.sessions += 1;
+                }
+                previous => {
+                    if let Some(streak) = previous
+                        && start - streak.end <= Duration::hours(BREAK_LIMIT_HOURS)
+                    {
+                        breaks.push((start - streak.end).num_minutes());
+                    }
+                    rhythm.streaks.push(Streak { start, end, sessions: 1 });
+                }
+            }
+        }
+        breaks.sort();
+        rhythm.breaks = breaks.len();
+        rhythm.median_break_minutes = median(&breaks);
+        rhythm
+    }
+
+    pub fn longest_streak(&self) -> Option<&Streak> {
+        self.streaks.iter().max_by_key(|streak| streak.duration())
+    }
+
+    pub fn hyperfocus_streaks(&self) -> usize {
+        self.streaks.iter().filter(|streak| streak.duration() >= Duration::minutes(HYPERFOCUS_MINUTES)).count()
+    }
+
+    /// Suggestions for a more sustainable pace; encouragement when the pace already is.
+    pub fn recommendations(&self) -> Vec<String> {
+        let mut recommendations = Vec::new();
+        if self.streaks.is_empty() {
+            return recommendations;
+        }
+
+        if let Some(longest) = self.longest_streak()
+            && longest.duration() >= Duration::hours(3)
+        {
+            recommendations.push(format!(
+                "Your longest stretch ran {} across {} sessions without a break over {} minutes. \
+                 A short pause every 90 minutes or so can keep that focus going for longer.",
+                hours_and_minutes(longest.duration().num_minutes()),
+                longest.sessions,
+                STREAK_GAP_MINUTES
+            ));
+        }
+
+        let hyperfocus = self.hyperfocus_streaks();
+        if self.streaks.len() >= 3 && hyperfocus * 3 >= self.streaks.len() {
+            recommendations.push(format!(
+                "{} of your {} work streaks ran past two hours. Planning a break partway through \
+                 may leave more energy for the next one.",
+                hyperfocus,
+                self.streaks.len()
+            ));
+        }
+
+        if let Some(median_break) = self.median_break_minutes
+            && self.breaks >= 3
+            && median_break < 10
+        {
+            recommendations.push(format!(
+                "Breaks between sessions are usually short ({} minutes). Stepping away for \
+                 10-15 minutes now and then helps you come back fresh.",
+                median_break
+            ));
+        }
+
+        if recommendations.is_empty() {
+            recommendations.push("Your pacing looks sustainable: no stretch ran past three hours without a break.".to_string());
+        }
+        recommendations
+    }
+
+    pub fn print(&self, zone: DisplayZone) {
+        println!("=== Work Rhythm ===");
+        if self.sessions == 0 {
+            println!("No sessions with a duration found.");
+            self.print_missing();
+            return;
+        }
+
+        let minutes = |value: Option<i64>| value.map(hours_and_minutes).unwrap_or_else(|| "-".to_string());
+        println!("Sessions: {}", self.sessions);
+        println!("Typical session: {} (longest {})", minutes(self.median_session_minutes), minutes(self.longest_session_minutes));
+        println!("Breaks between sessions: {} (typical {})", self.breaks, minutes(self.median_break_minutes));
+        println!(
+            "Work streaks: {} ({} of {} or more)",
+            self.streaks.len(),
+            self.hyperfocus_streaks(),
+            hours_and_minutes(HYPERFOCUS_MINUTES)
+        );
+        if let Some(longest) = self.longest_streak() {
+            println!(
+                "Longest streak: {} from {} ({} sessions)",
+                hours_and_minutes(longest.duration().num_minutes()),
+                zone.format_full(longest.start),
+                longest.sessions
+            );
+        }
+        self.print_missing();
+
+        println!("\nRecommendations:");
+        for recommendation in self.recommendations() {
+            println!("  - {}", recommendation);
+        }
+    }
+
+    fn print_missing(&self) {
+        if self.without_duration > 0 {
+            println!(
+                "{} sessions have no duration and were left out; `claude-logger backfill-durations` can estimate them.",
+                self.without_duration
+            );
+        }
+    }
+}
+
+fn median(sorted: &[i64]) -> Option<i64> {
+    match sorted.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((sorted[len / 2 - 1] + sorted[len / 2]) / 2),
+        len => Some(sorted[len / 2]),
+    }
+}
+
+fn hours_and_minutes(minutes: i64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h {}m", hours, minutes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::{generate, sessions};
+    use chrono::TimeZone;
+    use std::path::Path;
+
+    #[test]
+    fn finds_breaks_and_streaks() {
+        let options = sessions(6, 5, "api");
+        let day = Utc.with_ymd_and_hms(2025, 3, 3, 9, 0, 0).unwrap();
+        // (minutes after 9:00, length): a 3h20m streak with 5 and 10 minute pauses, a 50 minute
+        // break, one more session, then the next morning; the last has no duration
+        let plan = [(0, Some(90)), (95, Some(60)), (165, Some(35)), (250, Some(45)), (24 * 60, Some(30)), (26 * 60, None)];
+        let sessions: Vec<SessionMetadata> = generate(&options, Path::new("/logs"), day)
+            .into_iter()
+            .zip(plan)
+            .map(|(generated, (offset, length))| {
+                let mut session = generated.session;
+                session.timestamp = day + Duration::minutes(offset);
+                session.duration_seconds = length.map(|minutes| minutes * 60);
+                session
+            })
+            .collect();
+
+        let rhythm = Rhythm::from_sessions(&sessions);
+        assert_eq!((rhythm.sessions, rhythm.without_duration), (5, 1));
+        assert_eq!((rhythm.median_session_minutes, rhythm.longest_session_minutes), (Some(45), Some(90)));
+        // The overnight gap isn't a break
+        assert_eq!((rhythm.breaks, rhythm.median_break_minutes), (3, Some(10)));
+
+        let streaks: Vec<(i64, usize)> =
+            rhythm.streaks.iter().map(|streak| (streak.duration().num_minutes(), streak.sessions)).collect();
+        assert_eq!(streaks, [(200, 3), (45, 1), (30, 1)]);
+        assert_eq!(rhythm.hyperfocus_streaks(), 1);
+        assert!(rhythm.recommendations()[0].starts_with("Your longest stretch ran 3h 20m across 3 sessions"));
+        assert_eq!(hours_and_minutes(120), "2h");
+    }
+}
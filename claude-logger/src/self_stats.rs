@@ -0,0 +1,239 @@
+use crate::audit::{Action, AuditEntry};
+use crate::calendar::{Bucket, Period};
+use crate::config::{Config, StorageBackend};
+use crate::timezone::DisplayZone;
+use chrono::{DateTime, Duration, Utc};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Command};
+use std::collections::BTreeMap;
+
+/// Weeks of sessions shown, ending with the current one.
+const WEEKS: usize = 8;
+
+type Configured = fn(&Config) -> bool;
+
+/// Settings that only pay off through a command: what is set, when it is, and the command and
+/// flag that use it.
+const CONFIGURED: [(&str, Configured, &str, Option<&str>); 5] = [
+    (
+        "[billing] rates are set",
+        |config| config.billing.hourly_rate.is_some() || !config.billing.rates.is_empty(),
+        "analyze",
+        Some("--billing"),
+    ),
+    (
+        "[analysis] transition_date is set",
+        |config| config.analysis.transition_date.is_some(),
+        "analyze",
+        Some("--transition"),
+    ),
+    ("[storage] has a remote backend", |config| config.storage.backend != StorageBackend::Local, "archive", None),
+    ("[serve] has an API token", |config| config.serve.token.is_some(), "serve", None),
+    ("[redaction] has extra patterns", |config| !config.redaction.patterns.is_empty(), "share", None),
+];
+
+/// The command line that was run, reduced to its subcommands and the names of the flags given,
+/// e.g. `claude-logger analyze --billing`. Values and positional arguments are left out so
+/// nothing about the sessions themselves ends up in the record.
+pub fn invocation(command: &Command, matches: &ArgMatches) -> String {
+    let mut words = vec!["claude-logger".to_string()];
+    let mut flags: Vec<String> = Vec::new();
+    let (mut command, mut matches) = (command, matches);
+    loop {
+        for arg in command.get_arguments() {
+            if let Some(long) = arg.get_long()
+                && matches.try_contains_id(arg.get_id().as_str()).unwrap_or(false)
+                && matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine)
+            {
+                let flag = format!("--{}", long);
+                if !flags.contains(&flag) {
+                    flags.push(flag);
+                }
+            }
+        }
+        let Some((name, sub_matches)) = matches.subcommand() else {
+            break;
+        };
+        let Some(subcommand) = command.find_subcommand(name) else {
+            break;
+        };
+        words.push(name.to_string());
+        (command, matches) = (subcommand, sub_matches);
+    }
+    words.extend(flags);
+    words.join(" ")
+}
+
+/// The top-level command a recorded run used; an empty name is a logged claude session.
+fn command_name(entry: &AuditEntry) -> &str {
+    entry.command.split_whitespace().nth(1).filter(|word| !word.starts_with('-')).unwrap_or("")
+}
+
+/// How claude-logger itself gets used, from the audit log alone.
+#[derive(Debug, Clone)]
+pub struct SelfStats {
+    /// First recorded run; runs from before usage was recorded aren't counted
+    pub since: Option<DateTime<Utc>>,
+    pub runs: usize,
+    /// Each command and how often it ran, most used first
+    pub commands: Vec<(String, usize)>,
+    /// Sessions added each week, oldest first, ending with the current week
+    pub sessions_per_week: Vec<(Bucket, usize)>,
+    /// Commands never run, alphabetically
+    pub unused_commands: Vec<String>,
+    /// Configured capabilities whose command has never been run
+    pub unused_configured: Vec<String>,
+}
+
+impl SelfStats {
+    pub fn from_entries(entries: &[AuditEntry], cli: &Command, config: &Config, zone: DisplayZone, now: DateTime<Utc>) -> Self {
+        let runs: Vec<&AuditEntry> = entries.iter().filter(|entry| entry.action == Action::Ran).collect();
+
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for run in &runs {
+            *counts.entry(command_name(run)).or_default() += 1;
+        }
+        let mut commands: Vec<(String, usize)> = counts
+            .iter()
+            .map(|(name, count)| (if name.is_empty() { "(logged session)" } else { name }.to_string(), *count))
+            .collect();
+        commands.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+        let mut unused_commands: Vec<String> = cli
+            .get_subcommands()
+            .filter(|subcommand| !subcommand.is_hide_set())
+            .map(|subcommand| subcommand.get_name().to_string())
+            .filter(|name| name != "help" && !counts.contains_key(name.as_str()))
+            .collect();
+        unused_commands.sort();
+
+        let unused_configured = CONFIGURED
+            .iter()
+            .filter(|(_, configured, _, _)| configured(config))
+            .filter(|(_, _, command, flag)| {
+                !runs.iter().any(|run| {
+                    command_name(run) == *command && flag.is_none_or(|flag| run.command.split_whitespace().any(|word| word == flag))
+                })
+            })
+            .map(|(setting, _, command, flag)| {
+                let used_by = flag.map(|flag| format!("{} {}", command, flag)).unwrap_or_else(|| command.to_string());
+                format!("{}, but `claude-logger {}` has never been run", setting, used_by)
+            })
+            .collect();
+
+        let this_week = Period::Week.bucket(zone.date(now), &config.calendar);
+        let mut sessions_per_week: Vec<(Bucket, usize)> = (0..WEEKS)
+            .rev()
+            .map(|weeks_ago| {
+                let start = this_week.start - Duration::weeks(weeks_ago as i64);
+                (Bucket { start, end: start + Duration::days(7) }, 0)
+            })
+            .collect();
+        for entry in entries.iter().filter(|entry| entry.action == Action::Added) {
+            let week = Period::Week.bucket(zone.date(entry.timestamp), &config.calendar);
+            if let Some((_, count)) = sessions_per_week.iter_mut().find(|(bucket, _)| *bucket == week) {
+                *count += 1;
+            }
+        }
+
+        Self {
+            since: runs.first().map(|run| run.timestamp),
+            runs: runs.len(),
+            commands,
+            sessions_per_week,
+            unused_commands,
+            unused_configured,
+        }
+    }
+
+    pub fn print(&self, zone: DisplayZone) {
+        println!("=== claude-logger Usage ===");
+        match self.since {
+            Some(since) => println!(
+                "Recorded locally in audit.jsonl since {}; nothing is sent anywhere.",
+                zone.format(since, "%Y-%m-%d %H:%M")
+            ),
+            None => println!("No runs recorded yet; every claude-logger command from now on is counted."),
+        }
+
+        if !self.commands.is_empty() {
+            println!("\nCommands run: {} ({} different)", self.runs, self.commands.len());
+            for (name, count) in &self.commands {
+                println!("  {:<20} {:>5}", name, count);
+            }
+        }
+
+        println!("\nSessions logged per week:");
+        let most = self.sessions_per_week.iter().map(|(_, count)| *count).max().unwrap_or(0).max(1);
+        for (week, count) in &self.sessions_per_week {
+            let bar = "#".repeat((count * 30).div_ceil(most));
+            println!("  {}  {:>3} {}", week.start, count, bar);
+        }
+
+        if !self.unused_commands.is_empty() {
+            println!("\nNever used ({}): {}", self.unused_commands.len(), self.unused_commands.join(", "));
+        }
+        if !self.unused_configured.is_empty() {
+            println!("\nConfigured but never used:");
+            for capability in &self.unused_configured {
+                println!("  - {}", capability);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+    use chrono::TimeZone;
+
+    fn entry(action: Action, command: &str, timestamp: DateTime<Utc>) -> AuditEntry {
+        AuditEntry { timestamp, action, session_id: None, fields: Vec::new(), command: command.to_string() }
+    }
+
+    #[test]
+    fn counts_commands_weeks_and_unused_capabilities() {
+        let cli = crate::Cli::command();
+        let matches = cli
+            .clone()
+            .try_get_matches_from(["claude-logger", "--utc", "analyze", "--billing", "-p", "secret-client"])
+            .unwrap();
+        assert_eq!(invocation(&cli, &matches), "claude-logger analyze --utc --project --billing");
+        let matches = cli.clone().try_get_matches_from(["claude-logger", "transcripts", "link"]).unwrap();
+        assert_eq!(invocation(&cli, &matches), "claude-logger transcripts link");
+
+        // Wednesday 2025-03-12
+        let now = Utc.with_ymd_and_hms(2025, 3, 12, 12, 0, 0).unwrap();
+        let entries = [
+            entry(Action::Added, "claude-logger", now - Duration::days(8)),
+            entry(Action::Ran, "claude-logger", now - Duration::days(8)),
+            entry(Action::Ran, "claude-logger analyze --transition", now - Duration::days(2)),
+            entry(Action::Ran, "claude-logger show", now - Duration::days(1)),
+            entry(Action::Ran, "claude-logger analyze", now),
+            entry(Action::Added, "claude-logger", now),
+            entry(Action::Added, "claude-logger", now),
+        ];
+        let mut config = Config::default();
+        config.billing.hourly_rate = Some(120.0);
+        config.analysis.transition_date = chrono::NaiveDate::from_ymd_opt(2025, 3, 1);
+
+        let stats = SelfStats::from_entries(&entries, &cli, &config, DisplayZone::Utc, now);
+        assert_eq!(stats.runs, 4);
+        assert_eq!(stats.commands[0], ("analyze".to_string(), 2));
+        assert!(stats.commands.contains(&("(logged session)".to_string(), 1)));
+        assert!(stats.unused_commands.contains(&"badge".to_string()));
+        assert!(!stats.unused_commands.iter().any(|name| name == "show" || name == "help"));
+        // --transition was used, --billing never was
+        assert_eq!(
+            stats.unused_configured,
+            ["[billing] rates are set, but `claude-logger analyze --billing` has never been run"]
+        );
+
+        let weeks: Vec<(String, usize)> =
+            stats.sessions_per_week.iter().map(|(week, count)| (week.start.to_string(), *count)).collect();
+        assert_eq!(weeks.len(), WEEKS);
+        assert_eq!(weeks[WEEKS - 1], ("2025-03-10".to_string(), 2));
+        assert_eq!(weeks[WEEKS - 2], ("2025-03-03".to_string(), 1));
+    }
+}
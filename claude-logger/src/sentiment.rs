@@ -0,0 +1,387 @@
+use crate::analyzer::SessionAnalyzer;
+use crate::config::{SentimentBackendKind, SentimentConfig};
+use crate::conversation;
+use crate::hooks;
+use crate::patterns::ConversationPatterns;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::ops::AddAssign;
+use std::path::Path;
+use std::process::Stdio;
+use std::thread;
+use std::time::Duration;
+
+/// Per-backend scale factors, kept in the logs directory next to `patterns.toml`.
+pub const CALIBRATION_FILE: &str = "sentiment_calibration.json";
+
+/// Seconds a sentiment command may take for one session.
+const COMMAND_TIMEOUT_SECS: u64 = 30;
+
+/// Fewer sessions than this and a calibration mostly measures the sessions that were picked.
+const MIN_CALIBRATION_SESSIONS: usize = 5;
+
+/// A backend's enthusiasm and confusion for one conversation, on the backend's own scale.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RawSentiment {
+    pub enthusiasm: f64,
+    pub confusion: f64,
+}
+
+impl AddAssign for RawSentiment {
+    fn add_assign(&mut self, other: Self) {
+        self.enthusiasm += other.enthusiasm;
+        self.confusion += other.confusion;
+    }
+}
+
+/// Something that can take over enthusiasm and confusion detection from the built-in patterns.
+pub trait SentimentBackend: Send + Sync {
+    /// Names the backend in the calibration file: `model` or `command:<command>`.
+    fn id(&self) -> String;
+    /// Score a normalized conversation, as the built-in patterns see it.
+    fn score(&self, content: &str) -> Result<RawSentiment>;
+}
+
+/// Multipliers that bring a backend's scores onto the built-in patterns' scale, so sessions
+/// analyzed before the switch stay comparable with those analyzed after it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Calibration {
+    pub enthusiasm: f64,
+    pub confusion: f64,
+    /// Sessions both were run over
+    pub sessions: usize,
+    pub calibrated_at: DateTime<Utc>,
+}
+
+/// The configured backend with its calibration, as analysis uses it.
+pub struct Sentiment {
+    backend: Box<dyn SentimentBackend>,
+    calibration: Option<Calibration>,
+}
+
+impl Sentiment {
+    pub fn backend(&self) -> &dyn SentimentBackend {
+        self.backend.as_ref()
+    }
+
+    /// Enthusiasm and confusion markers for `content`, scaled by the calibration.
+    pub fn markers(&self, content: &str) -> Result<(usize, usize)> {
+        let raw = self.backend.score(content)?;
+        let (enthusiasm, confusion) = self.calibration.map_or((1.0, 1.0), |calibration| (calibration.enthusiasm, calibration.confusion));
+        Ok(((raw.enthusiasm * enthusiasm).round().max(0.0) as usize, (raw.confusion * confusion).round().max(0.0) as usize))
+    }
+}
+
+/// The backend `config` selects, or `None` for the built-in patterns.
+pub fn load(config: &SentimentConfig, logs_dir: &Path) -> Result<Option<Sentiment>> {
+    let backend: Box<dyn SentimentBackend> = match config.backend {
+        SentimentBackendKind::Patterns => return Ok(None),
+        SentimentBackendKind::Model => model()?,
+        SentimentBackendKind::Command => {
+            let command = config.command.clone().context("The command sentiment backend needs `command` under [analysis.sentiment]")?;
+            Box::new(CommandBackend { command, timeout: Duration::from_secs(COMMAND_TIMEOUT_SECS) })
+        }
+    };
+
+    let calibration = load_calibrations(logs_dir)?.remove(&backend.id());
+    if calibration.is_none() {
+        eprintln!(
+            "Warning: Sentiment backend `{}` is uncalibrated; run `claude-logger calibrate-sentiment` so its scores compare with earlier sessions",
+            backend.id()
+        );
+    }
+    Ok(Some(Sentiment { backend, calibration }))
+}
+
+pub fn load_calibrations(logs_dir: &Path) -> Result<BTreeMap<String, Calibration>> {
+    let path = logs_dir.join(CALIBRATION_FILE);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid sentiment calibration in {}", path.display()))
+}
+
+pub fn save_calibration(logs_dir: &Path, backend: &str, calibration: Calibration) -> Result<()> {
+    let mut calibrations = load_calibrations(logs_dir)?;
+    calibrations.insert(backend.to_string(), calibration);
+    let path = logs_dir.join(CALIBRATION_FILE);
+    fs::write(&path, serde_json::to_string_pretty(&calibrations)?).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Run the built-in patterns and `sentiment`'s backend over up to `limit` recent sessions and
+/// work out the factors that make the backend's totals match the patterns'.
+pub fn calibrate(analyzer: &SessionAnalyzer, patterns: &ConversationPatterns, sentiment: &Sentiment, limit: usize) -> Result<Calibration> {
+    let mut builtin = RawSentiment::default();
+    let mut backend = RawSentiment::default();
+    let mut sessions = 0;
+
+    for session in analyzer.metadata().recent_sessions(None, limit) {
+        let Ok(content) = analyzer.read_session_log(session) else {
+            continue;
+        };
+        let content = conversation::normalize(&content);
+        match sentiment.backend.score(&content) {
+            Ok(raw) => backend += raw,
+            Err(e) => {
+                eprintln!("Warning: Skipping {}: {:#}", session.id, e);
+                continue;
+            }
+        }
+        builtin += patterns.pattern_sentiment(&content);
+        sessions += 1;
+    }
+    if sessions < MIN_CALIBRATION_SESSIONS {
+        anyhow::bail!("Calibration needs at least {} sessions with readable logs, found {}", MIN_CALIBRATION_SESSIONS, sessions);
+    }
+
+    Ok(Calibration {
+        enthusiasm: factor(builtin.enthusiasm, backend.enthusiasm),
+        confusion: factor(builtin.confusion, backend.confusion),
+        sessions,
+        calibrated_at: Utc::now(),
+    })
+}
+
+/// What scales `backend` onto `builtin`; 1 when the backend found nothing to scale.
+fn factor(builtin: f64, backend: f64) -> f64 {
+    if backend > 0.0 { builtin / backend } else { 1.0 }
+}
+
+/// An external program: gets the conversation on stdin and prints
+/// `{"enthusiasm": <number>, "confusion": <number>}`.
+struct CommandBackend {
+    command: String,
+    timeout: Duration,
+}
+
+impl SentimentBackend for CommandBackend {
+    fn id(&self) -> String {
+        format!("command:{}", self.command)
+    }
+
+    fn score(&self, content: &str) -> Result<RawSentiment> {
+        let mut child = hooks::shell(&self.command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to start sentiment command `{}`", self.command))?;
+        // Written from a thread, so a command that never drains stdin still times out rather than
+        // blocking the write once the pipe fills; one that exits before reading isn't an error
+        if let Some(mut stdin) = child.stdin.take() {
+            let input = content.to_string();
+            thread::spawn(move || stdin.write_all(input.as_bytes()));
+        }
+        let status = hooks::wait_with_timeout(&mut child, self.timeout)
+            .with_context(|| format!("Sentiment command `{}` failed", self.command))?;
+        if !status.success() {
+            anyhow::bail!("Sentiment command `{}` exited with {}", self.command, status);
+        }
+
+        let mut output = String::new();
+        if let Some(mut stdout) = child.stdout.take() {
+            stdout.read_to_string(&mut output)?;
+        }
+        serde_json::from_str(output.trim())
+            .with_context(|| format!("Sentiment command `{}` printed {:?}, not {{\"enthusiasm\": n, \"confusion\": n}}", self.command, output.trim()))
+    }
+}
+
+#[cfg(feature = "sentiment-model")]
+fn model() -> Result<Box<dyn SentimentBackend>> {
+    Ok(Box::new(model::LexiconModel))
+}
+
+#[cfg(not(feature = "sentiment-model"))]
+fn model() -> Result<Box<dyn SentimentBackend>> {
+    anyhow::bail!("The sentiment model is not compiled in; rebuild with `--features sentiment-model`")
+}
+
+/// A small on-device model: a valence lexicon scored over the human side of the conversation,
+/// with negation and intensifiers, so "not great" and "I'm not confused" count the right way.
+#[cfg(feature = "sentiment-model")]
+mod model {
+    use super::{RawSentiment, SentimentBackend};
+    use anyhow::Result;
+
+    /// Positive valence of words that signal enthusiasm, 1-3.
+    const POSITIVE: &[(&str, f64)] = &[
+        ("amazing", 3.0), ("awesome", 3.0), ("brilliant", 3.0), ("excellent", 3.0), ("fantastic", 3.0),
+        ("perfect", 3.0), ("superb", 3.0), ("wonderful", 3.0), ("outstanding", 3.0), ("love", 2.5),
+        ("great", 2.0), ("nice", 1.5), ("good", 1.0), ("thanks", 1.0), ("cool", 1.5), ("neat", 1.5),
+        ("exactly", 1.5), ("precisely", 1.5), ("works", 1.0), ("beautiful", 2.5), ("helpful", 1.5),
+        ("impressive", 2.5), ("clean", 1.0), ("elegant", 2.0), ("yes", 0.5),
+    ];
+
+    /// Words and phrases that signal confusion, 1-3.
+    const CONFUSED: &[(&str, f64)] = &[
+        ("confused", 3.0), ("confusing", 2.5), ("unclear", 2.5), ("lost", 2.0), ("unsure", 2.0),
+        ("puzzled", 2.5), ("huh", 2.0), ("baffled", 3.0), ("understand", 1.5), ("clarify", 2.0),
+        ("mean", 1.0), ("why", 0.5), ("wait", 1.0), ("weird", 1.5), ("strange", 1.5),
+    ];
+
+    /// Words that make the confusion lexicon's weaker entries count: "what do you mean".
+    const CONFUSION_CUES: &[&str] = &["what", "how", "don't", "not", "no"];
+
+    const NEGATIONS: &[&str] = &["not", "no", "never", "don't", "doesn't", "isn't", "wasn't", "didn't", "hardly"];
+    const INTENSIFIERS: &[&str] = &["very", "really", "so", "super", "extremely", "totally", "absolutely", "incredibly"];
+
+    /// Stands in for clause-ending punctuation among the words.
+    const CLAUSE_END: &str = ".";
+    /// Words back a negation or intensifier reaches.
+    const SCOPE: usize = 3;
+    /// Share of a negated word's valence that survives, flipped: "not great" is mildly negative.
+    const NEGATION_FACTOR: f64 = -0.5;
+    const INTENSIFIER_FACTOR: f64 = 1.3;
+
+    pub struct LexiconModel;
+
+    impl SentimentBackend for LexiconModel {
+        fn id(&self) -> String {
+            "model".to_string()
+        }
+
+        fn score(&self, content: &str) -> Result<RawSentiment> {
+            let mut sentiment = RawSentiment::default();
+            for turn in human_turns(content) {
+                let words = tokenize(&turn);
+                let exclaimed = turn.contains('!');
+                let mut enthusiasm = 0.0;
+                let mut confusion = 0.0;
+                for (index, word) in words.iter().enumerate() {
+                    // Negations and intensifiers don't reach past the end of a clause
+                    let window = &words[index.saturating_sub(SCOPE)..index];
+                    let before = window.rsplit(|word| word == CLAUSE_END).next().unwrap_or_default();
+                    let modifier = modifier(before);
+                    if let Some(valence) = lookup(POSITIVE, word) {
+                        enthusiasm += valence * modifier;
+                    }
+                    if let Some(weight) = lookup(CONFUSED, word) {
+                        // "understand" and "mean" only count in "don't understand", "what do you mean"
+                        let cued = weight >= 2.0 || before.iter().any(|word| CONFUSION_CUES.contains(&word.as_str()));
+                        // "don't understand" is confusion; "not confused" isn't
+                        let negated = before.iter().any(|word| NEGATIONS.contains(&word.as_str()));
+                        if cued && (weight < 2.0 || !negated) {
+                            confusion += weight;
+                        }
+                    }
+                }
+                if exclaimed && enthusiasm > 0.0 {
+                    enthusiasm *= INTENSIFIER_FACTOR;
+                }
+                // One enthusiastic or confused message scores about one marker
+                sentiment.enthusiasm += (enthusiasm / 3.0).max(0.0);
+                sentiment.confusion += confusion / 3.0;
+            }
+            Ok(sentiment)
+        }
+    }
+
+    fn lookup(lexicon: &[(&str, f64)], word: &str) -> Option<f64> {
+        lexicon.iter().find(|(entry, _)| *entry == word).map(|(_, value)| *value)
+    }
+
+    fn modifier(before: &[String]) -> f64 {
+        let mut modifier = 1.0;
+        if before.iter().any(|word| NEGATIONS.contains(&word.as_str())) {
+            modifier *= NEGATION_FACTOR;
+        }
+        if before.last().is_some_and(|word| INTENSIFIERS.contains(&word.as_str())) {
+            modifier *= INTENSIFIER_FACTOR;
+        }
+        modifier
+    }
+
+    /// Lowercased words, with `CLAUSE_END` wherever punctuation ends a clause.
+    fn tokenize(text: &str) -> Vec<String> {
+        let mut words = Vec::new();
+        let mut word = String::new();
+        for c in text.chars() {
+            if c.is_alphanumeric() || c == '\'' {
+                word.extend(c.to_lowercase());
+                continue;
+            }
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            if matches!(c, ',' | '.' | ';' | ':' | '?' | '!') {
+                words.push(CLAUSE_END.to_string());
+            }
+        }
+        if !word.is_empty() {
+            words.push(word);
+        }
+        words
+    }
+
+    /// What the person typed: each `Human:` turn up to the next `Assistant:`.
+    fn human_turns(content: &str) -> Vec<String> {
+        let mut turns = Vec::new();
+        let mut current: Option<String> = None;
+        for line in content.lines() {
+            if let Some(prompt) = line.strip_prefix("Human:") {
+                turns.extend(current.replace(prompt.to_string()));
+            } else if line.starts_with("Assistant:") {
+                turns.extend(current.take());
+            } else if let Some(turn) = &mut current {
+                turn.push('\n');
+                turn.push_str(line);
+            }
+        }
+        turns.extend(current);
+        turns
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn scores_only_what_the_person_typed() {
+            let content = "Human: this is great, thanks!\nAssistant: Perfect, excellent, amazing.\nHuman: not great, I'm confused\nHuman: what do you mean by that? I'm not confused about the rest";
+            let sentiment = LexiconModel.score(content).unwrap();
+            // "great" and "thanks" with an exclamation; "not great" doesn't count
+            assert!((sentiment.enthusiasm - (2.0 + 1.0) * 1.3 / 3.0).abs() < 1e-9, "{:?}", sentiment);
+            // "confused", then "what ... mean"; "not confused" doesn't count
+            assert!((sentiment.confusion - (3.0 + 1.0) / 3.0).abs() < 1e-9, "{:?}", sentiment);
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_backends_are_scaled_by_their_calibration() {
+        let timeout = Duration::from_secs(COMMAND_TIMEOUT_SECS);
+        let backend =
+            CommandBackend { command: r#"cat > /dev/null; echo '{"enthusiasm": 4, "confusion": 0.5}'"#.to_string(), timeout };
+        assert_eq!(backend.score("Human: hi").unwrap(), RawSentiment { enthusiasm: 4.0, confusion: 0.5 });
+
+        let calibration = Calibration { enthusiasm: factor(2.0, 4.0), confusion: factor(3.0, 0.5), sessions: 5, calibrated_at: Utc::now() };
+        let sentiment = Sentiment { backend: Box::new(backend), calibration: Some(calibration) };
+        assert_eq!(sentiment.markers("Human: hi").unwrap(), (2, 3));
+        assert_eq!(factor(2.0, 0.0), 1.0);
+
+        let broken = CommandBackend { command: "echo nonsense".to_string(), timeout };
+        assert!(broken.score("").unwrap_err().to_string().contains("nonsense"));
+
+        let dir = tempfile::tempdir().unwrap();
+        save_calibration(dir.path(), "model", calibration).unwrap();
+        assert_eq!(load_calibrations(dir.path()).unwrap()["model"], calibration);
+    }
+
+    #[test]
+    fn a_command_that_never_reads_a_long_log_times_out() {
+        let stuck = CommandBackend { command: "sleep 30".to_string(), timeout: Duration::from_millis(500) };
+        let started = std::time::Instant::now();
+        let error = stuck.score(&"Human: hi\n".repeat(100_000)).unwrap_err();
+        assert!(format!("{:#}", error).contains("timed out"), "{:#}", error);
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+}
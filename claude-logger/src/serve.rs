@@ -0,0 +1,472 @@
+use crate::analyzer::SessionAnalyzer;
+use crate::config::Config;
+use crate::feed;
+use crate::filter;
+#[cfg(feature = "graphql")]
+use crate::graphql::{self, GraphQLContext};
+use crate::live::{self, LiveSession};
+use crate::logger::ClaudeLogger;
+use crate::timezone::DisplayZone;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::thread;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const FEED_LIMIT: usize = 20;
+/// Seconds between live metric events unless `?interval=` asks otherwise.
+const LIVE_INTERVAL_SECS: u64 = 2;
+
+/// Read-only HTTP API over the session archive.
+///
+/// Endpoints:
+/// - `GET /sessions?methodology=<name>&limit=<n>` - session metadata, newest first
+/// - `GET /sessions/{id}` - session summary with metrics and quality scores
+/// - `GET /report` - the full analysis report
+/// - `GET /stats?filter=<query>` - per-methodology aggregate statistics, optionally over the
+///   sessions a `--filter` query selects
+/// - `GET /feed.atom?limit=<n>` - Atom feed of recent sessions
+/// - `GET /live?session=<id>&interval=<secs>` - Server-Sent Events with the running session's
+///   metrics, a `metrics` event per interval and an `end` event when the session finishes
+/// - `POST /graphql` - GraphQL queries (requires the `graphql` feature)
+///
+/// When a token is configured every request must carry `Authorization: Bearer <token>`.
+pub struct ApiServer {
+    logs_dir: PathBuf,
+    config: Config,
+    token: Option<String>,
+    tls: Option<TlsFiles>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TlsFiles {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+struct ApiResponse {
+    status: u16,
+    body: String,
+    content_type: &'static str,
+}
+
+#[derive(Serialize)]
+struct ApiError {
+    error: String,
+}
+
+impl ApiServer {
+    pub fn new(logs_dir: &Path) -> Self {
+        Self {
+            logs_dir: logs_dir.to_path_buf(),
+            config: Config::default(),
+            token: None,
+            tls: None,
+        }
+    }
+
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token.filter(|token| !token.is_empty());
+        self
+    }
+
+    pub fn with_tls(mut self, tls: Option<TlsFiles>) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    pub fn run(&self, bind: &str, allow_unauthenticated: bool) -> Result<()> {
+        if self.token.is_none() && !is_loopback(bind) && !allow_unauthenticated {
+            return Err(anyhow::anyhow!(
+                "Refusing to serve full conversation logs on {} without a token; \
+                 set --token, CLAUDE_LOGGER_TOKEN or [serve] token, or pass --allow-unauthenticated",
+                bind
+            ));
+        }
+
+        let (server, scheme) = self.bind(bind)?;
+
+        println!("Serving session API on {}://{}", scheme, bind);
+        if self.token.is_none() {
+            eprintln!("Warning: API authentication is disabled");
+        }
+
+        for request in server.incoming_requests() {
+            self.handle(request);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tls")]
+    fn bind(&self, bind: &str) -> Result<(Server, &'static str)> {
+        let Some(tls) = &self.tls else {
+            let server = Server::http(bind)
+                .map_err(|e| anyhow::anyhow!("Failed to bind {}: {}", bind, e))?;
+            return Ok((server, "http"));
+        };
+
+        let ssl_config = tiny_http::SslConfig {
+            certificate: std::fs::read(&tls.cert)
+                .with_context(|| format!("Failed to read TLS certificate: {}", tls.cert.display()))?,
+            private_key: std::fs::read(&tls.key)
+                .with_context(|| format!("Failed to read TLS private key: {}", tls.key.display()))?,
+        };
+
+        let server = Server::https(bind, ssl_config)
+            .map_err(|e| anyhow::anyhow!("Failed to bind {} with TLS: {}", bind, e))?;
+        Ok((server, "https"))
+    }
+
+    #[cfg(not(feature = "tls"))]
+    fn bind(&self, bind: &str) -> Result<(Server, &'static str)> {
+        if self.tls.is_some() {
+            return Err(anyhow::anyhow!(
+                "TLS support is not compiled in; rebuild with `--features tls`"
+            ));
+        }
+
+        let server = Server::http(bind)
+            .map_err(|e| anyhow::anyhow!("Failed to bind {}: {}", bind, e))?;
+        Ok((server, "http"))
+    }
+
+    fn is_authorized(&self, request: &Request) -> bool {
+        let Some(token) = &self.token else {
+            return true;
+        };
+
+        request
+            .headers()
+            .iter()
+            .filter(|header| header.field.equiv("Authorization"))
+            .filter_map(|header| header.value.as_str().strip_prefix("Bearer "))
+            .any(|provided| constant_time_eq(provided.trim().as_bytes(), token.as_bytes()))
+    }
+
+    #[cfg_attr(not(feature = "graphql"), allow(unused_mut))]
+    fn handle(&self, mut request: Request) {
+        let url = request.url().to_string();
+        let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+
+        if !self.is_authorized(&request) {
+            let challenge = Header::from_bytes("WWW-Authenticate", "Bearer")
+                .expect("static header is valid");
+            Self::respond(request, Self::error(401, "Missing or invalid bearer token"), Some(challenge));
+            return;
+        }
+
+        if request.method() == &Method::Get && path == "/live" {
+            self.stream_live(request, query);
+            return;
+        }
+
+        let response = match request.method() {
+            Method::Get => {
+                let base_url = self.base_url(&request);
+                self.route(path, query, &base_url).unwrap_or_else(|e| Self::error(500, &e.to_string()))
+            }
+            #[cfg(feature = "graphql")]
+            Method::Post if path == "/graphql" => {
+                self.graphql(&mut request).unwrap_or_else(|e| Self::error(400, &e.to_string()))
+            }
+            _ => Self::error(405, "Only GET requests are supported"),
+        };
+
+        Self::respond(request, response, None);
+    }
+
+    /// Streams on a thread of its own so the other endpoints keep answering while a dashboard
+    /// is connected. The session is looked up first so a missing one still gets a JSON error.
+    fn stream_live(&self, request: Request, query: &str) {
+        let interval = query_param(query, "interval")
+            .and_then(|interval| interval.parse().ok())
+            .unwrap_or(LIVE_INTERVAL_SECS);
+
+        let mut logger = match ClaudeLogger::new_with_config(&self.logs_dir, self.config.clone()) {
+            Ok(logger) => logger,
+            Err(e) => return Self::respond(request, Self::error(500, &format!("{:#}", e)), None),
+        };
+        let session = match live::find_session(&logger, query_param(query, "session").as_deref()) {
+            Ok(session) => session,
+            Err(e) => return Self::respond(request, Self::error(404, &format!("{:#}", e)), None),
+        };
+
+        thread::spawn(move || {
+            let mut writer = request.into_writer();
+            // Failing to write means the client went away
+            let _ = stream_events(&mut logger, &session, interval, &mut writer);
+        });
+    }
+
+    fn respond(request: Request, response: ApiResponse, extra_header: Option<Header>) {
+        let header = Header::from_bytes("Content-Type", response.content_type)
+            .expect("static header is valid");
+        let mut http_response = Response::from_string(response.body)
+            .with_status_code(response.status)
+            .with_header(header);
+
+        if let Some(extra_header) = extra_header {
+            http_response.add_header(extra_header);
+        }
+
+        if let Err(e) = request.respond(http_response) {
+            eprintln!("Warning: Failed to send response: {}", e);
+        }
+    }
+
+    fn base_url(&self, request: &Request) -> String {
+        let scheme = if self.tls.is_some() { "https" } else { "http" };
+        let host = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("Host"))
+            .map(|header| header.value.as_str().to_string())
+            .unwrap_or_else(|| "localhost".to_string());
+        format!("{}://{}", scheme, host)
+    }
+
+    fn route(&self, path: &str, query: &str, base_url: &str) -> Result<ApiResponse> {
+        let mut analyzer = SessionAnalyzer::new_with_config(&self.logs_dir, &self.config)?;
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+        match segments.as_slice() {
+            ["sessions"] => {
+                let methodology = query_param(query, "methodology");
+                let limit = query_param(query, "limit")
+                    .and_then(|limit| limit.parse().ok())
+                    .unwrap_or(usize::MAX);
+
+                let sessions = analyzer.metadata().recent_sessions(methodology.as_deref(), limit);
+                Self::json(&sessions)
+            }
+            ["sessions", session_id] => {
+                if analyzer.metadata().get_session(session_id).is_none() {
+                    return Ok(Self::error(404, &format!("Session not found: {}", session_id)));
+                }
+                Self::json(&analyzer.get_session_summary(session_id)?)
+            }
+            ["report"] => Self::json(&analyzer.build_report()?),
+            ["feed.atom"] => {
+                let limit = query_param(query, "limit")
+                    .and_then(|limit| limit.parse().ok())
+                    .unwrap_or(FEED_LIMIT);
+
+                Ok(ApiResponse {
+                    status: 200,
+                    body: feed::sessions_feed(&analyzer, limit, Some(base_url), DisplayZone::Utc),
+                    content_type: "application/atom+xml",
+                })
+            }
+            ["stats"] => {
+                if let Some(expression) = query_param(query, "filter") {
+                    match filter::Query::parse(&expression, DisplayZone::Utc) {
+                        Ok(selected) => analyzer.retain_matching(&selected),
+                        Err(e) => return Ok(Self::error(400, &format!("{:#}", e))),
+                    };
+                }
+                let stats: BTreeMap<String, _> = analyzer
+                    .compare_methodologies()?
+                    .into_iter()
+                    .map(|(methodology, stats)| (methodology.to_string(), stats))
+                    .collect();
+                Self::json(&stats)
+            }
+            _ => Ok(Self::error(404, &format!("No such endpoint: {}", path))),
+        }
+    }
+
+    #[cfg(feature = "graphql")]
+    fn graphql(&self, request: &mut Request) -> Result<ApiResponse> {
+        let mut body = String::new();
+        request.as_reader().read_to_string(&mut body)
+            .context("Failed to read request body")?;
+
+        let graphql_request: juniper::http::GraphQLRequest = serde_json::from_str(&body)
+            .context("Invalid GraphQL request body")?;
+
+        let context = GraphQLContext::new(SessionAnalyzer::new_with_config(&self.logs_dir, &self.config)?);
+        let response = graphql_request.execute_sync(&graphql::schema(), &context);
+
+        let status = if response.is_ok() { 200 } else { 400 };
+        let body = serde_json::to_string_pretty(&response)
+            .context("Failed to serialize GraphQL response")?;
+        Ok(ApiResponse { status, body, content_type: "application/json" })
+    }
+
+    fn json<T: Serialize>(value: &T) -> Result<ApiResponse> {
+        let body = serde_json::to_string_pretty(value)
+            .context("Failed to serialize response to JSON")?;
+        Ok(ApiResponse { status: 200, body, content_type: "application/json" })
+    }
+
+    fn error(status: u16, message: &str) -> ApiResponse {
+        let body = serde_json::to_string(&ApiError { error: message.to_string() })
+            .unwrap_or_else(|_| "{}".to_string());
+        ApiResponse { status, body, content_type: "application/json" }
+    }
+}
+
+/// Write the response head and then one event per update, flushing each so it arrives at once.
+fn stream_events(logger: &mut ClaudeLogger, session: &LiveSession, interval: u64, writer: &mut dyn Write) -> Result<()> {
+    writer.write_all(
+        b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+    )?;
+    writer.flush()?;
+
+    live::follow(logger, session, interval, |metrics| {
+        let data = serde_json::to_string(metrics).context("Failed to serialize live metrics")?;
+        write!(writer, "event: metrics\ndata: {}\n\n", data)?;
+        writer.flush()?;
+        Ok(())
+    })?;
+
+    write!(writer, "event: end\ndata: {{\"session_id\":\"{}\"}}\n\n", session.id)?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn query_param(query: &str, name: &str) -> Option<String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| percent_decode(&value.replace('+', " ")))
+}
+
+/// Undo the `%XX` escapes in a query string value; malformed escapes are kept as they are.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            // `from_str_radix` alone would accept a sign, decoding `%+1`
+            .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn is_loopback(bind: &str) -> bool {
+    let host = bind.rsplit_once(':').map(|(host, _)| host).unwrap_or(bind);
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+
+    host == "localhost"
+        || host
+            .parse::<std::net::IpAddr>()
+            .map(|ip| ip.is_loopback())
+            .unwrap_or(false)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::{generate, sessions, Preset, Profile, SynthOptions};
+    use chrono::Utc;
+    use std::fs;
+
+    #[test]
+    fn streams_metrics_until_the_session_ends() {
+        let options = SynthOptions { profile: Profile::preset(Preset::Productive), ..sessions(1, 9, "api") };
+        let dir = tempfile::tempdir().unwrap();
+        let mut logger = ClaudeLogger::new_with_dir(dir.path()).unwrap();
+        let generated = generate(&options, dir.path(), Utc::now()).remove(0);
+        fs::create_dir_all(generated.session.log_file.parent().unwrap()).unwrap();
+        fs::write(&generated.session.log_file, &generated.log).unwrap();
+        let id = generated.session.id.clone();
+        // A finished session: one update, then the end event
+        logger.save_session(generated.session).unwrap();
+
+        let session = live::find_session(&logger, Some(&id)).unwrap();
+        let mut output = Vec::new();
+        stream_events(&mut logger, &session, 1, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let (head, events) = output.split_once("\r\n\r\n").unwrap();
+        assert!(head.contains("Content-Type: text/event-stream"));
+        let events: Vec<&str> = events.split_terminator("\n\n").collect();
+        assert_eq!(events.len(), 2);
+        let data: serde_json::Value =
+            serde_json::from_str(events[0].strip_prefix("event: metrics\ndata: ").unwrap()).unwrap();
+        assert_eq!(data["session_id"], id.as_str());
+        assert!(data["exchanges"].as_u64().unwrap() > 0);
+        assert_eq!(events[1], format!("event: end\ndata: {{\"session_id\":\"{}\"}}", id));
+    }
+
+    #[test]
+    fn requires_the_bearer_token_and_refuses_open_non_loopback_binds() {
+        let request = |authorization: Option<&str>| -> Request {
+            let request = tiny_http::TestRequest::new();
+            match authorization {
+                Some(value) => request.with_header(Header::from_bytes("Authorization", value).unwrap()),
+                None => request,
+            }
+            .into()
+        };
+        let server = ApiServer::new(Path::new("/logs")).with_token(Some("s3cret".to_string()));
+        assert!(server.is_authorized(&request(Some("Bearer s3cret"))));
+        assert!(!server.is_authorized(&request(Some("Bearer wrong!"))));
+        assert!(!server.is_authorized(&request(Some("Bearer s3cret-and-more"))));
+        assert!(!server.is_authorized(&request(Some("Basic s3cret"))));
+        assert!(!server.is_authorized(&request(None)));
+
+        let open = ApiServer::new(Path::new("/logs")).with_token(Some(String::new()));
+        assert!(open.is_authorized(&request(None)));
+        let error = open.run("0.0.0.0:8080", false).unwrap_err();
+        assert!(error.to_string().contains("without a token"), "{}", error);
+
+        assert!(constant_time_eq(b"s3cret", b"s3cret"));
+        assert!(!constant_time_eq(b"s3cret", b"s3cre"));
+        assert!(!constant_time_eq(b"", b"s3cret"));
+    }
+
+    #[test]
+    fn recognizes_loopback_binds() {
+        for bind in ["127.0.0.1:8080", "[::1]:8080", "localhost:80", "localhost"] {
+            assert!(is_loopback(bind), "{}", bind);
+        }
+        for bind in ["0.0.0.0:8080", "[::]:8080", "192.168.1.5:80", "example.com:80"] {
+            assert!(!is_loopback(bind), "{}", bind);
+        }
+    }
+
+    #[test]
+    fn decodes_query_parameters() {
+        let param = |query: &str| query_param(query, "filter").unwrap();
+        assert_eq!(param("filter=tag%3Aapi+since%207d"), "tag:api since 7d");
+        assert_eq!(param("limit=5&filter=caf%C3%A9"), "café");
+        assert_eq!(param("filter=100%"), "100%");
+        assert_eq!(param("filter=%4"), "%4");
+        assert_eq!(param("filter=%zz%20"), "%zz ");
+        assert_eq!(param("filter=%+1"), "% 1");
+        assert_eq!(percent_decode("%+1%-1"), "%+1%-1");
+        assert_eq!(query_param("limit=5", "filter"), None);
+    }
+}
@@ -1,6 +1,12 @@
+use crate::migrate;
+use crate::patterns::SessionQuality;
+use crate::pty::CaptureMode;
+use crate::terminal::TerminalInfo;
 use chrono::{DateTime, Duration, Utc};
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,60 +18,485 @@ pub struct SessionMetadata {
     pub working_directory: PathBuf,
     pub command: String,
     pub log_file: PathBuf,
-    pub duration: Option<Duration>,
+    /// `log_file` with terminal escapes stripped, written when the session completes
+    #[serde(default)]
+    pub clean_log_file: Option<PathBuf>,
+    /// `format@version` of the parser that wrote `clean_log_file`; a stale copy is re-read from the log
+    #[serde(default)]
+    pub log_parser: Option<String>,
+    /// Claude Code's own JSONL conversation file for this session, when found
+    #[serde(default)]
+    pub transcript_file: Option<PathBuf>,
+    /// Session length in whole seconds; see `duration()`
+    #[serde(default, alias = "duration_secs")]
+    pub duration_seconds: Option<i64>,
+    /// Set when `duration_seconds` and `end_time` were estimated by `backfill-durations`, not recorded
+    #[serde(default)]
+    pub duration_estimated: bool,
     pub end_time: Option<DateTime<Utc>>,
     pub features_worked_on: Vec<String>,
     pub creative_energy: Option<u8>,
+    #[serde(default)]
+    pub terminal: Option<TerminalInfo>,
+    #[serde(default)]
+    pub tmux_pane: Option<String>,
+    /// How the output was captured; `pipe` when no pseudo-terminal was available
+    #[serde(default)]
+    pub capture_mode: CaptureMode,
+    /// Set while the session is under a legal hold
+    #[serde(default)]
+    pub hold: Option<Hold>,
+    #[serde(default)]
+    pub archived_remote: Option<RemoteArchive>,
+    /// Commit that recorded the log, used to read it back once the file is gone
+    #[serde(default)]
+    pub git_commit: Option<String>,
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
+    /// Free-form notes about the whole session, oldest first
+    #[serde(default)]
+    pub notes: Vec<SessionNote>,
+    #[serde(default)]
+    pub review_state: ReviewState,
+    /// Follow-up work detected at the end of the conversation
+    #[serde(default)]
+    pub action_items: Vec<ActionItem>,
+    #[serde(default)]
+    pub reminders: Vec<Reminder>,
+    /// Free-form labels, e.g. for excluding scratch experiments from reports
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Why the session guard stopped claude, when it did
+    #[serde(default)]
+    pub termination_reason: Option<String>,
+    /// Tokens used and what they cost, from the transcript or Claude Code's `/cost` output
+    #[serde(default)]
+    pub usage: Option<TokenUsage>,
+    /// Files and lines changed in the project's git working tree while the session ran
+    #[serde(default)]
+    pub project_changes: Option<ProjectChanges>,
+    /// Each time Claude Code compacted the conversation, oldest first
+    #[serde(default)]
+    pub compactions: Vec<CompactionEvent>,
+    /// Who recorded the session, in a logs repository shared by a team
+    #[serde(default)]
+    pub author: Option<String>,
+    /// How much of `duration_seconds` was active rather than idle
+    #[serde(default)]
+    pub activity: Option<ActivityBreakdown>,
+    /// Team-defined fields, set with `--meta key=value`
+    #[serde(default)]
+    pub extra: HashMap<String, serde_json::Value>,
+    /// The session this one resumed with `claude --resume`/`--continue`
+    #[serde(default)]
+    pub parent_session_id: Option<String>,
+    /// Metrics and scores from the last analysis, the baseline `reanalyze` compares against
+    #[serde(default)]
+    pub analysis: Option<AnalysisSnapshot>,
 }
 
+/// A session's metrics and quality scores as computed at `analyzed_at`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisSnapshot {
+    pub analyzed_at: DateTime<Utc>,
+    pub metrics: AnalysisMetrics,
+    pub quality: SessionQuality,
+}
+
+/// A legal hold, keeping a session's metadata and logs from being archived, merged or deleted
+/// until it is released.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Hold {
+    pub reason: String,
+    pub placed_at: DateTime<Utc>,
+}
+
+/// A named stretch of time, such as a CLAUDE.md experiment, that reports compare.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Phase {
+    pub name: String,
+    pub started_at: DateTime<Utc>,
+    /// Unset while the phase is still running
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+impl Phase {
+    pub fn contains(&self, timestamp: DateTime<Utc>) -> bool {
+        timestamp >= self.started_at && self.ended_at.is_none_or(|ended_at| timestamp < ended_at)
+    }
+}
+
+/// A scheduled nudge to revisit a session.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Reminder {
+    pub note: String,
+    pub due_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    /// Set once the daemon has announced the reminder
+    pub notified: bool,
+    pub done: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ActionItem {
+    pub text: String,
+    pub state: ActionState,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActionState {
+    #[default]
+    Open,
+    Done,
+    Dismissed,
+}
+
+impl std::fmt::Display for ActionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActionState::Open => write!(f, "Open"),
+            ActionState::Done => write!(f, "Done"),
+            ActionState::Dismissed => write!(f, "Dismissed"),
+        }
+    }
+}
+
+/// Where a session stands in the retrospective review queue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ValueEnum)]
+pub enum ReviewState {
+    #[default]
+    Unreviewed,
+    Reviewed,
+    Flagged,
+}
+
+impl std::fmt::Display for ReviewState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReviewState::Unreviewed => write!(f, "Unreviewed"),
+            ReviewState::Reviewed => write!(f, "Reviewed"),
+            ReviewState::Flagged => write!(f, "Flagged"),
+        }
+    }
+}
+
+/// A reviewer's note attached to a range of lines in a session log.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Annotation {
+    pub category: String,
+    pub note: String,
+    /// First annotated line, 1-based
+    pub start_line: usize,
+    /// Last annotated line, inclusive
+    pub end_line: usize,
+    /// The annotated lines as they read when the note was made
+    pub excerpt: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A free-form note about a session, added after the fact.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SessionNote {
+    pub created_at: DateTime<Utc>,
+    pub text: String,
+}
+
+/// Location and checksum of a session log archived to remote storage.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RemoteArchive {
+    pub key: String,
+    pub sha256: String,
+    pub size: u64,
+    pub chunks: usize,
+    pub archived_at: DateTime<Utc>,
+}
+
+/// A development methodology, named by a built-in or by a `[methodology]` rule in the config.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
-pub enum Methodology {
-    ContextDriven,
-    CommandBased,
-    Unknown,
+#[serde(transparent)]
+pub struct Methodology(Cow<'static, str>);
+
+/// Parse `key=value` for `--meta`; values that are valid JSON keep their type, anything else is a string.
+pub fn parse_extra_field(field: &str) -> Result<(String, serde_json::Value), String> {
+    let (key, value) = field.split_once('=').ok_or_else(|| format!("expected key=value, got '{}'", field))?;
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(format!("missing key in '{}'", field));
+    }
+    let value = serde_json::from_str(value).unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+    Ok((key.to_string(), value))
+}
+
+impl SessionMetadata {
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration_seconds.map(Duration::seconds)
+    }
+
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration_seconds = Some(duration.num_seconds());
+    }
+
+    /// Non-interactive runs (`claude -p` / `--print`) rather than conversations.
+    pub fn is_headless(&self) -> bool {
+        self.command
+            .split_whitespace()
+            .any(|arg| arg == "-p" || arg == "--print" || arg.starts_with("--print="))
+    }
+
+    /// Where the cleaned copy of `log_file` lives: `<id>.clean.log` beside it.
+    pub fn clean_log_path(&self) -> PathBuf {
+        self.uncompressed_log_file().with_extension("clean.log")
+    }
+
+    /// Key identifying this session's log in a `LogStore`; the same once the log is compressed.
+    pub fn log_key(&self) -> String {
+        self.uncompressed_log_file()
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| format!("{}.log", self.id))
+    }
+
+    /// `log_file` without the `.gz` that `archive` adds.
+    fn uncompressed_log_file(&self) -> PathBuf {
+        match self.log_file.extension() {
+            Some(extension) if extension == "gz" => self.log_file.with_extension(""),
+            _ => self.log_file.clone(),
+        }
+    }
+}
+
+impl Methodology {
+    pub const CONTEXT_DRIVEN: Methodology = Methodology(Cow::Borrowed("ContextDriven"));
+    pub const COMMAND_BASED: Methodology = Methodology(Cow::Borrowed("CommandBased"));
+    pub const UNKNOWN: Methodology = Methodology(Cow::Borrowed("Unknown"));
+
+    const BUILT_IN: [(Methodology, &'static str); 3] = [
+        (Methodology::CONTEXT_DRIVEN, "Context-Driven"),
+        (Methodology::COMMAND_BASED, "Command-Based"),
+        (Methodology::UNKNOWN, "Unknown"),
+    ];
+
+    /// Parse a user-supplied methodology name. Built-in names are matched in any case, hyphenated
+    /// or joined; any other non-empty name is a user-defined methodology, kept as written.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        let joined = name.replace(['-', '_', ' '], "").to_lowercase();
+        let built_in = Self::BUILT_IN.into_iter().find(|(methodology, _)| methodology.0.to_lowercase() == joined);
+        Some(built_in.map_or_else(|| Methodology(Cow::Owned(name.to_string())), |(methodology, _)| methodology))
+    }
+
+    /// The name as stored in the metadata.
+    pub fn name(&self) -> &str {
+        &self.0
+    }
 }
 
 impl std::fmt::Display for Methodology {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Methodology::ContextDriven => write!(f, "Context-Driven"),
-            Methodology::CommandBased => write!(f, "Command-Based"),
-            Methodology::Unknown => write!(f, "Unknown"),
+        match Self::BUILT_IN.iter().find(|(methodology, _)| methodology == self) {
+            Some((_, label)) => write!(f, "{}", label),
+            None => write!(f, "{}", self.0),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AnalysisMetrics {
     pub exchanges: usize,
     pub code_blocks: usize,
+    /// File changes made through the edit tools, rather than code merely shown
+    pub file_edits: usize,
     pub questions_asked: usize,
     pub enthusiasm_markers: usize,
     pub confusion_markers: usize,
     pub compaction_indicators: usize,
+    /// Compiler errors, test failures and tracebacks in the output
+    pub error_events: usize,
+    /// Claude apologizing or starting over ("I apologize", "let me fix that")
+    pub retry_events: usize,
+    /// Exact counts, only known for sessions linked to a Claude Code transcript
+    pub tool_calls: usize,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Matches per user-defined category from `patterns.toml`
+    pub custom_markers: BTreeMap<String, usize>,
+    /// Calls per tool (Edit, Bash, Read, ...), counted from the `⏺ Tool(...)` lines in the
+    /// log, or exactly from the linked transcript
+    pub tool_usage: BTreeMap<String, usize>,
 }
 
-impl Default for AnalysisMetrics {
-    fn default() -> Self {
-        Self {
-            exchanges: 0,
-            code_blocks: 0,
-            questions_asked: 0,
-            enthusiasm_markers: 0,
-            confusion_markers: 0,
-            compaction_indicators: 0,
+/// What changed in a project's working tree during a session, from `git diff --numstat`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectChanges {
+    pub files_touched: usize,
+    pub lines_added: u64,
+    pub lines_removed: u64,
+    /// Paths relative to the repository root, sorted
+    pub files: Vec<String>,
+}
+
+impl ProjectChanges {
+    pub fn describe(&self) -> String {
+        format!("{} files, +{} -{} lines", self.files_touched, self.lines_added, self.lines_removed)
+    }
+}
+
+/// One time Claude Code compacted the conversation to free up context.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionEvent {
+    /// When it happened; only known when the session's transcript was found
+    #[serde(default)]
+    pub at: Option<DateTime<Utc>>,
+    /// Prompts sent before it
+    pub after_exchanges: usize,
+    #[serde(default)]
+    pub trigger: CompactionTrigger,
+    /// Tokens in context when it was compacted, when the transcript records it
+    #[serde(default)]
+    pub tokens_before: Option<u64>,
+}
+
+/// Whether Claude Code compacted on its own as the context filled up, or was asked to with `/compact`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompactionTrigger {
+    Auto,
+    Manual,
+    #[default]
+    Unknown,
+}
+
+impl std::fmt::Display for CompactionTrigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompactionTrigger::Auto => write!(f, "auto"),
+            CompactionTrigger::Manual => write!(f, "manual"),
+            CompactionTrigger::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// A session's wall-clock time split into active and idle, where idle is every stretch of more
+/// than `activity::IDLE_GAP_SECS` with no output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivityBreakdown {
+    pub active_secs: i64,
+    pub idle_secs: i64,
+    pub longest_idle_secs: i64,
+    pub source: ActivitySource,
+}
+
+impl ActivityBreakdown {
+    pub fn idle_percent(&self) -> f64 {
+        let total = self.active_secs + self.idle_secs;
+        if total == 0 { 0.0 } else { self.idle_secs as f64 * 100.0 / total as f64 }
+    }
+
+    /// Two captures of one session back to back, as when they are merged.
+    pub fn combine(&self, other: &ActivityBreakdown) -> ActivityBreakdown {
+        ActivityBreakdown {
+            active_secs: self.active_secs + other.active_secs,
+            idle_secs: self.idle_secs + other.idle_secs,
+            longest_idle_secs: self.longest_idle_secs.max(other.longest_idle_secs),
+            source: self.source,
+        }
+    }
+}
+
+/// What activity was measured from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActivitySource {
+    /// The log growing, sampled while the session was captured
+    Heartbeats,
+    /// Message timestamps in the Claude Code transcript
+    Transcript,
+}
+
+/// Token counts and spend for one session, or summed over several.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_creation_tokens: u64,
+    /// US dollars
+    pub cost: f64,
+}
+
+impl TokenUsage {
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cost += other.cost;
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_read_tokens + self.cache_creation_tokens
+    }
+}
+
+impl AnalysisMetrics {
+    /// Add another session's counts to these.
+    pub fn add(&mut self, other: &AnalysisMetrics) {
+        self.exchanges += other.exchanges;
+        self.code_blocks += other.code_blocks;
+        self.file_edits += other.file_edits;
+        self.questions_asked += other.questions_asked;
+        self.enthusiasm_markers += other.enthusiasm_markers;
+        self.confusion_markers += other.confusion_markers;
+        self.compaction_indicators += other.compaction_indicators;
+        self.error_events += other.error_events;
+        self.retry_events += other.retry_events;
+        self.tool_calls += other.tool_calls;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        for (name, count) in &other.custom_markers {
+            *self.custom_markers.entry(name.clone()).or_default() += count;
         }
+        for (tool, count) in &other.tool_usage {
+            *self.tool_usage.entry(tool.clone()).or_default() += count;
+        }
+    }
+
+    /// Each tool's calls and share of all calls, most used first.
+    pub fn tool_distribution(&self) -> Vec<(&str, usize, f64)> {
+        let total: usize = self.tool_usage.values().sum();
+        let mut tools: Vec<(&str, usize, f64)> = self
+            .tool_usage
+            .iter()
+            .map(|(tool, count)| (tool.as_str(), *count, *count as f64 * 100.0 / total as f64))
+            .collect();
+        tools.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        tools
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct MethodologyStats {
     pub sessions: usize,
+    #[serde(serialize_with = "serialize_seconds")]
     pub total_duration: Duration,
+    #[serde(serialize_with = "serialize_seconds")]
     pub avg_duration: Duration,
     pub creative_energy: Vec<u8>,
     pub avg_energy: Option<f64>,
     pub metrics: AnalysisMetrics,
+    /// Sessions with an active/idle breakdown, and their summed active and idle time
+    pub activity_sessions: usize,
+    #[serde(serialize_with = "serialize_seconds")]
+    pub active_duration: Duration,
+    #[serde(serialize_with = "serialize_seconds")]
+    pub idle_duration: Duration,
 }
 
 impl MethodologyStats {
@@ -77,14 +508,24 @@ impl MethodologyStats {
             creative_energy: Vec::new(),
             avg_energy: None,
             metrics: AnalysisMetrics::default(),
+            activity_sessions: 0,
+            active_duration: Duration::zero(),
+            idle_duration: Duration::zero(),
         }
     }
 
+    /// Share of the measured sessions' time spent idle.
+    pub fn idle_percent(&self) -> Option<f64> {
+        let total = self.active_duration + self.idle_duration;
+        (total > Duration::zero())
+            .then(|| self.idle_duration.num_seconds() as f64 * 100.0 / total.num_seconds() as f64)
+    }
+
     pub fn add_session(&mut self, session: &SessionMetadata, metrics: AnalysisMetrics) {
         self.sessions += 1;
         
-        if let Some(duration) = session.duration {
-            self.total_duration = self.total_duration + duration;
+        if let Some(duration) = session.duration() {
+            self.total_duration += duration;
             self.avg_duration = self.total_duration / self.sessions as i32;
         }
 
@@ -95,25 +536,46 @@ impl MethodologyStats {
             self.avg_energy = Some(avg);
         }
 
+        if let Some(activity) = &session.activity {
+            self.activity_sessions += 1;
+            self.active_duration += Duration::seconds(activity.active_secs);
+            self.idle_duration += Duration::seconds(activity.idle_secs);
+        }
+
         // Aggregate metrics
-        self.metrics.exchanges += metrics.exchanges;
-        self.metrics.code_blocks += metrics.code_blocks;
-        self.metrics.questions_asked += metrics.questions_asked;
-        self.metrics.enthusiasm_markers += metrics.enthusiasm_markers;
-        self.metrics.confusion_markers += metrics.confusion_markers;
-        self.metrics.compaction_indicators += metrics.compaction_indicators;
+        self.metrics.add(&metrics);
     }
 }
 
+impl Default for MethodologyStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Serialize a `Duration` as integer seconds rather than chrono's `[secs, nanos]`.
+fn serialize_seconds<S: serde::Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_i64(duration.num_seconds())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionsMetadata {
+    /// Layout version the data was last migrated to, one of `migrate::MIGRATIONS`; 0 for files
+    /// from before the version was recorded here
+    #[serde(default)]
+    pub schema_version: u32,
     pub sessions: HashMap<String, SessionMetadata>,
+    /// Experiment phases, oldest first
+    #[serde(default)]
+    pub phases: Vec<Phase>,
 }
 
 impl SessionsMetadata {
     pub fn new() -> Self {
         Self {
+            schema_version: migrate::CURRENT_VERSION,
             sessions: HashMap::new(),
+            phases: Vec::new(),
         }
     }
 
@@ -129,6 +591,25 @@ impl SessionsMetadata {
         self.sessions.get_mut(id)
     }
 
+    pub fn remove_session(&mut self, id: &str) -> Option<SessionMetadata> {
+        self.sessions.remove(id)
+    }
+
+    pub fn recent_sessions(&self, methodology_filter: Option<&str>, limit: usize) -> Vec<&SessionMetadata> {
+        let mut sessions: Vec<_> = self.sessions.values().collect();
+        
+        // Filter by methodology if specified
+        if let Some(method) = methodology_filter.and_then(Methodology::from_name) {
+            sessions.retain(|session| session.methodology == method);
+        }
+
+        // Sort by timestamp (newest first)
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.timestamp));
+        
+        // Apply limit
+        sessions.into_iter().take(limit).collect()
+    }
+
     pub fn sessions_by_methodology(&self) -> HashMap<Methodology, Vec<&SessionMetadata>> {
         let mut result = HashMap::new();
         
@@ -0,0 +1,129 @@
+use crate::analyzer::SessionAnalyzer;
+use crate::conversation::{parse_turns, Block, Speaker, Turn};
+use crate::highlight;
+use crate::redact::Redactor;
+use crate::timezone::DisplayZone;
+use anyhow::Result;
+
+/// Tool results with more lines than this start collapsed.
+const COLLAPSE_TOOL_LINES: usize = 3;
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; max-width: 52rem; margin: 2rem auto; padding: 0 1rem; color: #1f2328; background: #fff; line-height: 1.5; }
+header { border-bottom: 1px solid #d0d7de; margin-bottom: 1.5rem; }
+header h1 { font-size: 1.4rem; margin-bottom: 0.25rem; }
+header p { color: #59636e; margin-top: 0; }
+.turn { margin: 1rem 0; padding: 0.75rem 1rem; border-radius: 8px; }
+.turn .speaker { font-weight: 600; font-size: 0.85rem; text-transform: uppercase; letter-spacing: 0.04em; color: #59636e; }
+.human { background: #f0f6ff; border-left: 4px solid #0969da; }
+.assistant { background: #f6f8fa; border-left: 4px solid #8250df; }
+.other { background: #fff; border-left: 4px solid #d0d7de; }
+.text { white-space: pre-wrap; word-wrap: break-word; margin: 0.5rem 0; }
+pre { background: #2b303b; color: #c0c5ce; padding: 0.75rem; border-radius: 6px; overflow-x: auto; font-size: 0.85rem; }
+pre .lang { display: block; color: #65737e; font-size: 0.75rem; margin-bottom: 0.25rem; }
+details { margin: 0.5rem 0; }
+details summary { cursor: pointer; color: #59636e; font-size: 0.85rem; }
+details pre { background: #f6f8fa; color: #1f2328; border: 1px solid #d0d7de; }
+footer { color: #59636e; font-size: 0.8rem; border-top: 1px solid #d0d7de; margin-top: 2rem; padding-top: 0.5rem; }
+"#;
+
+/// A rendered transcript and the number of secrets scrubbed from it.
+pub struct SharedTranscript {
+    pub html: String,
+    pub redactions: usize,
+}
+
+/// Render a session as a standalone HTML page, redacting the log before anything is parsed.
+pub fn share_session(
+    analyzer: &SessionAnalyzer,
+    session_id: &str,
+    redactor: &Redactor,
+    zone: DisplayZone,
+    syntax_highlight: bool,
+) -> Result<SharedTranscript> {
+    let summary = analyzer.get_session_summary(session_id)?;
+    let (content, redactions) = redactor.apply(&analyzer.read_session_log(&summary.session)?);
+    let session = &summary.session;
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!(
+        "<title>{} &middot; {}</title>\n",
+        escape_html(&session.project),
+        escape_html(&session.id)
+    ));
+    html.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", STYLE));
+
+    html.push_str("<header>\n");
+    html.push_str(&format!("<h1>{}</h1>\n", escape_html(&session.project)));
+    let mut details = vec![
+        zone.format(session.timestamp, "%Y-%m-%d %H:%M"),
+        session.methodology.to_string(),
+    ];
+    if let Some(duration) = session.duration() {
+        details.push(format!("{}m", duration.num_minutes()));
+    }
+    details.push(format!("quality {:.1}/100", summary.quality.overall_score));
+    html.push_str(&format!("<p>{}</p>\n</header>\n", escape_html(&details.join(" · "))));
+
+    for turn in parse_turns(&content) {
+        render_turn(&mut html, &turn, syntax_highlight);
+    }
+
+    html.push_str(&format!(
+        "<footer>Session {} · exported by claude-logger · {} item(s) redacted</footer>\n",
+        escape_html(&session.id),
+        redactions
+    ));
+    html.push_str("</body>\n</html>\n");
+
+    Ok(SharedTranscript { html, redactions })
+}
+
+fn render_turn(html: &mut String, turn: &Turn, syntax_highlight: bool) {
+    let class = match turn.speaker {
+        Speaker::Human => "human",
+        Speaker::Assistant => "assistant",
+        Speaker::Other => "other",
+    };
+    let label = turn.speaker.label();
+    html.push_str(&format!(
+        "<section class=\"turn {}\">\n<div class=\"speaker\">{}</div>\n",
+        class, label
+    ));
+
+    for block in &turn.blocks {
+        match block {
+            Block::Text { text } => html.push_str(&format!("<div class=\"text\">{}</div>\n", escape_html(text))),
+            Block::Code { lang, code } => {
+                html.push_str("<pre><code>");
+                if !lang.is_empty() {
+                    html.push_str(&format!("<span class=\"lang\">{}</span>", escape_html(lang)));
+                }
+                let highlighted = syntax_highlight.then(|| highlight::html(code, lang)).flatten();
+                html.push_str(&highlighted.unwrap_or_else(|| escape_html(code)));
+                html.push_str("</code></pre>\n");
+            }
+            Block::ToolOutput { lines } => {
+                let open = if lines.len() > COLLAPSE_TOOL_LINES { "" } else { " open" };
+                html.push_str(&format!(
+                    "<details{}><summary>Tool output ({} line{})</summary><pre>{}</pre></details>\n",
+                    open,
+                    lines.len(),
+                    if lines.len() == 1 { "" } else { "s" },
+                    escape_html(&lines.join("\n"))
+                ));
+            }
+        }
+    }
+
+    html.push_str("</section>\n");
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
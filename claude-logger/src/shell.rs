@@ -0,0 +1,176 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const BLOCK_START: &str = "# BEGIN claude-logger shell alias";
+const BLOCK_END: &str = "# END claude-logger shell alias";
+
+/// Set to any non-empty value to run claude without logging.
+pub const OPT_OUT_VAR: &str = "CLAUDE_LOGGER_DISABLE";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+}
+
+impl Shell {
+    /// The login shell named by `SHELL`, when it is one we support.
+    pub fn detect() -> Option<Self> {
+        let shell = std::env::var("SHELL").ok()?;
+        match Path::new(&shell).file_name()?.to_str()? {
+            "bash" => Some(Shell::Bash),
+            "zsh" => Some(Shell::Zsh),
+            _ => None,
+        }
+    }
+
+    /// The startup file interactive shells read: `~/.bashrc`, or `.zshrc` in `ZDOTDIR` or home.
+    pub fn rc_file(self) -> Result<PathBuf> {
+        let home = dirs::home_dir().context("Failed to get home directory")?;
+        Ok(match self {
+            Shell::Bash => home.join(".bashrc"),
+            Shell::Zsh => std::env::var_os("ZDOTDIR").map(PathBuf::from).unwrap_or(home).join(".zshrc"),
+        })
+    }
+}
+
+/// The managed block: a `claude` function that runs claude through the logger from a terminal,
+/// and plain claude when piped, when opted out or when claude-logger isn't on the PATH.
+pub fn snippet() -> String {
+    format!(
+        r#"{start}
+# Log every interactive claude session; run `{var}=1 claude` to skip logging once.
+claude() {{
+  if [ -z "${{{var}:-}}" ] && [ -t 0 ] && [ -t 1 ] && command -v claude-logger >/dev/null 2>&1; then
+    claude-logger -- "$@"
+  else
+    command claude "$@"
+  fi
+}}
+{end}
+"#,
+        start = BLOCK_START,
+        end = BLOCK_END,
+        var = OPT_OUT_VAR
+    )
+}
+
+/// `content` with the managed block replaced by `block`, in place, or appended when it has none;
+/// `None` removes the block. Fails when the block has no end marker rather than dropping
+/// everything after its start.
+fn with_block(content: &str, block: Option<&str>) -> Result<String> {
+    let mut lines: Vec<&str> = Vec::new();
+    let mut in_block = false;
+    let mut position = None;
+    for line in content.lines() {
+        match line {
+            BLOCK_START => {
+                in_block = true;
+                position.get_or_insert(lines.len());
+            }
+            BLOCK_END => in_block = false,
+            _ if !in_block => lines.push(line),
+            _ => {}
+        }
+    }
+    if in_block {
+        anyhow::bail!("'{}' has no matching '{}' line; restore or remove it by hand", BLOCK_START, BLOCK_END);
+    }
+
+    let mut updated = String::new();
+    let position = position.unwrap_or(lines.len());
+    for (index, line) in lines.iter().enumerate() {
+        if index == position
+            && let Some(block) = block
+        {
+            updated.push_str(block);
+        }
+        updated.push_str(line);
+        updated.push('\n');
+    }
+    if position == lines.len()
+        && let Some(block) = block
+    {
+        if !updated.is_empty() && !updated.ends_with("\n\n") {
+            updated.push('\n');
+        }
+        updated.push_str(block);
+    }
+    Ok(updated)
+}
+
+/// Write the alias into `rc_file`, replacing an earlier copy. Returns whether the file changed.
+pub fn install(rc_file: &Path) -> Result<bool> {
+    rewrite(rc_file, Some(&snippet()))
+}
+
+/// Remove the alias from `rc_file`. Returns whether it was there.
+pub fn uninstall(rc_file: &Path) -> Result<bool> {
+    rewrite(rc_file, None)
+}
+
+fn rewrite(rc_file: &Path, block: Option<&str>) -> Result<bool> {
+    let existing = match fs::read_to_string(rc_file) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", rc_file.display())),
+    };
+    let updated = with_block(&existing, block).with_context(|| format!("Leaving {} unchanged", rc_file.display()))?;
+    if updated == existing {
+        return Ok(false);
+    }
+    fs::write(rc_file, updated).with_context(|| format!("Failed to write {}", rc_file.display()))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn installs_once_and_uninstalls_cleanly() {
+        let dir = tempfile::tempdir().unwrap();
+        let rc = dir.path().join(".zshrc");
+        fs::write(&rc, "export EDITOR=vim\n").unwrap();
+
+        assert!(install(&rc).unwrap());
+        assert!(!install(&rc).unwrap());
+        let installed = fs::read_to_string(&rc).unwrap();
+        assert!(installed.starts_with("export EDITOR=vim\n\n# BEGIN claude-logger shell alias\n"));
+        assert!(installed.contains("claude-logger -- \"$@\"") && installed.contains("command claude \"$@\""));
+        assert_eq!(installed.matches(BLOCK_START).count(), 1);
+
+        // Lines added after the block stay after it when the block is rewritten
+        fs::write(&rc, installed.replace(&snippet(), "# BEGIN claude-logger shell alias\nold\n# END claude-logger shell alias\n") + "alias ll='ls -l'\n").unwrap();
+        assert!(install(&rc).unwrap());
+        assert_eq!(fs::read_to_string(&rc).unwrap(), format!("export EDITOR=vim\n\n{}alias ll='ls -l'\n", snippet()));
+
+        assert!(uninstall(&rc).unwrap());
+        assert!(!uninstall(&rc).unwrap());
+        assert_eq!(fs::read_to_string(&rc).unwrap(), "export EDITOR=vim\n\nalias ll='ls -l'\n");
+    }
+
+    #[test]
+    fn leaves_a_block_without_an_end_marker_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let rc = dir.path().join(".bashrc");
+        let content = format!("{}\nold\nexport EDITOR=vim\nalias ll='ls -l'\n", BLOCK_START);
+        fs::write(&rc, &content).unwrap();
+
+        for result in [install(&rc), uninstall(&rc)] {
+            let error = format!("{:#}", result.unwrap_err());
+            assert!(error.contains("Leaving") && error.contains(BLOCK_END), "{}", error);
+        }
+        assert_eq!(fs::read_to_string(&rc).unwrap(), content);
+    }
+
+    #[test]
+    fn everything_after_the_separator_goes_to_claude() {
+        use clap::Parser;
+        let cli = crate::Cli::try_parse_from(["claude-logger", "--", "analyze", "-p", "--resume"]).unwrap();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.claude_args, ["analyze", "-p", "--resume"]);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::session::AnalysisMetrics;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Typed aggregates over a filtered set of sessions, for external dashboards.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AggregateStats {
+    /// Sessions matching the filter
+    pub sessions: usize,
+    /// Of those, sessions whose log could be read and analyzed
+    pub analyzed: usize,
+    pub first_session: Option<DateTime<Utc>>,
+    pub last_session: Option<DateTime<Utc>>,
+    pub by_methodology: BTreeMap<String, usize>,
+    pub by_project: BTreeMap<String, usize>,
+    /// Sessions per creative energy rating
+    pub by_energy: BTreeMap<u8, usize>,
+    /// Metric sums over analyzed sessions
+    pub totals: AnalysisMetrics,
+    pub minutes: Option<Distribution>,
+    pub exchanges: Option<Distribution>,
+    pub file_edits: Option<Distribution>,
+    pub overall_quality: Option<Distribution>,
+}
+
+/// Summary statistics of one value across sessions.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Distribution {
+    pub count: usize,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub median: f64,
+    pub p90: f64,
+}
+
+impl Distribution {
+    /// `None` when there are no values.
+    pub fn from_values(values: &[f64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let sum: f64 = sorted.iter().sum();
+
+        Some(Self {
+            count: sorted.len(),
+            sum,
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            mean: sum / sorted.len() as f64,
+            median: percentile(&sorted, 0.5),
+            p90: percentile(&sorted, 0.9),
+        })
+    }
+}
+
+/// Linear interpolation between the closest ranks of a sorted slice.
+fn percentile(sorted: &[f64], fraction: f64) -> f64 {
+    let rank = fraction * (sorted.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distribution_percentiles() {
+        let distribution = Distribution::from_values(&[4.0, 1.0, 3.0, 2.0, 10.0]).unwrap();
+
+        assert_eq!(distribution.count, 5);
+        assert_eq!((distribution.min, distribution.max), (1.0, 10.0));
+        assert_eq!(distribution.mean, 4.0);
+        assert_eq!(distribution.median, 3.0);
+        assert!((distribution.p90 - 7.6).abs() < 1e-9);
+        assert!(Distribution::from_values(&[]).is_none());
+    }
+}
@@ -0,0 +1,201 @@
+use crate::analyzer::SessionAnalyzer;
+use crate::conversation::{parse_turns, Block, Speaker};
+use crate::edits::parse_edits;
+use crate::phases::phase_at;
+use crate::session::{ActionState, SessionMetadata};
+use crate::timezone::DisplayZone;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Longest title taken from a session's opening prompt.
+const TITLE_CHARS: usize = 80;
+
+/// Files listed per chapter before the rest are summarized as a count.
+const CHAPTER_FILES: usize = 5;
+
+/// One session's part in a project's storyline.
+#[derive(Debug, Clone, Serialize)]
+pub struct Chapter {
+    pub session_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub title: String,
+    pub summary: String,
+    pub phase: Option<String>,
+    /// Files edited, in first-touched order
+    pub files: Vec<String>,
+    /// Reviewer annotations and completed follow-ups
+    pub decisions: Vec<String>,
+    pub open_items: Vec<String>,
+    pub log_file: String,
+    pub git_commit: Option<String>,
+}
+
+/// Every session in `project`, oldest first.
+pub fn storyline(analyzer: &SessionAnalyzer, project: &str) -> Result<Vec<Chapter>> {
+    let mut sessions: Vec<&SessionMetadata> = analyzer
+        .metadata()
+        .sessions
+        .values()
+        .filter(|session| session.project == project)
+        .collect();
+    if sessions.is_empty() {
+        return Err(anyhow::anyhow!("No sessions found for project: {}", project));
+    }
+    sessions.sort_by_key(|session| session.timestamp);
+
+    Ok(sessions.into_iter().map(|session| chapter(analyzer, session)).collect())
+}
+
+fn chapter(analyzer: &SessionAnalyzer, session: &SessionMetadata) -> Chapter {
+    let content = analyzer.read_session_log(session).unwrap_or_else(|e| {
+        eprintln!("Warning: Failed to read log for session {}: {:#}", session.id, e);
+        String::new()
+    });
+
+    let mut files: Vec<String> = Vec::new();
+    for edit in parse_edits(&content) {
+        if !files.contains(&edit.file) {
+            files.push(edit.file);
+        }
+    }
+
+    let mut decisions: Vec<String> = session
+        .annotations
+        .iter()
+        .map(|annotation| format!("[{}] {}", annotation.category, annotation.note))
+        .collect();
+    decisions.extend(
+        session
+            .action_items
+            .iter()
+            .filter(|item| item.state == ActionState::Done)
+            .map(|item| format!("Done: {}", item.text)),
+    );
+
+    Chapter {
+        session_id: session.id.clone(),
+        timestamp: session.timestamp,
+        title: title(&content, session),
+        summary: summary(analyzer, session),
+        phase: phase_at(analyzer.metadata(), session.timestamp).map(|phase| phase.name.clone()),
+        files,
+        decisions,
+        open_items: session
+            .action_items
+            .iter()
+            .filter(|item| item.state == ActionState::Open)
+            .map(|item| item.text.clone())
+            .collect(),
+        log_file: session.log_file.display().to_string(),
+        git_commit: session.git_commit.clone(),
+    }
+}
+
+/// The first line of the opening prompt, or the recorded features when there is none.
+pub fn title(content: &str, session: &SessionMetadata) -> String {
+    let prompt = parse_turns(content)
+        .into_iter()
+        .filter(|turn| turn.speaker == Speaker::Human)
+        .flat_map(|turn| turn.blocks)
+        .find_map(|block| match block {
+            Block::Text { text } => text.lines().map(str::trim).find(|line| !line.is_empty()).map(str::to_string),
+            _ => None,
+        });
+
+    match prompt {
+        Some(prompt) if prompt.chars().count() > TITLE_CHARS => {
+            format!("{}...", prompt.chars().take(TITLE_CHARS).collect::<String>().trim_end())
+        }
+        Some(prompt) => prompt,
+        None if !session.features_worked_on.is_empty() => session.features_worked_on.join(", "),
+        None => format!("{} session", session.methodology),
+    }
+}
+
+fn summary(analyzer: &SessionAnalyzer, session: &SessionMetadata) -> String {
+    let mut parts = vec![session.methodology.to_string()];
+
+    if let Some(duration) = session.duration() {
+        parts.push(format!("{}m", duration.num_minutes()));
+    }
+    if let Ok(summary) = analyzer.get_session_summary(&session.id) {
+        parts.push(format!("{} exchanges", summary.metrics.exchanges));
+        parts.push(format!("{} file edits", summary.metrics.file_edits));
+        parts.push(format!("quality {:.0}/100", summary.quality.overall_score));
+    }
+    if let Some(energy) = session.creative_energy {
+        parts.push(format!("energy {}/3", energy));
+    }
+
+    parts.join(", ")
+}
+
+/// Render the storyline as a markdown document.
+pub fn render_markdown(project: &str, chapters: &[Chapter], zone: DisplayZone) -> String {
+    let mut markdown = format!("# {}: the story so far\n\n", project);
+
+    if let (Some(first), Some(last)) = (chapters.first(), chapters.last()) {
+        let files: HashSet<&str> =
+            chapters.iter().flat_map(|chapter| chapter.files.iter().map(String::as_str)).collect();
+        markdown.push_str(&format!(
+            "{} sessions between {} and {}, touching {} files.\n\n",
+            chapters.len(),
+            zone.format(first.timestamp, "%Y-%m-%d"),
+            zone.format(last.timestamp, "%Y-%m-%d"),
+            files.len()
+        ));
+    }
+
+    let mut phase: Option<&str> = None;
+    for chapter in chapters {
+        if chapter.phase.as_deref() != phase {
+            phase = chapter.phase.as_deref();
+            if let Some(name) = phase {
+                markdown.push_str(&format!("---\n\n**Phase: {}**\n\n", name));
+            }
+        }
+
+        markdown.push_str(&format!(
+            "## {} - {}\n\n",
+            zone.format(chapter.timestamp, "%Y-%m-%d %H:%M"),
+            chapter.title
+        ));
+        markdown.push_str(&format!("{}\n\n", chapter.summary));
+
+        if !chapter.files.is_empty() {
+            let shown: Vec<String> = chapter.files.iter().take(CHAPTER_FILES).map(|file| format!("`{}`", file)).collect();
+            let more = chapter.files.len().saturating_sub(CHAPTER_FILES);
+            markdown.push_str(&format!("Files: {}", shown.join(", ")));
+            if more > 0 {
+                markdown.push_str(&format!(" and {} more", more));
+            }
+            markdown.push_str("\n\n");
+        }
+
+        if !chapter.decisions.is_empty() {
+            markdown.push_str("Key decisions:\n");
+            for decision in &chapter.decisions {
+                markdown.push_str(&format!("- {}\n", decision));
+            }
+            markdown.push('\n');
+        }
+
+        if !chapter.open_items.is_empty() {
+            markdown.push_str("Left open:\n");
+            for item in &chapter.open_items {
+                markdown.push_str(&format!("- [ ] {}\n", item));
+            }
+            markdown.push('\n');
+        }
+
+        markdown.push_str(&format!("Session `{}`, [log](<{}>)", chapter.session_id, chapter.log_file));
+        if let Some(commit) = &chapter.git_commit {
+            markdown.push_str(&format!(", commit `{}`", &commit[..commit.len().min(7)]));
+        }
+        markdown.push_str("\n\n");
+    }
+
+    markdown.trim_end().to_string() + "\n"
+}
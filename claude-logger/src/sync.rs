@@ -0,0 +1,417 @@
+use crate::logger::ClaudeLogger;
+use crate::notify::{Notifier, PeriodicChecks};
+use crate::project;
+use crate::session::{RemoteArchive, SessionMetadata};
+use crate::storage::LogStore;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+/// Compressed logs are uploaded in chunks of this size so interrupted syncs can resume.
+pub const CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+const STATE_FILE: &str = "sync_state.json";
+
+/// Upload progress persisted between runs, keyed by session id.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncState {
+    uploads: HashMap<String, PendingUpload>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PendingUpload {
+    sha256: String,
+    chunks: usize,
+    uploaded: Vec<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    session_id: String,
+    archive: RemoteArchive,
+    chunk_sha256: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct SyncOutcome {
+    pub archived: Vec<String>,
+    pub failed: Vec<(String, String)>,
+    pub pruned: usize,
+    /// The logs repository commit removing the pruned logs, if any were tracked
+    pub commit: Option<String>,
+}
+
+impl SyncState {
+    fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read sync state: {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse sync state: {}", path.display()))
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize sync state")?;
+        fs::write(path, json).with_context(|| format!("Failed to write sync state: {}", path.display()))
+    }
+}
+
+/// Archive every completed session that isn't archived yet.
+pub fn sync_once(logger: &mut ClaudeLogger, prune_local: bool) -> Result<SyncOutcome> {
+    let pending: Vec<String> = logger
+        .metadata()
+        .sessions
+        .values()
+        .filter(|session| needs_archive(session))
+        .map(|session| session.id.clone())
+        .collect();
+    archive_sessions(logger, &pending, prune_local)
+}
+
+/// Whether `session` is complete, still local and not archived yet.
+pub fn needs_archive(session: &SessionMetadata) -> bool {
+    session.end_time.is_some() && session.archived_remote.is_none() && session.log_file.exists()
+}
+
+/// Archive the given sessions, skipping any that don't need it.
+pub fn archive_sessions(logger: &mut ClaudeLogger, session_ids: &[String], prune_local: bool) -> Result<SyncOutcome> {
+    let state_path = logger.logs_dir().join(STATE_FILE);
+    let mut state = SyncState::load(&state_path)?;
+    let mut outcome = SyncOutcome::default();
+
+    let pending: Vec<SessionMetadata> = session_ids
+        .iter()
+        .filter_map(|id| logger.get_session(id))
+        .filter(|session| needs_archive(session))
+        .cloned()
+        .collect();
+
+    let store = logger
+        .log_store()
+        .context("Sync requires a remote storage backend; set [storage] in the config file")?;
+
+    let mut archived = Vec::new();
+    for session in pending {
+        match archive_session(store, &session, &mut state, &state_path) {
+            Ok(archive) => archived.push((session, archive)),
+            Err(e) => outcome.failed.push((session.id.clone(), format!("{:#}", e))),
+        }
+    }
+
+    if archived.is_empty() {
+        return Ok(outcome);
+    }
+
+    // Record every archive before removing anything, so a failed save never leaves a log that
+    // exists only remotely without the metadata pointing at it
+    let mut prunable = Vec::new();
+    for (mut session, archive) in archived {
+        session.archived_remote = Some(archive);
+        // A held session is still archived, but its local copy stays
+        if prune_local && session.hold.is_none() {
+            // The cleaned copy is rebuilt from the archive on demand
+            prunable.push((session.log_file.clone(), session.clean_log_file.take()));
+        }
+        outcome.archived.push(session.id.clone());
+        logger.add_session(session);
+    }
+    logger.save_metadata()?;
+
+    let mut removed = Vec::new();
+    for (log_file, clean_log_file) in prunable {
+        match fs::remove_file(&log_file) {
+            Ok(()) => {
+                outcome.pruned += 1;
+                removed.push(log_file);
+            }
+            Err(e) => eprintln!("Warning: Failed to remove local log {}: {}", log_file.display(), e),
+        }
+        if let Some(clean_log_file) = clean_log_file
+            && fs::remove_file(&clean_log_file).is_ok()
+        {
+            removed.push(clean_log_file);
+        }
+    }
+    if !removed.is_empty() {
+        let removed: Vec<&Path> = removed.iter().map(PathBuf::as_path).collect();
+        let message = format!("Remove {} local logs archived to remote storage", outcome.pruned);
+        outcome.commit = logger.git_repo().commit_changes(&removed, &[], &message)?;
+        if outcome.commit.is_some() {
+            logger.push_if_enabled();
+        }
+    }
+
+    Ok(outcome)
+}
+
+/// Run `sync_once` forever, sleeping `interval_secs` between passes.
+pub fn run_daemon(logger: &mut ClaudeLogger, interval_secs: u64, prune_local: bool) -> Result<()> {
+    println!("Syncing completed sessions and checking reminders every {}s (Ctrl-C to stop)...", interval_secs);
+
+    let notifier = Notifier::new(logger.config().notifications.clone());
+    let mut checks = PeriodicChecks::default();
+
+    loop {
+        if let Err(e) = logger.reload_metadata() {
+            eprintln!("Warning: Failed to reload metadata: {:#}", e);
+        }
+
+        match sync_once(logger, prune_local) {
+            Ok(outcome) => print_outcome(&outcome),
+            Err(e) => eprintln!("Warning: Sync pass failed: {:#}", e),
+        }
+
+        if let Err(e) = checks.run(logger, &notifier) {
+            eprintln!("Warning: Failed to check reminders: {:#}", e);
+        }
+        // Deliver what an earlier pass queued past the rate limits
+        notifier.flush();
+
+        thread::sleep(Duration::from_secs(interval_secs));
+    }
+}
+
+pub fn print_outcome(outcome: &SyncOutcome) {
+    for session_id in &outcome.archived {
+        println!("Archived {}", session_id);
+    }
+    for (session_id, error) in &outcome.failed {
+        eprintln!("Warning: Failed to archive {}: {}", session_id, error);
+    }
+    if outcome.pruned > 0 {
+        println!("Removed {} local log files (archived remotely)", outcome.pruned);
+    }
+    if let Some(commit) = &outcome.commit {
+        println!("Recorded in commit {}", &commit[..commit.len().min(7)]);
+    }
+}
+
+fn archive_session(
+    store: &dyn LogStore,
+    session: &SessionMetadata,
+    state: &mut SyncState,
+    state_path: &Path,
+) -> Result<RemoteArchive> {
+    // Encrypted logs stay encrypted in remote storage
+    let raw = project::read_stored_log(&session.log_file)?;
+    let compressed = compress(&raw)?;
+    let sha256 = hex_digest(&compressed);
+    let chunks: Vec<&[u8]> = compressed.chunks(CHUNK_SIZE).collect();
+    let key = format!("{}.gz", session.log_key());
+
+    // Start over if the log changed since the interrupted upload
+    let pending = state.uploads.entry(session.id.clone()).or_insert_with(|| PendingUpload {
+        sha256: sha256.clone(),
+        chunks: chunks.len(),
+        uploaded: Vec::new(),
+    });
+    if pending.sha256 != sha256 || pending.chunks != chunks.len() {
+        *pending = PendingUpload {
+            sha256: sha256.clone(),
+            chunks: chunks.len(),
+            uploaded: Vec::new(),
+        };
+    }
+
+    let mut chunk_sha256 = Vec::with_capacity(chunks.len());
+    for (index, chunk) in chunks.iter().enumerate() {
+        let expected = hex_digest(chunk);
+        chunk_sha256.push(expected.clone());
+
+        let already_uploaded = state
+            .uploads
+            .get(&session.id)
+            .is_some_and(|pending| pending.uploaded.contains(&index));
+        if already_uploaded {
+            continue;
+        }
+
+        let chunk_key = chunk_key(&key, index);
+        store.put(&chunk_key, chunk)?;
+
+        // Read the chunk back to verify the transfer
+        let stored = store.get(&chunk_key)?;
+        if hex_digest(&stored) != expected {
+            return Err(anyhow::anyhow!("Checksum mismatch after uploading {}", chunk_key));
+        }
+
+        if let Some(pending) = state.uploads.get_mut(&session.id) {
+            pending.uploaded.push(index);
+        }
+        state.save(state_path)?;
+    }
+
+    let archive = RemoteArchive {
+        key: key.clone(),
+        sha256,
+        size: compressed.len() as u64,
+        chunks: chunks.len(),
+        archived_at: Utc::now(),
+    };
+
+    // The manifest is written last and marks the archive as complete
+    let manifest = Manifest {
+        session_id: session.id.clone(),
+        archive: archive.clone(),
+        chunk_sha256,
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest).context("Failed to serialize manifest")?;
+    store.put(&format!("{}.manifest.json", key), &manifest_json)?;
+
+    state.uploads.remove(&session.id);
+    state.save(state_path)?;
+
+    Ok(archive)
+}
+
+/// The archive `sync` recorded for `log_key` in remote storage, from its manifest; for reading
+/// back a log whose metadata doesn't record the archive.
+pub fn find_archive(store: &dyn LogStore, log_key: &str) -> Result<RemoteArchive> {
+    let key = format!("{}.gz.manifest.json", log_key);
+    let manifest: Manifest = serde_json::from_slice(&store.get(&key)?)
+        .with_context(|| format!("Invalid archive manifest {}", key))?;
+    Ok(manifest.archive)
+}
+
+/// Download, verify and decompress an archived log.
+pub fn fetch_archive(store: &dyn LogStore, archive: &RemoteArchive) -> Result<Vec<u8>> {
+    let mut compressed = Vec::with_capacity(archive.size as usize);
+    for index in 0..archive.chunks {
+        compressed.extend(store.get(&chunk_key(&archive.key, index))?);
+    }
+
+    if hex_digest(&compressed) != archive.sha256 {
+        return Err(anyhow::anyhow!("Checksum mismatch for archived log {}", archive.key));
+    }
+
+    let mut raw = Vec::new();
+    GzDecoder::new(compressed.as_slice())
+        .read_to_end(&mut raw)
+        .with_context(|| format!("Failed to decompress {}", archive.key))?;
+    Ok(raw)
+}
+
+fn chunk_key(key: &str, index: usize) -> String {
+    format!("{}.part{:04}", key, index)
+}
+
+fn compress(data: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).context("Failed to compress log")?;
+    encoder.finish().context("Failed to compress log")
+}
+
+fn hex_digest(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::SessionAnalyzer;
+    use crate::config::Config;
+    use crate::storage::LocalStore;
+    use crate::synth::{generate, sessions};
+    use std::process::Command;
+
+    fn session_with_log(logs_dir: &Path, seed: u64) -> SessionMetadata {
+        let generated = generate(&sessions(1, seed, "api"), logs_dir, Utc::now()).remove(0);
+        fs::create_dir_all(generated.session.log_file.parent().unwrap()).unwrap();
+        fs::write(&generated.session.log_file, &generated.log).unwrap();
+        generated.session
+    }
+
+    #[test]
+    fn resumes_an_interrupted_upload_and_verifies_downloads() {
+        let dir = tempfile::tempdir().unwrap();
+        let session = session_with_log(dir.path(), 2);
+        let store = LocalStore::new(&dir.path().join("remote"));
+        let state_path = dir.path().join(STATE_FILE);
+
+        // An earlier run uploaded the only chunk, then stopped before writing the manifest
+        let compressed = compress(&project::read_stored_log(&session.log_file).unwrap()).unwrap();
+        let key = format!("{}.gz", session.log_key());
+        store.put(&chunk_key(&key, 0), b"uploaded before the interruption").unwrap();
+        let mut state = SyncState::default();
+        state.uploads.insert(
+            session.id.clone(),
+            PendingUpload { sha256: hex_digest(&compressed), chunks: 1, uploaded: vec![0] },
+        );
+        state.save(&state_path).unwrap();
+
+        let mut state = SyncState::load(&state_path).unwrap();
+        let archive = archive_session(&store, &session, &mut state, &state_path).unwrap();
+        assert_eq!(store.get(&chunk_key(&key, 0)).unwrap(), b"uploaded before the interruption");
+        assert!(SyncState::load(&state_path).unwrap().uploads.is_empty());
+        assert_eq!(find_archive(&store, &session.log_key()).unwrap().sha256, archive.sha256);
+        // The chunk skipped on resume doesn't match the archive
+        assert!(format!("{:#}", fetch_archive(&store, &archive).unwrap_err()).contains("Checksum mismatch"));
+
+        // Progress recorded for an older version of the log is discarded
+        let mut state = SyncState::default();
+        state.uploads.insert(session.id.clone(), PendingUpload { sha256: "stale".to_string(), chunks: 1, uploaded: vec![0] });
+        let archive = archive_session(&store, &session, &mut state, &state_path).unwrap();
+        assert_eq!(fetch_archive(&store, &archive).unwrap(), fs::read(&session.log_file).unwrap());
+    }
+
+    #[test]
+    fn prunes_local_logs_only_after_recording_the_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let logs = dir.path().join("logs");
+        let mut config = Config::default();
+        config.storage.path = Some(dir.path().join("remote"));
+        let mut logger = ClaudeLogger::new_with_config(&logs, config).unwrap();
+        for (key, value) in [("user.email", "test@example.com"), ("user.name", "Test")] {
+            Command::new("git").args(["config", key, value]).current_dir(&logs).status().unwrap();
+        }
+        let session = session_with_log(&logs, 3);
+        logger.complete_session(&session).unwrap();
+        let content = fs::read_to_string(&session.log_file).unwrap();
+
+        let outcome = sync_once(&mut logger, true).unwrap();
+        assert_eq!((outcome.archived.len(), outcome.pruned), (1, 1));
+        assert!(outcome.commit.is_some() && !session.log_file.exists());
+        let status = Command::new("git").args(["status", "--porcelain"]).current_dir(&logs).output().unwrap();
+        assert!(!String::from_utf8_lossy(&status.stdout).contains(".log"));
+
+        let analyzer = SessionAnalyzer::new_with_config(&logs, logger.config()).unwrap();
+        let archived = &analyzer.metadata().sessions[&session.id];
+        assert!(archived.archived_remote.is_some());
+        assert_eq!(analyzer.read_raw_session_log(archived).unwrap(), content);
+        // Without the archive recorded, the log is found through its manifest
+        assert_eq!(analyzer.read_raw_session_log(&session).unwrap(), content);
+        assert!(sync_once(&mut logger, true).unwrap().archived.is_empty());
+    }
+
+    #[test]
+    fn archives_a_held_session_without_pruning_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let logs = dir.path().join("logs");
+        let mut config = Config::default();
+        config.storage.path = Some(dir.path().join("remote"));
+        let mut logger = ClaudeLogger::new_with_config(&logs, config).unwrap();
+        for (key, value) in [("user.email", "test@example.com"), ("user.name", "Test")] {
+            Command::new("git").args(["config", key, value]).current_dir(&logs).status().unwrap();
+        }
+        let session = session_with_log(&logs, 4);
+        logger.complete_session(&session).unwrap();
+        crate::hold::place(&mut logger, std::slice::from_ref(&session.id), "litigation").unwrap();
+
+        let outcome = sync_once(&mut logger, true).unwrap();
+        assert_eq!((outcome.archived.len(), outcome.pruned), (1, 0));
+        assert!(outcome.commit.is_none() && session.log_file.exists());
+        assert!(logger.get_session(&session.id).unwrap().archived_remote.is_some());
+    }
+}
@@ -0,0 +1,372 @@
+use crate::logger::ClaudeLogger;
+use crate::project;
+use crate::pty::CaptureMode;
+use crate::session::{Methodology, ReviewState, SessionMetadata};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Tag carried by every generated session, so reports can exclude them.
+pub const SYNTHETIC_TAG: &str = "synthetic";
+
+/// Small deterministic generator (SplitMix64), so a seed always yields the same sessions.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..n`; zero when `n` is zero.
+    pub fn below(&mut self, n: u64) -> u64 {
+        if n == 0 { 0 } else { self.next_u64() % n }
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Named starting points for generated metrics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Preset {
+    #[default]
+    Balanced,
+    /// Many edits and code blocks, high energy, little confusion
+    Productive,
+    /// Long sessions full of questions and confusion, few edits
+    Struggling,
+    /// Short sessions with a handful of exchanges
+    Brief,
+}
+
+/// Metrics a generated session is built to have; each maps onto one `AnalysisMetrics` count.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Profile {
+    /// `Human:`/`Assistant:` lines
+    pub exchanges: usize,
+    pub code_blocks: usize,
+    pub file_edits: usize,
+    pub questions: usize,
+    pub enthusiasm: usize,
+    pub confusion: usize,
+    pub compaction: usize,
+    pub minutes: usize,
+    /// Creative energy, 1-3
+    pub energy: u8,
+}
+
+impl Profile {
+    pub fn preset(preset: Preset) -> Self {
+        let (exchanges, code_blocks, file_edits, questions, enthusiasm, confusion, compaction, minutes, energy) = match preset {
+            Preset::Balanced => (24, 6, 4, 5, 2, 1, 1, 35, 2),
+            Preset::Productive => (30, 10, 8, 3, 5, 0, 1, 45, 3),
+            Preset::Struggling => (40, 3, 1, 12, 0, 6, 3, 70, 1),
+            Preset::Brief => (6, 1, 1, 1, 1, 0, 0, 8, 2),
+        };
+        Self { exchanges, code_blocks, file_edits, questions, enthusiasm, confusion, compaction, minutes, energy }
+    }
+
+    /// Override one metric from a `name=value` pair, as given to `synth --set`.
+    pub fn set(&mut self, assignment: &str) -> Result<(), String> {
+        let (name, value) = assignment
+            .split_once('=')
+            .ok_or_else(|| format!("expected metric=value, got '{}'", assignment))?;
+        let value: usize = value.trim().parse().map_err(|_| format!("'{}' is not a whole number", value))?;
+        let slot = match name.trim() {
+            "exchanges" => &mut self.exchanges,
+            "code_blocks" => &mut self.code_blocks,
+            "file_edits" => &mut self.file_edits,
+            "questions" => &mut self.questions,
+            "enthusiasm" => &mut self.enthusiasm,
+            "confusion" => &mut self.confusion,
+            "compaction" => &mut self.compaction,
+            "minutes" => &mut self.minutes,
+            "energy" if (1..=3).contains(&value) => {
+                self.energy = value as u8;
+                return Ok(());
+            }
+            "energy" => return Err("energy must be 1, 2 or 3".to_string()),
+            other => return Err(format!("unknown metric '{}'", other)),
+        };
+        *slot = value;
+        Ok(())
+    }
+
+    /// This profile with every count scaled by a random factor within `±jitter`.
+    pub fn sample(&self, rng: &mut Rng, jitter: f64) -> Self {
+        let mut vary = |value: usize| {
+            let factor = 1.0 + jitter * (rng.unit() * 2.0 - 1.0);
+            (value as f64 * factor).round().max(0.0) as usize
+        };
+        Self {
+            exchanges: vary(self.exchanges),
+            code_blocks: vary(self.code_blocks),
+            file_edits: vary(self.file_edits),
+            questions: vary(self.questions),
+            enthusiasm: vary(self.enthusiasm),
+            confusion: vary(self.confusion),
+            compaction: vary(self.compaction),
+            minutes: vary(self.minutes).max(1),
+            energy: self.energy,
+        }
+    }
+}
+
+// Each line matches exactly one pattern of its kind and none of the others
+const ENTHUSIASM_LINES: &[&str] = &["That's perfect.", "Excellent work.", "Awesome, thanks."];
+const CONFUSION_LINES: &[&str] = &["I'm confused by this part.", "This is unclear to me.", "I'm not following."];
+const COMPACTION_LINES: &[&str] = &["Please keep it concise.", "Summarize the change for me."];
+const QUESTION_LINES: &[&str] = &["Does that cover the empty case?", "Which module owns this?", "Can we log the failure?"];
+
+/// A log whose analysis yields exactly `profile`'s counts.
+///
+/// Markers go on lines of their own between turns, so they never add exchanges;
+/// the filler text avoids every built-in pattern.
+pub fn generate_log(profile: &Profile, rng: &mut Rng) -> String {
+    let turns = profile.exchanges;
+    // Spread extras evenly over the human or assistant turns, or after the last line when there are none
+    let slot = |i: usize, count: usize, turn_count: usize| if turn_count == 0 { 0 } else { i * turn_count / count.max(1) };
+    let human_turns = turns.div_ceil(2);
+    let assistant_turns = turns / 2;
+
+    let mut after_human: Vec<Vec<String>> = vec![Vec::new(); human_turns.max(1)];
+    let mut after_assistant: Vec<Vec<String>> = vec![Vec::new(); assistant_turns.max(1)];
+    let mut pick = |lines: &[&str]| lines[rng.below(lines.len() as u64) as usize].to_string();
+
+    for (count, lines) in [
+        (profile.questions, QUESTION_LINES),
+        (profile.enthusiasm, ENTHUSIASM_LINES),
+        (profile.confusion, CONFUSION_LINES),
+        (profile.compaction, COMPACTION_LINES),
+    ] {
+        for i in 0..count {
+            after_human[slot(i, count, human_turns)].push(pick(lines));
+        }
+    }
+    for i in 0..profile.code_blocks {
+        after_assistant[slot(i, profile.code_blocks, assistant_turns)]
+            .push(format!("```rust\nfn step_{}() -> usize {{\n    {}\n}}\n```", i + 1, i));
+    }
+    for i in 0..profile.file_edits {
+        let file = format!("src/module_{}.rs", i + 1);
+        let line = 10 + i;
+        after_assistant[slot(i, profile.file_edits, assistant_turns)].push(format!(
+            "⏺ Update({file})\n  ⎿  Updated {file} with 1 addition and 1 removal\n       {line} -    let value = {old};\n       {line} +    let value = {new};\n",
+            file = file,
+            line = line,
+            old = i,
+            new = i + 1,
+        ));
+    }
+
+    let mut log = String::new();
+    for turn in 0..turns {
+        let (speaker, extras) = if turn % 2 == 0 {
+            ("Human", &after_human[turn / 2])
+        } else {
+            ("Assistant", &after_assistant[turn / 2])
+        };
+        log.push_str(&format!("{}: Working on step {} of the feature.\n", speaker, turn / 2 + 1));
+        for extra in extras {
+            log.push_str(extra);
+            log.push('\n');
+        }
+    }
+    if turns == 0 {
+        for extra in after_human.iter().chain(&after_assistant).flatten() {
+            log.push_str(extra);
+            log.push('\n');
+        }
+    } else if turns == 1 {
+        // With a single turn there is no assistant turn to follow
+        for extra in &after_assistant[0] {
+            log.push_str(extra);
+            log.push('\n');
+        }
+    }
+    log
+}
+
+/// What to generate.
+#[derive(Debug, Clone)]
+pub struct SynthOptions {
+    pub count: usize,
+    pub profile: Profile,
+    /// Relative spread of each metric between sessions; 0 reproduces the profile exactly
+    pub jitter: f64,
+    pub seed: u64,
+    /// Every session's methodology; alternates between the two when unset
+    pub methodology: Option<Methodology>,
+    pub projects: Vec<String>,
+    /// Sessions are spread over this many days, ending now
+    pub days: u32,
+}
+
+/// Options for `count` brief sessions in `project` over the last day, exactly as profiled; tests
+/// change other fields with struct update syntax.
+#[cfg(test)]
+pub(crate) fn sessions(count: usize, seed: u64, project: &str) -> SynthOptions {
+    SynthOptions {
+        count,
+        profile: Profile::preset(Preset::Brief),
+        jitter: 0.0,
+        seed,
+        methodology: None,
+        projects: vec![project.to_string()],
+        days: 1,
+    }
+}
+
+/// A generated session and the log it points at.
+#[derive(Debug, Clone)]
+pub struct SyntheticSession {
+    pub session: SessionMetadata,
+    pub log: String,
+}
+
+/// Sessions with logs filed under `logs_dir`, oldest first.
+pub fn generate(options: &SynthOptions, logs_dir: &Path, now: DateTime<Utc>) -> Vec<SyntheticSession> {
+    let mut rng = Rng::new(options.seed);
+    let span = Duration::days(options.days.max(1) as i64).num_seconds() as u64;
+    // Sorted offsets keep ids in timestamp order; distinct seconds keep them unique
+    let mut offsets: Vec<u64> = (0..options.count).map(|_| rng.below(span)).collect();
+    offsets.sort_unstable_by(|a, b| b.cmp(a));
+    offsets.dedup();
+    while offsets.len() < options.count {
+        let next = offsets.last().map_or(0, |last| last.saturating_sub(1));
+        offsets.push(next);
+    }
+
+    let profiles: Vec<Profile> = (0..options.count).map(|_| options.profile.sample(&mut rng, options.jitter)).collect();
+    // Start early enough that even the longest session has ended by `now`
+    let latest_start = now - Duration::minutes(profiles.iter().map(|profile| profile.minutes).max().unwrap_or(0) as i64);
+
+    offsets
+        .into_iter()
+        .zip(profiles)
+        .enumerate()
+        .map(|(index, (offset, profile))| {
+            let log = generate_log(&profile, &mut rng);
+            let timestamp = latest_start - Duration::seconds(offset as i64);
+            let id = timestamp.format("%Y-%m-%d_%H-%M-%S").to_string();
+            let project_name = options
+                .projects
+                .get(index % options.projects.len().max(1))
+                .cloned()
+                .unwrap_or_else(|| "demo".to_string());
+            let methodology = options.methodology.clone().unwrap_or(if index % 2 == 0 {
+                Methodology::CONTEXT_DRIVEN
+            } else {
+                Methodology::COMMAND_BASED
+            });
+            let end_time = timestamp + Duration::minutes(profile.minutes as i64);
+
+            let session = SessionMetadata {
+                log_file: logs_dir.join(project::directory_name(&project_name)).join(format!("{}.log", id)),
+                id,
+                timestamp,
+                working_directory: PathBuf::from("/synthetic").join(&project_name),
+                project: project_name,
+                methodology,
+                command: "claude".to_string(),
+                clean_log_file: None,
+                log_parser: None,
+                transcript_file: None,
+                duration_seconds: Some((end_time - timestamp).num_seconds()),
+                end_time: Some(end_time),
+                features_worked_on: Vec::new(),
+                creative_energy: Some(profile.energy),
+                terminal: None,
+                tmux_pane: None,
+                capture_mode: CaptureMode::Pty,
+                hold: None,
+                archived_remote: None,
+                git_commit: None,
+                annotations: Vec::new(),
+                notes: Vec::new(),
+                project_changes: None,
+                compactions: Vec::new(),
+                author: None,
+                activity: None,
+                duration_estimated: false,
+                review_state: ReviewState::Unreviewed,
+                action_items: Vec::new(),
+                reminders: Vec::new(),
+                tags: vec![SYNTHETIC_TAG.to_string()],
+                termination_reason: None,
+                usage: None,
+                extra: HashMap::new(),
+                parent_session_id: None,
+                analysis: None,
+            };
+            SyntheticSession { session, log }
+        })
+        .collect()
+}
+
+/// Write the logs and record the sessions, without committing them to git.
+pub fn write(logger: &mut ClaudeLogger, sessions: Vec<SyntheticSession>) -> Result<()> {
+    for SyntheticSession { session, log } in sessions {
+        if logger.get_session(&session.id).is_some() {
+            anyhow::bail!("Session {} already exists; pick another --seed", session.id);
+        }
+        if let Some(dir) = session.log_file.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create log directory: {}", dir.display()))?;
+        }
+        fs::write(&session.log_file, log)
+            .with_context(|| format!("Failed to write log file: {}", session.log_file.display()))?;
+        logger.add_session(session);
+    }
+    logger.save_metadata()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::ConversationPatterns;
+
+    #[test]
+    fn generated_logs_analyze_to_their_profile() {
+        let patterns = ConversationPatterns::new();
+        let mut rng = Rng::new(7);
+        for preset in Preset::value_variants() {
+            for exchanges in [0, 1, 2, 7] {
+                let profile = Profile { exchanges, ..Profile::preset(*preset) };
+                let metrics = patterns.analyze_content(&generate_log(&profile, &mut rng));
+
+                assert_eq!(metrics.exchanges, profile.exchanges, "{:?}", profile);
+                assert_eq!(metrics.code_blocks, profile.code_blocks, "{:?}", profile);
+                assert_eq!(metrics.file_edits, profile.file_edits, "{:?}", profile);
+                assert_eq!(metrics.questions_asked, profile.questions, "{:?}", profile);
+                assert_eq!(metrics.enthusiasm_markers, profile.enthusiasm, "{:?}", profile);
+                assert_eq!(metrics.confusion_markers, profile.confusion, "{:?}", profile);
+                assert_eq!(metrics.compaction_indicators, profile.compaction, "{:?}", profile);
+            }
+        }
+
+        let options = SynthOptions { profile: Profile::preset(Preset::Balanced), jitter: 0.3, ..sessions(5, 42, "api") };
+        let now = Utc::now();
+        let first = generate(&options, Path::new("/logs"), now);
+        let second = generate(&options, Path::new("/logs"), now);
+        assert_eq!(first.len(), 5);
+        assert_eq!(first.iter().map(|s| &s.log).collect::<Vec<_>>(), second.iter().map(|s| &s.log).collect::<Vec<_>>());
+        let mut ids: Vec<&str> = first.iter().map(|s| s.session.id.as_str()).collect();
+        ids.dedup();
+        assert_eq!(ids.len(), 5);
+    }
+}
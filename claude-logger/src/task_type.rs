@@ -0,0 +1,234 @@
+use crate::conversation::{parse_turns, Block, Speaker};
+use crate::session::Methodology;
+use regex::Regex;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+/// Methodologies need this many sessions of a task type before they're ranked for it.
+const MIN_TASK_SESSIONS: usize = 2;
+
+/// What a session set out to do, judged from its opening prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskType {
+    Debugging,
+    Feature,
+    Refactoring,
+    Other,
+}
+
+impl std::fmt::Display for TaskType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaskType::Debugging => write!(f, "Debugging"),
+            TaskType::Feature => write!(f, "Feature work"),
+            TaskType::Refactoring => write!(f, "Refactoring"),
+            TaskType::Other => write!(f, "Other"),
+        }
+    }
+}
+
+fn keywords() -> &'static [(TaskType, Regex)] {
+    static KEYWORDS: OnceLock<Vec<(TaskType, Regex)>> = OnceLock::new();
+    KEYWORDS.get_or_init(|| {
+        [
+            (
+                TaskType::Debugging,
+                r"(?i)\b(bug|fix|error|fail(s|ing|ed|ure)?|crash(es|ing)?|broken|panic|exception|debug|regression|wrong|doesn't work|not working)\b",
+            ),
+            (
+                TaskType::Refactoring,
+                r"(?i)\b(refactor(ing)?|clean ?up|rename|restructure|reorganize|extract|simplify|split up|deduplicate|move .+ into)\b",
+            ),
+            (
+                TaskType::Feature,
+                r"(?i)\b(add|implement|create|build|support|new|introduce|feature|allow|enable)\b",
+            ),
+        ]
+        .into_iter()
+        .map(|(task_type, pattern)| (task_type, Regex::new(pattern).expect("valid task type pattern")))
+        .collect()
+    })
+}
+
+/// Classify a session by the keywords in its opening prompt; ties go to the earlier task type.
+pub fn classify(content: &str) -> TaskType {
+    let prompt: String = parse_turns(content)
+        .into_iter()
+        .find(|turn| turn.speaker == Speaker::Human)
+        .map(|turn| {
+            turn.blocks
+                .into_iter()
+                .filter_map(|block| match block {
+                    Block::Text { text } => Some(text),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default();
+
+    keywords()
+        .iter()
+        .map(|(task_type, pattern)| (*task_type, pattern.find_iter(&prompt).count()))
+        .filter(|(_, matches)| *matches > 0)
+        .max_by(|(a, a_matches), (b, b_matches)| a_matches.cmp(b_matches).then(b.cmp(a)))
+        .map_or(TaskType::Other, |(task_type, _)| task_type)
+}
+
+/// One session's task type, methodology and outcome.
+#[derive(Debug, Clone)]
+pub struct TaskSample {
+    pub task_type: TaskType,
+    pub methodology: Methodology,
+    pub quality: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MethodologyScore {
+    pub methodology: Methodology,
+    pub sessions: usize,
+    pub avg_quality: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskTypeBreakdown {
+    pub task_type: TaskType,
+    /// Best average quality first
+    pub methodologies: Vec<MethodologyScore>,
+    /// The highest-scoring methodology, when at least two have enough sessions to compare
+    pub best: Option<Methodology>,
+}
+
+/// How each methodology scores per task type.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TaskTypeAnalysis {
+    pub task_types: Vec<TaskTypeBreakdown>,
+}
+
+impl TaskTypeAnalysis {
+    pub fn from_samples(samples: &[TaskSample]) -> Self {
+        let mut task_types: Vec<TaskType> = samples.iter().map(|sample| sample.task_type).collect();
+        task_types.sort();
+        task_types.dedup();
+
+        let task_types = task_types
+            .into_iter()
+            .map(|task_type| {
+                let mut methodologies: Vec<MethodologyScore> = Vec::new();
+                for sample in samples.iter().filter(|sample| sample.task_type == task_type) {
+                    match methodologies.iter_mut().find(|score| score.methodology == sample.methodology) {
+                        Some(score) => {
+                            score.avg_quality += sample.quality;
+                            score.sessions += 1;
+                        }
+                        None => methodologies.push(MethodologyScore {
+                            methodology: sample.methodology.clone(),
+                            sessions: 1,
+                            avg_quality: sample.quality,
+                        }),
+                    }
+                }
+                for score in &mut methodologies {
+                    score.avg_quality /= score.sessions as f64;
+                }
+                methodologies.sort_by(|a, b| b.avg_quality.total_cmp(&a.avg_quality));
+
+                let ranked: Vec<&MethodologyScore> = methodologies
+                    .iter()
+                    .filter(|score| score.sessions >= MIN_TASK_SESSIONS && score.methodology != Methodology::UNKNOWN)
+                    .collect();
+                let best = (ranked.len() >= 2).then(|| ranked[0].methodology.clone());
+                TaskTypeBreakdown { task_type, methodologies, best }
+            })
+            .collect();
+
+        Self { task_types }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.task_types.is_empty()
+    }
+
+    /// A recommendation when the best methodology depends on the kind of task.
+    pub fn recommendations(&self) -> Vec<String> {
+        let winners: Vec<(TaskType, &Methodology)> = self
+            .task_types
+            .iter()
+            .filter(|breakdown| breakdown.task_type != TaskType::Other)
+            .filter_map(|breakdown| breakdown.best.as_ref().map(|best| (breakdown.task_type, best)))
+            .collect();
+        if winners.iter().all(|(_, best)| *best == winners[0].1) {
+            return Vec::new();
+        }
+
+        let picks: Vec<String> = winners
+            .iter()
+            .map(|(task_type, best)| format!("{} for {}", best, task_type.to_string().to_lowercase()))
+            .collect();
+        vec![format!("The best methodology depends on the task: {}", picks.join(", "))]
+    }
+
+    pub fn print(&self) {
+        if self.is_empty() {
+            println!("No sessions found for analysis.");
+            return;
+        }
+
+        for breakdown in &self.task_types {
+            match &breakdown.best {
+                Some(best) => println!("{} (best: {}):", breakdown.task_type, best),
+                None => println!("{}:", breakdown.task_type),
+            }
+            for score in &breakdown.methodologies {
+                println!("  {:<16} {:>3} sessions, average quality {:.1}/100",
+                    score.methodology.to_string(), score.sessions, score.avg_quality);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(task_type: TaskType, methodology: Methodology, quality: f64) -> TaskSample {
+        TaskSample { task_type, methodology, quality }
+    }
+
+    #[test]
+    fn classifies_opening_prompts() {
+        assert_eq!(classify("Human: The upload test is failing with a panic\n\nAssistant: Let me look."), TaskType::Debugging);
+        assert_eq!(classify("Human: Add a --json flag to the export command\n\nAssistant: Sure."), TaskType::Feature);
+        assert_eq!(classify("Human: Refactor the parser and extract the tokenizer\n\nAssistant: OK."), TaskType::Refactoring);
+        assert_eq!(classify("Human: What does this module do?\n\nAssistant: It parses."), TaskType::Other);
+        // Later prompts don't change what the session set out to do
+        assert_eq!(classify("Human: Fix the crash\n\nAssistant: Done.\n\nHuman: Now add a test\n"), TaskType::Debugging);
+    }
+
+    #[test]
+    fn ranks_methodologies_per_task_type() {
+        let samples = [
+            sample(TaskType::Debugging, Methodology::CONTEXT_DRIVEN, 80.0),
+            sample(TaskType::Debugging, Methodology::CONTEXT_DRIVEN, 70.0),
+            sample(TaskType::Debugging, Methodology::COMMAND_BASED, 50.0),
+            sample(TaskType::Debugging, Methodology::COMMAND_BASED, 60.0),
+            sample(TaskType::Feature, Methodology::CONTEXT_DRIVEN, 55.0),
+            sample(TaskType::Feature, Methodology::CONTEXT_DRIVEN, 45.0),
+            sample(TaskType::Feature, Methodology::COMMAND_BASED, 75.0),
+            sample(TaskType::Feature, Methodology::COMMAND_BASED, 85.0),
+            sample(TaskType::Refactoring, Methodology::COMMAND_BASED, 90.0),
+        ];
+        let analysis = TaskTypeAnalysis::from_samples(&samples);
+
+        assert_eq!(analysis.task_types.len(), 3);
+        assert_eq!(analysis.task_types[0].best, Some(Methodology::CONTEXT_DRIVEN));
+        assert_eq!(analysis.task_types[0].methodologies[0].avg_quality, 75.0);
+        assert_eq!(analysis.task_types[1].best, Some(Methodology::COMMAND_BASED));
+        assert_eq!(analysis.task_types[2].best, None);
+        assert_eq!(
+            analysis.recommendations(),
+            vec!["The best methodology depends on the task: Context-Driven for debugging, Command-Based for feature work"]
+        );
+    }
+}
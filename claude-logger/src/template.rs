@@ -0,0 +1,41 @@
+use anyhow::{Context, Result};
+use handlebars::{handlebars_helper, no_escape, Handlebars};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+handlebars_helper!(fixed: |value: f64, *args| {
+    // `args` holds every parameter, the value included
+    let digits = args.get(1).and_then(|digits| digits.as_u64()).unwrap_or(1) as usize;
+    format!("{:.*}", digits, value)
+});
+handlebars_helper!(minutes: |seconds: i64| (seconds / 60).to_string());
+handlebars_helper!(json: |value: Value| serde_json::to_string_pretty(&value).unwrap_or_default());
+
+/// Render `data` through a user's Handlebars template.
+///
+/// Templates run in strict mode, so a misspelled field is an error rather than blank output.
+/// Output is only HTML-escaped for `.html`/`.htm` templates. Helpers: `fixed value [digits]`,
+/// `minutes seconds` and `json value`.
+pub fn render(template: &Path, data: &impl Serialize) -> Result<String> {
+    let source = fs::read_to_string(template)
+        .with_context(|| format!("Failed to read template: {}", template.display()))?;
+
+    let mut handlebars = Handlebars::new();
+    handlebars.set_strict_mode(true);
+    let is_html = template
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| matches!(extension.to_ascii_lowercase().as_str(), "html" | "htm"));
+    if !is_html {
+        handlebars.register_escape_fn(no_escape);
+    }
+    handlebars.register_helper("fixed", Box::new(fixed));
+    handlebars.register_helper("minutes", Box::new(minutes));
+    handlebars.register_helper("json", Box::new(json));
+
+    handlebars
+        .render_template(&source, data)
+        .map_err(|e| anyhow::anyhow!("Failed to render template {}: {}", template.display(), e))
+}
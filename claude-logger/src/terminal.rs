@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::process::{Command, Stdio};
+
+/// Terminals narrower than this are considered cramped for analysis purposes.
+pub const CRAMPED_COLUMNS: u16 = 100;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TerminalInfo {
+    pub columns: Option<u16>,
+    pub rows: Option<u16>,
+    pub term: Option<String>,
+    pub colorterm: Option<String>,
+    pub term_program: Option<String>,
+    pub over_ssh: bool,
+    pub in_tmux: bool,
+}
+
+impl TerminalInfo {
+    /// Capture the current terminal's size and renderer environment.
+    pub fn detect() -> Self {
+        let (columns, rows) = Self::detect_size();
+
+        Self {
+            columns,
+            rows,
+            term: non_empty_var("TERM"),
+            colorterm: non_empty_var("COLORTERM"),
+            term_program: non_empty_var("TERM_PROGRAM"),
+            over_ssh: ["SSH_CONNECTION", "SSH_CLIENT", "SSH_TTY"]
+                .iter()
+                .any(|var| non_empty_var(var).is_some()),
+            in_tmux: non_empty_var("TMUX").is_some(),
+        }
+    }
+
+    fn detect_size() -> (Option<u16>, Option<u16>) {
+        // `stty size` reads from stdin, so it only works when stdin is the terminal
+        let output = Command::new("stty")
+            .arg("size")
+            .stdin(Stdio::inherit())
+            .stderr(Stdio::null())
+            .output();
+
+        if let Ok(output) = output
+            && output.status.success()
+        {
+            let size = String::from_utf8_lossy(&output.stdout);
+            let mut parts = size.split_whitespace().map(|part| part.parse::<u16>().ok());
+            if let (Some(Some(rows)), Some(Some(columns))) = (parts.next(), parts.next()) {
+                return (Some(columns), Some(rows));
+            }
+        }
+
+        let columns = non_empty_var("COLUMNS").and_then(|value| value.parse().ok());
+        let rows = non_empty_var("LINES").and_then(|value| value.parse().ok());
+        (columns, rows)
+    }
+
+    pub fn is_cramped(&self) -> Option<bool> {
+        self.columns.map(|columns| columns < CRAMPED_COLUMNS)
+    }
+
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+
+        if let (Some(columns), Some(rows)) = (self.columns, self.rows) {
+            parts.push(format!("{}x{}", columns, rows));
+        }
+        if let Some(term) = &self.term {
+            parts.push(term.clone());
+        }
+        if let Some(program) = &self.term_program {
+            parts.push(program.clone());
+        }
+        if self.over_ssh {
+            parts.push("ssh".to_string());
+        }
+        if self.in_tmux {
+            parts.push("tmux".to_string());
+        }
+
+        if parts.is_empty() {
+            "unknown".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+fn non_empty_var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.is_empty())
+}
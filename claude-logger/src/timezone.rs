@@ -0,0 +1,62 @@
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+
+/// Zone used when showing timestamps; they are always stored in UTC.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DisplayZone {
+    #[default]
+    Local,
+    Utc,
+}
+
+impl DisplayZone {
+    pub fn from_flags(utc: bool) -> Self {
+        if utc {
+            DisplayZone::Utc
+        } else {
+            DisplayZone::Local
+        }
+    }
+
+    pub fn format(self, timestamp: DateTime<Utc>, fmt: &str) -> String {
+        match self {
+            DisplayZone::Local => timestamp.with_timezone(&Local).format(fmt).to_string(),
+            DisplayZone::Utc => timestamp.format(fmt).to_string(),
+        }
+    }
+
+    /// Date and time with the zone spelled out, e.g. `2025-01-10 10:00:00 UTC`.
+    pub fn format_full(self, timestamp: DateTime<Utc>) -> String {
+        match self {
+            DisplayZone::Local => self.format(timestamp, "%Y-%m-%d %H:%M:%S %:z"),
+            DisplayZone::Utc => self.format(timestamp, "%Y-%m-%d %H:%M:%S UTC"),
+        }
+    }
+
+    pub fn rfc3339(self, timestamp: DateTime<Utc>) -> String {
+        match self {
+            DisplayZone::Local => timestamp.with_timezone(&Local).to_rfc3339(),
+            DisplayZone::Utc => timestamp.to_rfc3339(),
+        }
+    }
+
+    /// Midnight at the start of `date` in this zone.
+    pub fn start_of_day(self, date: NaiveDate) -> DateTime<Utc> {
+        let midnight = date.and_hms_opt(0, 0, 0).expect("midnight is a valid time");
+        match self {
+            DisplayZone::Local => Local
+                .from_local_datetime(&midnight)
+                .earliest()
+                .map(|time| time.with_timezone(&Utc))
+                .unwrap_or_else(|| midnight.and_utc()),
+            DisplayZone::Utc => midnight.and_utc(),
+        }
+    }
+
+    /// Calendar date of the timestamp in this zone, for day-based bucketing.
+    pub fn date(self, timestamp: DateTime<Utc>) -> NaiveDate {
+        match self {
+            DisplayZone::Local => timestamp.with_timezone(&Local).date_naive(),
+            DisplayZone::Utc => timestamp.date_naive(),
+        }
+    }
+}
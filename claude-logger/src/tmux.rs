@@ -0,0 +1,262 @@
+use crate::anomaly::ActivityMonitor;
+use crate::hooks::{self, HookEvent};
+use crate::logger::ClaudeLogger;
+use crate::notify::{self, Notifier, PeriodicChecks};
+use crate::session::SessionMetadata;
+use crate::terminal::TerminalInfo;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::path::PathBuf;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+const PANE_FORMAT: &str = "#{pane_id}\t#{pane_current_command}\t#{pane_current_path}\t#{pane_width}\t#{pane_height}\t#{pane_pipe}";
+
+#[derive(Debug, Clone)]
+pub struct TmuxPane {
+    pub id: String,
+    pub command: String,
+    pub path: PathBuf,
+    pub width: Option<u16>,
+    pub height: Option<u16>,
+    pub piped: bool,
+}
+
+impl TmuxPane {
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        Some(Self {
+            id: fields.next()?.to_string(),
+            command: fields.next()?.to_string(),
+            path: PathBuf::from(fields.next()?),
+            width: fields.next()?.parse().ok(),
+            height: fields.next()?.parse().ok(),
+            piped: fields.next()? == "1",
+        })
+    }
+
+    pub fn is_running_claude(&self) -> bool {
+        self.command == "claude" || self.command.starts_with("claude-")
+    }
+
+    fn terminal_info(&self) -> TerminalInfo {
+        TerminalInfo {
+            columns: self.width,
+            rows: self.height,
+            in_tmux: true,
+            ..TerminalInfo::detect()
+        }
+    }
+}
+
+pub fn list_panes() -> Result<Vec<TmuxPane>> {
+    let output = Command::new("tmux")
+        .args(["list-panes", "-a", "-F", PANE_FORMAT])
+        .output()
+        .context("Failed to run tmux list-panes")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("tmux list-panes failed: {}", stderr.trim()));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().filter_map(TmuxPane::parse).collect())
+}
+
+fn start_pipe(pane_id: &str, log_file: &std::path::Path) -> Result<()> {
+    let quoted_path = log_file.display().to_string().replace('\'', "'\\''");
+
+    let output = Command::new("tmux")
+        .args(["pipe-pane", "-o", "-t", pane_id, &format!("cat >> '{}'", quoted_path)])
+        .output()
+        .context("Failed to run tmux pipe-pane")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("tmux pipe-pane failed for {}: {}", pane_id, stderr.trim()));
+    }
+
+    Ok(())
+}
+
+fn stop_pipe(pane_id: &str) -> Result<()> {
+    // Running pipe-pane without a command closes any existing pipe
+    Command::new("tmux")
+        .args(["pipe-pane", "-t", pane_id])
+        .output()
+        .context("Failed to run tmux pipe-pane")?;
+
+    Ok(())
+}
+
+/// Sessions currently being captured from a tmux pane.
+pub fn active_captures(logger: &ClaudeLogger) -> Vec<SessionMetadata> {
+    logger
+        .metadata()
+        .sessions
+        .values()
+        .filter(|session| session.tmux_pane.is_some() && session.end_time.is_none())
+        .cloned()
+        .collect()
+}
+
+/// Start capturing every tmux pane running claude that isn't captured yet.
+pub fn attach_claude_panes(logger: &mut ClaudeLogger) -> Result<Vec<SessionMetadata>> {
+    logger.ensure_encryption_key()?;
+    let active_panes: Vec<String> = active_captures(logger)
+        .into_iter()
+        .filter_map(|session| session.tmux_pane)
+        .collect();
+
+    let mut attached = Vec::new();
+
+    for pane in list_panes()? {
+        if !pane.is_running_claude() || pane.piped || active_panes.contains(&pane.id) {
+            continue;
+        }
+
+        let (log_file, mut session) = logger.create_session_log_in(&pane.path, &[])?;
+        session.terminal = Some(pane.terminal_info());
+        session.tmux_pane = Some(pane.id.clone());
+
+        start_pipe(&pane.id, &log_file)?;
+
+        logger.add_session(session.clone());
+        logger.save_metadata()?;
+        hooks::run(&logger.config().hooks, HookEvent::PreSession, &session);
+        attached.push(session);
+    }
+
+    Ok(attached)
+}
+
+/// Finish captures whose pane no longer runs claude, or all of them when `force` is set.
+pub fn finalize_captures(logger: &mut ClaudeLogger, force: bool) -> Result<Vec<SessionMetadata>> {
+    let mut finalized = Vec::new();
+
+    for (mut session, pane_id, pane_open) in ended_captures(active_captures(logger), list_panes(), force)? {
+        if pane_open {
+            stop_pipe(&pane_id)?;
+        }
+
+        let end_time = Utc::now();
+        session.set_duration(end_time.signed_duration_since(session.timestamp));
+        session.end_time = Some(end_time);
+
+        if !session.log_file.exists() {
+            // Nothing was written to the pane; keep the git commit from failing
+            std::fs::write(&session.log_file, "")
+                .with_context(|| format!("Failed to create log file: {}", session.log_file.display()))?;
+        }
+
+        logger.complete_session(&session)?;
+        finalized.push(session);
+    }
+
+    Ok(finalized)
+}
+
+/// The captures to finish with their pane and whether it is still open. Without the pane list,
+/// whether a pane still runs claude is unknown, so only `force` finishes captures then.
+fn ended_captures(
+    captures: Vec<SessionMetadata>,
+    panes: Result<Vec<TmuxPane>>,
+    force: bool,
+) -> Result<Vec<(SessionMetadata, String, bool)>> {
+    let panes = match panes {
+        Ok(panes) => panes,
+        Err(_) if force => Vec::new(),
+        Err(e) => return Err(e.context("Can't tell which captured tmux panes have ended")),
+    };
+    Ok(captures
+        .into_iter()
+        .filter_map(|session| {
+            let pane_id = session.tmux_pane.clone()?;
+            let pane = panes.iter().find(|pane| pane.id == pane_id);
+            let still_running = pane.is_some_and(|pane| pane.is_running_claude());
+            (force || !still_running).then_some((session, pane_id, pane.is_some()))
+        })
+        .collect())
+}
+
+/// Poll tmux, attaching to new claude panes and finalizing ended ones.
+pub fn watch(logger: &mut ClaudeLogger, interval_secs: u64) -> Result<()> {
+    println!("Watching tmux panes for claude sessions (Ctrl-C to stop)...");
+
+    let notifier = Notifier::new(logger.config().notifications.clone());
+    let mut checks = PeriodicChecks::default();
+    let mut monitor = ActivityMonitor::new(&logger.config().notifications);
+    monitor.refresh_baseline(logger.metadata());
+
+    loop {
+        if let Err(e) = logger.reload_metadata() {
+            eprintln!("Warning: Failed to reload metadata: {:#}", e);
+        }
+
+        for session in attach_claude_panes(logger)? {
+            println!("Capturing {} -> {}",
+                session.tmux_pane.as_deref().unwrap_or("?"),
+                session.log_file.display());
+        }
+
+        let finalized = finalize_captures(logger, false)?;
+        for session in &finalized {
+            notify::session_finalized(logger, &notifier, session);
+        }
+        if !finalized.is_empty() {
+            monitor.refresh_baseline(logger.metadata());
+        }
+        monitor.check(&active_captures(logger), &notifier);
+
+        if let Err(e) = checks.run(logger, &notifier) {
+            eprintln!("Warning: Failed to run periodic checks: {:#}", e);
+        }
+        // Deliver what an earlier pass queued past the rate limits
+        notifier.flush();
+
+        thread::sleep(Duration::from_secs(interval_secs));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::{generate, sessions};
+    use std::path::Path;
+
+    #[test]
+    fn test_parse_pane_line() {
+        let pane = TmuxPane::parse("%3\tclaude\t/home/me/project\t120\t40\t0").unwrap();
+
+        assert_eq!(pane.id, "%3");
+        assert!(pane.is_running_claude());
+        assert_eq!(pane.path, PathBuf::from("/home/me/project"));
+        assert_eq!(pane.width, Some(120));
+        assert!(!pane.piped);
+    }
+
+    #[test]
+    fn finalizes_only_ended_panes_and_nothing_when_tmux_is_unreachable() {
+        let mut captures = generate(&sessions(3, 6, "api"), Path::new("/logs"), Utc::now());
+        for (generated, pane) in captures.iter_mut().zip(["%1", "%2", "%3"]) {
+            generated.session.tmux_pane = Some(pane.to_string());
+        }
+        let captures: Vec<SessionMetadata> = captures.into_iter().map(|generated| generated.session).collect();
+        let panes = || {
+            Ok(vec![
+                TmuxPane::parse("%1\tclaude\t/work\t80\t24\t1").unwrap(),
+                TmuxPane::parse("%2\tzsh\t/work\t80\t24\t1").unwrap(),
+            ])
+        };
+        let ended = |panes, force| -> Result<Vec<(String, bool)>> {
+            Ok(ended_captures(captures.clone(), panes, force)?.into_iter().map(|(_, pane, open)| (pane, open)).collect())
+        };
+
+        assert_eq!(ended(panes(), false).unwrap(), [("%2".to_string(), true), ("%3".to_string(), false)]);
+        assert_eq!(ended(panes(), true).unwrap().len(), 3);
+        assert!(ended(Err(anyhow::anyhow!("no server running")), false).is_err());
+        assert_eq!(ended(Err(anyhow::anyhow!("no server running")), true).unwrap().len(), 3);
+    }
+}
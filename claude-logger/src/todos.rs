@@ -0,0 +1,212 @@
+use crate::analyzer::SessionAnalyzer;
+use crate::conversation;
+use crate::logger::ClaudeLogger;
+use crate::session::{ActionItem, ActionState, SessionMetadata, SessionsMetadata};
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Only the closing assistant turns are searched; earlier TODOs are usually resolved
+/// later in the same conversation.
+const FINAL_ASSISTANT_TURNS: usize = 2;
+
+struct TodoPatterns {
+    todo: Regex,
+    checkbox: Regex,
+    heading: Regex,
+    list_item: Regex,
+}
+
+fn patterns() -> &'static TodoPatterns {
+    static PATTERNS: OnceLock<TodoPatterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| TodoPatterns {
+        todo: Regex::new(r"(?i)^(?:[-*]\s+|//\s*|#\s*)?(?:TODO|FIXME)\b[:\s-]*(.+)$").unwrap(),
+        checkbox: Regex::new(r"^[-*]\s+\[ \]\s+(.+)$").unwrap(),
+        heading: Regex::new(
+            r"(?i)^#+.*\b(?:next steps|follow[- ]ups?|remaining work|action items)\b|\b(?:next steps|follow[- ]ups?|remaining work|action items)\b[^:]*:\**$",
+        )
+        .unwrap(),
+        list_item: Regex::new(r"^(?:[-*]|\d+[.)])\s+(?:\[ \]\s+)?(.+)$").unwrap(),
+    })
+}
+
+/// Pull TODO lines, unchecked boxes and "Next steps" lists out of the final
+/// assistant turns of a log.
+pub fn extract_action_items(content: &str) -> Vec<String> {
+    let patterns = patterns();
+    let mut items: Vec<String> = Vec::new();
+    let content = conversation::normalize(content);
+
+    for turn in final_assistant_turns(&content, FINAL_ASSISTANT_TURNS) {
+        let mut in_code_block = false;
+        let mut in_list = false;
+
+        for line in turn {
+            let line = line.trim();
+            if line.starts_with("```") {
+                in_code_block = !in_code_block;
+                continue;
+            }
+            if in_code_block {
+                continue;
+            }
+
+            let item = if patterns.heading.is_match(line) {
+                in_list = true;
+                None
+            } else if let Some(captures) = patterns.checkbox.captures(line) {
+                Some(captures[1].to_string())
+            } else if let Some(captures) = patterns.todo.captures(line) {
+                Some(captures[1].to_string())
+            } else if in_list && let Some(captures) = patterns.list_item.captures(line) {
+                Some(captures[1].to_string())
+            } else {
+                in_list = false;
+                None
+            };
+
+            if let Some(item) = item {
+                let item = item.trim().trim_end_matches("**").trim().to_string();
+                if !item.is_empty() && !items.contains(&item) {
+                    items.push(item);
+                }
+            }
+        }
+    }
+
+    items
+}
+
+/// Lines of the last `count` assistant turns, in log order.
+fn final_assistant_turns(content: &str, count: usize) -> Vec<Vec<&str>> {
+    let mut turns: Vec<Vec<&str>> = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+
+    for line in content.lines() {
+        if let Some(rest) = line.strip_prefix("Assistant:") {
+            turns.extend(current.take());
+            current = Some(vec![rest]);
+        } else if line.starts_with("Human:") {
+            turns.extend(current.take());
+        } else if let Some(turn) = current.as_mut() {
+            turn.push(line);
+        }
+    }
+    turns.extend(current);
+
+    let skip = turns.len().saturating_sub(count);
+    turns.into_iter().skip(skip).collect()
+}
+
+pub fn new_items(texts: Vec<String>) -> Vec<ActionItem> {
+    texts
+        .into_iter()
+        .map(|text| ActionItem {
+            text,
+            state: ActionState::Open,
+        })
+        .collect()
+}
+
+/// Action items in the given states across all sessions, newest session first.
+/// Each item comes with its 1-based position within the session.
+pub fn list<'a>(
+    metadata: &'a SessionsMetadata,
+    states: &[ActionState],
+) -> Vec<(&'a SessionMetadata, usize, &'a ActionItem)> {
+    metadata
+        .recent_sessions(None, usize::MAX)
+        .into_iter()
+        .flat_map(|session| {
+            session
+                .action_items
+                .iter()
+                .enumerate()
+                .map(move |(index, item)| (session, index + 1, item))
+        })
+        .filter(|(_, _, item)| states.contains(&item.state))
+        .collect()
+}
+
+/// Extract action items for sessions that have none yet, returning how many were found.
+pub fn scan(logger: &mut ClaudeLogger, analyzer: &SessionAnalyzer) -> Result<usize> {
+    let mut found = 0;
+
+    for session in analyzer.metadata().sessions.values() {
+        if !session.action_items.is_empty() || session.end_time.is_none() {
+            continue;
+        }
+
+        let content = match analyzer.read_session_log(session) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Warning: Failed to scan session {}: {:#}", session.id, e);
+                continue;
+            }
+        };
+
+        let items = new_items(extract_action_items(&content));
+        if items.is_empty() {
+            continue;
+        }
+
+        found += items.len();
+        let mut session = session.clone();
+        session.action_items = items;
+        logger.add_session(session);
+    }
+
+    if found > 0 {
+        logger.save_metadata()?;
+    }
+    Ok(found)
+}
+
+/// Change the state of item `number` (1-based) on a session.
+pub fn set_state(logger: &mut ClaudeLogger, session_id: &str, number: usize, state: ActionState) -> Result<ActionItem> {
+    let mut session = logger
+        .get_session(session_id)
+        .cloned()
+        .with_context(|| format!("Session not found: {}", session_id))?;
+
+    let item = number
+        .checked_sub(1)
+        .and_then(|index| session.action_items.get_mut(index))
+        .with_context(|| format!("Session {} has no action item {}", session_id, number))?;
+    item.state = state;
+    let item = item.clone();
+
+    logger.add_session(session);
+    logger.save_metadata()?;
+    Ok(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_items_from_final_turns_only() {
+        let content = "\
+Human: fix the parser
+Assistant: TODO: this one gets resolved later
+Human: ok
+Assistant: Done. Next steps:
+- Add a regression test
+2. Update the changelog
+That's all for now.
+- not an item
+Human: thanks
+Assistant: You're welcome!
+- [ ] Bump the version
+```
+// TODO: inside a code block
+```
+";
+
+        assert_eq!(
+            extract_action_items(content),
+            vec!["Add a regression test", "Update the changelog", "Bump the version"]
+        );
+    }
+}
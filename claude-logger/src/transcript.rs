@@ -0,0 +1,285 @@
+use crate::config::PricingConfig;
+use crate::logger::ClaudeLogger;
+use crate::session::{AnalysisMetrics, SessionMetadata, TokenUsage};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How far before the logged start a transcript may begin and still belong to the session.
+pub(crate) const START_SLACK_SECS: i64 = 120;
+
+/// Totals from one of Claude Code's JSONL conversation files.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Transcript {
+    pub session_id: Option<String>,
+    /// Where claude was started, as Claude Code recorded it
+    pub working_directory: Option<PathBuf>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Prompts typed by the user; tool results sent back to the model are not counted
+    pub exchanges: usize,
+    pub assistant_messages: usize,
+    pub tool_calls: usize,
+    pub tool_counts: BTreeMap<String, usize>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_creation_tokens: u64,
+    /// Lines that were not valid JSON
+    pub skipped_lines: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct Entry {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(rename = "sessionId")]
+    session_id: Option<String>,
+    cwd: Option<PathBuf>,
+    timestamp: Option<DateTime<Utc>>,
+    #[serde(rename = "isMeta", default)]
+    is_meta: bool,
+    /// The summary Claude Code sends in place of the conversation it compacted
+    #[serde(rename = "isCompactSummary", default)]
+    is_compact_summary: bool,
+    message: Option<Message>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    id: Option<String>,
+    #[serde(default)]
+    content: Value,
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+struct Usage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+    #[serde(default)]
+    cache_read_input_tokens: u64,
+    #[serde(default)]
+    cache_creation_input_tokens: u64,
+}
+
+impl Transcript {
+    pub fn parse(content: &str) -> Self {
+        let mut transcript = Transcript::default();
+        // Claude Code writes one line per content block, repeating the message's usage on each
+        let mut usage_by_message: HashMap<String, Usage> = HashMap::new();
+        let mut assistant_ids = HashSet::new();
+        let mut tool_ids = HashSet::new();
+
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            let Ok(entry) = serde_json::from_str::<Entry>(line) else {
+                transcript.skipped_lines += 1;
+                continue;
+            };
+
+            if transcript.session_id.is_none() {
+                transcript.session_id = entry.session_id.clone();
+            }
+            if transcript.working_directory.is_none() {
+                transcript.working_directory = entry.cwd.clone();
+            }
+            if let Some(timestamp) = entry.timestamp {
+                transcript.started_at = Some(transcript.started_at.map_or(timestamp, |started| started.min(timestamp)));
+                transcript.ended_at = Some(transcript.ended_at.map_or(timestamp, |ended| ended.max(timestamp)));
+            }
+
+            let Some(message) = entry.message else {
+                continue;
+            };
+            match entry.kind.as_str() {
+                "user" if !entry.is_meta && !entry.is_compact_summary && is_prompt(&message.content) => transcript.exchanges += 1,
+                "assistant" => {
+                    let id = message.id.clone().unwrap_or_else(|| format!("line-{}", assistant_ids.len()));
+                    assistant_ids.insert(id.clone());
+                    if let Some(usage) = message.usage {
+                        usage_by_message.insert(id, usage);
+                    }
+
+                    for block in message.content.as_array().into_iter().flatten() {
+                        if block["type"] != "tool_use" {
+                            continue;
+                        }
+                        let id = block["id"].as_str().unwrap_or_default().to_string();
+                        if id.is_empty() || tool_ids.insert(id) {
+                            let name = block["name"].as_str().unwrap_or("unknown").to_string();
+                            *transcript.tool_counts.entry(name).or_default() += 1;
+                            transcript.tool_calls += 1;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        transcript.assistant_messages = assistant_ids.len();
+        for usage in usage_by_message.values() {
+            transcript.input_tokens += usage.input_tokens;
+            transcript.output_tokens += usage.output_tokens;
+            transcript.cache_read_tokens += usage.cache_read_input_tokens;
+            transcript.cache_creation_tokens += usage.cache_creation_input_tokens;
+        }
+        transcript
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read transcript: {}", path.display()))?;
+        Ok(Self::parse(&content))
+    }
+
+    /// Replace the scrollback estimates with the transcript's exact counts.
+    pub fn apply_to(&self, metrics: &mut AnalysisMetrics) {
+        metrics.exchanges = self.exchanges;
+        metrics.tool_calls = self.tool_calls;
+        metrics.tool_usage = self.tool_counts.clone();
+        metrics.input_tokens = self.input_tokens;
+        metrics.output_tokens = self.output_tokens;
+    }
+
+    /// Estimated spend in US dollars at the configured prices.
+    pub fn estimated_cost(&self, pricing: &PricingConfig) -> f64 {
+        (self.input_tokens as f64 * pricing.input_per_mtok
+            + self.output_tokens as f64 * pricing.output_per_mtok
+            + self.cache_read_tokens as f64 * pricing.cache_read_per_mtok
+            + self.cache_creation_tokens as f64 * pricing.cache_write_per_mtok)
+            / 1_000_000.0
+    }
+
+    pub fn usage(&self, pricing: &PricingConfig) -> TokenUsage {
+        TokenUsage {
+            input_tokens: self.input_tokens,
+            output_tokens: self.output_tokens,
+            cache_read_tokens: self.cache_read_tokens,
+            cache_creation_tokens: self.cache_creation_tokens,
+            cost: self.estimated_cost(pricing),
+        }
+    }
+
+    pub fn print(&self) {
+        if let Some(session_id) = &self.session_id {
+            println!("Claude session: {}", session_id);
+        }
+        println!("Exchanges: {}", self.exchanges);
+        println!("Assistant messages: {}", self.assistant_messages);
+        println!("Tool calls: {}", self.tool_calls);
+        for (tool, count) in &self.tool_counts {
+            println!("  {:<16} {}", tool, count);
+        }
+        println!("Tokens: {} in, {} out ({} cache read, {} cache write)",
+            self.input_tokens, self.output_tokens, self.cache_read_tokens, self.cache_creation_tokens);
+        if self.skipped_lines > 0 {
+            println!("Skipped {} unreadable line(s)", self.skipped_lines);
+        }
+    }
+}
+
+/// A user entry typed at the prompt, rather than one carrying tool results back.
+pub(crate) fn is_prompt(content: &Value) -> bool {
+    match content {
+        Value::String(text) => !text.trim().is_empty(),
+        Value::Array(blocks) => blocks.iter().any(|block| block["type"] == "text"),
+        _ => false,
+    }
+}
+
+/// Where Claude Code keeps its per-project transcripts, honouring `CLAUDE_CONFIG_DIR`.
+pub fn projects_dir() -> Option<PathBuf> {
+    let base = match std::env::var_os("CLAUDE_CONFIG_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::home_dir()?.join(".claude"),
+    };
+    Some(base.join("projects"))
+}
+
+/// The directory name Claude Code derives from a working directory.
+pub fn project_key(working_directory: &Path) -> String {
+    working_directory
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// The transcript that started closest to the session within its logged time window.
+pub fn find_for_session(projects_dir: &Path, session: &SessionMetadata) -> Option<PathBuf> {
+    let dir = projects_dir.join(project_key(&session.working_directory));
+    let earliest = session.timestamp - Duration::seconds(START_SLACK_SECS);
+    // Without an end time only a transcript starting right at the session can match
+    let latest = session
+        .end_time
+        .or_else(|| session.duration().map(|duration| session.timestamp + duration))
+        .unwrap_or(session.timestamp + Duration::seconds(START_SLACK_SECS));
+
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "jsonl"))
+        .filter_map(|path| {
+            let started_at = Transcript::read(&path).ok()?.started_at?;
+            (started_at >= earliest && started_at <= latest).then_some((path, started_at))
+        })
+        .min_by_key(|(_, started_at)| (*started_at - session.timestamp).num_seconds().abs())
+        .map(|(path, _)| path)
+}
+
+/// Link every session without a transcript to the one Claude Code wrote, returning how many were linked.
+pub fn link_all(logger: &mut ClaudeLogger) -> Result<usize> {
+    let projects_dir = projects_dir().context("Could not find the Claude Code projects directory")?;
+
+    let mut linked = 0;
+    for session in logger.metadata_mut().sessions.values_mut() {
+        if session.transcript_file.as_ref().is_some_and(|path| path.exists()) {
+            continue;
+        }
+        if let Some(path) = find_for_session(&projects_dir, session) {
+            session.transcript_file = Some(path);
+            linked += 1;
+        }
+    }
+
+    if linked > 0 {
+        logger.save_metadata()?;
+    }
+    Ok(linked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_prompts_tools_and_deduplicated_usage() {
+        let content = r#"
+{"type":"user","sessionId":"abc","cwd":"/home/me/app","timestamp":"2025-01-01T10:00:00Z","message":{"role":"user","content":"fix the bug"}}
+{"type":"assistant","timestamp":"2025-01-01T10:00:05Z","message":{"id":"m1","content":[{"type":"text","text":"Looking"}],"usage":{"input_tokens":100,"output_tokens":20}}}
+{"type":"assistant","timestamp":"2025-01-01T10:00:06Z","message":{"id":"m1","content":[{"type":"tool_use","id":"t1","name":"Edit","input":{}}],"usage":{"input_tokens":100,"output_tokens":20}}}
+{"type":"user","timestamp":"2025-01-01T10:00:07Z","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"ok"}]}}
+{"type":"user","isMeta":true,"timestamp":"2025-01-01T10:00:08Z","message":{"role":"user","content":"<local-command-stdout>"}}
+not json
+{"type":"user","timestamp":"2025-01-01T10:01:00Z","message":{"role":"user","content":[{"type":"text","text":"thanks"}]}}
+"#;
+        let transcript = Transcript::parse(content);
+
+        assert_eq!(transcript.session_id.as_deref(), Some("abc"));
+        assert_eq!(transcript.working_directory.as_deref(), Some(Path::new("/home/me/app")));
+        assert_eq!(transcript.exchanges, 2);
+        assert_eq!(transcript.assistant_messages, 1);
+        assert_eq!(transcript.tool_calls, 1);
+        assert_eq!(transcript.tool_counts["Edit"], 1);
+        assert_eq!((transcript.input_tokens, transcript.output_tokens), (100, 20));
+        assert_eq!(transcript.skipped_lines, 1);
+        assert_eq!(project_key(Path::new("/home/me/my.app")), "-home-me-my-app");
+    }
+}
@@ -0,0 +1,216 @@
+use crate::analyzer::SessionAnalyzer;
+use crate::patterns::{analyze_session_quality, get_patterns};
+use crate::session::{Methodology, SessionMetadata};
+use crate::timezone::DisplayZone;
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// Relative change in the wrong direction that counts as a regression.
+const REGRESSION_RATIO: f64 = 0.10;
+
+/// Averages over one side of the switch.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WindowSummary {
+    pub sessions: usize,
+    pub context_driven_share: f64,
+    pub overall: f64,
+    pub engagement: f64,
+    pub clarity: f64,
+    pub productivity: f64,
+    pub exchanges: f64,
+    pub confusion_markers: f64,
+    pub minutes: Option<f64>,
+    pub creative_energy: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransitionReport {
+    pub switch_date: NaiveDate,
+    pub window: usize,
+    pub before: WindowSummary,
+    pub after: WindowSummary,
+}
+
+/// Which way a metric should move; neutral ones are shown but never flagged.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Better {
+    Higher,
+    Lower,
+    Neutral,
+}
+
+struct Comparison {
+    label: &'static str,
+    before: Option<f64>,
+    after: Option<f64>,
+    better: Better,
+}
+
+/// Compare the `window` sessions before `switch_date` with the `window` sessions from it onwards.
+pub fn transition_report(
+    analyzer: &SessionAnalyzer,
+    switch_date: NaiveDate,
+    window: usize,
+    zone: DisplayZone,
+) -> TransitionReport {
+    let switch = zone.start_of_day(switch_date);
+
+    let mut sessions: Vec<&SessionMetadata> = analyzer.metadata().sessions.values().collect();
+    sessions.sort_by_key(|session| session.timestamp);
+    let (before, after): (Vec<&SessionMetadata>, Vec<&SessionMetadata>) =
+        sessions.into_iter().partition(|session| session.timestamp < switch);
+
+    let before_window = &before[before.len().saturating_sub(window)..];
+    let after_window = &after[..after.len().min(window)];
+
+    TransitionReport {
+        switch_date,
+        window,
+        before: summarize(analyzer, before_window),
+        after: summarize(analyzer, after_window),
+    }
+}
+
+fn summarize(analyzer: &SessionAnalyzer, sessions: &[&SessionMetadata]) -> WindowSummary {
+    let mut summary = WindowSummary::default();
+    let mut minutes = Vec::new();
+    let mut energy = Vec::new();
+    let mut context_driven = 0;
+
+    for session in sessions {
+        let content = match analyzer.read_session_log(session) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Warning: Failed to analyze session {}: {:#}", session.id, e);
+                continue;
+            }
+        };
+        let metrics = get_patterns().analyze_content(&content);
+        let quality = analyze_session_quality(&content);
+
+        summary.sessions += 1;
+        summary.overall += quality.overall_score;
+        summary.engagement += quality.engagement_score;
+        summary.clarity += quality.clarity_score;
+        summary.productivity += quality.productivity_score;
+        summary.exchanges += metrics.exchanges as f64;
+        summary.confusion_markers += metrics.confusion_markers as f64;
+        if session.methodology == Methodology::CONTEXT_DRIVEN {
+            context_driven += 1;
+        }
+        minutes.extend(session.duration().map(|duration| duration.num_minutes() as f64));
+        energy.extend(session.creative_energy.map(f64::from));
+    }
+
+    if summary.sessions > 0 {
+        let count = summary.sessions as f64;
+        summary.context_driven_share = context_driven as f64 / count;
+        summary.overall /= count;
+        summary.engagement /= count;
+        summary.clarity /= count;
+        summary.productivity /= count;
+        summary.exchanges /= count;
+        summary.confusion_markers /= count;
+    }
+    summary.minutes = average(&minutes);
+    summary.creative_energy = average(&energy);
+    summary
+}
+
+fn average(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+impl TransitionReport {
+    fn comparisons(&self) -> Vec<Comparison> {
+        let (before, after) = (&self.before, &self.after);
+        let row = |label, before, after, better| Comparison {
+            label,
+            before,
+            after,
+            better,
+        };
+
+        vec![
+            row(
+                "Context-driven %",
+                Some(before.context_driven_share * 100.0),
+                Some(after.context_driven_share * 100.0),
+                Better::Neutral,
+            ),
+            row("Overall quality", Some(before.overall), Some(after.overall), Better::Higher),
+            row("Engagement", Some(before.engagement), Some(after.engagement), Better::Higher),
+            row("Clarity", Some(before.clarity), Some(after.clarity), Better::Higher),
+            row("Productivity", Some(before.productivity), Some(after.productivity), Better::Higher),
+            row("Exchanges", Some(before.exchanges), Some(after.exchanges), Better::Neutral),
+            row("Confusion markers", Some(before.confusion_markers), Some(after.confusion_markers), Better::Lower),
+            row("Duration (min)", before.minutes, after.minutes, Better::Neutral),
+            row("Creative energy", before.creative_energy, after.creative_energy, Better::Higher),
+        ]
+    }
+
+    /// Metrics that moved the wrong way by more than 10% after the switch.
+    pub fn regressions(&self) -> Vec<String> {
+        if self.before.sessions == 0 || self.after.sessions == 0 {
+            return Vec::new();
+        }
+
+        self.comparisons()
+            .into_iter()
+            .filter_map(|row| {
+                let (before, after) = (row.before?, row.after?);
+                // Anything appearing from a zero baseline counts as a full swing
+                let change = if before != 0.0 {
+                    (after - before) / before.abs()
+                } else if after != 0.0 {
+                    after.signum()
+                } else {
+                    0.0
+                };
+                let worse = match row.better {
+                    Better::Higher => -change,
+                    Better::Lower => change,
+                    Better::Neutral => return None,
+                };
+                (worse > REGRESSION_RATIO).then(|| {
+                    format!("{} regressed from {:.1} to {:.1} ({:+.0}%)", row.label, before, after, change * 100.0)
+                })
+            })
+            .collect()
+    }
+
+    pub fn print(&self) {
+        println!("=== Methodology Transition: {} ===", self.switch_date);
+        println!("Comparing up to {} sessions on each side ({} before, {} after)\n",
+            self.window, self.before.sessions, self.after.sessions);
+
+        if self.before.sessions == 0 || self.after.sessions == 0 {
+            println!("Not enough sessions on both sides of the switch date to compare.");
+            return;
+        }
+
+        println!("  {:<22} {:>10} {:>10} {:>9}", "Metric", "Before", "After", "Change");
+        for row in self.comparisons() {
+            let (Some(before), Some(after)) = (row.before, row.after) else {
+                println!("  {:<22} {:>10} {:>10} {:>9}", row.label, format_value(row.before), format_value(row.after), "-");
+                continue;
+            };
+            println!("  {:<22} {:>10.1} {:>10.1} {:>+9.1}", row.label, before, after, after - before);
+        }
+
+        let regressions = self.regressions();
+        println!();
+        if regressions.is_empty() {
+            println!("No regressions detected after the switch.");
+        } else {
+            println!("Regressions:");
+            for regression in regressions {
+                println!("  ! {}", regression);
+            }
+        }
+    }
+}
+
+fn format_value(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_string(), |value| format!("{:.1}", value))
+}
@@ -0,0 +1,165 @@
+use crate::logger::ClaudeLogger;
+use crate::session::SessionMetadata;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Deleted sessions wait here, one directory each, until restored or expired.
+pub const TRASH_DIR: &str = ".trash";
+
+const ENTRY_FILE: &str = "session.json";
+
+/// A deleted session, with what's needed to put it back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub deleted_at: DateTime<Utc>,
+    pub session: SessionMetadata,
+    /// Sessions that resumed this one and were relinked to its parent on deletion
+    #[serde(default)]
+    pub relinked: Vec<String>,
+    /// Where each file kept in the trash came from
+    #[serde(default)]
+    pub files: Vec<PathBuf>,
+}
+
+impl TrashEntry {
+    pub fn expires_at(&self, retention_days: u32) -> DateTime<Utc> {
+        self.deleted_at + Duration::days(retention_days.into())
+    }
+}
+
+fn entry_dir(logs_dir: &Path, session_id: &str) -> PathBuf {
+    logs_dir.join(TRASH_DIR).join(session_id)
+}
+
+/// Move `entry`'s files into the trash and record it there.
+pub fn put(logs_dir: &Path, entry: &TrashEntry) -> Result<()> {
+    let dir = entry_dir(logs_dir, &entry.session.id);
+    // An older deletion of a session with the same id is superseded
+    if dir.exists() {
+        fs::remove_dir_all(&dir).with_context(|| format!("Failed to clear {}", dir.display()))?;
+    }
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let json = serde_json::to_string_pretty(entry).context("Failed to serialize trash entry")?;
+    fs::write(dir.join(ENTRY_FILE), json).with_context(|| format!("Failed to write trash entry in {}", dir.display()))?;
+    for file in &entry.files {
+        let name = file.file_name().context("Invalid log file name")?;
+        fs::rename(file, dir.join(name)).with_context(|| format!("Failed to move {} to the trash", file.display()))?;
+    }
+    Ok(())
+}
+
+/// Every session in the trash, most recently deleted first.
+pub fn list(logs_dir: &Path) -> Result<Vec<TrashEntry>> {
+    let trash = logs_dir.join(TRASH_DIR);
+    let Ok(dirs) = fs::read_dir(&trash) else {
+        return Ok(Vec::new());
+    };
+
+    let mut entries = Vec::new();
+    for dir in dirs.flatten().filter(|entry| entry.path().is_dir()) {
+        let path = dir.path().join(ENTRY_FILE);
+        let entry = fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| serde_json::from_str::<TrashEntry>(&content).map_err(anyhow::Error::from));
+        match entry {
+            Ok(entry) => entries.push(entry),
+            Err(e) => eprintln!("Warning: Skipping {}: {:#}", path.display(), e),
+        }
+    }
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.deleted_at));
+    Ok(entries)
+}
+
+/// Permanently remove sessions deleted more than `retention_days` ago, returning their ids.
+pub fn purge_expired(logs_dir: &Path, retention_days: u32, now: DateTime<Utc>) -> Result<Vec<String>> {
+    let mut purged = Vec::new();
+    for entry in list(logs_dir)? {
+        if entry.expires_at(retention_days) <= now {
+            let dir = entry_dir(logs_dir, &entry.session.id);
+            fs::remove_dir_all(&dir).with_context(|| format!("Failed to remove {}", dir.display()))?;
+            purged.push(entry.session.id);
+        }
+    }
+    Ok(purged)
+}
+
+/// Put a deleted session's files and metadata back, committing the files to the logs repository.
+pub fn restore(logger: &mut ClaudeLogger, session_id: &str) -> Result<SessionMetadata> {
+    let dir = entry_dir(logger.logs_dir(), session_id);
+    let path = dir.join(ENTRY_FILE);
+    if !path.exists() {
+        anyhow::bail!("Session {} is not in the trash", session_id);
+    }
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let entry: TrashEntry =
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+    if logger.get_session(session_id).is_some() {
+        anyhow::bail!("A session {} exists again; delete it before restoring the old one", session_id);
+    }
+
+    for file in &entry.files {
+        if file.exists() {
+            anyhow::bail!("{} exists again; move it aside before restoring", file.display());
+        }
+        let name = file.file_name().context("Invalid log file name")?;
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        fs::rename(dir.join(name), file).with_context(|| format!("Failed to restore {}", file.display()))?;
+    }
+
+    let metadata = logger.metadata_mut();
+    for child_id in &entry.relinked {
+        // Only undo the relink if nobody has changed the parent since
+        if let Some(child) = metadata.get_session_mut(child_id)
+            && child.parent_session_id == entry.session.parent_session_id
+        {
+            child.parent_session_id = Some(session_id.to_string());
+        }
+    }
+    metadata.add_session(entry.session.clone());
+    logger.save_metadata()?;
+    fs::remove_dir_all(&dir).with_context(|| format!("Failed to remove {}", dir.display()))?;
+
+    let files: Vec<&Path> = entry.files.iter().map(PathBuf::as_path).collect();
+    if logger.git_repo().commit_changes(&[], &files, &format!("Restore session {} | {}", session_id, entry.session.project))?.is_some() {
+        logger.push_if_enabled();
+    }
+    Ok(entry.session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::{generate, sessions};
+
+    #[test]
+    fn keeps_entries_until_they_expire() {
+        let options = sessions(2, 4, "api");
+        let dir = tempfile::tempdir().unwrap();
+        let now = Utc::now();
+        for (age, generated) in [10, 40].into_iter().zip(generate(&options, dir.path(), now)) {
+            fs::create_dir_all(generated.session.log_file.parent().unwrap()).unwrap();
+            fs::write(&generated.session.log_file, &generated.log).unwrap();
+            let entry = TrashEntry {
+                deleted_at: now - Duration::days(age),
+                files: vec![generated.session.log_file.clone()],
+                session: generated.session,
+                relinked: Vec::new(),
+            };
+            put(dir.path(), &entry).unwrap();
+            assert!(!entry.files[0].exists());
+        }
+
+        assert_eq!(list(dir.path()).unwrap().len(), 2);
+        let purged = purge_expired(dir.path(), 30, now).unwrap();
+        let remaining = list(dir.path()).unwrap();
+        assert_eq!(purged.len(), 1);
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].deleted_at, now - Duration::days(10));
+    }
+}
@@ -0,0 +1,202 @@
+use crate::analyzer::SessionAnalyzer;
+use crate::calendar::{self, Bucket, Period};
+use crate::config::CalendarConfig;
+use crate::session::{AnalysisSnapshot, SessionMetadata};
+use crate::timezone::DisplayZone;
+use chrono::Duration;
+
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Averages over the sessions in one period; `None` where no session had the value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrendPoint {
+    pub bucket: Bucket,
+    pub sessions: usize,
+    /// Enthusiasm markers per session
+    pub enthusiasm: Option<f64>,
+    /// Confusion markers per session
+    pub confusion: Option<f64>,
+    /// Overall quality score, 0-100
+    pub quality: Option<f64>,
+    pub duration_minutes: Option<f64>,
+}
+
+type Series = fn(&TrendPoint) -> Option<f64>;
+
+/// The series shown, as (name, value per period).
+pub const SERIES: [(&str, Series); 5] = [
+    ("Sessions", |point| Some(point.sessions as f64)),
+    ("Enthusiasm", |point| point.enthusiasm),
+    ("Confusion", |point| point.confusion),
+    ("Quality", |point| point.quality),
+    ("Duration (m)", |point| point.duration_minutes),
+];
+
+/// The last `count` periods up to the latest session, empty periods included so gaps show.
+pub fn trends(
+    analyzer: &SessionAnalyzer,
+    period: Period,
+    calendar: &CalendarConfig,
+    zone: DisplayZone,
+    count: usize,
+) -> Vec<TrendPoint> {
+    let buckets = calendar::bucket_sessions(analyzer.metadata().sessions.values(), period, calendar, zone);
+    let (Some(first), Some(last)) = (buckets.keys().next().copied(), buckets.keys().last().copied()) else {
+        return Vec::new();
+    };
+
+    let mut points = Vec::new();
+    let mut bucket = last;
+    while points.len() < count && bucket >= first {
+        let sessions = buckets.get(&bucket).map(Vec::as_slice).unwrap_or_default();
+        points.push(point(analyzer, bucket, sessions));
+        bucket = period.bucket(bucket.start - Duration::days(1), calendar);
+    }
+    points.reverse();
+    points
+}
+
+fn point(analyzer: &SessionAnalyzer, bucket: Bucket, sessions: &[&SessionMetadata]) -> TrendPoint {
+    let snapshots: Vec<AnalysisSnapshot> = sessions
+        .iter()
+        .filter_map(|session| match &session.analysis {
+            Some(snapshot) => Some(snapshot.clone()),
+            None => match analyzer.read_session_log(session) {
+                Ok(content) => Some(SessionAnalyzer::snapshot(session, &content)),
+                Err(e) => {
+                    eprintln!("Warning: Leaving {} out of the trends: {:#}", session.id, e);
+                    None
+                }
+            },
+        })
+        .collect();
+
+    TrendPoint {
+        bucket,
+        sessions: sessions.len(),
+        enthusiasm: mean(snapshots.iter().map(|snapshot| snapshot.metrics.enthusiasm_markers as f64)),
+        confusion: mean(snapshots.iter().map(|snapshot| snapshot.metrics.confusion_markers as f64)),
+        quality: mean(snapshots.iter().map(|snapshot| snapshot.quality.overall_score)),
+        duration_minutes: mean(
+            sessions
+                .iter()
+                .filter_map(|session| session.duration())
+                .map(|duration| duration.num_seconds() as f64 / 60.0),
+        ),
+    }
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), value| (sum + value, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// One character per value, scaled between the series' minimum and maximum; gaps are blank.
+pub fn sparkline(values: &[Option<f64>]) -> String {
+    let known = values.iter().flatten();
+    let min = known.clone().copied().fold(f64::INFINITY, f64::min);
+    let max = known.copied().fold(f64::NEG_INFINITY, f64::max);
+
+    values
+        .iter()
+        .map(|value| match value {
+            None => ' ',
+            Some(_) if max <= min => SPARK_LEVELS[SPARK_LEVELS.len() / 2],
+            Some(value) => {
+                let level = ((value - min) / (max - min) * (SPARK_LEVELS.len() - 1) as f64).round() as usize;
+                SPARK_LEVELS[level.min(SPARK_LEVELS.len() - 1)]
+            }
+        })
+        .collect()
+}
+
+pub fn print(points: &[TrendPoint]) {
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        println!("No sessions found.");
+        return;
+    };
+    println!(
+        "=== Trends: {} to {} ({} periods) ===",
+        first.bucket.start,
+        last.bucket.end - Duration::days(1),
+        points.len()
+    );
+
+    for (name, series) in SERIES {
+        let values: Vec<Option<f64>> = points.iter().map(series).collect();
+        let known: Vec<f64> = values.iter().flatten().copied().collect();
+        let range = match (known.first(), known.last()) {
+            (Some(first), Some(last)) => format!(
+                "{:.1} -> {:.1}  (min {:.1}, max {:.1})",
+                first,
+                last,
+                known.iter().copied().fold(f64::INFINITY, f64::min),
+                known.iter().copied().fold(f64::NEG_INFINITY, f64::max)
+            ),
+            _ => "no data".to_string(),
+        };
+        println!("{:<14} {}  {}", name, sparkline(&values), range);
+    }
+}
+
+/// One row per period, blank cells where there was no data.
+pub fn to_csv(points: &[TrendPoint]) -> String {
+    let mut csv = String::from("period_start,period_end,sessions,enthusiasm,confusion,quality,duration_minutes\n");
+    let cell = |value: Option<f64>| value.map(|value| format!("{:.2}", value)).unwrap_or_default();
+    for point in points {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            point.bucket.start,
+            point.bucket.end - Duration::days(1),
+            point.sessions,
+            cell(point.enthusiasm),
+            cell(point.confusion),
+            cell(point.quality),
+            cell(point.duration_minutes)
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::{generate, sessions, Preset, Profile, SynthOptions};
+    use chrono::Utc;
+    use std::fs;
+
+    #[test]
+    fn sparklines_scale_and_show_gaps() {
+        assert_eq!(sparkline(&[Some(0.0), Some(7.0), None, Some(3.5)]), "▁█ ▅");
+        assert_eq!(sparkline(&[Some(2.0), Some(2.0)]), "▅▅");
+        assert_eq!(sparkline(&[None]), " ");
+    }
+
+    #[test]
+    fn fills_empty_periods_between_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = SynthOptions { profile: Profile::preset(Preset::Balanced), ..sessions(2, 6, "api") };
+        let now = Utc::now();
+        let mut metadata = crate::session::SessionsMetadata::new();
+        for (weeks_ago, generated) in [3, 0].into_iter().zip(generate(&options, dir.path(), now)) {
+            let mut session = generated.session;
+            session.timestamp = now - Duration::weeks(weeks_ago);
+            fs::create_dir_all(session.log_file.parent().unwrap()).unwrap();
+            fs::write(&session.log_file, &generated.log).unwrap();
+            metadata.add_session(session);
+        }
+        let store = crate::metadata_store::JsonMetadataStore::new(dir.path());
+        crate::metadata_store::MetadataStore::save(&store, &metadata).unwrap();
+        let analyzer = SessionAnalyzer::new_with_dir(dir.path()).unwrap();
+
+        let points = trends(&analyzer, Period::Week, &CalendarConfig::default(), DisplayZone::Utc, 10);
+        let sessions: Vec<usize> = points.iter().map(|point| point.sessions).collect();
+        assert_eq!(sessions, [1, 0, 0, 1]);
+        assert!(points[0].quality.is_some() && points[1].quality.is_none());
+        assert_eq!(to_csv(&points).lines().count(), 5);
+
+        let recent = trends(&analyzer, Period::Week, &CalendarConfig::default(), DisplayZone::Utc, 2);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[1], points[3]);
+    }
+}
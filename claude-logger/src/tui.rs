@@ -0,0 +1,563 @@
+use crate::analyzer::SessionAnalyzer;
+use crate::logger::ClaudeLogger;
+use crate::patterns::analyze_session_quality;
+use crate::timezone::DisplayZone;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::cmp::Ordering;
+
+/// A session as listed in the dashboard.
+#[derive(Debug, Clone)]
+pub struct SessionRow {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    pub project: String,
+    pub methodology: String,
+    pub minutes: Option<i64>,
+    pub energy: Option<u8>,
+    /// Overall quality; `None` when the log could not be read
+    pub quality: Option<f64>,
+}
+
+impl SessionRow {
+    /// Case-insensitive match on id, project, methodology and tags.
+    pub fn matches(&self, query: &str, tags: &[String]) -> bool {
+        let query = query.to_lowercase();
+        [&self.id, &self.project, &self.methodology]
+            .into_iter()
+            .chain(tags)
+            .any(|field| field.to_lowercase().contains(&query))
+    }
+}
+
+/// Every session with its quality score, newest first.
+pub fn session_rows(analyzer: &SessionAnalyzer) -> Vec<SessionRow> {
+    let mut rows: Vec<SessionRow> = analyzer
+        .metadata()
+        .sessions
+        .values()
+        .map(|session| SessionRow {
+            id: session.id.clone(),
+            timestamp: session.timestamp,
+            project: session.project.clone(),
+            methodology: session.methodology.to_string(),
+            minutes: session.duration().map(|duration| duration.num_minutes()),
+            energy: session.creative_energy,
+            quality: analyzer
+                .read_session_log(session)
+                .ok()
+                .map(|content| analyze_session_quality(&content).overall_score),
+        })
+        .collect();
+    sort_rows(&mut rows, SortColumn::Date, true);
+    rows
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortColumn {
+    Date,
+    Methodology,
+    Duration,
+    Energy,
+    Quality,
+}
+
+impl SortColumn {
+    pub const ALL: [SortColumn; 5] =
+        [SortColumn::Date, SortColumn::Methodology, SortColumn::Duration, SortColumn::Energy, SortColumn::Quality];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            SortColumn::Date => "Date",
+            SortColumn::Methodology => "Methodology",
+            SortColumn::Duration => "Duration",
+            SortColumn::Energy => "Energy",
+            SortColumn::Quality => "Quality",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|column| *column == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// Sort by `column`, ties broken by date. Sessions missing the value sort last either way.
+pub fn sort_rows(rows: &mut [SessionRow], column: SortColumn, descending: bool) {
+    fn missing_last<T: PartialOrd>(a: Option<T>, b: Option<T>, descending: bool) -> Ordering {
+        match (a, b) {
+            (Some(a), Some(b)) => {
+                let ordering = a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+                if descending { ordering.reverse() } else { ordering }
+            }
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+
+    rows.sort_by(|a, b| {
+        let ordering = match column {
+            SortColumn::Date => Ordering::Equal,
+            SortColumn::Methodology => {
+                let ordering = a.methodology.cmp(&b.methodology);
+                if descending { ordering.reverse() } else { ordering }
+            }
+            SortColumn::Duration => missing_last(a.minutes, b.minutes, descending),
+            SortColumn::Energy => missing_last(a.energy, b.energy, descending),
+            SortColumn::Quality => missing_last(a.quality, b.quality, descending),
+        };
+        ordering.then_with(|| {
+            let by_date = a.timestamp.cmp(&b.timestamp);
+            if descending { by_date.reverse() } else { by_date }
+        })
+    });
+}
+
+#[cfg(feature = "tui")]
+pub use dashboard::run;
+
+#[cfg(not(feature = "tui"))]
+pub fn run(_analyzer: &SessionAnalyzer, _logger: &mut ClaudeLogger, _zone: DisplayZone) -> Result<()> {
+    Err(anyhow::anyhow!("The dashboard is not compiled in; rebuild with `--features tui`"))
+}
+
+#[cfg(feature = "tui")]
+mod dashboard {
+    use super::*;
+    use crate::annotate;
+    use anyhow::Context;
+    use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+    use ratatui::layout::{Constraint, Layout};
+    use ratatui::style::{Modifier, Style};
+    use ratatui::text::Text;
+    use ratatui::widgets::{Block, Paragraph, Row, Table, TableState};
+    use ratatui::{DefaultTerminal, Frame};
+    use std::io::IsTerminal;
+
+    /// Lines moved by PgUp/PgDn in the detail view.
+    const PAGE: u16 = 20;
+
+    struct Detail {
+        session_id: String,
+        title: String,
+        text: Text<'static>,
+        scroll: u16,
+        /// Rows of text shown at once, as of the last draw
+        height: u16,
+        /// Index in `text` of the log's first line
+        log_start: usize,
+        /// The log as shown, which annotation line numbers count into
+        log: String,
+        /// Text lines where the selection started and where it ends now
+        selection: Option<(usize, usize)>,
+        /// The annotation being typed for the selection
+        input: Option<String>,
+    }
+
+    impl Detail {
+        fn last_line(&self) -> usize {
+            self.text.lines.len().saturating_sub(1)
+        }
+
+        /// The selection as 1-based log line numbers.
+        fn selected_lines(&self) -> Option<(usize, usize)> {
+            let (anchor, cursor) = self.selection?;
+            Some((anchor.min(cursor) - self.log_start + 1, anchor.max(cursor) - self.log_start + 1))
+        }
+
+        /// Move the end of the selection by `delta` lines within the log, scrolling to keep it shown.
+        fn extend(&mut self, delta: isize) {
+            let (log_start, last_line) = (self.log_start, self.last_line());
+            let Some((_, cursor)) = &mut self.selection else {
+                return;
+            };
+            *cursor = cursor.saturating_add_signed(delta).clamp(log_start, last_line.max(log_start));
+            let cursor = (*cursor).min(u16::MAX as usize) as u16;
+            if cursor < self.scroll {
+                self.scroll = cursor;
+            } else if cursor >= self.scroll.saturating_add(self.height.max(1)) {
+                self.scroll = cursor - self.height.max(1) + 1;
+            }
+        }
+    }
+
+    struct App<'a> {
+        analyzer: &'a SessionAnalyzer,
+        /// Saves annotations made in the detail view
+        logger: &'a mut ClaudeLogger,
+        zone: DisplayZone,
+        rows: Vec<SessionRow>,
+        /// Indices into `rows` that pass the search
+        visible: Vec<usize>,
+        sort: SortColumn,
+        descending: bool,
+        query: String,
+        searching: bool,
+        table: TableState,
+        detail: Option<Detail>,
+        status: Option<String>,
+    }
+
+    /// Browse sessions until the user quits, restoring the terminal however it ends.
+    pub fn run(analyzer: &SessionAnalyzer, logger: &mut ClaudeLogger, zone: DisplayZone) -> Result<()> {
+        if !std::io::stdout().is_terminal() {
+            anyhow::bail!("The dashboard needs an interactive terminal");
+        }
+
+        let mut app = App {
+            analyzer,
+            logger,
+            zone,
+            rows: session_rows(analyzer),
+            visible: Vec::new(),
+            sort: SortColumn::Date,
+            descending: true,
+            query: String::new(),
+            searching: false,
+            table: TableState::default(),
+            detail: None,
+            status: None,
+        };
+        app.refresh();
+
+        let mut terminal = ratatui::try_init().context("Failed to set up the terminal")?;
+        let result = app.event_loop(&mut terminal);
+        ratatui::restore();
+        result
+    }
+
+    impl App<'_> {
+        fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+            loop {
+                terminal.draw(|frame| self.draw(frame)).context("Failed to draw the dashboard")?;
+                let Event::Key(key) = event::read().context("Failed to read terminal input")? else {
+                    continue;
+                };
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                let quit = if self.detail.is_some() {
+                    self.detail_key(key)
+                } else if self.searching {
+                    self.search_key(key);
+                    false
+                } else {
+                    self.list_key(key)
+                };
+                if quit {
+                    return Ok(());
+                }
+            }
+        }
+
+        /// Re-sort and re-filter, keeping the selected session when it is still shown.
+        fn refresh(&mut self) {
+            let selected = self.selected().map(|row| row.id.clone());
+            sort_rows(&mut self.rows, self.sort, self.descending);
+
+            let metadata = self.analyzer.metadata();
+            self.visible = (0..self.rows.len())
+                .filter(|&index| {
+                    let row = &self.rows[index];
+                    let tags = metadata.get_session(&row.id).map_or(&[][..], |session| &session.tags[..]);
+                    self.query.is_empty() || row.matches(&self.query, tags)
+                })
+                .collect();
+
+            let position = selected
+                .and_then(|id| self.visible.iter().position(|&index| self.rows[index].id == id))
+                .or((!self.visible.is_empty()).then_some(0));
+            self.table.select(position);
+        }
+
+        fn selected(&self) -> Option<&SessionRow> {
+            let index = *self.visible.get(self.table.selected()?)?;
+            self.rows.get(index)
+        }
+
+        /// Returns whether to quit.
+        fn list_key(&mut self, key: KeyEvent) -> bool {
+            self.status = None;
+            match key.code {
+                KeyCode::Char('q') => return true,
+                KeyCode::Esc if !self.query.is_empty() => {
+                    self.query.clear();
+                    self.refresh();
+                }
+                KeyCode::Esc => return true,
+                KeyCode::Down | KeyCode::Char('j') => self.table.select_next(),
+                KeyCode::Up | KeyCode::Char('k') => self.table.select_previous(),
+                KeyCode::Home | KeyCode::Char('g') => self.table.select_first(),
+                KeyCode::End | KeyCode::Char('G') => self.table.select_last(),
+                KeyCode::Char('/') => self.searching = true,
+                KeyCode::Char('s') => {
+                    self.sort = self.sort.next();
+                    self.refresh();
+                }
+                KeyCode::Char('r') => {
+                    self.descending = !self.descending;
+                    self.refresh();
+                }
+                KeyCode::Char(digit @ '1'..='5') => {
+                    let column = SortColumn::ALL[digit as usize - '1' as usize];
+                    // Choosing the current column again flips its direction
+                    if column == self.sort {
+                        self.descending = !self.descending;
+                    }
+                    self.sort = column;
+                    self.refresh();
+                }
+                KeyCode::Enter => self.open_selected(),
+                _ => {}
+            }
+            false
+        }
+
+        fn search_key(&mut self, key: KeyEvent) {
+            match key.code {
+                KeyCode::Enter => self.searching = false,
+                KeyCode::Esc => {
+                    self.searching = false;
+                    self.query.clear();
+                }
+                KeyCode::Backspace => {
+                    self.query.pop();
+                }
+                KeyCode::Char(c) => self.query.push(c),
+                _ => return,
+            }
+            self.refresh();
+        }
+
+        /// Returns whether to quit.
+        fn detail_key(&mut self, key: KeyEvent) -> bool {
+            self.status = None;
+            let Some(detail) = &mut self.detail else {
+                return false;
+            };
+            if let Some(input) = &mut detail.input {
+                match key.code {
+                    KeyCode::Enter => self.save_annotation(),
+                    KeyCode::Esc => detail.input = None,
+                    KeyCode::Backspace => {
+                        input.pop();
+                    }
+                    KeyCode::Char(c) => input.push(c),
+                    _ => {}
+                }
+                return false;
+            }
+            if detail.selection.is_some() {
+                match key.code {
+                    KeyCode::Down | KeyCode::Char('j') => detail.extend(1),
+                    KeyCode::Up | KeyCode::Char('k') => detail.extend(-1),
+                    KeyCode::PageDown | KeyCode::Char(' ') => detail.extend(PAGE as isize),
+                    KeyCode::PageUp => detail.extend(-(PAGE as isize)),
+                    KeyCode::Char('a') => detail.input = Some(String::new()),
+                    KeyCode::Char('v') | KeyCode::Esc => detail.selection = None,
+                    KeyCode::Char('q') => return true,
+                    _ => {}
+                }
+                return false;
+            }
+
+            let last_line = detail.last_line().min(u16::MAX as usize) as u16;
+            match key.code {
+                KeyCode::Char('q') => return true,
+                KeyCode::Esc | KeyCode::Backspace => self.detail = None,
+                // Start selecting at the first log line in view
+                KeyCode::Char('v') if detail.last_line() < detail.log_start => {
+                    self.status = Some("The log is empty".to_string());
+                }
+                KeyCode::Char('v') => {
+                    let line = (detail.scroll as usize).clamp(detail.log_start, detail.last_line());
+                    detail.selection = Some((line, line));
+                    detail.extend(0);
+                }
+                KeyCode::Char('a') => self.status = Some("Select lines with v first".to_string()),
+                KeyCode::Down | KeyCode::Char('j') => detail.scroll = detail.scroll.saturating_add(1),
+                KeyCode::Up | KeyCode::Char('k') => detail.scroll = detail.scroll.saturating_sub(1),
+                KeyCode::PageDown | KeyCode::Char(' ') => detail.scroll = detail.scroll.saturating_add(PAGE),
+                KeyCode::PageUp => detail.scroll = detail.scroll.saturating_sub(PAGE),
+                KeyCode::Home | KeyCode::Char('g') => detail.scroll = 0,
+                KeyCode::End | KeyCode::Char('G') => detail.scroll = last_line,
+                _ => {}
+            }
+            if let Some(detail) = &mut self.detail {
+                detail.scroll = detail.scroll.min(last_line);
+            }
+            false
+        }
+
+        /// Save the typed annotation over the selected lines.
+        fn save_annotation(&mut self) {
+            let Some(detail) = &mut self.detail else {
+                return;
+            };
+            let (Some(input), Some(lines)) = (detail.input.take(), detail.selected_lines()) else {
+                return;
+            };
+            let (category, note) = annotate::split_category(&input);
+            let saved = annotate::new_annotation(&detail.log, lines, &category, &note)
+                .and_then(|annotation| annotate::add_annotation(self.logger, &detail.session_id, annotation));
+            self.status = Some(match saved {
+                Ok(()) => {
+                    detail.selection = None;
+                    format!("Annotated lines {}-{} [{}]", lines.0, lines.1, category)
+                }
+                Err(e) => {
+                    // Keep what was typed so it can be fixed
+                    detail.input = Some(input);
+                    format!("{:#}", e)
+                }
+            });
+        }
+
+        fn open_selected(&mut self) {
+            let Some(id) = self.selected().map(|row| row.id.clone()) else {
+                return;
+            };
+            let summary = match self.analyzer.get_session_summary(&id) {
+                Ok(summary) => summary,
+                Err(e) => {
+                    self.status = Some(format!("{}: {:#}", id, e));
+                    return;
+                }
+            };
+            let log = self
+                .analyzer
+                .read_session_log(&summary.session)
+                .unwrap_or_else(|e| format!("(log unavailable: {:#})", e));
+            let header = format!("{}\n\n=== Log ===", summary.summary_text(self.zone));
+            let log_start = header.lines().count();
+            self.detail = Some(Detail {
+                session_id: id.clone(),
+                title: format!(" {} — {} ", summary.session.project, id),
+                text: Text::from(format!("{}\n{}", header, log)),
+                scroll: 0,
+                height: 0,
+                log_start,
+                log,
+                selection: None,
+                input: None,
+            });
+        }
+
+        fn draw(&mut self, frame: &mut Frame) {
+            let [main, footer] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+
+            if let Some(detail) = &mut self.detail {
+                detail.height = main.height.saturating_sub(2);
+                let mut text = detail.text.clone();
+                if let Some((anchor, cursor)) = detail.selection {
+                    for line in &mut text.lines[anchor.min(cursor)..=anchor.max(cursor)] {
+                        line.style = Style::new().add_modifier(Modifier::REVERSED);
+                    }
+                }
+                let paragraph = Paragraph::new(text)
+                    .block(Block::bordered().title(detail.title.as_str()))
+                    .scroll((detail.scroll, 0));
+                frame.render_widget(paragraph, main);
+
+                let footer_text = match (&detail.input, detail.selected_lines(), &self.status) {
+                    (Some(input), Some((start, end)), _) => {
+                        format!("Annotate lines {}-{} (category: note) › {}█", start, end, input)
+                    }
+                    (_, _, Some(status)) => status.clone(),
+                    (_, Some((start, end)), _) => {
+                        format!("Lines {}-{} selected  j/k extend  a annotate  v/Esc cancel", start, end)
+                    }
+                    _ => "j/k scroll  PgUp/PgDn page  g/G top/bottom  v select  Esc back  q quit".to_string(),
+                };
+                frame.render_widget(Paragraph::new(footer_text), footer);
+                return;
+            }
+
+            let header = Row::new(SortColumn::ALL.iter().enumerate().map(|(index, column)| {
+                let arrow = match (*column == self.sort, self.descending) {
+                    (true, true) => " ▼",
+                    (true, false) => " ▲",
+                    (false, _) => "",
+                };
+                format!("{} {}{}", index + 1, column.title(), arrow)
+            }).chain(["Project".to_string(), "Session".to_string()]))
+            .style(Style::new().add_modifier(Modifier::BOLD));
+
+            let rows = self.visible.iter().map(|&index| {
+                let row = &self.rows[index];
+                Row::new([
+                    self.zone.format(row.timestamp, "%Y-%m-%d %H:%M"),
+                    row.methodology.clone(),
+                    row.minutes.map(|minutes| format!("{} min", minutes)).unwrap_or_else(|| "-".to_string()),
+                    row.energy.map(|energy| format!("{}/3", energy)).unwrap_or_else(|| "-".to_string()),
+                    row.quality.map(|quality| format!("{:.1}", quality)).unwrap_or_else(|| "-".to_string()),
+                    row.project.clone(),
+                    row.id.clone(),
+                ])
+            });
+
+            let widths = [
+                Constraint::Length(18),
+                Constraint::Length(16),
+                Constraint::Length(12),
+                Constraint::Length(10),
+                Constraint::Length(11),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ];
+            let title = format!(" Sessions ({} of {}) ", self.visible.len(), self.rows.len());
+            let table = Table::new(rows, widths)
+                .header(header)
+                .block(Block::bordered().title(title))
+                .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+            frame.render_stateful_widget(table, main, &mut self.table);
+
+            let footer_text = if self.searching {
+                format!("/{}█", self.query)
+            } else if let Some(status) = &self.status {
+                status.clone()
+            } else if !self.query.is_empty() {
+                format!("filter: {}  (Esc clears)  Enter open  1-5/s sort  r reverse  q quit", self.query)
+            } else {
+                "Enter open  / search  1-5/s sort  r reverse  j/k move  q quit".to_string()
+            };
+            frame.render_widget(Paragraph::new(footer_text), footer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn row(id: &str, day: u32, minutes: Option<i64>, quality: Option<f64>) -> SessionRow {
+        SessionRow {
+            id: id.to_string(),
+            timestamp: Utc.with_ymd_and_hms(2025, 3, day, 9, 0, 0).unwrap(),
+            project: "api".to_string(),
+            methodology: "Context-Driven".to_string(),
+            minutes,
+            energy: None,
+            quality,
+        }
+    }
+
+    #[test]
+    fn sorts_with_missing_values_last() {
+        let mut rows = vec![row("a", 1, Some(30), None), row("b", 2, None, Some(80.0)), row("c", 3, Some(90), Some(40.0))];
+
+        sort_rows(&mut rows, SortColumn::Duration, true);
+        let ids: Vec<&str> = rows.iter().map(|row| row.id.as_str()).collect();
+        assert_eq!(ids, ["c", "a", "b"]);
+
+        sort_rows(&mut rows, SortColumn::Quality, false);
+        let ids: Vec<&str> = rows.iter().map(|row| row.id.as_str()).collect();
+        assert_eq!(ids, ["c", "b", "a"]);
+
+        assert!(rows[0].matches("API", &[]));
+        assert!(rows[0].matches("urgent", &["Urgent-fix".to_string()]));
+        assert!(!rows[0].matches("web", &[]));
+    }
+}
@@ -0,0 +1,205 @@
+use crate::git::GitRepo;
+use crate::metadata::{migrate_entry, unknown_fields, LoadMode};
+use crate::session::SessionMetadata;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// JSON Schema for `sessions_metadata.json`.
+pub const METADATA_SCHEMA: &str = include_str!("../schema/sessions_metadata.schema.json");
+
+/// JSON Schema for the `sync_state.json` sidecar.
+pub const SYNC_STATE_SCHEMA: &str = include_str!("../schema/sync_state.schema.json");
+
+/// A single problem found in stored data.
+#[derive(Debug, Clone)]
+pub struct ValidationIssue {
+    pub file: PathBuf,
+    /// JSON pointer to the offending value
+    pub location: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let file = self
+            .file
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        if self.location.is_empty() {
+            write!(f, "{}: {}", file, self.message)
+        } else {
+            write!(f, "{} at {}: {}", file, self.location, self.message)
+        }
+    }
+}
+
+/// Check the metadata file and known sidecars in `logs_dir` against their schemas
+/// and the invariants the rest of the tool relies on. Strict mode also flags fields
+/// this version doesn't know about.
+pub fn validate_logs_dir(logs_dir: &Path, mode: LoadMode) -> Result<Vec<ValidationIssue>> {
+    let mut issues = Vec::new();
+
+    let metadata_file = logs_dir.join("sessions_metadata.json");
+    if metadata_file.exists() {
+        let value = read_json(&metadata_file, &mut issues)?;
+        if let Some(value) = value {
+            issues.extend(check_schema(METADATA_SCHEMA, &value, &metadata_file)?);
+            let repo = GitRepo::open(logs_dir);
+            check_invariants(&value, &metadata_file, repo.as_ref(), mode, &mut issues);
+        }
+    }
+
+    let sync_state = logs_dir.join("sync_state.json");
+    if sync_state.exists()
+        && let Some(value) = read_json(&sync_state, &mut issues)?
+    {
+        issues.extend(check_schema(SYNC_STATE_SCHEMA, &value, &sync_state)?);
+    }
+
+    Ok(issues)
+}
+
+fn read_json(path: &Path, issues: &mut Vec<ValidationIssue>) -> Result<Option<Value>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    match serde_json::from_str(&content) {
+        Ok(value) => Ok(Some(value)),
+        Err(e) => {
+            issues.push(ValidationIssue {
+                file: path.to_path_buf(),
+                location: String::new(),
+                message: format!("not valid JSON: {}", e),
+            });
+            Ok(None)
+        }
+    }
+}
+
+fn check_schema(schema: &str, value: &Value, file: &Path) -> Result<Vec<ValidationIssue>> {
+    let schema: Value = serde_json::from_str(schema).context("Built-in schema is not valid JSON")?;
+    let validator = jsonschema::options()
+        .should_validate_formats(true)
+        .build(&schema)
+        .map_err(|e| anyhow::anyhow!("Built-in schema is invalid: {}", e))?;
+
+    Ok(validator
+        .iter_errors(value)
+        .map(|error| ValidationIssue {
+            file: file.to_path_buf(),
+            location: error.instance_path().to_string(),
+            message: error.to_string(),
+        })
+        .collect())
+}
+
+/// Relationships between fields that a schema cannot express.
+///
+/// Sessions that don't deserialize are skipped; the schema check already reports them.
+fn check_invariants(
+    value: &Value,
+    file: &Path,
+    repo: Option<&GitRepo>,
+    mode: LoadMode,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let Some(sessions) = value.get("sessions").and_then(Value::as_object) else {
+        return;
+    };
+
+    let mut keys: Vec<&String> = sessions.keys().collect();
+    keys.sort();
+
+    for key in keys {
+        let mut raw = sessions[key].clone();
+        migrate_entry(&mut raw);
+        let Ok(session) = serde_json::from_value::<SessionMetadata>(raw.clone()) else {
+            continue;
+        };
+        let mut issue = |field: &str, message: String| {
+            issues.push(ValidationIssue {
+                file: file.to_path_buf(),
+                location: format!("/sessions/{}{}", key, field),
+                message,
+            })
+        };
+
+        if mode == LoadMode::Strict {
+            for field in unknown_fields(&raw, &session) {
+                issue(&format!("/{}", field), "unknown field; it will be dropped the next time metadata is saved".to_string());
+            }
+        }
+
+        if &session.id != key {
+            issue("/id", format!("id '{}' does not match its key; rename one of them", session.id));
+        }
+
+        if let Some(end_time) = session.end_time
+            && end_time < session.timestamp
+        {
+            issue(
+                "/end_time",
+                format!("end_time {} is before timestamp {}", end_time.to_rfc3339(), session.timestamp.to_rfc3339()),
+            );
+        }
+
+        if let Some(secs) = session.duration_seconds
+            && secs < 0
+        {
+            issue("/duration_seconds", format!("duration is negative ({}s)", secs));
+        }
+
+        let recoverable = session.log_file.exists()
+            || session.archived_remote.is_some()
+            || session.git_commit.is_some()
+            || repo.is_some_and(|repo| matches!(repo.last_commit_for(&repo.relative_path(&session.log_file)), Ok(Some(_))));
+        if !recoverable {
+            issue(
+                "/log_file",
+                format!(
+                    "{} is missing and the session has no archive or commit to restore it from",
+                    session.log_file.display()
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_schema_and_invariant_problems() {
+        let dir = tempfile::tempdir().unwrap();
+        let metadata = serde_json::json!({
+            "sessions": {
+                "s1": {
+                    "id": "s1",
+                    "timestamp": "2025-01-12T14:30:00Z",
+                    "project": "beta",
+                    "methodology": "CommandBased",
+                    "working_directory": "/tmp/beta",
+                    "command": "claude",
+                    "log_file": dir.path().join("s1.log"),
+                    "duration_seconds": 60,
+                    "end_time": "2025-01-01T00:00:00Z",
+                    "features_worked_on": [],
+                    "creative_energy": 7
+                }
+            }
+        });
+        fs::write(dir.path().join("s1.log"), "Human: hi").unwrap();
+        fs::write(dir.path().join("sessions_metadata.json"), metadata.to_string()).unwrap();
+
+        let issues = validate_logs_dir(dir.path(), LoadMode::Lenient).unwrap();
+        let locations: Vec<&str> = issues.iter().map(|issue| issue.location.as_str()).collect();
+
+        assert_eq!(issues.len(), 2);
+        assert!(locations.contains(&"/sessions/s1/creative_energy"));
+        assert!(locations.contains(&"/sessions/s1/end_time"));
+    }
+}